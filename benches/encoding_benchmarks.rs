@@ -1,5 +1,5 @@
 use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
-use three_word_networking::{four_word_encoder::FourWordDictionary, *};
+use four_word_networking::{dictionary4k::Dictionary4K, *};
 // use std::net::{SocketAddr, IpAddr, Ipv4Addr, Ipv6Addr};
 use std::time::Duration;
 
@@ -41,6 +41,23 @@ fn bench_ipv6_encoding(c: &mut Criterion) {
     });
 }
 
+fn bench_encode_into(c: &mut Criterion) {
+    let encoder = FourWordAdaptiveEncoder::new().unwrap();
+    let test_addresses: Vec<std::net::SocketAddr> =
+        ["192.168.1.1:443", "[::1]:443", "[2001:db8::1]:443"]
+            .iter()
+            .map(|addr| addr.parse().unwrap())
+            .collect();
+
+    c.bench_function("encode_into_word_phrase", |b| {
+        b.iter(|| {
+            for addr in &test_addresses {
+                let _: Result<WordPhrase> = encoder.encode_phrase(black_box(*addr));
+            }
+        })
+    });
+}
+
 fn bench_decoding(c: &mut Criterion) {
     let encoder = FourWordAdaptiveEncoder::new().unwrap();
     let test_words = vec![
@@ -82,12 +99,12 @@ fn bench_round_trip(c: &mut Criterion) {
 // Additional comprehensive benchmarks
 
 fn bench_dictionary_performance(c: &mut Criterion) {
-    let dict = FourWordDictionary::new().unwrap();
+    let dict = Dictionary4K::new().unwrap();
 
     c.bench_function("dictionary_word_lookup", |b| {
         b.iter(|| {
             for i in 0..1000 {
-                let _ = dict.get_word(black_box(i % 16384));
+                let _ = dict.get_word(black_box(i % 4096));
             }
         })
     });
@@ -96,7 +113,7 @@ fn bench_dictionary_performance(c: &mut Criterion) {
         b.iter(|| {
             let words = ["apple", "orange", "banana", "grape", "cherry"];
             for word in words {
-                let _ = dict.find_word(black_box(word));
+                let _ = dict.get_index(black_box(word));
             }
         })
     });
@@ -166,7 +183,7 @@ fn bench_memory_usage(c: &mut Criterion) {
 
     group.bench_function("dictionary_creation", |b| {
         b.iter(|| {
-            let _ = FourWordDictionary::new();
+            let _ = Dictionary4K::new();
         })
     });
 
@@ -324,6 +341,7 @@ criterion_group!(
     targets =
         bench_ipv4_encoding,
         bench_ipv6_encoding,
+        bench_encode_into,
         bench_decoding,
         bench_round_trip,
         bench_dictionary_performance,
@@ -0,0 +1,115 @@
+//! Build script generating the built-in dictionary tables.
+//!
+//! Each 4,096-word list is turned into `const` arrays at build time so the
+//! library needs no statics, lazy initialisation or filesystem access at
+//! runtime. The lists are validated here, so a malformed wordlist fails the
+//! build instead of the first encode, and their fingerprints are computed
+//! here too.
+//!
+//! English is always built; other languages are only generated when their
+//! `lang-*` feature is enabled.
+
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const DICTIONARY_SIZE: usize = 4096;
+
+/// (feature, wordlist, generated file) for every optional language
+const LANGUAGES: &[(&str, &str, &str)] = &[
+    ("LANG_ES", "wordlists/spanish.txt", "dictionary_words_es.rs"),
+    ("LANG_FR", "wordlists/french.txt", "dictionary_words_fr.rs"),
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    generate("GOLD_WORDLIST.txt", "dictionary_words.rs");
+    for (feature, wordlist, generated) in LANGUAGES {
+        if env::var_os(format!("CARGO_FEATURE_{feature}")).is_some() {
+            generate(wordlist, generated);
+        }
+    }
+}
+
+/// Validates `wordlist` and writes its lookup tables to `$OUT_DIR/<generated>`
+fn generate(wordlist: &str, generated: &str) {
+    println!("cargo:rerun-if-changed={wordlist}");
+
+    let raw = fs::read_to_string(wordlist)
+        .unwrap_or_else(|e| panic!("failed to read wordlist {wordlist}: {e}"));
+    let words: Vec<String> = raw
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(DICTIONARY_SIZE)
+        .map(|s| s.trim().to_lowercase())
+        .collect();
+
+    assert_eq!(
+        words.len(),
+        DICTIONARY_SIZE,
+        "{wordlist}: dictionary must contain exactly {DICTIONARY_SIZE} words"
+    );
+
+    let mut seen = HashSet::with_capacity(DICTIONARY_SIZE);
+    for word in &words {
+        assert!(
+            seen.insert(word.as_str()),
+            "{wordlist}: duplicate word found: {word}"
+        );
+        assert!(
+            word.bytes().all(|b| b.is_ascii_lowercase()),
+            "{wordlist}: word must be lowercase ASCII: {word}"
+        );
+    }
+
+    // Indices ordered by word, for binary-search reverse lookup
+    let mut sorted: Vec<u16> = (0..DICTIONARY_SIZE as u16).collect();
+    sorted.sort_by(|&a, &b| words[a as usize].cmp(&words[b as usize]));
+
+    let mut out = String::new();
+    // The tables are only ever used by reference, which the compiler promotes
+    // to a single read-only allocation, so `const` costs nothing over `static`
+    writeln!(out, "/// Dictionary words indexed by their position").unwrap();
+    writeln!(out, "#[allow(clippy::large_const_arrays)]").unwrap();
+    writeln!(out, "pub(super) const WORDS: [&str; {DICTIONARY_SIZE}] = [").unwrap();
+    for word in &words {
+        writeln!(out, "    {word:?},").unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    writeln!(out, "/// Word indices in lexicographic word order").unwrap();
+    writeln!(out, "#[allow(clippy::large_const_arrays)]").unwrap();
+    writeln!(
+        out,
+        "pub(super) const SORTED_INDICES: [u16; {DICTIONARY_SIZE}] = ["
+    )
+    .unwrap();
+    for index in &sorted {
+        writeln!(out, "    {index},").unwrap();
+    }
+    writeln!(out, "];").unwrap();
+
+    // Same hashing as `wordlist::fingerprint_words`
+    let mut hasher = Sha256::new();
+    for word in &words {
+        hasher.update(word.as_bytes());
+        hasher.update(b"\n");
+    }
+    let fingerprint: [u8; 32] = hasher.finalize().into();
+    writeln!(
+        out,
+        "/// SHA-256 of the words in index order, newline-terminated"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "pub(super) const FINGERPRINT: [u8; 32] = {fingerprint:?};"
+    )
+    .unwrap();
+
+    let dest = Path::new(&env::var("OUT_DIR").unwrap()).join(generated);
+    fs::write(dest, out).expect("failed to write dictionary tables");
+}
@@ -0,0 +1,85 @@
+#ifndef FOUR_WORD_NETWORKING_H
+#define FOUR_WORD_NETWORKING_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Size of an output buffer that fits any phrase plus its NUL terminator
+#define TWN_PHRASE_CAPACITY 193
+
+// Port value meaning "no port"
+#define TWN_NO_PORT 65535
+
+// Result of every `twn_*` call
+typedef enum TwnStatus {
+  // Success
+  TWN_STATUS_OK = 0,
+  // A required pointer argument was NULL
+  TWN_STATUS_NULL_POINTER = 1,
+  // The phrase is not valid UTF-8
+  TWN_STATUS_INVALID_UTF8 = 2,
+  // The phrase does not decode
+  TWN_STATUS_INVALID_PHRASE = 3,
+  // The output buffer is too small; [`TWN_PHRASE_CAPACITY`] always suffices
+  TWN_STATUS_BUFFER_TOO_SMALL = 4,
+  // The input was already an IP address rather than a phrase
+  TWN_STATUS_ALREADY_DECODED = 5,
+  // An unexpected internal failure
+  TWN_STATUS_INTERNAL = 6,
+} TwnStatus;
+
+// A decoded address
+typedef struct TwnAddress {
+  // 4 for IPv4, 6 for IPv6
+  uint8_t family;
+  // Address bytes in network order; IPv4 uses the first four
+  uint8_t ip[16];
+  // Port number, [`TWN_NO_PORT`] if the phrase carries none
+  uint16_t port;
+  // Whether the phrase carries a port
+  bool has_port;
+} TwnAddress;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Encodes a 4-byte IPv4 address and port into a NUL-terminated phrase.
+//
+// Pass [`TWN_NO_PORT`] to encode the address alone.
+//
+// # Safety
+//
+// `ip` must point to 4 readable bytes and `out` to `out_len` writable bytes.
+enum TwnStatus twn_encode_v4(const uint8_t *ip, uint16_t port, char *out, size_t out_len);
+
+// Encodes a 16-byte IPv6 address and port into a NUL-terminated phrase.
+//
+// Pass [`TWN_NO_PORT`] to encode the address alone.
+//
+// # Safety
+//
+// `ip` must point to 16 readable bytes and `out` to `out_len` writable bytes.
+enum TwnStatus twn_encode_v6(const uint8_t *ip, uint16_t port, char *out, size_t out_len);
+
+// Decodes a NUL-terminated phrase into `out`.
+//
+// # Safety
+//
+// `words` must be a valid NUL-terminated string and `out` must point to a
+// writable `TwnAddress`.
+enum TwnStatus twn_decode(const char *words, struct TwnAddress *out);
+
+// Returns a static, NUL-terminated description of `status`
+const char *twn_status_message(enum TwnStatus status);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* FOUR_WORD_NETWORKING_H */
@@ -0,0 +1,157 @@
+//! Compile-time phrases for [`four_word_networking`].
+//!
+//! [`words!`] encodes an address when the crate using it is compiled, and
+//! [`addr!`] decodes a phrase to a `SocketAddr` expression that is valid in
+//! a `const`. Well-known endpoints, such as bootstrap nodes, can be embedded
+//! in a binary either way round without encoding anything at runtime, and a
+//! mistyped address or phrase fails the build instead of the first
+//! connection:
+//!
+//! ```rust
+//! use four_word_networking_macros::{addr, words};
+//! use std::net::SocketAddr;
+//!
+//! const BOOTSTRAP: SocketAddr = addr!("book abstract junk restriction");
+//! const BOOTSTRAP_WORDS: &str = words!("192.168.1.1:443");
+//!
+//! assert_eq!(BOOTSTRAP, "192.168.1.1:443".parse().unwrap());
+//! assert_eq!(BOOTSTRAP_WORDS, "book abstract junk restriction");
+//! ```
+//!
+//! Both macros take one string literal and use the built-in English
+//! wordlist. Phrases may be separated by spaces, dots or dashes, as anywhere
+//! else. A phrase without a port decodes to port 65535, the "no port"
+//! marker.
+//!
+//! ```compile_fail
+//! const BAD: std::net::SocketAddr = four_word_networking_macros::addr!("ocean qqqq zzzz xxxx");
+//! ```
+
+use four_word_networking::FourWordAdaptiveEncoder;
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+use std::net::SocketAddr;
+
+/// Expands to the phrase for an address, with or without a port, as a
+/// `&'static str` literal.
+///
+/// ```rust
+/// let words: &'static str = four_word_networking_macros::words!("[::1]:443");
+/// assert_eq!(words.split(' ').count(), 6);
+/// ```
+#[proc_macro]
+pub fn words(input: TokenStream) -> TokenStream {
+    expand(input, |text| {
+        let words = encoder()?.encode(text).map_err(|e| e.to_string())?;
+        Ok(TokenTree::Literal(Literal::string(&words)).into())
+    })
+}
+
+/// Expands to a `std::net::SocketAddr` expression for a phrase, usable in
+/// a `const`.
+///
+/// ```rust
+/// const NODE: std::net::SocketAddr = four_word_networking_macros::addr!("zoo-acid-tulsa-angry");
+/// assert_eq!(NODE.ip().to_string(), "10.0.0.1");
+/// ```
+#[proc_macro]
+pub fn addr(input: TokenStream) -> TokenStream {
+    expand(input, |text| {
+        let addr = encoder()?
+            .decode_socket_addr(text)
+            .map_err(|e| e.to_string())?;
+        Ok(socket_addr_tokens(addr))
+    })
+}
+
+/// Runs `body` on the string literal of `input`, turning any failure into a
+/// `compile_error!` at the literal
+fn expand(
+    input: TokenStream,
+    body: impl FnOnce(&str) -> Result<TokenStream, String>,
+) -> TokenStream {
+    let (text, span) = match string_literal(input) {
+        Ok(literal) => literal,
+        Err((message, span)) => return compile_error(&message, span),
+    };
+    body(&text).unwrap_or_else(|message| compile_error(&message, span))
+}
+
+/// The contents and span of the single string literal making up `input`
+fn string_literal(input: TokenStream) -> Result<(String, Span), (String, Span)> {
+    let mut tokens = input.into_iter();
+    let (Some(token), None) = (tokens.next(), tokens.next()) else {
+        return Err((
+            "expected a single string literal".to_string(),
+            Span::call_site(),
+        ));
+    };
+    match token {
+        // Literals passed through macro_rules! arrive wrapped in an invisible group
+        TokenTree::Group(group) if group.delimiter() == Delimiter::None => {
+            string_literal(group.stream())
+        }
+        TokenTree::Literal(literal) => {
+            let span = literal.span();
+            let source = literal.to_string();
+            match source.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+                Some(text) if !text.contains('\\') => Ok((text.to_string(), span)),
+                Some(_) => Err(("escapes are not supported here".to_string(), span)),
+                None => Err(("expected a string literal".to_string(), span)),
+            }
+        }
+        other => Err(("expected a string literal".to_string(), other.span())),
+    }
+}
+
+/// The encoder both macros use
+fn encoder() -> Result<FourWordAdaptiveEncoder, String> {
+    FourWordAdaptiveEncoder::new().map_err(|e| e.to_string())
+}
+
+/// A `const`-compatible expression constructing `addr`
+fn socket_addr_tokens(addr: SocketAddr) -> TokenStream {
+    let source = match addr {
+        SocketAddr::V4(v4) => {
+            let [a, b, c, d] = v4.ip().octets();
+            format!(
+                "::std::net::SocketAddr::V4(::std::net::SocketAddrV4::new(\
+                 ::std::net::Ipv4Addr::new({a}u8, {b}u8, {c}u8, {d}u8), {}u16))",
+                v4.port()
+            )
+        }
+        SocketAddr::V6(v6) => {
+            let segments = v6
+                .ip()
+                .segments()
+                .map(|segment| format!("{segment:#06x}u16"))
+                .join(", ");
+            format!(
+                "::std::net::SocketAddr::V6(::std::net::SocketAddrV6::new(\
+                 ::std::net::Ipv6Addr::new({segments}), {}u16, {}u32, {}u32))",
+                v6.port(),
+                v6.flowinfo(),
+                v6.scope_id()
+            )
+        }
+    };
+    source
+        .parse()
+        .expect("generated SocketAddr expression is valid Rust")
+}
+
+/// `compile_error!(message)` pointing at `span`
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let mut literal = Literal::string(message);
+    literal.set_span(span);
+    let mut group = Group::new(Delimiter::Parenthesis, TokenTree::Literal(literal).into());
+    group.set_span(span);
+    let mut bang = Punct::new('!', Spacing::Alone);
+    bang.set_span(span);
+    [
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct(bang),
+        TokenTree::Group(group),
+    ]
+    .into_iter()
+    .collect()
+}
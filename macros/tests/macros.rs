@@ -0,0 +1,40 @@
+//! The macros agree with the runtime encoder.
+
+use four_word_networking::FourWordAdaptiveEncoder;
+use four_word_networking_macros::{addr, words};
+use std::net::SocketAddr;
+
+const IPV4: SocketAddr = addr!("book abstract junk restriction");
+const IPV6: SocketAddr = addr!("aim.tulsa.tulsa.abstract.astronomy.adolescent");
+const NO_PORT: SocketAddr = addr!("zoo-acid-tulsa-angry");
+const IPV4_WORDS: &str = words!("192.168.1.1:443");
+
+macro_rules! bootstrap {
+    ($words:literal) => {
+        addr!($words)
+    };
+}
+
+#[test]
+fn test_words_matches_runtime_encoding() {
+    let encoder = FourWordAdaptiveEncoder::new().unwrap();
+    assert_eq!(IPV4_WORDS, encoder.encode("192.168.1.1:443").unwrap());
+    assert_eq!(
+        words!("[2001:db8::1]:8080"),
+        encoder.encode("[2001:db8::1]:8080").unwrap()
+    );
+    assert_eq!(words!("10.0.0.1"), encoder.encode("10.0.0.1").unwrap());
+}
+
+#[test]
+fn test_addr_matches_runtime_decoding() {
+    let encoder = FourWordAdaptiveEncoder::new().unwrap();
+    assert_eq!(IPV4, "192.168.1.1:443".parse::<SocketAddr>().unwrap());
+    assert_eq!(IPV6, "[2001:db8::1]:8080".parse::<SocketAddr>().unwrap());
+    assert_eq!(
+        NO_PORT,
+        encoder.decode_socket_addr("zoo acid tulsa angry").unwrap()
+    );
+    assert_eq!(NO_PORT.port(), 65535);
+    assert_eq!(bootstrap!("book abstract junk restriction"), IPV4);
+}
@@ -0,0 +1,287 @@
+//! Entropy audit of a deployment's phrases.
+//!
+//! Phrases inherit the structure of the addresses behind them. A fleet in a
+//! single /24, or IPv6 hosts sharing a /48, produce phrases that repeat the
+//! same words at the same positions, so an observer who knows the deployment
+//! can guess most of a phrase. [`entropy_report`] measures this from a sample of real phrases
+//! and recommends what to do about it; the report serializes to JSON for
+//! security review:
+//!
+//! ```rust
+//! use four_word_networking::audit::{Recommendation, entropy_report};
+//!
+//! let phrases: Vec<String> = (1..=50)
+//!     .map(|host| four_word_networking::encode(([10, 0, 0, host], 443)))
+//!     .collect::<Result<_, _>>()?;
+//! let report = entropy_report(&phrases);
+//! assert!(report.constant_positions.len() >= 2);
+//! assert!(matches!(report.recommendations[0], Recommendation::ContextProfile { .. }));
+//! # #[cfg(feature = "serde")]
+//! println!("{}", report.to_json()?);
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::dictionary4k::DICTIONARY_SIZE;
+#[cfg(feature = "serde")]
+use crate::error::Result;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Samples below which the report asks for more instead of judging skew
+pub const MIN_SAMPLES: usize = 32;
+
+/// Share of samples one word may take at a position before it counts as skewed
+pub const SKEW_THRESHOLD: f64 = 0.5;
+
+/// Word distribution at one position of the sampled phrases
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PositionStats {
+    /// Zero-based word position
+    pub position: usize,
+    /// Number of phrases long enough to have this position
+    pub samples: usize,
+    /// Number of different words seen here
+    pub distinct_words: usize,
+    /// Shannon entropy of the observed words, in bits
+    pub entropy_bits: f64,
+    /// Highest entropy these samples could show: log2 of `samples`, capped at 12 bits
+    pub max_entropy_bits: f64,
+    /// Most frequent word, ties broken alphabetically
+    pub most_common: String,
+    /// Fraction of samples using `most_common`
+    pub most_common_share: f64,
+}
+
+/// Advice derived from the measured skew
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "kind", rename_all = "snake_case"))]
+pub enum Recommendation {
+    /// Too few samples to judge; collect at least `needed` phrases
+    MoreSamples {
+        /// Minimum sample count
+        needed: usize,
+    },
+    /// Every phrase has the same word at these positions, so they identify
+    /// the deployment rather than the host; agree on them out of band as a
+    /// context profile and exchange only the remaining words
+    ContextProfile {
+        /// Zero-based positions with a single word across all samples
+        constant_positions: Vec<usize>,
+    },
+    /// One word dominates these positions without being constant, so phrases
+    /// are guessable; salt the encoding per deployment to spread them out
+    Salt {
+        /// Zero-based positions above [`SKEW_THRESHOLD`]
+        skewed_positions: Vec<usize>,
+    },
+}
+
+/// Result of [`entropy_report`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct EntropyReport {
+    /// Number of phrases analyzed (empty ones are ignored)
+    pub samples: usize,
+    /// Number of different phrases among them
+    pub distinct_phrases: usize,
+    /// Per-position statistics, position 0 first
+    pub positions: Vec<PositionStats>,
+    /// Sum of the per-position entropies; an upper bound on the phrase entropy
+    pub total_entropy_bits: f64,
+    /// Positions at which every phrase has the same word
+    pub constant_positions: Vec<usize>,
+    /// What to do about the skew, most important first; empty if none found
+    pub recommendations: Vec<Recommendation>,
+}
+
+impl EntropyReport {
+    #[cfg(feature = "serde")]
+    /// Serializes the report as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Measures how evenly `samples` (phrases in any separator or case) use the wordlist
+pub fn entropy_report<S: AsRef<str>>(samples: &[S]) -> EntropyReport {
+    let phrases: Vec<Vec<String>> = samples
+        .iter()
+        .map(|sample| {
+            sample
+                .as_ref()
+                .split(|c: char| c.is_whitespace() || c == '.' || c == '-')
+                .filter(|word| !word.is_empty())
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>()
+        })
+        .filter(|words| !words.is_empty())
+        .collect();
+    let distinct_phrases = phrases.iter().collect::<HashSet<_>>().len();
+    let longest = phrases.iter().map(Vec::len).max().unwrap_or(0);
+
+    let positions: Vec<PositionStats> = (0..longest)
+        .map(|position| {
+            position_stats(
+                position,
+                phrases.iter().filter_map(|words| words.get(position)),
+            )
+        })
+        .collect();
+    let total_entropy_bits = positions.iter().map(|stats| stats.entropy_bits).sum();
+    let constant_positions: Vec<usize> = positions
+        .iter()
+        .filter(|stats| stats.samples == phrases.len() && stats.distinct_words == 1)
+        .map(|stats| stats.position)
+        .collect();
+
+    let mut recommendations = Vec::new();
+    if phrases.len() < MIN_SAMPLES {
+        recommendations.push(Recommendation::MoreSamples {
+            needed: MIN_SAMPLES,
+        });
+    } else {
+        if !constant_positions.is_empty() {
+            recommendations.push(Recommendation::ContextProfile {
+                constant_positions: constant_positions.clone(),
+            });
+        }
+        let skewed_positions: Vec<usize> = positions
+            .iter()
+            .filter(|stats| stats.distinct_words > 1 && stats.most_common_share > SKEW_THRESHOLD)
+            .map(|stats| stats.position)
+            .collect();
+        if !skewed_positions.is_empty() {
+            recommendations.push(Recommendation::Salt { skewed_positions });
+        }
+    }
+
+    EntropyReport {
+        samples: phrases.len(),
+        distinct_phrases,
+        positions,
+        total_entropy_bits,
+        constant_positions,
+        recommendations,
+    }
+}
+
+/// Computes the distribution of `words` seen at `position`
+fn position_stats<'a>(position: usize, words: impl Iterator<Item = &'a String>) -> PositionStats {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for word in words {
+        *counts.entry(word.as_str()).or_default() += 1;
+    }
+    let samples: usize = counts.values().sum();
+    let total = samples as f64;
+
+    let entropy_bits = counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total;
+            p * (1.0 / p).log2()
+        })
+        .sum();
+    let (most_common, top) = counts
+        .iter()
+        .max_by(|(a, x), (b, y)| x.cmp(y).then_with(|| b.cmp(a)))
+        .map_or(("", 0), |(&word, &count)| (word, count));
+
+    PositionStats {
+        position,
+        samples,
+        distinct_words: counts.len(),
+        entropy_bits,
+        max_entropy_bits: total.min(DICTIONARY_SIZE as f64).log2().max(0.0),
+        most_common: most_common.to_string(),
+        most_common_share: if samples == 0 {
+            0.0
+        } else {
+            top as f64 / total
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_all(addresses: impl Iterator<Item = std::net::SocketAddr>) -> Vec<String> {
+        addresses.map(|addr| crate::encode(addr).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_shared_subnet_recommends_context_profile() {
+        let phrases = encode_all((1..=64).map(|host| ([192, 168, 1, host], 443).into()));
+        let report = entropy_report(&phrases);
+        assert_eq!(report.samples, 64);
+        assert_eq!(report.distinct_phrases, 64);
+        // Only the host byte varies, so most positions never change
+        assert!(report.constant_positions.len() >= 2, "{report:?}");
+        for &position in &report.constant_positions {
+            assert_eq!(report.positions[position].entropy_bits, 0.0);
+        }
+        assert_eq!(
+            report.recommendations,
+            [Recommendation::ContextProfile {
+                constant_positions: report.constant_positions.clone()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_spread_addresses_have_no_findings() {
+        // Multiplying by an odd constant scatters the addresses over the space
+        let phrases = encode_all((0u32..256).map(|n| {
+            let ip = n.wrapping_mul(0x9e37_79b9);
+            (std::net::Ipv4Addr::from(ip), (n as u16).wrapping_mul(257)).into()
+        }));
+        let report = entropy_report(&phrases);
+        assert!(report.constant_positions.is_empty());
+        assert!(report.recommendations.is_empty(), "{report:?}");
+        assert!(report.positions[0].entropy_bits > 6.0);
+    }
+
+    #[test]
+    fn test_skew_and_small_samples() {
+        let mut phrases = vec!["ocean thunder falcon star"; 40];
+        phrases.extend(["river thunder falcon star"; 10]);
+        let report = entropy_report(&phrases);
+        assert_eq!(report.constant_positions, [1, 2, 3]);
+        assert_eq!(
+            report.recommendations,
+            [
+                Recommendation::ContextProfile {
+                    constant_positions: vec![1, 2, 3]
+                },
+                Recommendation::Salt {
+                    skewed_positions: vec![0]
+                }
+            ]
+        );
+        assert!((report.positions[0].most_common_share - 0.8).abs() < 1e-9);
+
+        let report = entropy_report(&phrases[..3]);
+        assert_eq!(
+            report.recommendations,
+            [Recommendation::MoreSamples {
+                needed: MIN_SAMPLES
+            }]
+        );
+        assert!(entropy_report::<&str>(&[]).positions.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_report_json() {
+        let json = entropy_report(&["ocean thunder falcon star"])
+            .to_json()
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["samples"], 1);
+        assert_eq!(value["recommendations"][0]["kind"], "more_samples");
+    }
+}
@@ -0,0 +1,169 @@
+//! Batch encoding and decoding.
+//!
+//! Converting many addresses at once, such as when rewriting logs, shares a
+//! single encoder across the whole batch. With the `rayon` feature the
+//! `par_*` variants spread the batch over rayon's thread pool; they need a
+//! `Sync` wordlist and inputs, so the sequential methods keep the same
+//! bounds in every build. Results keep the order of the input either way,
+//! and one bad entry does not fail the rest.
+//!
+//! [`convert_batch`](FourWordAdaptiveEncoder::convert_batch) takes a mixed
+//! list, such as the lines of a peer list, and runs
+//! [`convert_entry`](FourWordAdaptiveEncoder::convert_entry) on each line.
+
+#![forbid(unsafe_code)]
+
+use crate::conversion::Conversion;
+use crate::error::Result;
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::wordlist::Wordlist;
+use std::net::SocketAddr;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+impl<W: Wordlist> FourWordAdaptiveEncoder<W> {
+    /// Encodes every address in `addrs`, in order
+    pub fn encode_batch(&self, addrs: &[SocketAddr]) -> Vec<Result<String>> {
+        addrs.iter().map(|&addr| self.encode_addr(addr)).collect()
+    }
+
+    /// Decodes every phrase in `phrases`, in order
+    pub fn decode_batch<S: AsRef<str>>(&self, phrases: &[S]) -> Vec<Result<SocketAddr>> {
+        phrases
+            .iter()
+            .map(|words| self.decode_socket_addr(words.as_ref()))
+            .collect()
+    }
+
+    /// Encodes each address and decodes each phrase in `entries`, in order
+    pub fn convert_batch<S: AsRef<str>>(&self, entries: &[S]) -> Vec<Result<Conversion>> {
+        entries
+            .iter()
+            .map(|entry| self.convert_entry(entry.as_ref()))
+            .collect()
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<W: Wordlist + Sync> FourWordAdaptiveEncoder<W> {
+    /// [`encode_batch`](Self::encode_batch) on rayon's thread pool
+    pub fn par_encode_batch(&self, addrs: &[SocketAddr]) -> Vec<Result<String>> {
+        addrs
+            .par_iter()
+            .map(|&addr| self.encode_addr(addr))
+            .collect()
+    }
+
+    /// [`decode_batch`](Self::decode_batch) on rayon's thread pool
+    pub fn par_decode_batch<S: AsRef<str> + Sync>(&self, phrases: &[S]) -> Vec<Result<SocketAddr>> {
+        phrases
+            .par_iter()
+            .map(|words| self.decode_socket_addr(words.as_ref()))
+            .collect()
+    }
+
+    /// [`convert_batch`](Self::convert_batch) on rayon's thread pool
+    pub fn par_convert_batch<S: AsRef<str> + Sync>(
+        &self,
+        entries: &[S],
+    ) -> Vec<Result<Conversion>> {
+        entries
+            .par_iter()
+            .map(|entry| self.convert_entry(entry.as_ref()))
+            .collect()
+    }
+}
+
+impl<W: Wordlist> FourWordAdaptiveEncoder<W> {
+    /// Encodes a parsed address into a space-separated phrase
+    fn encode_addr(&self, addr: SocketAddr) -> Result<String> {
+        let mut words = String::new();
+        self.encode_into(addr, &mut words)?;
+        Ok(words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_round_trip_preserves_order() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let addrs: Vec<SocketAddr> = (0..200u16)
+            .map(|n| {
+                if n % 2 == 0 {
+                    SocketAddr::from(([10, 0, (n >> 8) as u8, n as u8], n))
+                } else {
+                    SocketAddr::from(([0xfe80, 0, 0, 0, 0, 0, 0, n], n))
+                }
+            })
+            .collect();
+
+        let phrases: Vec<String> = encoder
+            .encode_batch(&addrs)
+            .into_iter()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(phrases[1], encoder.encode(&addrs[1].to_string()).unwrap());
+
+        let decoded: Vec<SocketAddr> = encoder
+            .decode_batch(&phrases)
+            .into_iter()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(decoded, addrs);
+
+        #[cfg(feature = "rayon")]
+        {
+            let parallel: Vec<String> = encoder
+                .par_encode_batch(&addrs)
+                .into_iter()
+                .collect::<Result<_>>()
+                .unwrap();
+            assert_eq!(parallel, phrases);
+            let decoded: Vec<SocketAddr> = encoder
+                .par_decode_batch(&phrases)
+                .into_iter()
+                .collect::<Result<_>>()
+                .unwrap();
+            assert_eq!(decoded, addrs);
+        }
+    }
+
+    #[test]
+    fn test_batch_isolates_failures() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let results = encoder.decode_batch(&["ocean thunder falcon", "not real words here"]);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|result| result.is_err()));
+        assert!(encoder.decode_batch::<&str>(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_convert_batch_handles_mixed_entries() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let words = encoder.encode("192.168.1.1:443").unwrap();
+        let entries = [
+            "192.168.1.1:443".to_string(),
+            format!("  {words}  "),
+            "fe80::1".to_string(),
+            "192.168.1.300".to_string(),
+            "ocean thunder".to_string(),
+        ];
+        let results: Vec<Result<String>> = encoder
+            .convert_batch(&entries)
+            .into_iter()
+            .map(|result| result.map(|conversion| conversion.to_string()))
+            .collect();
+        assert_eq!(results[0].as_ref().unwrap(), &words);
+        assert_eq!(results[1].as_ref().unwrap(), "192.168.1.1:443");
+        assert_eq!(
+            encoder.decode(results[2].as_ref().unwrap()).unwrap(),
+            "fe80::1"
+        );
+        assert!(results[3].is_err());
+        assert!(results[4].is_err());
+    }
+}
@@ -11,10 +11,51 @@
 //!   4wn a abaddon amphipoda arian  # Decodes to exact IPv4:port
 //!   4wn [2001:db8::1]:443      # Encodes to 8 or 12 words with visual distinction
 //!   4wn ocean thunder falcon star book april wing moon    # Decodes to IPv6
+//!   4wn --language es 10.0.0.1:80  # Encodes with the Spanish wordlist (lang-es feature)
+//!   4wn --nat64-prefix 2001:db8:64::/96 [2001:db8:64::a00:1]:80  # 6 words via the embedded IPv4
+//!   4wn --provider-prefixes prefixes.txt [2a00:1450:4001::1]:443  # 6 words via a prefix index
+//!   4wn provider-table < delegated-ripencc-extended-latest > prefixes.txt
+//!   4wn daemon                  # Serves encode/decode requests on a UNIX socket
+//!   4wn audit < phrases.txt     # JSON entropy report for a deployment's phrases
+//!   4wn audit --dictionary      # JSON quality audit of the wordlist (homophones, edit distance, ...)
+//!   4wn gen-vectors > vectors.json  # Golden input/words/output vectors for other implementations
+//!   4wn repl                    # Interactive entry with word completion (repl feature)
+//!   4wn explain [fe80::1]:443   # Category, bit layout and the bits each word carries
+//!   4wn verify --ipv4 10.0.0.0/8  # Round-trips a range and random samples on every core
+//!   4wn serve                   # HTTP/JSON API: POST /encode, POST /decode, GET /health, GET /metrics
+//!   4wn dial ocean thunder falcon star  # TCP connect to the phrase's endpoint, with latency
+//!   4wn --qr 192.168.1.1:443    # Words plus a QR code of them (qr feature)
+//!   4wn --emoji 192.168.1.1:443 # Two emoji per word; emoji rows decode like phrases
+//!   4wn --phonetic 10.0.0.1:80  # NATO spelling and spoken port digits, for dictation
+//!   4wn --numeric 10.0.0.1:80   # Dictionary indices instead of words; indices decode like words
+//!   4wn example.com:443         # A phrase per A/AAAA record of a hostname (resolve feature)
+//!   tail -f app.log | 4wn filter   # Rewrites addresses in text as phrases (--reverse: back)
 
-use clap::Parser;
-use four_word_networking::{FourWordAdaptiveEncoder, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use four_word_networking::cidr::Ipv4Cidr;
+use four_word_networking::conversion::{Conversion, Direction};
+use four_word_networking::dial::ProbeOutcome;
+use four_word_networking::dictionary_audit::{AuditPolicy, audit_dictionary};
+use four_word_networking::emoji::is_emoji_row;
+use four_word_networking::filter::TextFilter;
+use four_word_networking::format::normalize_phrase;
+use four_word_networking::http::DEFAULT_LISTEN;
+use four_word_networking::phonetic::is_read_back;
+use four_word_networking::ports::{display_socket_addr, parse_socket_addr};
+use four_word_networking::profile::MAX_PROVIDER_PREFIXES;
+use four_word_networking::provider_prefix::parse_prefix_list;
+use four_word_networking::transport::Protocol;
+use four_word_networking::verify::{AddressSampler, ipv4_with_port};
+use four_word_networking::{
+    Alphabet, Dictionary4K, EncodingInspector, EncodingProfile, EphemeralEncoder, Formatter,
+    FourWordAdaptiveEncoder, FourWordError, HttpServer, KeyedEncoder, Language, Nat64Prefix,
+    PhraseKey, Result, VerifyReport,
+};
+use std::fs::File;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
 use std::process;
+use std::time::{Duration, SystemTime};
 
 #[derive(Parser)]
 #[command(
@@ -26,6 +67,9 @@ use std::process;
     version
 )]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Input to convert (IP:port or words)
     /// Can be a single string or multiple words
     input: Vec<String>,
@@ -37,6 +81,214 @@ struct Cli {
     /// Output format for scripting (minimal output)
     #[arg(short, long)]
     quiet: bool,
+
+    /// Wordlist language as an ISO 639-1 code (default: en).
+    /// When decoding without this flag, the language is detected from the words
+    #[arg(short, long)]
+    language: Option<Language>,
+
+    /// Custom wordlist file (4,096 words, one per line) used instead of a built-in language.
+    /// The list must pass the dictionary audit (see `4wn audit --dictionary`)
+    #[arg(long, conflicts_with = "language")]
+    wordlist: Option<PathBuf>,
+
+    /// Accept a --wordlist that fails the dictionary audit
+    #[arg(long, requires = "wordlist")]
+    skip_wordlist_audit: bool,
+
+    /// NAT64 translation prefix (e.g. 64:ff9b:1::/48); addresses under it encode
+    /// via their embedded IPv4. Repeatable; both sides must list the same prefixes in order
+    #[arg(long = "nat64-prefix", value_name = "PREFIX")]
+    nat64_prefixes: Vec<Nat64Prefix>,
+
+    /// Provider prefix list (one prefix per line, or RIR delegation stats); global
+    /// unicast addresses under a listed prefix encode via its index. Both sides
+    /// must use the same file
+    #[arg(long = "provider-prefixes", value_name = "FILE")]
+    provider_prefixes: Option<PathBuf>,
+
+    /// Show well-known ports by service name when decoding (e.g. :https instead of :443).
+    /// Service names are always accepted when encoding
+    #[arg(long)]
+    service_names: bool,
+
+    /// Shared secret file; phrases are keyed with it so only holders of the same
+    /// file can read the address (surrounding whitespace is ignored)
+    #[arg(long = "key-file", value_name = "FILE")]
+    key_file: Option<PathBuf>,
+
+    /// With --key-file, rotate phrases every MINUTES; a phrase decodes until the
+    /// end of the window after the one it was made in, then reports it has expired
+    #[arg(long, value_name = "MINUTES", requires = "key_file")]
+    rotate: Option<u64>,
+
+    /// Convert one address or phrase per line from FILE (stdin if FILE is omitted
+    /// or -), in input order. A failed line prints as an empty line and is reported
+    /// on stderr; the run continues and exits with status 1
+    #[arg(
+        long,
+        value_name = "FILE",
+        num_args = 0..=1,
+        default_missing_value = "-",
+        conflicts_with_all = ["input", "key_file"]
+    )]
+    batch: Option<PathBuf>,
+
+    /// Output format. json and csv give the input, words, address, port, category
+    /// and whether the phrase is lossless for each entry, and report failed
+    /// entries in place
+    #[arg(long, value_enum, default_value_t = Format::Plain, conflicts_with = "key_file")]
+    format: Format,
+
+    /// Write phrases as emoji, two per word, instead of words (an address or a
+    /// phrase converts to emoji). Emoji input is always decoded
+    #[arg(long, conflicts_with_all = ["batch", "key_file", "format"])]
+    emoji: bool,
+
+    /// Spell each word of the phrase in the NATO alphabet and say the port's
+    /// digits, for reading an address out. Read-backs are always decoded
+    #[arg(long, conflicts_with_all = ["batch", "key_file", "format", "emoji"])]
+    phonetic: bool,
+
+    /// Write each word as its dictionary index, joined by '-', for keyboards
+    /// that cannot easily type the words. Indices, or a mix of words and
+    /// indices, are always decoded
+    #[arg(long, conflicts_with_all = ["batch", "key_file", "format", "emoji", "phonetic"])]
+    numeric: bool,
+
+    /// Also print a QR code of the phrase when encoding, for scanning with
+    /// another device
+    #[cfg(feature = "qr")]
+    #[arg(long, conflicts_with_all = ["batch", "key_file"])]
+    qr: bool,
+}
+
+/// Output format for conversions
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// The converted words or address only
+    Plain,
+    /// JSON: one object, or an array of objects with --batch
+    Json,
+    /// CSV with a header row
+    Csv,
+}
+
+/// Columns of `--format csv`
+const CSV_HEADER: &str = "line,input,direction,words,ip,port,category,lossless,error";
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a persistent encoder daemon on a UNIX socket
+    #[cfg(unix)]
+    Daemon {
+        /// Socket path (defaults to $XDG_RUNTIME_DIR/4wn.sock)
+        #[arg(long)]
+        socket: Option<PathBuf>,
+    },
+    /// Print a JSON entropy report for phrases read from stdin, one per line
+    Audit {
+        /// Audit the wordlist instead (homophones, confusable and offensive
+        /// words, minimum edit distance); honours --language and --wordlist
+        #[arg(long)]
+        dictionary: bool,
+    },
+    /// Print the published test vectors as JSON: inputs covering every IPv4/IPv6
+    /// category and port edge case with the words and output they must give
+    GenVectors,
+    /// Build a provider prefix list for --provider-prefixes from a prefix list or
+    /// RIR delegation stats on stdin (deduplicated, at most 256 entries)
+    ProviderTable,
+    /// Show how an address (or the address a phrase decodes to) maps to words:
+    /// the category, the bit layout, the bits each word carries and whether the
+    /// phrase is lossless
+    Explain {
+        /// Address or phrase
+        #[arg(required = true)]
+        input: Vec<String>,
+    },
+    /// Interactive mode: Tab completes words, a preview line decodes as you type
+    /// and Up/Down recall earlier entries (Ctrl-D or Ctrl-C to quit)
+    #[cfg(feature = "repl")]
+    Repl,
+    /// Encode and decode IPv4 ranges and random IPv4 and IPv6 samples on every
+    /// core, and exit with status 1 if any address fails to round-trip
+    Verify(VerifyArgs),
+    /// Serve an HTTP/JSON API with the configured encoder: POST /encode
+    /// {"address": ...}, POST /decode {"words": ...}, GET /health and
+    /// GET /metrics (Prometheus)
+    Serve {
+        /// Address to listen on; keep it on loopback, there is no authentication
+        #[arg(long, value_name = "ADDR", default_value = DEFAULT_LISTEN)]
+        listen: String,
+    },
+    /// Decode a phrase (or take an address) and probe the endpoint, printing the
+    /// latency; exits with status 1 unless it answers. A trailing tcp/udp/quic
+    /// keyword picks the protocol
+    Dial {
+        /// Phrase or address
+        #[arg(required = true)]
+        input: Vec<String>,
+
+        /// Protocol to probe with (default: the phrase's tag, else tcp). UDP
+        /// sends an empty datagram; no reply means open or filtered
+        #[arg(long, short = 'p')]
+        protocol: Option<Protocol>,
+
+        /// Milliseconds to wait for each answer
+        #[arg(long, value_name = "MS", default_value_t = 3000)]
+        timeout: u64,
+
+        /// Number of probes, one second apart
+        #[arg(long, short = 'c', default_value_t = 1)]
+        count: u32,
+    },
+    /// Copy stdin to stdout line by line, rewriting IPv4 and IPv6 addresses
+    /// (with or without ports) as phrases joined by '-'
+    Filter {
+        /// Rewrite joined phrases back to addresses instead
+        #[arg(long)]
+        reverse: bool,
+    },
+}
+
+/// Options of `4wn verify`
+#[derive(Args)]
+struct VerifyArgs {
+    /// IPv4 range to check exhaustively (e.g. 10.0.0.0/8), with ports derived
+    /// from the seed. Repeatable
+    #[arg(long, value_name = "CIDR")]
+    ipv4: Vec<Ipv4Cidr>,
+
+    /// Random IPv4 addresses and ports to check
+    #[arg(long, value_name = "N", default_value_t = 1_000_000)]
+    ipv4_samples: u64,
+
+    /// Random IPv6 addresses and ports to check, spread over every category
+    #[arg(long, value_name = "N", default_value_t = 1_000_000)]
+    ipv6_samples: u64,
+
+    /// Seed for the samples and range ports; rerun with the printed seed to
+    /// repeat a run (default: from the clock)
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Worker threads (default: one per core)
+    #[arg(long)]
+    threads: Option<usize>,
+}
+
+/// Addresses checked per unit of work in `4wn verify`
+const VERIFY_CHUNK: u64 = 1 << 16;
+
+/// A unit of work in `4wn verify`
+enum VerifyJob {
+    /// Addresses `start..=end`
+    Ipv4Range { start: u32, end: u32 },
+    /// `count` IPv4 samples from a sampler seeded with `seed`
+    Ipv4Samples { seed: u64, count: u64 },
+    /// `count` IPv6 samples from a sampler seeded with `seed`
+    Ipv6Samples { seed: u64, count: u64 },
 }
 
 fn main() {
@@ -49,7 +301,112 @@ fn main() {
 }
 
 fn run(cli: Cli) -> Result<()> {
-    let encoder = FourWordAdaptiveEncoder::new()?;
+    // Explained with the encoder configured below
+    let mut explain = None;
+    let mut verify = None;
+    let mut serve = None;
+    let mut dial = None;
+    let mut filter = None;
+    let mut audit_wordlist = false;
+    match cli.command {
+        None => {}
+        Some(Command::Explain { input }) => explain = Some(input.join(" ")),
+        Some(Command::Verify(args)) => verify = Some(args),
+        Some(Command::Serve { listen }) => serve = Some(listen),
+        Some(Command::Dial {
+            input,
+            protocol,
+            timeout,
+            count,
+        }) => dial = Some((input, protocol, Duration::from_millis(timeout), count)),
+        Some(Command::Filter { reverse }) => filter = Some(reverse),
+        #[cfg(unix)]
+        Some(Command::Daemon { socket }) => return run_daemon(socket, cli.quiet),
+        Some(Command::Audit { dictionary: true }) => audit_wordlist = true,
+        Some(Command::Audit { dictionary: false }) => return run_audit(),
+        Some(Command::GenVectors) => {
+            println!(
+                "{}",
+                four_word_networking::test_vectors::test_vectors()?.to_json()?
+            );
+            return Ok(());
+        }
+        Some(Command::ProviderTable) => return run_provider_table(cli.quiet),
+        #[cfg(feature = "repl")]
+        Some(Command::Repl) => {
+            return four_word_networking::repl::Repl::new(FourWordAdaptiveEncoder::new()?)
+                .run(&console::Term::stdout());
+        }
+    }
+
+    let encoder = match &cli.wordlist {
+        Some(path) => {
+            let dictionary = Dictionary4K::from_reader(File::open(path)?)?;
+            if !cli.quiet {
+                eprintln!("Wordlist fingerprint: {}", dictionary.fingerprint_hex());
+            }
+            // Reporting on a list is how its problems are found, so it is not gated
+            if !cli.skip_wordlist_audit && !audit_wordlist {
+                audit_dictionary(&dictionary).check(&AuditPolicy::default())?;
+            }
+            FourWordAdaptiveEncoder::with_wordlist(dictionary)?
+        }
+        None => FourWordAdaptiveEncoder::with_language(cli.language.unwrap_or_default())?,
+    };
+    let mut profile = EncodingProfile::new();
+    for prefix in &cli.nat64_prefixes {
+        profile.add_nat64_prefix(*prefix)?;
+    }
+    if let Some(path) = &cli.provider_prefixes {
+        for prefix in parse_prefix_list(&std::fs::read_to_string(path)?)? {
+            profile.add_provider_prefix(prefix)?;
+        }
+    }
+    let encoder = encoder.with_profile(profile);
+
+    if audit_wordlist {
+        println!("{}", audit_dictionary(encoder.wordlist()).to_json()?);
+        return Ok(());
+    }
+
+    if let Some(input) = &explain {
+        let inspection = EncodingInspector::new(encoder).inspect(input)?;
+        match cli.format {
+            Format::Plain => println!("{inspection}"),
+            Format::Json => println!("{}", serde_json::to_string_pretty(&inspection)?),
+            Format::Csv => {
+                return Err(FourWordError::unsupported(
+                    "explain as CSV",
+                    "explain prints plain text or JSON",
+                ));
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(args) = &verify {
+        return run_verify(&encoder, args, cli.quiet);
+    }
+
+    if let Some(reverse) = filter {
+        return run_filter(TextFilter::new(encoder), reverse);
+    }
+
+    if let Some((input, protocol, timeout, count)) = dial {
+        return run_dial(&encoder, input, protocol, timeout, count, cli.quiet);
+    }
+
+    if let Some(listen) = &serve {
+        let listener = HttpServer::bind(listen.as_str())?;
+        if !cli.quiet {
+            eprintln!("4wn serving HTTP on {}", listener.local_addr()?);
+        }
+        return HttpServer::new(encoder).serve(listener);
+    }
+
+    if let Some(path) = &cli.batch {
+        return run_batch(&encoder, path, cli.format, cli.service_names);
+    }
 
     // Join input arguments
     let input = if cli.input.len() == 1 {
@@ -60,18 +417,525 @@ fn run(cli: Cli) -> Result<()> {
         cli.input.join(" ")
     };
 
+    if let Some(path) = &cli.key_file {
+        let key = PhraseKey::from_secret(std::fs::read(path)?.trim_ascii())?;
+        let keyed = KeyedEncoder::new(encoder, key);
+        return match cli.rotate {
+            Some(minutes) => {
+                let period = Duration::from_secs(minutes.saturating_mul(60));
+                let ephemeral = EphemeralEncoder::new(keyed, period)?;
+                convert_keyed(
+                    &input,
+                    cli.service_names,
+                    |addr| ephemeral.encode_at(addr, SystemTime::now()),
+                    |words| ephemeral.decode_at(words, SystemTime::now()),
+                )
+            }
+            None => convert_keyed(
+                &input,
+                cli.service_names,
+                |addr| keyed.encode(addr),
+                |words| keyed.decode(words),
+            ),
+        };
+    }
+
+    // Names that are neither an address nor a phrase are looked up
+    #[cfg(feature = "resolve")]
+    if four_word_networking::resolve::parse_host(&input).is_ok() && encoder.decode(&input).is_err()
+    {
+        return run_resolve(&encoder, &input, cli.format, cli.quiet);
+    }
+
+    if cli.format != Format::Plain {
+        let result = encoder.convert_entry(&input);
+        match cli.format {
+            Format::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(&json_record(1, &input, &result)?)?
+            ),
+            _ => println!("{CSV_HEADER}\n{}", csv_record(1, &input, &result)),
+        }
+        return result.map(drop);
+    }
+
+    if is_emoji_row(&input) || is_read_back(&input) {
+        let addr = Formatter::new().parse(&encoder, &input)?;
+        if addr.port() == 65535 {
+            println!("{}", addr.ip());
+        } else if cli.service_names {
+            println!("{}", display_socket_addr(addr));
+        } else {
+            println!("{addr}");
+        }
+        return Ok(());
+    }
+    if cli.emoji {
+        let addr = encoder.convert_entry(&input)?.socket_addr();
+        let formatter = Formatter::new().with_alphabet(Alphabet::Emoji);
+        println!("{}", formatter.format(&encoder, addr)?);
+        return Ok(());
+    }
+    if cli.phonetic {
+        let addr = encoder.convert_entry(&input)?.socket_addr();
+        let formatter = Formatter::new().with_alphabet(Alphabet::Phonetic);
+        println!("{}", formatter.format(&encoder, addr)?);
+        return Ok(());
+    }
+    if cli.numeric {
+        let addr = encoder.convert_entry(&input)?.socket_addr();
+        let formatter = Formatter::new().with_alphabet(Alphabet::Numeric);
+        println!("{}", formatter.format(&encoder, addr)?);
+        return Ok(());
+    }
+
     // Detect input type based on content
     if looks_like_words(&input) {
         // Input is words, decode to IP:port
-        decode_words(&encoder, &input, cli.verbose, cli.quiet)
+        let detect_language = cli.language.is_none() && cli.wordlist.is_none();
+        decode_words(
+            &encoder,
+            &input,
+            detect_language,
+            cli.service_names,
+            cli.verbose,
+            cli.quiet,
+        )
     } else {
         // Input is IP:port, encode to words
-        encode_address(&encoder, &input, cli.verbose, cli.quiet)
+        encode_address(&encoder, &input, cli.verbose, cli.quiet)?;
+        #[cfg(feature = "qr")]
+        if cli.qr {
+            let qr = four_word_networking::qr::PhraseQr::new(&encoder.encode(&input)?)?;
+            println!("{}", qr.to_terminal());
+        }
+        Ok(())
+    }
+}
+
+/// Resolve a hostname and print a phrase for each of its A/AAAA records
+#[cfg(feature = "resolve")]
+fn run_resolve(
+    encoder: &FourWordAdaptiveEncoder,
+    input: &str,
+    format: Format,
+    quiet: bool,
+) -> Result<()> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let resolved = runtime.block_on(four_word_networking::resolve::resolve_and_encode_with(
+        encoder, input,
+    ))?;
+    let address = |addr: SocketAddr| match addr.port() {
+        65535 => addr.ip().to_string(),
+        _ => addr.to_string(),
+    };
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(&resolved)?),
+        Format::Csv => {
+            println!("family,address,words,lossless");
+            for entry in &resolved {
+                println!(
+                    "{},{},{},{}",
+                    entry.family,
+                    address(entry.addr),
+                    entry.words,
+                    entry.lossless
+                );
+            }
+        }
+        Format::Plain => {
+            for entry in &resolved {
+                if quiet {
+                    println!("{}", entry.words);
+                } else {
+                    let note = if entry.lossless { "" } else { "  (lossy)" };
+                    println!(
+                        "{:<4}  {:<41}  {}{note}",
+                        entry.family,
+                        address(entry.addr),
+                        entry.words
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print the entropy report for the phrases on stdin
+fn run_audit() -> Result<()> {
+    use std::io::BufRead;
+
+    let phrases = std::io::stdin()
+        .lock()
+        .lines()
+        .collect::<std::io::Result<Vec<_>>>()?;
+    let report = four_word_networking::audit::entropy_report(&phrases);
+    println!("{}", report.to_json()?);
+    Ok(())
+}
+
+/// Convert every line of `path` (stdin for `-`), reporting failed lines on stderr
+fn run_batch(
+    encoder: &FourWordAdaptiveEncoder,
+    path: &Path,
+    format: Format,
+    service_names: bool,
+) -> Result<()> {
+    use std::io::{BufWriter, Read, Write};
+
+    let text = if path == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin().lock().read_to_string(&mut text)?;
+        text
+    } else {
+        std::fs::read_to_string(path)?
+    };
+    let lines: Vec<&str> = text.lines().collect();
+    #[cfg(feature = "rayon")]
+    let results = encoder.par_convert_batch(&lines);
+    #[cfg(not(feature = "rayon"))]
+    let results = encoder.convert_batch(&lines);
+
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    let mut failed = 0;
+    let mut records = Vec::new();
+    if format == Format::Csv {
+        writeln!(out, "{CSV_HEADER}")?;
+    }
+    for (number, (line, result)) in lines.iter().zip(results).enumerate() {
+        if line.trim().is_empty() {
+            if format == Format::Plain {
+                writeln!(out)?;
+            }
+            continue;
+        }
+        failed += usize::from(result.is_err());
+        match format {
+            Format::Plain => match result {
+                Ok(conversion) => writeln!(out, "{}", plain_output(&conversion, service_names))?,
+                Err(e) => {
+                    eprintln!("line {}: {e}", number + 1);
+                    writeln!(out)?;
+                }
+            },
+            Format::Json => records.push(json_record(number + 1, line, &result)?),
+            Format::Csv => writeln!(out, "{}", csv_record(number + 1, line, &result))?,
+        }
+    }
+    if format == Format::Json {
+        serde_json::to_writer_pretty(&mut out, &records)?;
+        writeln!(out)?;
+    }
+    out.flush()?;
+
+    if failed > 0 {
+        return Err(FourWordError::unsupported(
+            "convert every line",
+            format!("{failed} of {} lines failed", lines.len()),
+        ));
+    }
+    Ok(())
+}
+
+/// The converted words, or the decoded address (by service name if asked)
+fn plain_output(conversion: &Conversion, service_names: bool) -> String {
+    if service_names && conversion.direction == Direction::Decode && conversion.port.is_some() {
+        display_socket_addr(conversion.socket_addr())
+    } else {
+        conversion.to_string()
+    }
+}
+
+/// JSON object for entry `line`: the conversion, or the input and its error
+fn json_record(line: usize, input: &str, result: &Result<Conversion>) -> Result<serde_json::Value> {
+    Ok(match result {
+        Ok(conversion) => {
+            let mut record = serde_json::to_value(conversion)?;
+            record["line"] = line.into();
+            record
+        }
+        Err(e) => {
+            serde_json::json!({ "line": line, "input": input.trim(), "error": e.to_string() })
+        }
+    })
+}
+
+/// CSV row for entry `line`, in [`CSV_HEADER`] order
+fn csv_record(line: usize, input: &str, result: &Result<Conversion>) -> String {
+    let fields = match result {
+        Ok(c) => [
+            c.input.clone(),
+            c.direction.to_string(),
+            c.words.clone(),
+            c.ip.to_string(),
+            c.port.map(|port| port.to_string()).unwrap_or_default(),
+            c.category.to_string(),
+            c.lossless.to_string(),
+            String::new(),
+        ],
+        Err(e) => [
+            input.trim().to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            e.to_string(),
+        ],
+    };
+    let mut row = line.to_string();
+    for field in fields {
+        row.push(',');
+        row.push_str(&csv_field(&field));
+    }
+    row
+}
+
+/// Quotes a CSV field if it holds a comma, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Print the provider prefix list compiled from stdin
+fn run_provider_table(quiet: bool) -> Result<()> {
+    use std::io::Read;
+
+    let mut text = String::new();
+    std::io::stdin().lock().read_to_string(&mut text)?;
+    let prefixes = parse_prefix_list(&text)?;
+    if prefixes.len() > MAX_PROVIDER_PREFIXES && !quiet {
+        eprintln!(
+            "Keeping the first {MAX_PROVIDER_PREFIXES} of {} prefixes",
+            prefixes.len()
+        );
+    }
+    for prefix in prefixes.iter().take(MAX_PROVIDER_PREFIXES) {
+        println!("{prefix}");
+    }
+    Ok(())
+}
+
+/// Round-trip the requested ranges and samples, printing progress and a summary
+fn run_verify(encoder: &FourWordAdaptiveEncoder, args: &VerifyArgs, quiet: bool) -> Result<()> {
+    use std::io::IsTerminal;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+    use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+    });
+    let threads = args
+        .threads
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+        .max(1);
+
+    let mut jobs = Vec::new();
+    for cidr in &args.ipv4 {
+        let first = u64::from(u32::from(cidr.network()));
+        let last = first + (1u64 << (32 - cidr.prefix_len())) - 1;
+        for start in (first..=last).step_by(VERIFY_CHUNK as usize) {
+            let end = (start + VERIFY_CHUNK - 1).min(last);
+            jobs.push(VerifyJob::Ipv4Range {
+                start: start as u32,
+                end: end as u32,
+            });
+        }
+    }
+    // Each chunk gets its own sampler so the workers share nothing
+    for (samples, ipv6) in [(args.ipv4_samples, false), (args.ipv6_samples, true)] {
+        for (chunk, start) in (0..samples).step_by(VERIFY_CHUNK as usize).enumerate() {
+            let seed = seed ^ ((chunk as u64) << 1 | u64::from(ipv6));
+            let count = VERIFY_CHUNK.min(samples - start);
+            jobs.push(match ipv6 {
+                false => VerifyJob::Ipv4Samples { seed, count },
+                true => VerifyJob::Ipv6Samples { seed, count },
+            });
+        }
+    }
+    let total: u64 = jobs
+        .iter()
+        .map(|job| match *job {
+            VerifyJob::Ipv4Range { start, end } => u64::from(end - start) + 1,
+            VerifyJob::Ipv4Samples { count, .. } | VerifyJob::Ipv6Samples { count, .. } => count,
+        })
+        .sum();
+    if !quiet {
+        eprintln!("Verifying {total} addresses on {threads} threads (seed {seed})");
+    }
+
+    let next_job = AtomicUsize::new(0);
+    let done = AtomicU64::new(0);
+    let report = Mutex::new(VerifyReport::default());
+    let started = Instant::now();
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    while let Some(job) = jobs.get(next_job.fetch_add(1, Ordering::Relaxed)) {
+                        let chunk = match *job {
+                            VerifyJob::Ipv4Range { start, end } => encoder
+                                .verify((start..=end).map(|ip| ipv4_with_port(ip.into(), seed))),
+                            VerifyJob::Ipv4Samples { seed, count } => {
+                                let mut sampler = AddressSampler::new(seed);
+                                encoder.verify((0..count).map(|_| sampler.ipv4()))
+                            }
+                            VerifyJob::Ipv6Samples { seed, count } => {
+                                let mut sampler = AddressSampler::new(seed);
+                                encoder.verify((0..count).map(|_| sampler.ipv6()))
+                            }
+                        };
+                        done.fetch_add(chunk.checked, Ordering::Relaxed);
+                        report
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .merge(chunk);
+                    }
+                })
+            })
+            .collect();
+
+        if !quiet && std::io::stderr().is_terminal() {
+            while !workers.iter().all(|worker| worker.is_finished()) {
+                let checked = done.load(Ordering::Relaxed);
+                eprint!(
+                    "\r{checked}/{total} ({:.1}%)",
+                    checked as f64 * 100.0 / total.max(1) as f64
+                );
+                std::thread::sleep(Duration::from_millis(200));
+            }
+            eprint!("\r\x1b[K");
+        }
+    });
+
+    let report = report.into_inner().unwrap_or_else(|e| e.into_inner());
+    if !quiet {
+        eprintln!("Finished in {:.1?}", started.elapsed());
+    }
+    println!("{report}");
+    for mismatch in &report.examples {
+        println!("  {mismatch}");
+    }
+    if report.is_ok() {
+        Ok(())
+    } else {
+        Err(FourWordError::SelfCheckFailed(format!(
+            "{} of {} round trips failed (seed {seed})",
+            report.failed, report.checked
+        )))
+    }
+}
+
+/// Probe the endpoint behind a phrase `count` times, printing each answer
+fn run_dial(
+    encoder: &FourWordAdaptiveEncoder,
+    mut input: Vec<String>,
+    protocol: Option<Protocol>,
+    timeout: Duration,
+    count: u32,
+    quiet: bool,
+) -> Result<()> {
+    use four_word_networking::dial::probe;
+
+    let tag = match input.last().map(|word| word.parse::<Protocol>()) {
+        Some(Ok(tag)) if input.len() > 1 => {
+            input.pop();
+            Some(tag)
+        }
+        _ => None,
+    };
+    let protocol = protocol.or(tag).unwrap_or(Protocol::Tcp);
+    let conversion = encoder.convert_entry(&input.join(" "))?;
+    let addr = conversion.socket_addr();
+    if !quiet {
+        println!("Dialing {addr} ({}) over {protocol}", conversion.words);
+    }
+
+    let mut answered = Vec::new();
+    let mut open = 0;
+    for attempt in 0..count.max(1) {
+        if attempt > 0 {
+            std::thread::sleep(Duration::from_secs(1));
+        }
+        match probe(addr, protocol, timeout) {
+            Ok(result) => {
+                println!("{result}");
+                if result.is_open() {
+                    open += 1;
+                }
+                if result.outcome != ProbeOutcome::NoReply {
+                    answered.push(result.elapsed.as_secs_f64() * 1000.0);
+                }
+            }
+            // No port or an unsupported protocol fails every attempt alike
+            Err(e @ FourWordError::Unsupported { .. }) => return Err(e),
+            Err(e) => println!("{protocol} {addr}: {e}"),
+        }
+    }
+    if count > 1 && !quiet {
+        print!("{count} probes, {open} open");
+        if !answered.is_empty() {
+            let min = answered.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = answered.iter().copied().fold(0.0, f64::max);
+            let avg = answered.iter().sum::<f64>() / answered.len() as f64;
+            print!(", min/avg/max {min:.1}/{avg:.1}/{max:.1} ms");
+        }
+        println!();
+    }
+    if open > 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!("{addr} did not answer over {protocol}")).into())
+    }
+}
+
+/// Rewrite addresses (or phrases, with `reverse`) in stdin, flushing each line
+fn run_filter(filter: TextFilter, reverse: bool) -> Result<()> {
+    use std::io::{BufRead, Write};
+
+    let mut input = std::io::stdin().lock();
+    let mut output = std::io::stdout().lock();
+    let mut line = Vec::new();
+    while input.read_until(b'\n', &mut line)? > 0 {
+        let text = String::from_utf8_lossy(&line);
+        let rewritten = if reverse {
+            filter.words_to_addresses(&text)
+        } else {
+            filter.addresses_to_words(&text)
+        };
+        output.write_all(rewritten.as_bytes())?;
+        output.flush()?;
+        line.clear();
+    }
+    Ok(())
+}
+
+/// Run the encoder daemon in the foreground
+#[cfg(unix)]
+fn run_daemon(socket: Option<PathBuf>, quiet: bool) -> Result<()> {
+    use four_word_networking::daemon::{Daemon, default_socket_path};
+
+    let path = socket.unwrap_or_else(default_socket_path);
+    let listener = Daemon::bind(&path)?;
+    if !quiet {
+        eprintln!("4wn daemon listening on {}", path.display());
     }
+    Daemon::new()?.serve(listener)
 }
 
 /// Check if input looks like words (contains dots, dashes, spaces, all alphabetic)
-fn looks_like_words(input: &str) -> bool {
+fn looks_like_words(raw: &str) -> bool {
+    let input = &*normalize_phrase(raw);
     // Handle space-separated words or separator-based words
     let segments: Vec<&str> = if input.contains(' ') && !input.contains('-') && !input.contains(':')
     {
@@ -94,10 +958,42 @@ fn looks_like_words(input: &str) -> bool {
         return false;
     }
 
-    // Check if all segments are alphabetic and meet minimum length requirement
-    segments
-        .iter()
-        .all(|segment| segment.len() >= 1 && segment.chars().all(|c| c.is_alphabetic()))
+    // Every segment is a word, or a word's dictionary index when the input is
+    // not dotted (dotted numbers are IPv4, even mistyped)
+    let dotted = raw.contains('.');
+    segments.iter().all(|segment| {
+        !segment.is_empty()
+            && (segment.chars().all(|c| c.is_alphabetic())
+                || (!dotted && segment.bytes().all(|b| b.is_ascii_digit())))
+    })
+}
+
+/// Encodes an address as a keyed phrase, or decodes anything else as one
+fn convert_keyed(
+    input: &str,
+    service_names: bool,
+    encode: impl Fn(SocketAddr) -> Result<String>,
+    decode: impl Fn(&str) -> Result<SocketAddr>,
+) -> Result<()> {
+    let addr = parse_socket_addr(input).or_else(|e| {
+        input
+            .parse::<IpAddr>()
+            .map(|ip| SocketAddr::new(ip, 65535))
+            .map_err(|_| e)
+    });
+    if let Ok(addr) = addr {
+        println!("{}", encode(addr)?);
+        return Ok(());
+    }
+    let addr = decode(input)?;
+    if addr.port() == 65535 {
+        println!("{}", addr.ip());
+    } else if service_names {
+        println!("{}", display_socket_addr(addr));
+    } else {
+        println!("{addr}");
+    }
+    Ok(())
 }
 
 /// Encode IP address to words
@@ -116,6 +1012,7 @@ fn encode_address(
         // Detailed output
         println!("Input: {address}");
         println!("Words: {words}");
+        println!("Dictionary: {}", describe_dictionary(encoder));
         println!("Encoding: Perfect (100% reversible)");
 
         if words.contains('.') && !words.contains('-') {
@@ -140,10 +1037,20 @@ fn encode_address(
 fn decode_words(
     encoder: &FourWordAdaptiveEncoder,
     words: &str,
+    detect_language: bool,
+    service_names: bool,
     verbose: bool,
     quiet: bool,
 ) -> Result<()> {
-    let address = encoder.decode(words)?;
+    let (dictionary, mut address) = if detect_language {
+        let (language, address) = encoder.decode_any_language(words)?;
+        (language.to_string(), address)
+    } else {
+        (describe_dictionary(encoder), encoder.decode(words)?)
+    };
+    if service_names && let Ok(addr) = parse_socket_addr(&address) {
+        address = display_socket_addr(addr);
+    }
 
     if quiet {
         // Minimal output for scripting
@@ -152,6 +1059,7 @@ fn decode_words(
         // Detailed output
         println!("Input: {words}");
         println!("Address: {address}");
+        println!("Dictionary: {dictionary}");
         println!("Decoding: Perfect reconstruction");
 
         if words.contains('.') && !words.contains('-') {
@@ -167,6 +1075,14 @@ fn decode_words(
     Ok(())
 }
 
+/// Names the encoder's wordlist: its language code, or the custom list's fingerprint
+fn describe_dictionary(encoder: &FourWordAdaptiveEncoder) -> String {
+    match encoder.language() {
+        Some(language) => language.to_string(),
+        None => format!("custom ({})", encoder.wordlist().fingerprint_hex()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -178,9 +1094,11 @@ mod tests {
 
         // Valid words - 6 words with spaces
         assert!(looks_like_words("ocean thunder falcon star book april"));
-        
+
         // Valid words - 9 words with spaces
-        assert!(looks_like_words("ocean thunder falcon star book april wing moon river"));
+        assert!(looks_like_words(
+            "ocean thunder falcon star book april wing moon river"
+        ));
 
         // Valid words - 4 words with dots
         assert!(looks_like_words("ocean.thunder.falcon.star"));
@@ -4,27 +4,30 @@ use std::net::SocketAddrV6;
 fn main() {
     let compressor = Ipv6Compressor::new();
     let encoder = FourWordIpv6Encoder::new();
-    
+
     let addr_str = "[2001:db8:85a3::8a2e:370:7334]:443";
     println!("=== Testing complex address: {} ===", addr_str);
-    
+
     let addr: SocketAddrV6 = addr_str.parse().unwrap();
     println!("Parsed IPv6: {} port: {}", addr.ip(), addr.port());
-    
+
     // Check what category the compressor assigns
     match compressor.compress(*addr.ip(), Some(addr.port())) {
         Ok(compressed) => {
             println!("Category: {:?}", compressed.category);
-            println!("Compressed data length: {} bytes", compressed.compressed_data.len());
-            println!("Compressed data: {:?}", compressed.compressed_data);
-            
+            println!(
+                "Compressed data length: {} bytes",
+                compressed.as_bytes().len()
+            );
+            println!("Compressed data: {:?}", compressed.as_bytes());
+
             // Try encoding
             match encoder.encode(&addr) {
                 Ok(encoded) => {
                     println!("Encoded successfully: {} words", encoded.word_count());
                     println!("Encoded category: {:?}", encoded.category());
-                    println!("Encoded string: '{}'", encoded.to_string());
-                    
+                    println!("Encoded string: '{}'", encoded);
+
                     // Try decoding
                     match encoder.decode(&encoded) {
                         Ok(decoded) => {
@@ -44,4 +47,4 @@ fn main() {
             println!("Compression error: {}", e);
         }
     }
-}
\ No newline at end of file
+}
@@ -3,9 +3,9 @@ use std::net::Ipv6Addr;
 
 fn main() {
     println!("=== Detailed IPv6 encoding/decoding debug ===\n");
-    
+
     let encoder = FourWordAdaptiveEncoder::new().unwrap();
-    
+
     // Test specific problematic addresses
     let test_addresses = vec![
         ("::1", "Loopback"),
@@ -13,23 +13,23 @@ fn main() {
         ("::", "Unspecified"),
         ("2001:db8::1", "Documentation"),
     ];
-    
+
     for (addr, description) in test_addresses {
         println!("\n========================================");
         println!("Testing: {} ({})", addr, description);
         println!("========================================");
-        
+
         match encoder.encode(addr) {
             Ok(encoded) => {
                 println!("Encoded: '{}'", encoded);
                 let word_count = encoded.split_whitespace().count();
                 println!("Word count: {}", word_count);
-                
+
                 // Try to decode with detailed error handling
                 match encoder.decode(&encoded) {
                     Ok(decoded) => {
                         println!("Decoded: '{}'", decoded);
-                        
+
                         // Check if it matches
                         if decoded == addr {
                             println!("✓ Exact match!");
@@ -40,53 +40,56 @@ fn main() {
                             println!("  Expected: {}", addr);
                             println!("  Got: {}", decoded);
                         }
-                    },
+                    }
                     Err(e) => {
                         println!("Decode error: {:?}", e);
-                        
+
                         // Try to understand the error
                         match e {
-                            four_word_networking::FourWordError::InvalidInput(msg) => {
+                            four_word_networking::FourWordError::DecompressionError(msg) => {
                                 println!("  Error detail: {}", msg);
-                            },
+                            }
                             _ => {
                                 println!("  Other error type");
                             }
                         }
                     }
                 }
-            },
+            }
             Err(e) => {
                 println!("Encode error: {:?}", e);
             }
         }
     }
-    
+
     println!("\n\n=== Testing category detection ===");
-    
+
     // Test category detection for each address type
     let test_ips = vec![
         (Ipv6Addr::LOCALHOST, "Loopback"),
         (Ipv6Addr::UNSPECIFIED, "Unspecified"),
         ("fe80::1".parse::<Ipv6Addr>().unwrap(), "Link-local"),
         ("2001:db8::1".parse::<Ipv6Addr>().unwrap(), "Documentation"),
-        ("2001:4860:4860::8888".parse::<Ipv6Addr>().unwrap(), "Global unicast"),
+        (
+            "2001:4860:4860::8888".parse::<Ipv6Addr>().unwrap(),
+            "Global unicast",
+        ),
     ];
-    
+
     use four_word_networking::ipv6_compression::Ipv6Compressor;
     let compressor = Ipv6Compressor::new();
-    
+
     for (ip, expected) in test_ips {
         println!("\nIP: {} (expected: {})", ip, expected);
         match compressor.compress(ip, Some(0)) {
             Ok(compressed) => {
                 println!("  Category: {:?}", compressed.category);
-                println!("  Compressed size: {} bytes", compressed.compressed_data.len());
+                println!("  Compressed size: {} bytes", compressed.as_bytes().len());
                 println!("  Category bits: {}", compressed.category.to_bits());
-            },
+            }
             Err(e) => {
                 println!("  Compression error: {:?}", e);
             }
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,247 @@
+//! Memoizing wrapper for workloads that convert the same addresses repeatedly.
+//!
+//! [`CachedEncoder`] keeps the most recently used encode and decode results in
+//! two least-recently-used caches of a fixed capacity. Failed conversions are
+//! not cached. The caches sit behind mutexes, so a single `CachedEncoder` can
+//! be shared between threads like the encoder it wraps. Under `cfg(loom)` the
+//! mutexes and counters are loom's, so the model in
+//! `tests/concurrency_tests.rs` checks every interleaving of lookups and
+//! inserts.
+
+#![forbid(unsafe_code)]
+
+use crate::dictionary4k::Dictionary4K;
+use crate::error::Result;
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::wordlist::Wordlist;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::net::SocketAddr;
+use std::sync::PoisonError;
+
+#[cfg(loom)]
+use loom::sync::atomic::{AtomicU64, Ordering};
+#[cfg(loom)]
+use loom::sync::{Mutex, MutexGuard};
+#[cfg(not(loom))]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(not(loom))]
+use std::sync::{Mutex, MutexGuard};
+
+/// Hit and miss counts since the cache was created or last cleared
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups answered from the cache
+    pub hits: u64,
+    /// Lookups that had to run the encoder
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// Fraction of lookups answered from the cache (0.0 when there were none)
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// Least-recently-used map with a fixed capacity
+#[derive(Debug)]
+struct Lru<K, V> {
+    capacity: usize,
+    /// Value and last-use tick for every key
+    entries: HashMap<K, (V, u64)>,
+    /// Keys ordered by last use, oldest first
+    order: BTreeMap<u64, K>,
+    tick: u64,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Lru<K, V> {
+    fn new(capacity: usize) -> Self {
+        Lru {
+            capacity,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Returns the value for `key`, marking it most recently used
+    fn get<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: std::borrow::Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.tick += 1;
+        let (value, last_used) = self.entries.get_mut(key)?;
+        let owned_key = self.order.remove(last_used)?;
+        *last_used = self.tick;
+        self.order.insert(self.tick, owned_key);
+        Some(value.clone())
+    }
+
+    /// Stores `value`, evicting the least recently used entry when full
+    fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, last_used)) = self.entries.remove(&key) {
+            self.order.remove(&last_used);
+        } else if self.entries.len() >= self.capacity
+            && let Some((_, oldest)) = self.order.pop_first()
+        {
+            self.entries.remove(&oldest);
+        }
+        self.order.insert(self.tick, key.clone());
+        self.entries.insert(key, (value, self.tick));
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+/// A [`FourWordAdaptiveEncoder`] that remembers recent results
+pub struct CachedEncoder<W = Dictionary4K> {
+    encoder: FourWordAdaptiveEncoder<W>,
+    encodings: Mutex<Lru<SocketAddr, String>>,
+    decodings: Mutex<Lru<String, SocketAddr>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<W: Wordlist> CachedEncoder<W> {
+    /// Wraps `encoder`, caching up to `capacity` encodings and `capacity` decodings.
+    ///
+    /// A capacity of zero disables caching but still counts misses.
+    pub fn new(encoder: FourWordAdaptiveEncoder<W>, capacity: usize) -> Self {
+        CachedEncoder {
+            encoder,
+            encodings: Mutex::new(Lru::new(capacity)),
+            decodings: Mutex::new(Lru::new(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Encodes `addr`, reusing the previous phrase if it is still cached
+    pub fn encode(&self, addr: SocketAddr) -> Result<String> {
+        if let Some(words) = lock(&self.encodings).get(&addr) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(words);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let mut words = String::new();
+        self.encoder.encode_into(addr, &mut words)?;
+        lock(&self.encodings).insert(addr, words.clone());
+        Ok(words)
+    }
+
+    /// Decodes `words`, reusing the previous address if it is still cached.
+    ///
+    /// Phrases are cached exactly as given, so differently spaced or cased
+    /// spellings of one phrase occupy separate entries.
+    pub fn decode(&self, words: &str) -> Result<SocketAddr> {
+        if let Some(addr) = lock(&self.decodings).get(words) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(addr);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let addr = self.encoder.decode_socket_addr(words)?;
+        lock(&self.decodings).insert(words.to_string(), addr);
+        Ok(addr)
+    }
+
+    /// Returns the hit and miss counts
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the number of cached encodings and decodings
+    pub fn len(&self) -> usize {
+        lock(&self.encodings).len() + lock(&self.decodings).len()
+    }
+
+    /// Checks whether nothing is cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops every cached result and resets the statistics
+    pub fn clear(&self) {
+        lock(&self.encodings).clear();
+        lock(&self.decodings).clear();
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+
+    /// Returns the wrapped encoder
+    pub fn encoder(&self) -> &FourWordAdaptiveEncoder<W> {
+        &self.encoder
+    }
+}
+
+/// Locks a cache, recovering it if another thread panicked while holding it
+///
+/// Every operation leaves the cache consistent, so a poisoned lock is safe to reuse.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_evicts_least_recently_used() {
+        let mut lru = Lru::new(2);
+        lru.insert(1, "one");
+        lru.insert(2, "two");
+        assert_eq!(lru.get(&1), Some("one"));
+        lru.insert(3, "three");
+        assert_eq!(lru.get(&2), None);
+        assert_eq!(lru.get(&1), Some("one"));
+        assert_eq!(lru.get(&3), Some("three"));
+        assert_eq!(lru.len(), 2);
+
+        let mut disabled = Lru::new(0);
+        disabled.insert(1, "one");
+        assert_eq!(disabled.len(), 0);
+    }
+
+    #[test]
+    fn test_cached_encoder_counts_hits() {
+        let cached = CachedEncoder::new(FourWordAdaptiveEncoder::new().unwrap(), 16);
+        let addr: SocketAddr = "192.168.1.1:443".parse().unwrap();
+
+        let words = cached.encode(addr).unwrap();
+        assert_eq!(cached.encode(addr).unwrap(), words);
+        assert_eq!(cached.decode(&words).unwrap(), addr);
+        assert_eq!(cached.decode(&words).unwrap(), addr);
+        assert_eq!(cached.stats(), CacheStats { hits: 2, misses: 2 });
+        assert_eq!(cached.stats().hit_rate(), 0.5);
+
+        // Failures are returned but never cached
+        assert!(cached.decode("not real words here").is_err());
+        assert_eq!(cached.len(), 2);
+
+        cached.clear();
+        assert!(cached.is_empty());
+        assert_eq!(cached.stats(), CacheStats::default());
+    }
+}
@@ -0,0 +1,268 @@
+//! IPv6 category decision table.
+//!
+//! [`decision_table`] lists every prefix the compressor treats specially, in
+//! priority order, as plain data. [`verify_decision_table`] checks that the
+//! runtime categorizer agrees with the table, so a new category added in one
+//! place but not the other fails the test suite instead of silently changing
+//! how addresses encode.
+//!
+//! NAT64 and provider prefixes registered in an
+//! [`EncodingProfile`](crate::EncodingProfile) are deployment-specific, so
+//! they are not part of the table; the compressor checks NAT64 prefixes before
+//! consulting it and provider prefixes for global unicast results. Only the well-known `64:ff9b::/96` is
+//! built in.
+
+#![forbid(unsafe_code)]
+
+use crate::ipv6_compression::{Ipv6Category, Ipv6Compressor};
+use std::fmt;
+use std::net::Ipv6Addr;
+
+/// One row of the decision table: addresses in `prefix/prefix_len` get `category`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CategoryRule {
+    /// Network prefix (host bits are zero)
+    pub prefix: Ipv6Addr,
+    /// Prefix length in bits (0-128)
+    pub prefix_len: u8,
+    /// Category assigned to matching addresses
+    pub category: Ipv6Category,
+    /// Human-readable name of the range
+    pub description: &'static str,
+}
+
+impl CategoryRule {
+    /// Checks whether `ip` falls within this rule's prefix
+    pub fn matches(&self, ip: &Ipv6Addr) -> bool {
+        let mask = prefix_mask(self.prefix_len);
+        u128::from(*ip) & mask == u128::from(self.prefix)
+    }
+
+    /// First and last address covered by the prefix
+    fn range(&self) -> (u128, u128) {
+        let first = u128::from(self.prefix);
+        (first, first | !prefix_mask(self.prefix_len))
+    }
+}
+
+impl fmt::Display for CategoryRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} -> {:?} ({})",
+            self.prefix, self.prefix_len, self.category, self.description
+        )
+    }
+}
+
+/// Rules in priority order; the first matching rule decides the category
+const RULES: &[CategoryRule] = &[
+    CategoryRule {
+        prefix: Ipv6Addr::LOCALHOST,
+        prefix_len: 128,
+        category: Ipv6Category::Loopback,
+        description: "Loopback",
+    },
+    CategoryRule {
+        prefix: Ipv6Addr::UNSPECIFIED,
+        prefix_len: 128,
+        category: Ipv6Category::Unspecified,
+        description: "Unspecified",
+    },
+    CategoryRule {
+        prefix: Ipv6Addr::new(0, 0, 0, 0, 0, 0xffff, 0, 0),
+        prefix_len: 96,
+        category: Ipv6Category::V4Mapped,
+        description: "IPv4-mapped",
+    },
+    CategoryRule {
+        prefix: Ipv6Addr::new(0, 0, 0, 0, 0xffff, 0, 0, 0),
+        prefix_len: 96,
+        category: Ipv6Category::V4Mapped,
+        description: "IPv4-translated (RFC 2765)",
+    },
+    CategoryRule {
+        prefix: Ipv6Addr::new(0x64, 0xff9b, 0, 0, 0, 0, 0, 0),
+        prefix_len: 96,
+        category: Ipv6Category::Nat64,
+        description: "NAT64 well-known prefix",
+    },
+    CategoryRule {
+        prefix: Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 0),
+        prefix_len: 64,
+        category: Ipv6Category::LinkLocal,
+        description: "Link-local (fe80::/64 only)",
+    },
+    CategoryRule {
+        prefix: Ipv6Addr::new(0xfc00, 0, 0, 0, 0, 0, 0, 0),
+        prefix_len: 7,
+        category: Ipv6Category::UniqueLocal,
+        description: "Unique local",
+    },
+    CategoryRule {
+        prefix: Ipv6Addr::new(0x2001, 0x0db8, 0, 0, 0, 0, 0, 0),
+        prefix_len: 32,
+        category: Ipv6Category::Documentation,
+        description: "Documentation",
+    },
+    CategoryRule {
+        prefix: Ipv6Addr::new(0x2001, 0, 0, 0, 0, 0, 0, 0),
+        prefix_len: 32,
+        category: Ipv6Category::Teredo,
+        description: "Teredo (RFC 4380)",
+    },
+    CategoryRule {
+        prefix: Ipv6Addr::new(0x2002, 0, 0, 0, 0, 0, 0, 0),
+        prefix_len: 16,
+        category: Ipv6Category::SixToFour,
+        description: "6to4 (RFC 3056)",
+    },
+    CategoryRule {
+        prefix: Ipv6Addr::new(0x2000, 0, 0, 0, 0, 0, 0, 0),
+        prefix_len: 3,
+        category: Ipv6Category::GlobalUnicast,
+        description: "Global unicast",
+    },
+    CategoryRule {
+        prefix: Ipv6Addr::new(0xff00, 0, 0, 0, 0, 0, 0, 0),
+        prefix_len: 8,
+        category: Ipv6Category::Multicast,
+        description: "Multicast",
+    },
+    CategoryRule {
+        prefix: Ipv6Addr::UNSPECIFIED,
+        prefix_len: 0,
+        category: Ipv6Category::Special,
+        description: "Everything else (reserved, IPv4-compatible, rest of fe80::/10)",
+    },
+];
+
+/// Returns the complete prefix -> category mapping, highest priority first.
+///
+/// The last rule is `::/0`, so every address matches at least one rule.
+pub fn decision_table() -> &'static [CategoryRule] {
+    RULES
+}
+
+/// Categorizes `ip` by walking the decision table
+pub fn lookup(ip: &Ipv6Addr) -> Ipv6Category {
+    RULES
+        .iter()
+        .find(|rule| rule.matches(ip))
+        .map_or(Ipv6Category::Special, |rule| rule.category)
+}
+
+/// Categorizes `ip` exactly as the compressor does at runtime
+pub fn categorize_address(ip: &Ipv6Addr) -> Ipv6Category {
+    Ipv6Compressor::categorize_address(ip)
+}
+
+/// An address the runtime categorizer and the decision table disagree on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CategoryMismatch {
+    /// The probed address
+    pub address: Ipv6Addr,
+    /// Category according to the decision table
+    pub table: Ipv6Category,
+    /// Category according to [`categorize_address`]
+    pub runtime: Ipv6Category,
+}
+
+/// Checks that [`categorize_address`] agrees with the decision table everywhere.
+///
+/// The rule prefixes cut the address space into intervals within which the
+/// table's answer is constant. Checking the first and last address of every
+/// interval covers the whole space as long as the runtime uses no prefix the
+/// table lacks; the interval midpoints catch most ranges added to only one side.
+pub fn verify_decision_table() -> std::result::Result<(), Vec<CategoryMismatch>> {
+    let mut boundaries = vec![0u128];
+    for rule in RULES {
+        let (first, last) = rule.range();
+        boundaries.push(first);
+        if let Some(next) = last.checked_add(1) {
+            boundaries.push(next);
+        }
+    }
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let mut mismatches = Vec::new();
+    for (i, &start) in boundaries.iter().enumerate() {
+        let end = boundaries.get(i + 1).map_or(u128::MAX, |next| next - 1);
+        for probe in [start, start + (end - start) / 2, end] {
+            let address = Ipv6Addr::from(probe);
+            let (table, runtime) = (lookup(&address), categorize_address(&address));
+            if table != runtime {
+                mismatches.push(CategoryMismatch {
+                    address,
+                    table,
+                    runtime,
+                });
+            }
+        }
+    }
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches)
+    }
+}
+
+/// Network mask with the top `len` bits set
+fn prefix_mask(len: u8) -> u128 {
+    u128::MAX.checked_shl(128 - u32::from(len)).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runtime_matches_table() {
+        assert_eq!(verify_decision_table(), Ok(()));
+    }
+
+    #[test]
+    fn test_table_is_well_formed() {
+        let table = decision_table();
+        for rule in table {
+            // Host bits must be clear or the prefix would never match itself
+            assert!(rule.matches(&rule.prefix), "{rule}");
+        }
+        let last = table.last().unwrap();
+        assert_eq!(last.prefix_len, 0, "table must end with a catch-all");
+    }
+
+    #[test]
+    fn test_lookup_examples() {
+        let cases = [
+            ("::1", Ipv6Category::Loopback),
+            ("::", Ipv6Category::Unspecified),
+            ("::ffff:192.0.2.1", Ipv6Category::V4Mapped),
+            ("::ffff:0:192.0.2.1", Ipv6Category::V4Mapped),
+            ("64:ff9b::192.0.2.1", Ipv6Category::Nat64),
+            ("fe80::1", Ipv6Category::LinkLocal),
+            ("fe80:0:0:1::", Ipv6Category::Special),
+            ("fd00::1", Ipv6Category::UniqueLocal),
+            ("2001:db8::1", Ipv6Category::Documentation),
+            ("2001:0:4136:e378:8000:63bf:3fff:fdd2", Ipv6Category::Teredo),
+            ("2002:c000:204::1", Ipv6Category::SixToFour),
+            ("2606:4700::1111", Ipv6Category::GlobalUnicast),
+            ("ff02::1", Ipv6Category::Multicast),
+            ("::192.0.2.1", Ipv6Category::Special),
+        ];
+        for (address, category) in cases {
+            let ip: Ipv6Addr = address.parse().unwrap();
+            assert_eq!(lookup(&ip), category, "{address}");
+            assert_eq!(categorize_address(&ip), category, "{address}");
+        }
+    }
+
+    #[test]
+    fn test_prefix_mask() {
+        assert_eq!(prefix_mask(0), 0);
+        assert_eq!(prefix_mask(128), u128::MAX);
+        assert_eq!(prefix_mask(8), 0xff << 120);
+    }
+}
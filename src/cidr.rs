@@ -0,0 +1,210 @@
+//! IPv4 subnets (CIDR blocks) as words.
+//!
+//! A block such as `192.168.0.0/24` is written as the four words of its
+//! network address (encoded without a port) followed by a fifth word whose
+//! dictionary index is the prefix length. The length is therefore always
+//! carried explicitly rather than guessed from the address class, and a
+//! subnet phrase can never be mistaken for a host phrase, which has four.
+//!
+//! ```rust
+//! use four_word_networking::cidr::Ipv4Cidr;
+//!
+//! let subnet: Ipv4Cidr = "192.168.0.0/24".parse()?;
+//! let words = subnet.to_words()?;
+//! assert_eq!(words.split(' ').count(), 5);
+//! assert_eq!(Ipv4Cidr::from_words(&words)?, subnet);
+//! assert!(subnet.contains([192, 168, 0, 42].into()));
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::language::Language;
+use crate::word_address::WordAddress;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr};
+use std::str::FromStr;
+
+/// An IPv4 network: address and prefix length, with no host bits set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ipv4Cidr {
+    network: Ipv4Addr,
+    prefix_len: u8,
+}
+
+impl Ipv4Cidr {
+    /// Creates a block, checking the length and that no host bits are set
+    pub fn new(network: Ipv4Addr, prefix_len: u8) -> Result<Self> {
+        if prefix_len > 32 {
+            return Err(FourWordError::out_of_range(
+                "IPv4 prefix length",
+                "at most 32",
+                format!("/{prefix_len}"),
+            ));
+        }
+        if u32::from(network) & !mask(prefix_len) != 0 {
+            return Err(FourWordError::invalid_value(
+                "subnet",
+                &format!("{network}/{prefix_len}"),
+                format!(
+                    "host bits are set; the network is {}/{prefix_len}",
+                    Ipv4Addr::from(u32::from(network) & mask(prefix_len))
+                ),
+            ));
+        }
+        Ok(Ipv4Cidr {
+            network,
+            prefix_len,
+        })
+    }
+
+    /// Creates the block of length `prefix_len` containing `addr`, clearing host bits
+    pub fn containing(addr: Ipv4Addr, prefix_len: u8) -> Result<Self> {
+        Self::new(
+            Ipv4Addr::from(u32::from(addr) & mask(prefix_len.min(32))),
+            prefix_len,
+        )
+    }
+
+    /// Returns the network address
+    pub fn network(&self) -> Ipv4Addr {
+        self.network
+    }
+
+    /// Returns the prefix length in bits
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Returns true if `addr` is inside the block
+    pub fn contains(&self, addr: Ipv4Addr) -> bool {
+        u32::from(addr) & mask(self.prefix_len) == u32::from(self.network)
+    }
+
+    /// Encodes the block as five English words
+    pub fn to_words(&self) -> Result<String> {
+        let network = WordAddress::new(self.network)?;
+        let dictionary = Language::English.dictionary();
+        let length = dictionary
+            .get_word(u16::from(self.prefix_len))
+            .ok_or(FourWordError::InvalidWordIndex(u16::from(self.prefix_len)))?;
+        Ok(format!("{} {length}", network.phrase()))
+    }
+
+    /// Decodes words produced by [`to_words`](Self::to_words)
+    pub fn from_words(words: &str) -> Result<Self> {
+        let words = words.trim();
+        let (network, length) = words.rsplit_once(' ').ok_or_else(|| {
+            FourWordError::invalid_value(
+                "subnet phrase",
+                words,
+                "expected an address phrase and a length word",
+            )
+        })?;
+        let IpAddr::V4(network) = WordAddress::from_phrase(network)?.ip() else {
+            return Err(FourWordError::NotEncoding {
+                expected: "an IPv4 subnet",
+            });
+        };
+        let prefix_len = Language::English
+            .dictionary()
+            .get_index(length)
+            .ok_or_else(|| FourWordError::unknown_word(4, length))?;
+        let prefix_len = u8::try_from(prefix_len)
+            .ok()
+            .filter(|&len| len <= 32)
+            .ok_or_else(|| {
+                FourWordError::invalid_value(
+                    "prefix length word",
+                    length,
+                    "stands for more than 32",
+                )
+            })?;
+        Self::new(network, prefix_len)
+    }
+}
+
+/// Netmask with the top `prefix_len` bits set
+fn mask(prefix_len: u8) -> u32 {
+    u32::MAX
+        .checked_shl(32 - u32::from(prefix_len))
+        .unwrap_or(0)
+}
+
+impl fmt::Display for Ipv4Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix_len)
+    }
+}
+
+impl FromStr for Ipv4Cidr {
+    type Err = FourWordError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (network, len) = s
+            .split_once('/')
+            .ok_or_else(|| FourWordError::invalid_value("subnet", s, "expected ADDRESS/LENGTH"))?;
+        let network = network
+            .parse()
+            .map_err(|_| FourWordError::invalid_value("subnet", s, "invalid IPv4 network"))?;
+        let len = len
+            .parse()
+            .map_err(|_| FourWordError::invalid_value("subnet", s, "invalid prefix length"))?;
+        Self::new(network, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trips() {
+        for subnet in [
+            "0.0.0.0/0",
+            "10.0.0.0/8",
+            "172.16.0.0/12",
+            "192.168.0.0/24",
+            "192.168.1.128/25",
+            "203.0.113.7/32",
+        ] {
+            let cidr: Ipv4Cidr = subnet.parse().unwrap();
+            assert_eq!(cidr.to_string(), subnet);
+            let words = cidr.to_words().unwrap();
+            assert_eq!(words.split(' ').count(), 5, "{words}");
+            assert_eq!(Ipv4Cidr::from_words(&words).unwrap(), cidr, "{words}");
+        }
+    }
+
+    #[test]
+    fn test_length_is_explicit() {
+        let a: Ipv4Cidr = "10.0.0.0/8".parse().unwrap();
+        let b: Ipv4Cidr = "10.0.0.0/16".parse().unwrap();
+        let (a, b) = (a.to_words().unwrap(), b.to_words().unwrap());
+        assert_ne!(a, b);
+        assert_eq!(a.rsplit_once(' ').unwrap().0, b.rsplit_once(' ').unwrap().0);
+    }
+
+    #[test]
+    fn test_host_bits_and_membership() {
+        assert!("192.168.0.1/24".parse::<Ipv4Cidr>().is_err());
+        assert!("192.168.0.0/33".parse::<Ipv4Cidr>().is_err());
+        let cidr = Ipv4Cidr::containing([192, 168, 0, 1].into(), 24).unwrap();
+        assert_eq!(cidr.to_string(), "192.168.0.0/24");
+        assert!(cidr.contains([192, 168, 0, 255].into()));
+        assert!(!cidr.contains([192, 168, 1, 0].into()));
+    }
+
+    #[test]
+    fn test_rejects_other_phrases() {
+        let host = crate::encode(([192, 168, 0, 1], 80)).unwrap();
+        assert!(Ipv4Cidr::from_words(&host).is_err());
+
+        let network = WordAddress::new(Ipv4Addr::new(192, 168, 0, 0)).unwrap();
+        let dictionary = Language::English.dictionary();
+        let not_a_length = dictionary.get_word(33).unwrap();
+        assert!(Ipv4Cidr::from_words(&format!("{} {not_a_length}", network.phrase())).is_err());
+        assert!(Ipv4Cidr::from_words(network.phrase()).is_err());
+    }
+}
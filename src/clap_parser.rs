@@ -0,0 +1,191 @@
+//! Value parsers for accepting phrases as typed `clap` arguments.
+//!
+//! ```rust
+//! use clap::{Arg, Command};
+//! use four_word_networking::clap_parser::WordAddressValueParser;
+//! use std::net::SocketAddr;
+//!
+//! let matches = Command::new("connect")
+//!     .arg(Arg::new("peer").value_parser(WordAddressValueParser::new()))
+//!     .try_get_matches_from(["connect", "book abstract junk restriction"])?;
+//! let peer: &SocketAddr = matches.get_one("peer").unwrap();
+//! assert_eq!(peer.to_string(), "192.168.1.1:443");
+//! # Ok::<(), clap::Error>(())
+//! ```
+//!
+//! Like [`FourWordAdaptiveEncoder::decode_socket_addr`], both parsers yield
+//! port 65535 for addresses given without a port.
+
+#![forbid(unsafe_code)]
+
+use crate::error::FourWordError;
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::language::Language;
+use crate::ports;
+use clap::builder::TypedValueParser;
+use clap::error::{ContextKind, ContextValue, ErrorKind};
+use clap::{Arg, Command, Error};
+use std::ffi::OsStr;
+use std::net::{IpAddr, SocketAddr};
+
+/// Parses a word phrase into the [`SocketAddr`] it encodes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WordAddressValueParser {
+    language: Language,
+}
+
+impl WordAddressValueParser {
+    /// Accepts English phrases
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accepts phrases in `language` instead
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+}
+
+impl TypedValueParser for WordAddressValueParser {
+    type Value = SocketAddr;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<SocketAddr, Error> {
+        let words = to_str(cmd, arg, value)?;
+        let encoder = FourWordAdaptiveEncoder::with_language(self.language)
+            .map_err(|e| invalid_value(cmd, arg, words, &e))?;
+        encoder.decode_socket_addr(words).map_err(|e| {
+            // Lenient decoding explains unknown words with suggested corrections
+            let reason = match encoder.decode_fuzzy(words, false) {
+                Err(unknown @ FourWordError::UnknownWord { .. }) => unknown,
+                _ => e,
+            };
+            invalid_value(cmd, arg, words, &reason)
+        })
+    }
+}
+
+/// Parses either an IP address (with optional port or service name) or a word phrase
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhraseOrAddressValueParser {
+    phrases: WordAddressValueParser,
+}
+
+impl PhraseOrAddressValueParser {
+    /// Accepts addresses and English phrases
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accepts phrases in `language` instead
+    pub fn language(mut self, language: Language) -> Self {
+        self.phrases = self.phrases.language(language);
+        self
+    }
+}
+
+impl TypedValueParser for PhraseOrAddressValueParser {
+    type Value = SocketAddr;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<SocketAddr, Error> {
+        let input = to_str(cmd, arg, value)?.trim();
+        if let Ok(addr) = ports::parse_socket_addr(input) {
+            return Ok(addr);
+        }
+        if let Ok(ip) = input.parse::<IpAddr>() {
+            return Ok(SocketAddr::new(ip, 65535));
+        }
+        self.phrases.parse_ref(cmd, arg, value)
+    }
+}
+
+/// Rejects arguments that are not valid UTF-8
+fn to_str<'a>(cmd: &Command, arg: Option<&Arg>, value: &'a OsStr) -> Result<&'a str, Error> {
+    value.to_str().ok_or_else(|| {
+        let mut error = Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd);
+        if let Some(arg) = arg {
+            error.insert(
+                ContextKind::InvalidArg,
+                ContextValue::String(arg.to_string()),
+            );
+        }
+        error
+    })
+}
+
+/// Reports `value` as invalid, with the encoder's reason and any suggestions
+fn invalid_value(cmd: &Command, arg: Option<&Arg>, value: &str, reason: &FourWordError) -> Error {
+    let arg = arg.map_or_else(|| "...".to_string(), ToString::to_string);
+    Error::raw(
+        ErrorKind::ValueValidation,
+        format!("invalid value '{value}' for '{arg}': {reason}\n"),
+    )
+    .with_cmd(cmd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(parser: impl TypedValueParser<Value = SocketAddr>) -> Command {
+        Command::new("test").arg(Arg::new("peer").long("peer").value_parser(parser))
+    }
+
+    #[test]
+    fn test_parses_phrases() {
+        let words = FourWordAdaptiveEncoder::new()
+            .unwrap()
+            .encode("[2001:db8::1]:8080")
+            .unwrap();
+        let matches = command(WordAddressValueParser::new())
+            .try_get_matches_from(["test", "--peer", &words])
+            .unwrap();
+        let peer: &SocketAddr = matches.get_one("peer").unwrap();
+        assert_eq!(peer.to_string(), "[2001:db8::1]:8080");
+
+        // Addresses are not phrases
+        assert!(
+            command(WordAddressValueParser::new())
+                .try_get_matches_from(["test", "--peer", "10.0.0.1:80"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_phrase_or_address() {
+        let parse = |input: &str| {
+            command(PhraseOrAddressValueParser::new())
+                .try_get_matches_from(["test", "--peer", input])
+                .map(|matches| *matches.get_one::<SocketAddr>("peer").unwrap())
+        };
+        assert_eq!(parse("10.0.0.1:80").unwrap().to_string(), "10.0.0.1:80");
+        assert_eq!(parse("10.0.0.1:http").unwrap().to_string(), "10.0.0.1:80");
+        assert_eq!(parse("10.0.0.1").unwrap().port(), 65535);
+        let words = FourWordAdaptiveEncoder::new()
+            .unwrap()
+            .encode("10.0.0.1:80")
+            .unwrap();
+        assert_eq!(parse(&words).unwrap().to_string(), "10.0.0.1:80");
+    }
+
+    #[test]
+    fn test_error_names_argument_and_suggests() {
+        let error = command(WordAddressValueParser::new())
+            .try_get_matches_from(["test", "--peer", "oceen thunder falcon star"])
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+        let message = error.to_string();
+        assert!(message.contains("--peer <peer>"), "{message}");
+        assert!(message.contains("did you mean"), "{message}");
+    }
+}
@@ -3,6 +3,8 @@
 //! This module implements sophisticated compression techniques to reduce
 //! IP addresses and ports to fit within the 42-bit limit of four words.
 
+#![forbid(unsafe_code)]
+
 use crate::error::FourWordError;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
@@ -118,7 +120,7 @@ impl PortCompressor {
                     .find(|(_, c)| *c == code)
                     .map(|(port, _)| Some(*port))
                     .ok_or_else(|| {
-                        FourWordError::InvalidInput("Invalid common port code".to_string())
+                        FourWordError::DecompressionError("Invalid common port code".to_string())
                     })
             }
             8 => {
@@ -129,14 +131,18 @@ impl PortCompressor {
                         .iter()
                         .find(|(_, c)| *c == code)
                         .map(|(port, _)| Some(*port))
-                        .ok_or_else(|| FourWordError::InvalidInput("Invalid port code".to_string()))
+                        .ok_or_else(|| {
+                            FourWordError::DecompressionError("Invalid port code".to_string())
+                        })
                 } else {
                     self.frequent_ports
                         .iter()
                         .find(|(_, c)| *c == code)
                         .map(|(port, _)| Some(*port))
                         .ok_or_else(|| {
-                            FourWordError::InvalidInput("Invalid frequent port code".to_string())
+                            FourWordError::DecompressionError(
+                                "Invalid frequent port code".to_string(),
+                            )
                         })
                 }
             }
@@ -144,12 +150,12 @@ impl PortCompressor {
                 if data.len() >= 2 {
                     Ok(Some(((data[0] as u16) << 8) | (data[1] as u16)))
                 } else {
-                    Err(FourWordError::InvalidInput(
+                    Err(FourWordError::DecompressionError(
                         "Insufficient data for port".to_string(),
                     ))
                 }
             }
-            _ => Err(FourWordError::InvalidInput(format!(
+            _ => Err(FourWordError::DecompressionError(format!(
                 "Invalid port bit count: {bits}"
             ))),
         }
@@ -185,7 +191,7 @@ impl IpCompressor {
 
         let total_bits = addr_bits + port_bits;
         if total_bits > MAX_BITS {
-            return Err(FourWordError::InvalidInput(format!(
+            return Err(FourWordError::CompressionError(format!(
                 "Compressed size {total_bits} bits exceeds maximum {MAX_BITS} bits"
             )));
         }
@@ -293,7 +299,7 @@ impl IpCompressor {
         }
         // Public IPv6 - too large to fit
         else {
-            Err(FourWordError::InvalidInput(
+            Err(FourWordError::CompressionError(
                 "Public IPv6 addresses cannot be compressed to fit in 42 bits".to_string(),
             ))
         }
@@ -328,7 +334,7 @@ impl IpCompressor {
                 if data.len() >= 2 {
                     Ok(IpAddr::V4(Ipv4Addr::new(192, 168, data[0], data[1])))
                 } else {
-                    Err(FourWordError::InvalidInput(
+                    Err(FourWordError::DecompressionError(
                         "Insufficient data for 192.168.x.x".to_string(),
                     ))
                 }
@@ -337,7 +343,7 @@ impl IpCompressor {
                 if data.len() >= 3 {
                     Ok(IpAddr::V4(Ipv4Addr::new(10, data[0], data[1], data[2])))
                 } else {
-                    Err(FourWordError::InvalidInput(
+                    Err(FourWordError::DecompressionError(
                         "Insufficient data for 10.x.x.x".to_string(),
                     ))
                 }
@@ -352,7 +358,7 @@ impl IpCompressor {
                         data[2],
                     )))
                 } else {
-                    Err(FourWordError::InvalidInput(
+                    Err(FourWordError::DecompressionError(
                         "Insufficient data for 172.16-31.x.x".to_string(),
                     ))
                 }
@@ -363,7 +369,7 @@ impl IpCompressor {
                         data[0], data[1], data[2], data[3],
                     )))
                 } else {
-                    Err(FourWordError::InvalidInput(
+                    Err(FourWordError::DecompressionError(
                         "Insufficient data for public IPv4".to_string(),
                     ))
                 }
@@ -387,7 +393,7 @@ impl IpCompressor {
                         (interface_id & 0xFFFF) as u16,
                     )))
                 } else {
-                    Err(FourWordError::InvalidInput(
+                    Err(FourWordError::DecompressionError(
                         "Insufficient data for link-local IPv6".to_string(),
                     ))
                 }
@@ -399,12 +405,12 @@ impl IpCompressor {
                     let seg2 = ((data[4] as u16) << 8) | (data[5] as u16);
                     Ok(IpAddr::V6(Ipv6Addr::new(seg0, seg1, seg2, 0, 0, 0, 0, 0)))
                 } else {
-                    Err(FourWordError::InvalidInput(
+                    Err(FourWordError::DecompressionError(
                         "Insufficient data for unique local IPv6".to_string(),
                     ))
                 }
             }
-            AddressType::Ipv6Public => Err(FourWordError::InvalidInput(
+            AddressType::Ipv6Public => Err(FourWordError::DecompressionError(
                 "Public IPv6 decompression not supported".to_string(),
             )),
         }
@@ -599,7 +605,7 @@ impl CompressedAddress {
                 bits.read_bits(8)? as u8,
             ],
             AddressType::Ipv6Public => {
-                return Err(FourWordError::InvalidInput(
+                return Err(FourWordError::DecompressionError(
                     "Public IPv6 not supported".to_string(),
                 ));
             }
@@ -706,7 +712,7 @@ impl<'a> BitReader<'a> {
 
         while bits_read < num_bits {
             if self.byte_index >= self.data.len() {
-                return Err(FourWordError::InvalidInput(
+                return Err(FourWordError::DecompressionError(
                     "Insufficient data for bit reading".to_string(),
                 ));
             }
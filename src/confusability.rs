@@ -0,0 +1,292 @@
+//! How easily two words are mistaken for each other.
+//!
+//! [`confusability`] scores a pair of words from 0 (nothing alike) to 1
+//! (identical, or pronounced the same) by the closer of their spelling and
+//! their rough pronunciation. [`phrase_confusability`] reports the worst
+//! adjacent pair of a phrase, for interfaces that want to warn before a
+//! phrase is read aloud.
+//!
+//! No same-length encoding can keep every phrase clear of confusable
+//! neighbours, since every pair of dictionary words is some address's
+//! encoding. With
+//! [`with_confusion_avoidance`](crate::FourWordAdaptiveEncoder::with_confusion_avoidance)
+//! the encoder instead rewrites a phrase that has a pair at or above
+//! [`HIGH_CONFUSABILITY`]: it shifts the word at position `i` by
+//! `(k + 1) * (2i + 1)` places in the wordlist and leads with a selector word
+//! for `k`, trying `k` in order until no adjacent pair, selector included,
+//! is confusable. Selector words sit at index [`FIRST_SELECTOR`] or later, so
+//! they never look like a [version tag](crate::version). Such phrases are one
+//! word longer and every decoder undoes the shift, whether or not it avoids
+//! confusion itself:
+//!
+//! ```rust
+//! use four_word_networking::FourWordAdaptiveEncoder;
+//! use four_word_networking::confusability::{HIGH_CONFUSABILITY, confusability, phrase_confusability};
+//!
+//! assert_eq!(confusability("night", "knight"), 1.0);
+//! assert!(confusability("ocean", "table") < HIGH_CONFUSABILITY);
+//!
+//! let encoder = FourWordAdaptiveEncoder::new()?.with_confusion_avoidance(true);
+//! let words = encoder.encode("[::1]:443")?;
+//! assert!(phrase_confusability(&words) < HIGH_CONFUSABILITY);
+//! assert_eq!(encoder.decode(&words)?, "[::1]:443");
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::dictionary_audit::phonetic_key;
+use crate::error::{FourWordError, Result};
+use crate::fuzzy::edit_distance;
+use crate::phrase::write_words;
+use crate::wordlist::Wordlist;
+use std::borrow::Cow;
+use std::fmt;
+
+/// Score at which two adjacent words count as confusable
+pub const HIGH_CONFUSABILITY: f64 = 0.75;
+
+/// Index of the first selector word; lower indices are left to version tags
+pub const FIRST_SELECTOR: u16 = 64;
+
+/// Word counts of phrases without a selector: IPv4, then the IPv6 lengths
+const PLAIN_LENGTHS: [usize; 4] = [4, 6, 9, 12];
+
+/// Scores how easily `a` and `b` are mistaken for each other, from 0 to 1.
+///
+/// The score is the higher of the spelling and the pronunciation
+/// similarity, each one minus the edit distance over the longer length.
+/// Case is ignored.
+pub fn confusability(a: &str, b: &str) -> f64 {
+    let (a, b) = (a.to_ascii_lowercase(), b.to_ascii_lowercase());
+    let spelling = similarity(&a, &b);
+    let sound = similarity(&phonetic_key(&a), &phonetic_key(&b));
+    spelling.max(sound)
+}
+
+/// Highest [`confusability`] of any two adjacent words of `words`, or 0 for
+/// fewer than two words.
+///
+/// Words may be separated by whitespace, dots or dashes.
+pub fn phrase_confusability(words: &str) -> f64 {
+    let words: Vec<&str> = words
+        .split(|c: char| c.is_whitespace() || c == '.' || c == '-')
+        .filter(|word| !word.is_empty())
+        .collect();
+    words
+        .windows(2)
+        .map(|pair| confusability(pair[0], pair[1]))
+        .fold(0.0, f64::max)
+}
+
+/// One minus the edit distance of `a` and `b` over the longer length
+fn similarity(a: &str, b: &str) -> f64 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - edit_distance(a, b) as f64 / longest as f64
+}
+
+/// Writes `phrase` to `out`, with a selector and shifted words if two of its
+/// adjacent words are confusable
+pub(crate) fn separate<W: Wordlist + ?Sized>(
+    wordlist: &W,
+    phrase: &str,
+    out: &mut impl fmt::Write,
+) -> Result<()> {
+    let words: Vec<&str> = phrase.split(' ').collect();
+    if is_clear(&words) {
+        return Ok(out.write_str(phrase)?);
+    }
+    let indices = words
+        .iter()
+        .enumerate()
+        .map(|(position, word)| {
+            wordlist
+                .get_index(word)
+                .ok_or_else(|| FourWordError::unknown_word(position, word))
+        })
+        .collect::<Result<Vec<u16>>>()?;
+    let size = u16::try_from(wordlist.len()).unwrap_or(u16::MAX);
+    for selector in FIRST_SELECTOR..size {
+        let mut shifted = vec![selector];
+        shifted.extend(shift(&indices, selector, size, Shift::Forward));
+        let mut candidate = String::new();
+        write_words(wordlist, &shifted, &mut candidate)?;
+        if is_clear(&candidate.split(' ').collect::<Vec<_>>()) {
+            return Ok(out.write_str(&candidate)?);
+        }
+    }
+    Err(FourWordError::unsupported(
+        "avoid confusable words",
+        format!("no selector keeps '{phrase}' free of confusable neighbours"),
+    ))
+}
+
+/// Undoes [`separate`]: drops a leading selector from `words` and shifts the
+/// rest back.
+///
+/// The result is only accepted if [`separate`] turns it back into `split`,
+/// so a stray extra word rarely passes for a selector. Other phrases are
+/// returned as they are.
+pub(crate) fn unshift<'a, W: Wordlist + ?Sized>(
+    wordlist: &W,
+    words: &'a str,
+    split: &[&str],
+) -> Result<Cow<'a, str>> {
+    let Some((first, rest)) = split.split_first() else {
+        return Ok(Cow::Borrowed(words));
+    };
+    let selector = match wordlist.get_index(first) {
+        Some(index) if index >= FIRST_SELECTOR && PLAIN_LENGTHS.contains(&rest.len()) => index,
+        _ => return Ok(Cow::Borrowed(words)),
+    };
+    let Some(indices) = rest
+        .iter()
+        .map(|word| wordlist.get_index(word))
+        .collect::<Option<Vec<u16>>>()
+    else {
+        return Ok(Cow::Borrowed(words));
+    };
+    let size = u16::try_from(wordlist.len()).unwrap_or(u16::MAX);
+    let mut plain = String::new();
+    write_words(
+        wordlist,
+        &shift(&indices, selector, size, Shift::Back),
+        &mut plain,
+    )?;
+
+    let mut separated = String::new();
+    separate(wordlist, &plain, &mut separated)?;
+    let canonical = separated
+        .split(' ')
+        .map(|word| wordlist.get_index(word))
+        .eq(split.iter().map(|word| wordlist.get_index(word)));
+    Ok(if canonical {
+        Cow::Owned(plain)
+    } else {
+        Cow::Borrowed(words)
+    })
+}
+
+/// Whether no two adjacent `words` are confusable
+fn is_clear(words: &[&str]) -> bool {
+    words
+        .windows(2)
+        .all(|pair| confusability(pair[0], pair[1]) < HIGH_CONFUSABILITY)
+}
+
+/// Which way [`shift`] moves indices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shift {
+    Forward,
+    Back,
+}
+
+/// Moves the index at position `i` by `(k + 1) * (2i + 1)` places, where `k`
+/// is the selector's offset from [`FIRST_SELECTOR`]
+fn shift(indices: &[u16], selector: u16, size: u16, direction: Shift) -> Vec<u16> {
+    let size = u32::from(size);
+    let k = u32::from(selector - FIRST_SELECTOR);
+    indices
+        .iter()
+        .enumerate()
+        .map(|(position, &index)| {
+            let offset = (k + 1) * (2 * position as u32 + 1) % size;
+            let moved = match direction {
+                Shift::Forward => u32::from(index) + offset,
+                Shift::Back => u32::from(index) + size - offset,
+            };
+            (moved % size) as u16
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FourWordAdaptiveEncoder;
+    use crate::dictionary4k::DICTIONARY;
+
+    #[test]
+    fn test_confusability_scores() {
+        assert_eq!(confusability("ocean", "OCEAN"), 1.0);
+        assert_eq!(confusability("maid", "made"), 1.0);
+        assert!(confusability("table", "cable") >= HIGH_CONFUSABILITY);
+        assert!(confusability("cat", "bat") < HIGH_CONFUSABILITY);
+        assert!(confusability("ocean", "ladder") < 0.5);
+        assert_eq!(
+            confusability("table", "cable"),
+            confusability("cable", "table")
+        );
+
+        assert_eq!(phrase_confusability("ocean"), 0.0);
+        assert_eq!(phrase_confusability("ocean-ocean"), 1.0);
+        assert!(phrase_confusability("table ladder cable") < HIGH_CONFUSABILITY);
+    }
+
+    #[test]
+    fn test_shift_round_trips() {
+        let indices = [0, 1, 4095, 2048, 17, 300];
+        for selector in [FIRST_SELECTOR, 100, 4095] {
+            let moved = shift(&indices, selector, 4096, Shift::Forward);
+            assert_ne!(moved, indices);
+            assert_eq!(shift(&moved, selector, 4096, Shift::Back), indices);
+        }
+    }
+
+    #[test]
+    fn test_avoidance_clears_repeated_words() {
+        let plain = FourWordAdaptiveEncoder::new().unwrap();
+        let encoder = FourWordAdaptiveEncoder::new()
+            .unwrap()
+            .with_confusion_avoidance(true);
+        // Zero runs repeat the first dictionary word
+        for address in ["[::1]:443", "0.0.0.0:0", "[fe80::1]:22", "10.0.0.1:80"] {
+            let words = encoder.encode(address).unwrap();
+            assert!(phrase_confusability(&words) < HIGH_CONFUSABILITY, "{words}");
+            assert_eq!(encoder.decode(&words).unwrap(), address);
+            // Decoders without the option read it too
+            assert_eq!(plain.decode(&words).unwrap(), address);
+        }
+
+        let repeated = plain.encode("0.0.0.0:0").unwrap();
+        assert!(phrase_confusability(&repeated) >= HIGH_CONFUSABILITY);
+        let separated = encoder.encode("0.0.0.0:0").unwrap();
+        assert_eq!(separated.split(' ').count(), 5);
+        let selector = separated.split(' ').next().unwrap();
+        assert!(DICTIONARY.get_index(selector).unwrap() >= FIRST_SELECTOR);
+        // Another selector, or one before a clear phrase, is not accepted
+        let dictionary = DICTIONARY;
+        let other = dictionary.get_word(dictionary.get_index(selector).unwrap() + 1);
+        let wrong = format!("{} {}", other.unwrap(), &separated[selector.len() + 1..]);
+        assert!(plain.decode(&wrong).is_err());
+        let clear = plain.encode("192.168.1.1:443").unwrap();
+        assert!(plain.decode(&format!("{selector} {clear}")).is_err());
+
+        let network: std::net::Ipv6Addr = "2001:db8::".parse().unwrap();
+        let prefix = encoder.encode_prefix(network, 48).unwrap();
+        assert!(
+            phrase_confusability(&prefix) < HIGH_CONFUSABILITY,
+            "{prefix}"
+        );
+        assert_eq!(encoder.decode_prefix(&prefix).unwrap(), (network, 48));
+
+        // Clear phrases are left alone
+        assert!(phrase_confusability(&clear) < HIGH_CONFUSABILITY);
+        assert_eq!(encoder.encode("192.168.1.1:443").unwrap(), clear);
+    }
+
+    #[test]
+    fn test_avoidance_with_allocation_free_output() {
+        let encoder = FourWordAdaptiveEncoder::new()
+            .unwrap()
+            .with_confusion_avoidance(true);
+        let addr = "[::1]:443".parse().unwrap();
+        let mut words = String::new();
+        encoder.encode_into(addr, &mut words).unwrap();
+        assert_eq!(words, encoder.encode("[::1]:443").unwrap());
+        assert_eq!(encoder.decode_socket_addr(&words).unwrap(), addr);
+    }
+}
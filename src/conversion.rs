@@ -0,0 +1,195 @@
+//! Conversion results with the details scripts need.
+//!
+//! [`FourWordAdaptiveEncoder::convert_entry`] encodes an address or decodes a
+//! phrase, as the CLI does, and returns a [`Conversion`] holding the input,
+//! the phrase, the address and port, the address category and whether the
+//! phrase reproduces the input exactly. With the `serde` feature it
+//! serializes to JSON; `4wn --format json|csv` prints it.
+//!
+//! ```rust
+//! use four_word_networking::FourWordAdaptiveEncoder;
+//! use four_word_networking::conversion::Direction;
+//!
+//! let encoder = FourWordAdaptiveEncoder::new()?;
+//! let conversion = encoder.convert_entry("[fd00:1234:5678::1]:443")?;
+//! assert_eq!(conversion.direction, Direction::Encode);
+//! assert_eq!(conversion.category, "unique_local");
+//! // Unique local phrases keep only the /64
+//! assert!(!conversion.lossless);
+//! assert_eq!(conversion.to_string(), conversion.words);
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::Result;
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::ipv6_compression::Ipv6Category;
+use crate::wordlist::Wordlist;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+
+/// Port that marks an address without one
+const NO_PORT: u16 = 65535;
+
+/// Whether an entry was an address or a phrase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "lowercase"))]
+pub enum Direction {
+    /// An address was encoded to words
+    Encode,
+    /// A phrase was decoded to an address
+    Decode,
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Direction::Encode => "encode",
+            Direction::Decode => "decode",
+        })
+    }
+}
+
+/// One converted entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Conversion {
+    /// The entry as given, trimmed
+    pub input: String,
+    /// Whether the entry was encoded or decoded
+    pub direction: Direction,
+    /// The phrase: the encoding of an address, or the phrase that was decoded
+    pub words: String,
+    /// The address the phrase decodes to
+    pub ip: IpAddr,
+    /// The port, or `None` for an address without one
+    pub port: Option<u16>,
+    /// `ipv4`, or the IPv6 category the phrase was encoded under (such as
+    /// `global_unicast` or `link_local`)
+    pub category: &'static str,
+    /// False when the phrase drops part of an input address, such as a unique
+    /// local interface ID or a port that does not fit; always true for decoding
+    pub lossless: bool,
+}
+
+impl Conversion {
+    /// Returns the decoded address, with port 65535 for "no port"
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.ip, self.port.unwrap_or(NO_PORT))
+    }
+}
+
+impl fmt::Display for Conversion {
+    /// Writes the result: the words when encoding, the address when decoding
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.direction, self.port) {
+            (Direction::Encode, _) => f.write_str(&self.words),
+            (Direction::Decode, None) => write!(f, "{}", self.ip),
+            (Direction::Decode, Some(_)) => write!(f, "{}", self.socket_addr()),
+        }
+    }
+}
+
+impl<W: Wordlist> FourWordAdaptiveEncoder<W> {
+    /// Encodes `entry` if it is an address, otherwise decodes it as a phrase.
+    ///
+    /// Dictionary words never contain digits or colons, so an entry with a
+    /// colon, or with digits and no dash or whitespace between them (`10.0.0.1`),
+    /// is treated as an address. Phrases with words written as indices
+    /// (`1492-88-2051-7`) are decoded.
+    pub fn convert_entry(&self, entry: &str) -> Result<Conversion> {
+        let input = entry.trim();
+        let is_address = input.contains(':')
+            || (input.contains(|c: char| c.is_ascii_digit())
+                && (input.contains('.')
+                    || !input.contains(|c: char| c == '-' || c.is_whitespace())));
+        let (direction, words, addr, lossless) = if is_address {
+            let words = self.encode(input)?;
+            let addr = self.decode_socket_addr(&words)?;
+            let lossless = self.parse_address(input)? == addr;
+            (Direction::Encode, words, addr, lossless)
+        } else {
+            let addr = self.decode_socket_addr(input)?;
+            (Direction::Decode, input.to_string(), addr, true)
+        };
+        Ok(Conversion {
+            input: input.to_string(),
+            direction,
+            words,
+            ip: addr.ip(),
+            port: (addr.port() != NO_PORT).then_some(addr.port()),
+            category: category_name(self.ipv6_category(addr)?),
+            lossless,
+        })
+    }
+}
+
+/// Machine-readable name of an encoding category (`None` is IPv4)
+pub(crate) fn category_name(category: Option<Ipv6Category>) -> &'static str {
+    match category {
+        None => "ipv4",
+        Some(Ipv6Category::Loopback) => "loopback",
+        Some(Ipv6Category::LinkLocal) => "link_local",
+        Some(Ipv6Category::UniqueLocal) => "unique_local",
+        Some(Ipv6Category::Documentation) => "documentation",
+        Some(Ipv6Category::GlobalUnicast) => "global_unicast",
+        Some(Ipv6Category::Unspecified) => "unspecified",
+        Some(Ipv6Category::Special) => "special",
+        Some(Ipv6Category::Multicast) => "multicast",
+        Some(Ipv6Category::Provider) => "provider",
+        Some(Ipv6Category::Nat64) => "nat64",
+        Some(Ipv6Category::Prefix) => "prefix",
+        Some(Ipv6Category::V4Mapped) => "v4_mapped",
+        Some(Ipv6Category::Teredo) => "teredo",
+        Some(Ipv6Category::SixToFour) => "six_to_four",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_entry_both_ways() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let encoded = encoder.convert_entry(" 192.168.1.1:443 ").unwrap();
+        assert_eq!(encoded.input, "192.168.1.1:443");
+        assert_eq!(encoded.direction, Direction::Encode);
+        assert_eq!((encoded.category, encoded.port), ("ipv4", Some(443)));
+        assert!(encoded.lossless);
+
+        let decoded = encoder.convert_entry(&encoded.words).unwrap();
+        assert_eq!(decoded.direction, Direction::Decode);
+        assert_eq!(decoded.to_string(), "192.168.1.1:443");
+        assert_eq!(decoded.socket_addr(), encoded.socket_addr());
+
+        let lossy = encoder
+            .convert_entry("[2606:4700:4700::1111]:4433")
+            .unwrap();
+        assert_eq!((lossy.category, lossy.port), ("global_unicast", None));
+        assert!(!lossy.lossless);
+
+        let no_port = encoder.convert_entry("fe80::1").unwrap();
+        let decoded = encoder.convert_entry(&no_port.words).unwrap();
+        assert_eq!(decoded.to_string(), "fe80::1");
+        assert_eq!(decoded.category, "link_local");
+
+        assert!(encoder.convert_entry("192.168.1.300").is_err());
+        assert!(encoder.convert_entry("ocean thunder").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_fields() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let conversion = encoder.convert_entry("10.0.0.1").unwrap();
+        let json = serde_json::to_value(&conversion).unwrap();
+        assert_eq!(json["direction"], "encode");
+        assert_eq!(json["ip"], "10.0.0.1");
+        assert!(json["port"].is_null());
+        assert_eq!(json["lossless"], true);
+    }
+}
@@ -0,0 +1,337 @@
+//! Persistent encoder daemon and client over a UNIX domain socket.
+//!
+//! Many short-lived processes on one host can share a single warmed-up
+//! encoder and dictionary by talking to a `4wn daemon` instead of each
+//! loading their own.
+//!
+//! ## Protocol
+//!
+//! The protocol is line-oriented UTF-8. Each request is one line and gets
+//! exactly one response line:
+//!
+//! ```text
+//! ENCODE 192.168.1.1:443      ->  OK <words>
+//! DECODE <words>              ->  OK 192.168.1.1:443
+//! PING                        ->  OK PONG
+//! <anything else>             ->  ERR <message>
+//! ```
+//!
+//! Errors from the encoder are returned as `ERR <message>` and leave the
+//! connection open for further requests. Each connection is served on its
+//! own thread; once [`MAX_CONNECTIONS`] are open (see
+//! [`Daemon::with_max_connections`]) further clients get a single
+//! `ERR daemon busy` line and are disconnected.
+//!
+//! ## Platforms
+//!
+//! The daemon only exists on UNIX, where it listens on a domain socket.
+//! Windows named pipes are not supported; on Windows run `4wn serve` and use
+//! the HTTP API instead.
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// File name of the daemon socket inside the runtime directory
+pub const SOCKET_NAME: &str = "4wn.sock";
+
+/// Default limit on connections served at once
+pub const MAX_CONNECTIONS: usize = 64;
+
+/// Returns the default socket path: `$XDG_RUNTIME_DIR/4wn.sock`, falling back to the temp dir
+pub fn default_socket_path() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join(SOCKET_NAME)
+}
+
+/// Daemon serving encode/decode requests from a shared encoder
+#[derive(Clone)]
+pub struct Daemon {
+    encoder: Arc<FourWordAdaptiveEncoder>,
+    max_connections: usize,
+}
+
+impl Daemon {
+    /// Creates a daemon with a freshly initialised encoder
+    pub fn new() -> Result<Self> {
+        Ok(Daemon {
+            encoder: Arc::new(FourWordAdaptiveEncoder::new()?),
+            max_connections: MAX_CONNECTIONS,
+        })
+    }
+
+    /// Serves at most `max` connections at once, turning further clients away
+    pub fn with_max_connections(mut self, max: usize) -> Self {
+        self.max_connections = max;
+        self
+    }
+
+    /// Binds a listener at `path`, replacing a stale socket if one exists.
+    ///
+    /// Only a socket no daemon answers on is removed. Anything else at `path`
+    /// (a regular file, a directory, a symlink) is left alone and reported as
+    /// an error.
+    pub fn bind(path: &Path) -> Result<UnixListener> {
+        match std::fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.file_type().is_socket() => {
+                // Refuse to steal a socket from a daemon that is still running
+                if UnixStream::connect(path).is_ok() {
+                    return Err(FourWordError::Daemon(format!(
+                        "daemon already listening on {}",
+                        path.display()
+                    )));
+                }
+                std::fs::remove_file(path)?;
+            }
+            Ok(_) => {
+                return Err(FourWordError::Daemon(format!(
+                    "{} exists and is not a socket",
+                    path.display()
+                )));
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        Ok(UnixListener::bind(path)?)
+    }
+
+    /// Accepts connections forever, serving each on its own thread
+    pub fn serve(&self, listener: UnixListener) -> Result<()> {
+        let open = Arc::new(AtomicUsize::new(0));
+        for stream in listener.incoming() {
+            let mut stream = stream?;
+            // Only this loop adds connections, so the count cannot overshoot
+            if open.load(Ordering::Acquire) >= self.max_connections {
+                // The client may already be gone; there is no one else to tell
+                let _ = writeln!(
+                    stream,
+                    "ERR daemon busy: {} connections open",
+                    self.max_connections
+                );
+                continue;
+            }
+            let slot = ConnectionSlot::take(&open);
+            let daemon = self.clone();
+            thread::spawn(move || {
+                let _slot = slot;
+                // A client hanging up mid-request is not a daemon failure
+                let _ = daemon.serve_connection(stream);
+            });
+        }
+        Ok(())
+    }
+
+    /// Serves requests on a single connection until the client disconnects
+    pub fn serve_connection(&self, stream: UnixStream) -> Result<()> {
+        let mut writer = stream.try_clone()?;
+        let reader = BufReader::new(stream);
+
+        for line in reader.lines() {
+            let response = self.handle_line(&line?);
+            writer.write_all(response.as_bytes())?;
+            writer.write_all(b"\n")?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Handles a single protocol line and returns the response line (without newline)
+    pub fn handle_line(&self, line: &str) -> String {
+        let line = line.trim();
+        let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+        let argument = argument.trim();
+
+        let result = match command.to_ascii_uppercase().as_str() {
+            "ENCODE" => self.encoder.encode(argument),
+            "DECODE" => self.encoder.decode(argument),
+            "PING" => Ok("PONG".to_string()),
+            _ => Err(FourWordError::Daemon(format!("unknown command: {command}"))),
+        };
+
+        match result {
+            // Responses are single lines; keep multi-line errors on one line
+            Ok(value) => format!("OK {value}"),
+            Err(e) => format!("ERR {}", e.to_string().replace('\n', " ")),
+        }
+    }
+}
+
+/// One open connection, counted until the thread serving it ends
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    fn take(open: &Arc<AtomicUsize>) -> Self {
+        open.fetch_add(1, Ordering::AcqRel);
+        ConnectionSlot(Arc::clone(open))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Client for a running `4wn daemon`
+pub struct Client {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl Client {
+    /// Connects to a daemon listening on `path`
+    pub fn connect(path: impl AsRef<Path>) -> Result<Self> {
+        let stream = UnixStream::connect(path)?;
+        Ok(Client {
+            writer: stream.try_clone()?,
+            reader: BufReader::new(stream),
+        })
+    }
+
+    /// Connects to a daemon on the default socket path
+    pub fn connect_default() -> Result<Self> {
+        Self::connect(default_socket_path())
+    }
+
+    /// Encodes an IP address via the daemon
+    pub fn encode(&mut self, address: &str) -> Result<String> {
+        self.request("ENCODE", address)
+    }
+
+    /// Decodes words via the daemon
+    pub fn decode(&mut self, words: &str) -> Result<String> {
+        self.request("DECODE", words)
+    }
+
+    /// Checks that the daemon is alive
+    pub fn ping(&mut self) -> Result<()> {
+        self.request("PING", "").map(|_| ())
+    }
+
+    fn request(&mut self, command: &str, argument: &str) -> Result<String> {
+        if argument.contains('\n') {
+            return Err(FourWordError::invalid_value(
+                "request argument",
+                argument,
+                "must not contain newlines",
+            ));
+        }
+        writeln!(self.writer, "{command} {argument}")?;
+        self.writer.flush()?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(FourWordError::Daemon(
+                "daemon closed the connection".to_string(),
+            ));
+        }
+
+        let line = line.trim_end_matches(['\r', '\n']);
+        if let Some(value) = line.strip_prefix("OK ") {
+            Ok(value.to_string())
+        } else if let Some(message) = line.strip_prefix("ERR ") {
+            Err(FourWordError::Daemon(message.to_string()))
+        } else {
+            Err(FourWordError::Daemon(format!("malformed response: {line}")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_line() {
+        let daemon = Daemon::new().unwrap();
+        assert_eq!(daemon.handle_line("PING"), "OK PONG");
+
+        let encoded = daemon.handle_line("ENCODE 192.168.1.1:443");
+        let words = encoded.strip_prefix("OK ").unwrap();
+        assert_eq!(words.split(' ').count(), 4);
+        assert_eq!(
+            daemon.handle_line(&format!("DECODE {words}")),
+            "OK 192.168.1.1:443"
+        );
+
+        assert!(daemon.handle_line("ENCODE not-an-ip").starts_with("ERR "));
+        assert!(daemon.handle_line("BOGUS").starts_with("ERR "));
+    }
+
+    #[test]
+    fn test_client_roundtrip_over_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SOCKET_NAME);
+        let listener = Daemon::bind(&path).unwrap();
+        let daemon = Daemon::new().unwrap();
+        thread::spawn(move || daemon.serve(listener));
+
+        let mut client = Client::connect(&path).unwrap();
+        client.ping().unwrap();
+
+        let words = client.encode("[::1]:8080").unwrap();
+        assert_eq!(client.decode(&words).unwrap(), "[::1]:8080");
+
+        // Errors are reported without dropping the connection
+        assert!(matches!(
+            client.encode("garbage"),
+            Err(FourWordError::Daemon(_))
+        ));
+        client.ping().unwrap();
+
+        // A second daemon must not hijack a live socket
+        assert!(Daemon::bind(&path).is_err());
+    }
+
+    #[test]
+    fn test_bind_leaves_other_files_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SOCKET_NAME);
+        std::fs::write(&path, "not a socket").unwrap();
+        assert!(matches!(Daemon::bind(&path), Err(FourWordError::Daemon(_))));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "not a socket");
+
+        // A stale socket with no daemon behind it is replaced
+        let stale = dir.path().join("stale.sock");
+        drop(UnixListener::bind(&stale).unwrap());
+        assert!(Daemon::bind(&stale).is_ok());
+    }
+
+    #[test]
+    fn test_connection_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SOCKET_NAME);
+        let listener = Daemon::bind(&path).unwrap();
+        let daemon = Daemon::new().unwrap().with_max_connections(1);
+        thread::spawn(move || daemon.serve(listener));
+
+        let mut first = Client::connect(&path).unwrap();
+        first.ping().unwrap();
+
+        let mut busy = String::new();
+        BufReader::new(UnixStream::connect(&path).unwrap())
+            .read_line(&mut busy)
+            .unwrap();
+        assert!(busy.starts_with("ERR daemon busy"), "{busy}");
+
+        // The slot is released once the first client disconnects
+        drop(first);
+        let reconnected = (0..100).any(|_| {
+            thread::sleep(std::time::Duration::from_millis(10));
+            Client::connect(&path)
+                .and_then(|mut client| client.ping())
+                .is_ok()
+        });
+        assert!(reconnected);
+    }
+}
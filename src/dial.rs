@@ -0,0 +1,199 @@
+//! Reachability probes for decoded endpoints.
+//!
+//! Support staff reading a phrase off a screen want to know whether the
+//! service behind it answers, not just what address it is. [`probe`] opens a
+//! TCP connection, or sends an empty UDP datagram, to an address and reports
+//! how it answered and how long that took; `4wn dial` does this for a phrase,
+//! like `ping` by words.
+//!
+//! A refused connection still proves the host is up, so it is an outcome
+//! rather than an error, with its round-trip time. UDP services often ignore
+//! an empty datagram, so [`ProbeOutcome::NoReply`] over UDP means "open or
+//! filtered". ICMP echo needs raw sockets and therefore privileges, so it is
+//! not offered.
+//!
+//! ```rust,no_run
+//! use four_word_networking::dial::{ProbeOutcome, probe};
+//! use four_word_networking::transport::Protocol;
+//! use std::time::Duration;
+//!
+//! let addr = four_word_networking::decode("ocean thunder falcon star")?;
+//! let result = probe(addr, Protocol::Tcp, Duration::from_secs(3))?;
+//! if result.outcome == ProbeOutcome::Open {
+//!     println!("up, {:.1?}", result.elapsed);
+//! }
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::transport::Protocol;
+use std::fmt;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, UdpSocket};
+use std::time::{Duration, Instant};
+
+/// Port that marks an address without one
+const NO_PORT: u16 = 65535;
+
+/// How an endpoint answered a probe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeOutcome {
+    /// The TCP connection was accepted, or a UDP reply arrived
+    Open,
+    /// The host refused: a TCP reset, or an ICMP port unreachable for UDP
+    Refused,
+    /// Nothing came back before the timeout
+    NoReply,
+}
+
+/// Result of one probe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Probe {
+    /// The address probed
+    pub addr: SocketAddr,
+    /// Protocol the probe used
+    pub protocol: Protocol,
+    /// How the endpoint answered
+    pub outcome: ProbeOutcome,
+    /// Time until the answer, or the timeout for [`ProbeOutcome::NoReply`]
+    pub elapsed: Duration,
+}
+
+impl Probe {
+    /// True when the service answered
+    pub fn is_open(&self) -> bool {
+        self.outcome == ProbeOutcome::Open
+    }
+}
+
+impl fmt::Display for Probe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = self.elapsed.as_secs_f64() * 1000.0;
+        match (self.outcome, self.protocol) {
+            (ProbeOutcome::Open, Protocol::Tcp) => {
+                write!(
+                    f,
+                    "{} {}: connected in {ms:.1} ms",
+                    self.protocol, self.addr
+                )
+            }
+            (ProbeOutcome::Open, _) => {
+                write!(f, "{} {}: reply in {ms:.1} ms", self.protocol, self.addr)
+            }
+            (ProbeOutcome::Refused, _) => write!(
+                f,
+                "{} {}: refused in {ms:.1} ms (host up, port closed)",
+                self.protocol, self.addr
+            ),
+            (ProbeOutcome::NoReply, Protocol::Tcp) => {
+                write!(f, "{} {}: no reply in {ms:.0} ms", self.protocol, self.addr)
+            }
+            (ProbeOutcome::NoReply, _) => write!(
+                f,
+                "{} {}: no reply in {ms:.0} ms (open or filtered)",
+                self.protocol, self.addr
+            ),
+        }
+    }
+}
+
+/// Probes `addr` over `protocol`, waiting at most `timeout` for an answer.
+///
+/// QUIC is probed as UDP. Errors are addresses that cannot be probed (no
+/// port, SCTP) and local failures such as an unreachable network.
+pub fn probe(addr: SocketAddr, protocol: Protocol, timeout: Duration) -> Result<Probe> {
+    if addr.port() == NO_PORT {
+        return Err(FourWordError::unsupported(
+            "dial",
+            format!("{} has no port", addr.ip()),
+        ));
+    }
+    let started = Instant::now();
+    let answer = match protocol {
+        Protocol::Tcp => TcpStream::connect_timeout(&addr, timeout).map(drop),
+        Protocol::Udp | Protocol::Quic => probe_udp(addr, timeout),
+        Protocol::Sctp => {
+            return Err(FourWordError::unsupported("probe sctp", "try tcp or udp"));
+        }
+    };
+    let elapsed = started.elapsed();
+    let outcome = match answer {
+        Ok(()) => ProbeOutcome::Open,
+        Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => ProbeOutcome::Refused,
+        Err(e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+            ) =>
+        {
+            ProbeOutcome::NoReply
+        }
+        Err(e) => return Err(e.into()),
+    };
+    Ok(Probe {
+        addr,
+        protocol,
+        outcome,
+        elapsed,
+    })
+}
+
+/// Sends an empty datagram and waits for any reply
+fn probe_udp(addr: SocketAddr, timeout: Duration) -> io::Result<()> {
+    let local: SocketAddr = match addr {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(local)?;
+    // Connected, so an ICMP port unreachable surfaces as ConnectionRefused
+    socket.connect(addr)?;
+    socket.set_read_timeout(Some(timeout))?;
+    socket.send(&[])?;
+    socket.recv(&mut [0; 1]).map(drop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    #[test]
+    fn test_tcp_open_and_refused() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let open = probe(addr, Protocol::Tcp, TIMEOUT).unwrap();
+        assert!(open.is_open(), "{open}");
+        assert!(
+            open.to_string()
+                .starts_with(&format!("tcp {addr}: connected in"))
+        );
+
+        drop(listener);
+        let refused = probe(addr, Protocol::Tcp, TIMEOUT).unwrap();
+        assert_eq!(refused.outcome, ProbeOutcome::Refused);
+    }
+
+    #[test]
+    fn test_udp_reply() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let mut buf = [0; 16];
+            let (_, from) = server.recv_from(&mut buf).unwrap();
+            server.send_to(b"hi", from).unwrap();
+        });
+        assert!(probe(addr, Protocol::Udp, TIMEOUT).unwrap().is_open());
+    }
+
+    #[test]
+    fn test_unprobeable_addresses() {
+        let no_port: SocketAddr = ([127, 0, 0, 1], NO_PORT).into();
+        assert!(probe(no_port, Protocol::Tcp, TIMEOUT).is_err());
+        let addr: SocketAddr = ([127, 0, 0, 1], 80).into();
+        assert!(probe(addr, Protocol::Sctp, TIMEOUT).is_err());
+    }
+}
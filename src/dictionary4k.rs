@@ -2,76 +2,340 @@
 //!
 //! This module provides a dictionary of exactly 4,096 (2^12) words for encoding
 //! IP addresses using four words. Each word can represent 12 bits of information.
+//!
+//! The word tables are generated at build time from `GOLD_WORDLIST.txt` (and
+//! the `wordlists/` files for enabled `lang-*` features), so the dictionary
+//! involves no statics, lazy initialisation or filesystem access.
+//!
+//! Custom wordlists can also be supplied at runtime with
+//! [`Dictionary4K::from_reader`] or [`Dictionary4K::from_slice`]. Two parties
+//! should compare [`Dictionary4K::fingerprint`]s before exchanging words, since
+//! the same address maps to different words under different lists.
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::language::Language;
+use crate::wordlist::{Wordlist, fingerprint_words};
+use std::cmp::Ordering;
+use std::io::Read;
+use std::sync::Arc;
 
-use once_cell::sync::Lazy;
-use std::collections::HashMap;
+/// Number of words in every dictionary (2^12)
+pub const DICTIONARY_SIZE: usize = 4096;
 
-/// Static dictionary containing exactly 4,096 words
-pub static DICTIONARY: Lazy<Dictionary4K> =
-    Lazy::new(|| Dictionary4K::new().expect("Failed to initialize 4K dictionary"));
+mod english {
+    include!(concat!(env!("OUT_DIR"), "/dictionary_words.rs"));
+}
+
+#[cfg(feature = "lang-es")]
+mod spanish {
+    include!(concat!(env!("OUT_DIR"), "/dictionary_words_es.rs"));
+}
+
+#[cfg(feature = "lang-fr")]
+mod french {
+    include!(concat!(env!("OUT_DIR"), "/dictionary_words_fr.rs"));
+}
+
+/// The built-in English 4,096-word dictionary
+pub const DICTIONARY: Dictionary4K = Dictionary4K::builtin();
 
 /// A dictionary of 4,096 words for four-word encoding
+///
+/// Cloning is cheap: built-in tables are borrowed and custom tables are
+/// shared, and freed with the last clone.
+#[derive(Debug, Clone)]
 pub struct Dictionary4K {
-    /// Words indexed by their position (0-4095)
+    tables: Tables,
+}
+
+/// Backing storage for a dictionary's lookup tables
+#[derive(Debug, Clone)]
+enum Tables {
+    /// Tables generated at build time
+    Builtin {
+        /// Words indexed by their position (0-4095)
+        words: &'static [&'static str; DICTIONARY_SIZE],
+        /// Word indices in lexicographic order, for reverse lookup
+        sorted_indices: &'static [u16; DICTIONARY_SIZE],
+        /// SHA-256 fingerprint computed by the build script
+        fingerprint: &'static [u8; 32],
+    },
+    /// Tables loaded at runtime
+    Custom(Arc<CustomTables>),
+}
+
+/// Runtime-loaded wordlist with its precomputed fingerprint
+#[derive(Debug)]
+struct CustomTables {
     words: Vec<String>,
-    /// Reverse lookup: word -> index
-    word_to_index: HashMap<String, u16>,
+    sorted_indices: Vec<u16>,
+    fingerprint: [u8; 32],
 }
 
 impl Dictionary4K {
-    /// Creates a new dictionary from the embedded word list
-    pub fn new() -> Result<Self, String> {
-        let wordlist = include_str!("../GOLD_WORDLIST.txt");
-        let words: Vec<String> = wordlist
+    /// Returns the built-in English dictionary
+    pub const fn builtin() -> Self {
+        Self::from_tables(
+            &english::WORDS,
+            &english::SORTED_INDICES,
+            &english::FINGERPRINT,
+        )
+    }
+
+    /// Returns the built-in dictionary for `language`
+    pub const fn for_language(language: Language) -> Self {
+        match language {
+            Language::English => Self::builtin(),
+            #[cfg(feature = "lang-es")]
+            Language::Spanish => Self::from_tables(
+                &spanish::WORDS,
+                &spanish::SORTED_INDICES,
+                &spanish::FINGERPRINT,
+            ),
+            #[cfg(feature = "lang-fr")]
+            Language::French => Self::from_tables(
+                &french::WORDS,
+                &french::SORTED_INDICES,
+                &french::FINGERPRINT,
+            ),
+        }
+    }
+
+    const fn from_tables(
+        words: &'static [&'static str; DICTIONARY_SIZE],
+        sorted_indices: &'static [u16; DICTIONARY_SIZE],
+        fingerprint: &'static [u8; 32],
+    ) -> Self {
+        Dictionary4K {
+            tables: Tables::Builtin {
+                words,
+                sorted_indices,
+                fingerprint,
+            },
+        }
+    }
+
+    /// Loads a custom wordlist with one word per line.
+    ///
+    /// Blank lines are skipped and words are trimmed and lowercased. The list
+    /// is then validated as described in [`Dictionary4K::from_slice`].
+    pub fn from_reader(mut reader: impl Read) -> Result<Self> {
+        let mut raw = String::new();
+        reader.read_to_string(&mut raw)?;
+        let words: Vec<&str> = raw
             .lines()
-            .filter(|line| !line.trim().is_empty())
-            .take(4096)
-            .map(|s| s.trim().to_lowercase())
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
             .collect();
+        Self::from_slice(&words)
+    }
 
-        if words.len() != 4096 {
-            return Err(format!(
-                "Dictionary must contain exactly 4096 words, found {}",
+    /// Builds a custom dictionary from words in index order.
+    ///
+    /// The list must contain exactly 4,096 unique words made only of ASCII
+    /// letters (case is folded), and no word may be a prefix of another so
+    /// that words stay unambiguous when typed or autocompleted. The built-in
+    /// lists predate that rule, so a list matching one of their fingerprints
+    /// is accepted as it is.
+    pub fn from_slice<S: AsRef<str>>(words: &[S]) -> Result<Self> {
+        if words.len() != DICTIONARY_SIZE {
+            return Err(FourWordError::DictionaryError(format!(
+                "expected exactly {DICTIONARY_SIZE} words, got {}",
                 words.len()
-            ));
+            )));
+        }
+
+        let words: Vec<String> = words
+            .iter()
+            .map(|word| word.as_ref().trim().to_ascii_lowercase())
+            .collect();
+        if let Some((index, word)) = words
+            .iter()
+            .enumerate()
+            .find(|(_, word)| word.is_empty() || !word.bytes().all(|b| b.is_ascii_lowercase()))
+        {
+            return Err(FourWordError::DictionaryError(format!(
+                "word {index} ({word:?}) must consist of ASCII letters only"
+            )));
         }
 
-        let mut word_to_index = HashMap::with_capacity(4096);
-        for (index, word) in words.iter().enumerate() {
-            if word_to_index.insert(word.clone(), index as u16).is_some() {
-                return Err(format!("Duplicate word found: {word}"));
+        let mut sorted_indices: Vec<u16> = (0..DICTIONARY_SIZE as u16).collect();
+        sorted_indices.sort_by(|&a, &b| words[a as usize].cmp(&words[b as usize]));
+        let fingerprint = fingerprint_words(words.iter().map(String::as_str));
+        let builtin = Language::ALL
+            .iter()
+            .any(|language| language.dictionary().fingerprint() == fingerprint);
+
+        // In sorted order a word is followed by every word it prefixes, so
+        // checking neighbours catches both duplicates and prefix collisions
+        for pair in sorted_indices.windows(2) {
+            let (first, second) = (&words[pair[0] as usize], &words[pair[1] as usize]);
+            if first == second {
+                return Err(FourWordError::DictionaryError(format!(
+                    "duplicate word: {first}"
+                )));
+            }
+            if !builtin && second.starts_with(first.as_str()) {
+                return Err(FourWordError::DictionaryError(format!(
+                    "'{first}' is a prefix of '{second}'"
+                )));
             }
         }
 
         Ok(Dictionary4K {
-            words,
-            word_to_index,
+            tables: Tables::Custom(Arc::new(CustomTables {
+                words,
+                sorted_indices,
+                fingerprint,
+            })),
         })
     }
 
+    /// Creates a new dictionary from the embedded word list
+    ///
+    /// The list is validated at build time, so this never fails for the
+    /// built-in words.
+    pub fn new() -> std::result::Result<Self, String> {
+        Ok(Self::builtin())
+    }
+
     /// Gets a word by its index (0-4095)
     pub fn get_word(&self, index: u16) -> Option<&str> {
-        if index < 4096 {
-            self.words.get(index as usize).map(|s| s.as_str())
-        } else {
-            None
+        match &self.tables {
+            Tables::Builtin { words, .. } => words.get(index as usize).copied(),
+            Tables::Custom(tables) => tables.words.get(index as usize).map(String::as_str),
         }
     }
 
-    /// Gets the index of a word (0-4095)
+    /// Gets the index of a word (0-4095), ignoring ASCII case
     pub fn get_index(&self, word: &str) -> Option<u16> {
-        self.word_to_index.get(&word.to_lowercase()).copied()
+        let sorted_indices = self.sorted_indices();
+        sorted_indices
+            .binary_search_by(|&index| compare_ignore_case(self.word_at(index), word))
+            .ok()
+            .map(|position| sorted_indices[position])
+    }
+
+    /// Words starting with `prefix` (ignoring case), in alphabetical order.
+    ///
+    /// The words are found by binary search over the sorted index table, so
+    /// this costs two lookups plus the words returned.
+    pub fn words_with_prefix(&self, prefix: &str) -> impl Iterator<Item = &str> {
+        self.prefix_indices(prefix)
+            .iter()
+            .map(|&index| self.word_at(index))
+    }
+
+    /// Indices of the words starting with `prefix`, in alphabetical order
+    pub(crate) fn prefix_indices(&self, prefix: &str) -> &[u16] {
+        let prefix = prefix.to_lowercase();
+        let sorted_indices = self.sorted_indices();
+        let start = sorted_indices.partition_point(|&index| self.word_at(index) < prefix.as_str());
+        let len = sorted_indices[start..]
+            .partition_point(|&index| self.word_at(index).starts_with(&prefix));
+        &sorted_indices[start..start + len]
+    }
+
+    /// Word at `index` when it lives in a built-in table, so outlives `self`
+    pub(crate) fn static_word(&self, index: u16) -> Option<&'static str> {
+        match &self.tables {
+            Tables::Builtin { words, .. } => words.get(index as usize).copied(),
+            Tables::Custom(_) => None,
+        }
+    }
+
+    /// Checks whether `word` is in the dictionary, ignoring ASCII case
+    pub fn contains(&self, word: &str) -> bool {
+        self.get_index(word).is_some()
     }
 
     /// Returns the total number of words (always 4096)
     pub fn len(&self) -> usize {
-        self.words.len()
+        DICTIONARY_SIZE
     }
 
     /// Checks if the dictionary is empty (always false for valid dictionary)
     pub fn is_empty(&self) -> bool {
-        self.words.is_empty()
+        false
+    }
+
+    /// Returns a stable SHA-256 fingerprint of the wordlist.
+    ///
+    /// The hash covers every word in index order, so two dictionaries with the
+    /// same fingerprint encode every address to the same words. It is
+    /// computed once, by the build script for built-in lists.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        match &self.tables {
+            Tables::Builtin { fingerprint, .. } => **fingerprint,
+            Tables::Custom(tables) => tables.fingerprint,
+        }
+    }
+
+    /// Returns the fingerprint as lowercase hex, for display and comparison
+    pub fn fingerprint_hex(&self) -> String {
+        hex::encode(self.fingerprint())
     }
+
+    /// Checks whether two dictionaries map every index to the same word
+    pub fn is_compatible_with(&self, other: &Dictionary4K) -> bool {
+        self.fingerprint() == other.fingerprint()
+    }
+
+    /// Fails with [`FourWordError::DictionaryMismatch`] unless the
+    /// fingerprint is `expected`, given in hex of either case
+    pub fn expect_fingerprint(&self, expected: &str) -> Result<()> {
+        let actual = self.fingerprint_hex();
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            return Err(FourWordError::DictionaryMismatch {
+                expected: expected.trim().to_ascii_lowercase(),
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    /// Word indices in lexicographic order
+    fn sorted_indices(&self) -> &[u16] {
+        match &self.tables {
+            Tables::Builtin { sorted_indices, .. } => &sorted_indices[..],
+            Tables::Custom(tables) => &tables.sorted_indices[..],
+        }
+    }
+
+    /// Word at a valid index; indices come from the sorted table so are in range
+    fn word_at(&self, index: u16) -> &str {
+        match &self.tables {
+            Tables::Builtin { words, .. } => words[index as usize],
+            Tables::Custom(tables) => &tables.words[index as usize],
+        }
+    }
+}
+
+impl Wordlist for Dictionary4K {
+    fn get_word(&self, index: u16) -> Option<&str> {
+        Dictionary4K::get_word(self, index)
+    }
+
+    fn get_index(&self, word: &str) -> Option<u16> {
+        Dictionary4K::get_index(self, word)
+    }
+
+    fn len(&self) -> usize {
+        DICTIONARY_SIZE
+    }
+
+    fn fingerprint(&self) -> [u8; 32] {
+        Dictionary4K::fingerprint(self)
+    }
+}
+
+/// Compares a lowercase dictionary word with arbitrary-case input without allocating
+fn compare_ignore_case(dictionary_word: &str, input: &str) -> Ordering {
+    dictionary_word
+        .bytes()
+        .cmp(input.bytes().map(|b| b.to_ascii_lowercase()))
 }
 
 #[cfg(test)]
@@ -112,4 +376,139 @@ mod tests {
         assert_eq!(dict.get_index(word), Some(0));
         assert_eq!(dict.get_index(&word.to_uppercase()), Some(0));
     }
+
+    #[test]
+    fn test_every_word_round_trips() {
+        let dict = DICTIONARY;
+        for index in 0..4096u16 {
+            let word = dict.get_word(index).unwrap();
+            assert_eq!(dict.get_index(word), Some(index), "word {word}");
+        }
+        assert_eq!(dict.get_index("notarealword"), None);
+        assert_eq!(dict.get_index(""), None);
+    }
+
+    /// A valid custom list: base-26 spellings padded to a fixed width can't prefix each other
+    fn custom_words() -> Vec<String> {
+        (0..DICTIONARY_SIZE)
+            .map(|mut n| {
+                let mut word = String::from("w");
+                for _ in 0..3 {
+                    word.push((b'a' + (n % 26) as u8) as char);
+                    n /= 26;
+                }
+                word
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_custom_dictionary_from_reader() {
+        let words = custom_words();
+        let dict = Dictionary4K::from_reader(words.join("\n").as_bytes()).unwrap();
+        assert_eq!(dict.get_word(5), Some(words[5].as_str()));
+        assert_eq!(dict.get_index(&words[4095].to_uppercase()), Some(4095));
+        assert!(!dict.contains("ocean"));
+
+        let again = Dictionary4K::from_slice(&words).unwrap();
+        assert!(dict.is_compatible_with(&again));
+        assert!(!dict.is_compatible_with(&DICTIONARY));
+    }
+
+    #[test]
+    fn test_custom_dictionary_validation() {
+        let mut words = custom_words();
+        assert!(Dictionary4K::from_slice(&words[..4095]).is_err());
+
+        words[1] = words[0].clone();
+        assert!(Dictionary4K::from_slice(&words).is_err());
+
+        words[1] = "wa-1".to_string();
+        assert!(Dictionary4K::from_slice(&words).is_err());
+
+        words[1] = "waa".to_string(); // prefix of "waaa"
+        assert!(Dictionary4K::from_slice(&words).is_err());
+
+        // The built-in lists have prefixes but are accepted as they are
+        let english: Vec<&str> = (0..4096)
+            .filter_map(|i| DICTIONARY.static_word(i))
+            .collect();
+        let dict = Dictionary4K::from_slice(&english).unwrap();
+        assert!(dict.is_compatible_with(&DICTIONARY));
+        assert_eq!(dict.get_index(english[9]), Some(9));
+    }
+
+    #[test]
+    fn test_clones_share_custom_tables() {
+        let custom = Dictionary4K::from_slice(&custom_words()).unwrap();
+        let clone = custom.clone();
+        assert!(custom.is_compatible_with(&clone));
+        assert_eq!(clone.get_word(7), custom.get_word(7));
+        assert!(matches!(
+            (&custom.tables, &clone.tables),
+            (Tables::Custom(a), Tables::Custom(b)) if Arc::ptr_eq(a, b)
+        ));
+        assert_eq!(custom.static_word(7), None);
+    }
+
+    #[test]
+    fn test_words_with_prefix() {
+        let dict = DICTIONARY;
+        let words: Vec<&str> = dict.words_with_prefix("OCE").collect();
+        assert!(words.contains(&"ocean"), "{words:?}");
+        assert!(words.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(words.iter().all(|word| word.starts_with("oce")));
+        let expected = (0..4096u16)
+            .filter_map(|index| dict.get_word(index))
+            .filter(|word| word.starts_with("oce"))
+            .count();
+        assert_eq!(words.len(), expected);
+
+        assert_eq!(dict.words_with_prefix("").count(), DICTIONARY_SIZE);
+        assert_eq!(dict.words_with_prefix("xqzv").count(), 0);
+
+        assert_eq!(dict.static_word(1), dict.get_word(1));
+
+        let words = custom_words();
+        let dict = Dictionary4K::from_slice(&words).unwrap();
+        let expected = words.iter().filter(|word| word.starts_with("wab")).count();
+        assert_eq!(dict.words_with_prefix("WAB").count(), expected);
+        let exact: Vec<&str> = dict.words_with_prefix(&words[7]).collect();
+        assert_eq!(exact, [&words[7]]);
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        assert_eq!(
+            DICTIONARY.fingerprint(),
+            Dictionary4K::builtin().fingerprint()
+        );
+        assert_eq!(DICTIONARY.fingerprint_hex().len(), 64);
+        // The build script hashes exactly as the runtime does
+        for &language in Language::ALL {
+            let dict = language.dictionary();
+            let words = (0..4096).filter_map(|index| dict.get_word(index));
+            assert_eq!(dict.fingerprint(), fingerprint_words(words), "{language}");
+        }
+
+        // Reordering changes the mapping, so it must change the fingerprint
+        let mut words = custom_words();
+        let original = Dictionary4K::from_slice(&words).unwrap();
+        words.swap(0, 1);
+        let swapped = Dictionary4K::from_slice(&words).unwrap();
+        assert_ne!(original.fingerprint(), swapped.fingerprint());
+
+        let expected = original.fingerprint_hex().to_uppercase();
+        original.expect_fingerprint(&expected).unwrap();
+        match swapped.expect_fingerprint(&expected) {
+            Err(FourWordError::DictionaryMismatch {
+                expected: e,
+                actual,
+            }) => {
+                assert_eq!(e, original.fingerprint_hex());
+                assert_eq!(actual, swapped.fingerprint_hex());
+            }
+            other => panic!("expected DictionaryMismatch, got {other:?}"),
+        }
+    }
 }
@@ -0,0 +1,512 @@
+//! Quality audit of a 4,096-word dictionary.
+//!
+//! Every word of a dictionary is read aloud, typed and glanced at, so a list
+//! is only as good as its worst pair. [`audit_dictionary`] scans a wordlist
+//! for the problems that make phrases fail in practice:
+//!
+//! - homophones, which a listener cannot tell apart ("weak" and "week")
+//! - visually confusable words, which differ only in glyphs that look alike
+//!   in common fonts ("modern" and "modem")
+//! - offensive terms, which nobody wants to read out as their address
+//! - near neighbours, measured as the minimum pairwise edit distance
+//!
+//! The report serializes to JSON, and [`DictionaryAudit::check`] gates a
+//! custom list against an [`AuditPolicy`]:
+//!
+//! ```rust
+//! use four_word_networking::dictionary4k::DICTIONARY;
+//! use four_word_networking::dictionary_audit::{AuditPolicy, audit_dictionary};
+//!
+//! let audit = audit_dictionary(&DICTIONARY);
+//! assert_eq!(audit.min_edit_distance, 1);
+//! audit.check(&AuditPolicy {
+//!     allow_offensive: true,
+//!     ..AuditPolicy::default()
+//! })?;
+//! # #[cfg(feature = "serde")]
+//! println!("{}", audit.to_json()?);
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::fuzzy::edit_distance;
+use crate::normalize::HOMOPHONE_GROUPS;
+use crate::wordlist::Wordlist;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Words rejected as offensive, matched against whole dictionary words
+pub(crate) const OFFENSIVE_WORDS: &[&str] = &[
+    "arse", "ass", "bastard", "bitch", "bollocks", "boob", "butt", "cock", "crap", "cunt", "dick",
+    "dildo", "dyke", "fag", "fuck", "homo", "jizz", "nazi", "nigger", "penis", "piss", "porn",
+    "prick", "pussy", "rape", "retard", "sex", "shit", "slut", "spic", "tit", "turd", "twat",
+    "vagina", "wank", "whore",
+];
+
+/// Glyph sequences that read as another sequence at a glance, most specific first
+const CONFUSABLE_GLYPHS: &[(&str, &str)] = &[
+    ("rn", "m"),
+    ("cl", "d"),
+    ("vv", "w"),
+    ("nn", "m"),
+    ("i", "l"),
+];
+
+/// Thresholds a dictionary must meet to pass [`DictionaryAudit::check`]
+///
+/// The default limits admit any list no worse than the built-in English one,
+/// except that offensive words are refused outright. The built-in list
+/// predates the audit and keeps its two ("sex" and "shit") because replacing
+/// them would change every phrase that uses them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditPolicy {
+    /// Smallest allowed edit distance between any two words
+    pub min_edit_distance: usize,
+    /// Homophone groups tolerated before the list is rejected
+    pub max_homophone_groups: usize,
+    /// Confusable groups tolerated before the list is rejected
+    pub max_confusable_groups: usize,
+    /// Whether offensive words are tolerated
+    pub allow_offensive: bool,
+}
+
+impl Default for AuditPolicy {
+    fn default() -> Self {
+        AuditPolicy {
+            min_edit_distance: 1,
+            max_homophone_groups: 40,
+            max_confusable_groups: 16,
+            allow_offensive: false,
+        }
+    }
+}
+
+impl AuditPolicy {
+    /// A policy that accepts any list of distinct words, for reporting only
+    pub fn permissive() -> Self {
+        AuditPolicy {
+            min_edit_distance: 1,
+            max_homophone_groups: usize::MAX,
+            max_confusable_groups: usize::MAX,
+            allow_offensive: true,
+        }
+    }
+}
+
+/// Result of [`audit_dictionary`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct DictionaryAudit {
+    /// Number of words audited
+    pub words: usize,
+    /// Hex fingerprint of the audited list
+    pub fingerprint: String,
+    /// Groups of words that sound alike, each sorted, groups in order of their first word
+    pub homophones: Vec<Vec<String>>,
+    /// Groups of words that look alike, each sorted, groups in order of their first word
+    pub confusables: Vec<Vec<String>>,
+    /// Offensive words in the list, sorted
+    pub offensive: Vec<String>,
+    /// Smallest edit distance between two different entries (0 for duplicates)
+    pub min_edit_distance: usize,
+    /// Pairs at `min_edit_distance`, each sorted, in lexicographic order
+    pub closest_pairs: Vec<(String, String)>,
+}
+
+impl DictionaryAudit {
+    /// Lists every way this audit falls short of `policy`
+    pub fn violations(&self, policy: &AuditPolicy) -> Vec<String> {
+        let mut violations = Vec::new();
+        if self.min_edit_distance < policy.min_edit_distance {
+            let examples = self.closest_pairs.iter().take(3);
+            violations.push(format!(
+                "minimum edit distance {} is below {} (e.g. {})",
+                self.min_edit_distance,
+                policy.min_edit_distance,
+                examples
+                    .map(|(a, b)| format!("{a}/{b}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        if self.homophones.len() > policy.max_homophone_groups {
+            violations.push(format!(
+                "{} homophone groups exceed the limit of {}",
+                self.homophones.len(),
+                policy.max_homophone_groups
+            ));
+        }
+        if self.confusables.len() > policy.max_confusable_groups {
+            violations.push(format!(
+                "{} confusable groups exceed the limit of {}",
+                self.confusables.len(),
+                policy.max_confusable_groups
+            ));
+        }
+        if !policy.allow_offensive && !self.offensive.is_empty() {
+            violations.push(format!("offensive words: {}", self.offensive.join(", ")));
+        }
+        violations
+    }
+
+    /// Fails with [`FourWordError::DictionaryError`] unless the audit meets `policy`
+    pub fn check(&self, policy: &AuditPolicy) -> Result<()> {
+        let violations = self.violations(policy);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(FourWordError::DictionaryError(format!(
+                "wordlist failed audit: {}",
+                violations.join("; ")
+            )))
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    /// Serializes the audit as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Audits every word of `wordlist`
+pub fn audit_dictionary<W: Wordlist + ?Sized>(wordlist: &W) -> DictionaryAudit {
+    let words: Vec<String> = (0..wordlist.len())
+        .filter_map(|index| wordlist.get_word(u16::try_from(index).ok()?))
+        .map(str::to_lowercase)
+        .collect();
+
+    let mut homophones = group_by_key(&words, phonetic_key);
+    // Spellings the phonetic key cannot relate ("break"/"brake") come from the
+    // curated groups the voice normalizer uses
+    for group in HOMOPHONE_GROUPS {
+        let members: Vec<String> = group
+            .iter()
+            .filter(|member| words.iter().any(|word| word == *member))
+            .map(|member| member.to_string())
+            .collect();
+        let known = |found: &Vec<String>| members.iter().all(|member| found.contains(member));
+        if members.len() > 1 && !homophones.iter().any(known) {
+            homophones.push(members);
+        }
+    }
+    homophones.iter_mut().for_each(|group| group.sort());
+    homophones.sort();
+
+    let mut offensive: Vec<String> = words
+        .iter()
+        .filter(|word| OFFENSIVE_WORDS.contains(&word.as_str()))
+        .cloned()
+        .collect();
+    offensive.sort();
+
+    let (min_edit_distance, closest_pairs) = closest_pairs(&words);
+    DictionaryAudit {
+        words: words.len(),
+        fingerprint: hex::encode(wordlist.fingerprint()),
+        homophones,
+        confusables: group_by_key(&words, visual_key),
+        offensive,
+        min_edit_distance,
+        closest_pairs,
+    }
+}
+
+/// Groups distinct words sharing a key, keeping only groups of two or more
+fn group_by_key(words: &[String], key: impl Fn(&str) -> String) -> Vec<Vec<String>> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for word in words {
+        let group = groups.entry(key(word)).or_default();
+        if !group.contains(word) {
+            group.push(word.clone());
+        }
+    }
+    let mut groups: Vec<Vec<String>> = groups
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .map(|mut group| {
+            group.sort();
+            group
+        })
+        .collect();
+    groups.sort();
+    groups
+}
+
+/// Rough English pronunciation of `word`: silent letters dropped, consonant
+/// spellings unified and long vowels marked, so that "maid" and "made" or
+/// "night" and "knight" share a key while "mad" does not
+pub(crate) fn phonetic_key(word: &str) -> String {
+    let mut word = word.to_string();
+    for (silent, sound) in [
+        ("kn", "n"),
+        ("wr", "r"),
+        ("gn", "n"),
+        ("ps", "s"),
+        ("wh", "w"),
+    ] {
+        if let Some(rest) = word.strip_prefix(silent) {
+            word = format!("{sound}{rest}");
+        }
+    }
+
+    let bytes = word.as_bytes();
+    let is_vowel = |b: u8| b"aeiou".contains(&b);
+    // A final "e" after vowel-consonant lengthens the vowel ("made", "rode")
+    let mut long_vowel = None;
+    let mut end = bytes.len();
+    if bytes.len() > 3
+        && bytes[end - 1] == b'e'
+        && !is_vowel(bytes[end - 2])
+        && is_vowel(bytes[end - 3])
+        && !is_vowel(bytes[end - 4])
+    {
+        long_vowel = Some(end - 3);
+        end -= 1;
+    }
+
+    let mut key = String::with_capacity(end);
+    let mut i = 0;
+    while i < end {
+        let rest = &word[i..end];
+        let (sound, consumed) = if long_vowel == Some(i) {
+            (bytes[i].to_ascii_uppercase() as char, 1)
+        } else if rest.starts_with("igh") {
+            ('I', 3)
+        } else if let Some(&(_, sound)) = [
+            ("ai", 'A'),
+            ("ay", 'A'),
+            ("ee", 'E'),
+            ("ea", 'E'),
+            ("oa", 'O'),
+            ("ow", 'O'),
+            ("oo", 'U'),
+            ("ew", 'U'),
+            ("ue", 'U'),
+            ("ph", 'f'),
+            ("ck", 'k'),
+        ]
+        .iter()
+        .find(|(spelling, _)| rest.starts_with(spelling))
+        {
+            (sound, 2)
+        } else {
+            let sound = match bytes[i] {
+                b'c' if matches!(bytes.get(i + 1), Some(b'e' | b'i' | b'y')) => 's',
+                b'c' | b'q' => 'k',
+                b'z' => 's',
+                other => other as char,
+            };
+            (sound, 1)
+        };
+        // Doubled consonants sound single ("butter", "buter")
+        if !(key.ends_with(sound) && sound.is_ascii_lowercase() && !is_vowel(sound as u8)) {
+            key.push(sound);
+        }
+        i += consumed;
+    }
+    key
+}
+
+/// `word` with glyph sequences that look alike folded together
+fn visual_key(word: &str) -> String {
+    let mut key = String::with_capacity(word.len());
+    let mut rest = word;
+    while !rest.is_empty() {
+        match CONFUSABLE_GLYPHS
+            .iter()
+            .find(|(glyphs, _)| rest.starts_with(glyphs))
+        {
+            Some((glyphs, folded)) => {
+                key.push_str(folded);
+                rest = &rest[glyphs.len()..];
+            }
+            None => {
+                let c = rest.chars().next().unwrap_or_default();
+                key.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    key
+}
+
+/// Finds the smallest edit distance between two entries and every pair at it
+fn closest_pairs(words: &[String]) -> (usize, Vec<(String, String)>) {
+    let mut sorted: Vec<&str> = words.iter().map(String::as_str).collect();
+    sorted.sort_unstable();
+
+    let mut best = usize::MAX;
+    let mut pairs = Vec::new();
+    // Close pairs are the norm in a 4,096-word list, so a cheap check for one
+    // edit finds them without computing every full distance
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for word in &sorted {
+        *counts.entry(word).or_default() += 1;
+    }
+    for (&word, &count) in &counts {
+        if count > 1 {
+            best = 0;
+            pairs.push((word.to_string(), word.to_string()));
+        }
+    }
+    if best > 0 {
+        for (i, first) in sorted.iter().enumerate() {
+            for second in &sorted[i + 1..] {
+                if first != second && within_one_edit(first.as_bytes(), second.as_bytes()) {
+                    best = 1;
+                    pairs.push((first.to_string(), second.to_string()));
+                }
+            }
+        }
+    }
+    if best == usize::MAX {
+        for (i, first) in sorted.iter().enumerate() {
+            for second in &sorted[i + 1..] {
+                if first.len().abs_diff(second.len()) > best {
+                    continue;
+                }
+                let distance = edit_distance(first, second);
+                if distance < best {
+                    best = distance;
+                    pairs.clear();
+                }
+                if distance == best {
+                    pairs.push((first.to_string(), second.to_string()));
+                }
+            }
+        }
+    }
+    pairs.sort();
+    (if best == usize::MAX { 0 } else { best }, pairs)
+}
+
+/// Whether `a` and `b` are at most one edit apart, counting an adjacent
+/// transposition as one edit like [`edit_distance`]
+fn within_one_edit(a: &[u8], b: &[u8]) -> bool {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if long.len() - short.len() > 1 {
+        return false;
+    }
+    let prefix = short.iter().zip(long).take_while(|(x, y)| x == y).count();
+    if short.len() == long.len() {
+        let suffix = |skip: usize| short[prefix + skip..] == long[prefix + skip..];
+        prefix == short.len()
+            || suffix(1)
+            || (prefix + 1 < short.len()
+                && short[prefix] == long[prefix + 1]
+                && short[prefix + 1] == long[prefix]
+                && suffix(2))
+    } else {
+        short[prefix..] == long[prefix + 1..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary4k::{DICTIONARY, DICTIONARY_SIZE, Dictionary4K};
+
+    /// A valid custom dictionary: "x" followed by three letters, with `words` in front
+    fn dictionary_with(words: &[&str]) -> Dictionary4K {
+        let mut list: Vec<String> = words.iter().map(|word| word.to_string()).collect();
+        let letters = b"bcdfghjklmnpqrstvwxz";
+        let mut filler = (0..letters.len().pow(3)).map(|n| {
+            let letter = |k: u32| letters[n / letters.len().pow(k) % letters.len()] as char;
+            format!("x{}{}{}", letter(2), letter(1), letter(0))
+        });
+        while list.len() < DICTIONARY_SIZE {
+            list.push(filler.next().unwrap());
+        }
+        Dictionary4K::from_slice(&list).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_metrics() {
+        let audit = audit_dictionary(&DICTIONARY);
+        assert_eq!(audit.words, DICTIONARY_SIZE);
+        assert_eq!(audit.fingerprint, DICTIONARY.fingerprint_hex());
+        assert_eq!(audit.min_edit_distance, 1);
+        assert!(!audit.closest_pairs.is_empty());
+        assert!(
+            audit
+                .homophones
+                .contains(&vec!["meat".to_string(), "meet".to_string()])
+        );
+        assert_eq!(audit.offensive, ["sex", "shit"]);
+        assert_eq!(audit.violations(&AuditPolicy::default()).len(), 1);
+        audit
+            .check(&AuditPolicy {
+                allow_offensive: true,
+                ..AuditPolicy::default()
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_findings() {
+        let audit = audit_dictionary(&dictionary_with(&[
+            "maid", "made", "night", "knight", "break", "brake", "modern", "modem", "fuck",
+        ]));
+        for group in [["made", "maid"], ["knight", "night"], ["brake", "break"]] {
+            assert!(audit.homophones.contains(&group.map(String::from).to_vec()));
+        }
+        assert!(
+            audit
+                .confusables
+                .contains(&vec!["modem".to_string(), "modern".to_string()])
+        );
+        assert_eq!(audit.offensive, ["fuck"]);
+        assert_eq!(audit.min_edit_distance, 1);
+
+        let violations = audit.violations(&AuditPolicy {
+            max_homophone_groups: 0,
+            ..AuditPolicy::default()
+        });
+        assert_eq!(violations.len(), 2, "{violations:?}");
+        assert!(audit.check(&AuditPolicy::permissive()).is_ok());
+        assert!(matches!(
+            audit.check(&AuditPolicy::default()),
+            Err(FourWordError::DictionaryError(_))
+        ));
+    }
+
+    #[test]
+    fn test_phonetic_key() {
+        assert_eq!(phonetic_key("made"), phonetic_key("maid"));
+        assert_eq!(phonetic_key("week"), phonetic_key("weak"));
+        assert_eq!(phonetic_key("phone"), phonetic_key("fone"));
+        assert_ne!(phonetic_key("mad"), phonetic_key("made"));
+        assert_ne!(phonetic_key("mad"), phonetic_key("mud"));
+    }
+
+    #[test]
+    fn test_edit_distance_search() {
+        for (a, b) in [("ocean", "ocaen"), ("ocean", "oceans"), ("ocean", "otean")] {
+            assert!(within_one_edit(a.as_bytes(), b.as_bytes()), "{a} {b}");
+            assert_eq!(edit_distance(a, b), 1);
+        }
+        assert!(!within_one_edit(b"ocean", b"acme"));
+        assert!(!within_one_edit(b"abcd", b"badc"));
+
+        let words = ["alpha", "bravo", "delta"].map(String::from);
+        assert_eq!(
+            closest_pairs(&words),
+            (4, vec![("alpha".to_string(), "delta".to_string())])
+        );
+        let words = ["alpha", "alpha", "bravo"].map(String::from);
+        assert_eq!(closest_pairs(&words).0, 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_audit_json() {
+        let json = audit_dictionary(&DICTIONARY).to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["words"], DICTIONARY_SIZE);
+        assert!(value["closest_pairs"][0].is_array());
+    }
+}
@@ -0,0 +1,263 @@
+//! LAN advertisement and discovery by phrase over mDNS / DNS-SD.
+//!
+//! [`Discovery::advertise`] registers a service whose DNS-SD instance name is
+//! its phrase, on the host `<phrase-slug>.local.`, with the full phrase in a
+//! `words` TXT record. [`Discovery::browse`] collects the peers advertising
+//! the same service type and returns each one's phrase and decoded socket
+//! address, so the words work as LAN identifiers rather than display strings.
+//!
+//! DNS labels are limited to 63 bytes. Longer phrases (most IPv6 ones) are
+//! shortened to the leading words plus an 8-digit hash of the whole phrase;
+//! the TXT record always carries the full phrase.
+//!
+//! ```rust,no_run
+//! use four_word_networking::discovery::Discovery;
+//! use std::time::Duration;
+//!
+//! let discovery = Discovery::new()?;
+//! let advertised = discovery.advertise("192.168.1.20:8080".parse()?)?;
+//! println!("advertising as {advertised}");
+//!
+//! for peer in discovery.browse(Duration::from_secs(3))? {
+//!     println!("{} -> {}", peer.address, peer.address.addr());
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::slug::slug;
+use crate::word_address::WordSocketAddr;
+use mdns_sd::{ResolvedService, ServiceDaemon, ServiceEvent, ServiceInfo};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+/// DNS-SD service type used unless another is configured
+pub const SERVICE_TYPE: &str = "_4wn._tcp.local.";
+
+/// TXT record key holding the full phrase
+pub const WORDS_KEY: &str = "words";
+
+/// Longest DNS label in bytes
+const MAX_LABEL_LEN: usize = 63;
+
+/// Hex digits of the phrase hash appended to shortened names
+const HASH_DIGITS: usize = 8;
+
+/// A service found on the LAN
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Peer {
+    /// DNS-SD full name, e.g. `ocean thunder falcon star._4wn._tcp.local.`
+    pub fullname: String,
+    /// The advertised phrase and the socket address it decodes to
+    pub address: WordSocketAddr,
+}
+
+/// mDNS responder and browser for one DNS-SD service type
+pub struct Discovery {
+    daemon: ServiceDaemon,
+    service_type: String,
+}
+
+impl Discovery {
+    /// Starts an mDNS daemon for [`SERVICE_TYPE`]
+    pub fn new() -> Result<Self> {
+        Self::with_service_type(SERVICE_TYPE)
+    }
+
+    /// Starts an mDNS daemon for an application-specific service type such as
+    /// `_myapp._udp.local.`
+    pub fn with_service_type(service_type: &str) -> Result<Self> {
+        let daemon = ServiceDaemon::new().map_err(discovery_error)?;
+        Ok(Discovery {
+            daemon,
+            service_type: service_type.to_string(),
+        })
+    }
+
+    /// Returns the service type advertised and browsed
+    pub fn service_type(&self) -> &str {
+        &self.service_type
+    }
+
+    /// Advertises a service listening on `addr` under its English phrase.
+    ///
+    /// `addr` must be the address peers should dial, so the unspecified
+    /// address is rejected. The advertisement lasts until
+    /// [`withdraw`](Self::withdraw) or until the `Discovery` is dropped.
+    pub fn advertise(&self, addr: SocketAddr) -> Result<WordSocketAddr> {
+        if addr.ip().is_unspecified() {
+            return Err(FourWordError::unsupported(
+                "advertise",
+                format!("{addr} is unspecified; use the address peers should dial"),
+            ));
+        }
+        let address = WordSocketAddr::new(addr)?;
+        let instance = instance_name(address.phrase());
+        let host = format!("{}.local.", slug(&instance));
+        let info = ServiceInfo::new(
+            &self.service_type,
+            &instance,
+            &host,
+            addr.ip(),
+            addr.port(),
+            &[(WORDS_KEY, address.phrase())][..],
+        )
+        .map_err(discovery_error)?;
+        self.daemon.register(info).map_err(discovery_error)?;
+        Ok(address)
+    }
+
+    /// Stops advertising `address`
+    pub fn withdraw(&self, address: &WordSocketAddr) -> Result<()> {
+        let fullname = format!("{}.{}", instance_name(address.phrase()), self.service_type);
+        self.daemon.unregister(&fullname).map_err(discovery_error)?;
+        Ok(())
+    }
+
+    /// Browses the LAN for `timeout` and returns the peers found, sorted by phrase.
+    ///
+    /// Services whose phrase does not decode, or decodes to an address they
+    /// are not actually advertising, are skipped.
+    pub fn browse(&self, timeout: Duration) -> Result<Vec<Peer>> {
+        let events = self
+            .daemon
+            .browse(&self.service_type)
+            .map_err(discovery_error)?;
+        let deadline = Instant::now() + timeout;
+        let mut peers = HashMap::new();
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            match events.recv_timeout(remaining) {
+                Ok(ServiceEvent::ServiceResolved(service)) => {
+                    if let Some(peer) = peer_from(&service) {
+                        peers.insert(peer.fullname.clone(), peer);
+                    }
+                }
+                Ok(ServiceEvent::ServiceRemoved(_, fullname)) => {
+                    peers.remove(&fullname);
+                }
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+        // Browsing is restarted by the next call; a failure here leaves nothing to clean up
+        let _ = self.daemon.stop_browse(&self.service_type);
+
+        let mut peers: Vec<Peer> = peers.into_values().collect();
+        peers.sort_by(|a, b| a.address.phrase().cmp(b.address.phrase()));
+        Ok(peers)
+    }
+}
+
+impl Drop for Discovery {
+    fn drop(&mut self) {
+        // Stops the daemon thread, which withdraws its advertisements
+        let _ = self.daemon.shutdown();
+    }
+}
+
+/// DNS-SD instance name for `phrase`, shortened to fit in one label
+fn instance_name(phrase: &str) -> String {
+    if phrase.len() <= MAX_LABEL_LEN {
+        return phrase.to_string();
+    }
+    let hash = hex::encode(Sha256::digest(phrase.as_bytes()));
+    let budget = MAX_LABEL_LEN - HASH_DIGITS - 1;
+    let mut name = String::with_capacity(MAX_LABEL_LEN);
+    for word in phrase.split(' ') {
+        if name.len() + word.len() + 1 > budget {
+            break;
+        }
+        name.push_str(word);
+        name.push(' ');
+    }
+    name.push_str(&hash[..HASH_DIGITS]);
+    name
+}
+
+/// Builds a peer from a resolved service, if its phrase matches what it advertises
+fn peer_from(service: &ResolvedService) -> Option<Peer> {
+    let words = service.get_property_val_str(WORDS_KEY)?;
+    let addresses: Vec<IpAddr> = service
+        .get_addresses()
+        .iter()
+        .map(|ip| ip.to_ip_addr())
+        .collect();
+    check_peer(&service.fullname, words, service.port, &addresses)
+}
+
+/// Decodes `words` and checks them against the advertised port and addresses
+fn check_peer(fullname: &str, words: &str, port: u16, addresses: &[IpAddr]) -> Option<Peer> {
+    let address = WordSocketAddr::from_phrase(words).ok()?;
+    let addr = address.addr();
+    (addr.port() == port && addresses.contains(&addr.ip())).then(|| Peer {
+        fullname: fullname.to_string(),
+        address,
+    })
+}
+
+/// Wraps an mDNS failure
+fn discovery_error(e: mdns_sd::Error) -> FourWordError {
+    FourWordError::Discovery(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instance_names_fit_a_label() {
+        let short = crate::encode(([192, 168, 1, 20], 8080)).unwrap();
+        assert_eq!(instance_name(&short), short);
+
+        let long = "abcdefghijklmn ".repeat(6);
+        let long = long.trim_end();
+        let name = instance_name(long);
+        assert!(name.len() <= MAX_LABEL_LEN, "{name}");
+        assert!(name.starts_with("abcdefghijklmn abcdefghijklmn abcdefghijklmn "));
+        assert_ne!(name, instance_name(&format!("{long} x")));
+    }
+
+    #[test]
+    fn test_check_peer_requires_matching_address() {
+        let addr: SocketAddr = "192.168.1.20:8080".parse().unwrap();
+        let words = crate::encode(addr).unwrap();
+        let name = format!("{words}.{SERVICE_TYPE}");
+
+        let peer = check_peer(&name, &words, 8080, &[addr.ip()]).unwrap();
+        assert_eq!(peer.address.addr(), addr);
+        assert_eq!(peer.fullname, name);
+
+        // Words that disagree with the records are not trusted
+        assert!(check_peer(&name, &words, 9090, &[addr.ip()]).is_none());
+        assert!(check_peer(&name, &words, 8080, &["10.0.0.1".parse().unwrap()]).is_none());
+        assert!(check_peer(&name, "not real words", 8080, &[addr.ip()]).is_none());
+    }
+
+    #[test]
+    fn test_advertise_rejects_unspecified() {
+        let discovery = Discovery::new().unwrap();
+        assert!(
+            discovery
+                .advertise("0.0.0.0:8080".parse().unwrap())
+                .is_err()
+        );
+    }
+
+    #[test]
+    #[ignore = "needs multicast on a network interface"]
+    fn test_advertise_and_browse() {
+        let service_type = "_4wn-test._udp.local.";
+        let advertiser = Discovery::with_service_type(service_type).unwrap();
+        let browser = Discovery::with_service_type(service_type).unwrap();
+        let addr = "127.0.0.1:4040".parse().unwrap();
+        let advertised = advertiser.advertise(addr).unwrap();
+
+        let peers = browser.browse(Duration::from_secs(3)).unwrap();
+        assert!(peers.iter().any(|peer| peer.address == advertised));
+        advertiser.withdraw(&advertised).unwrap();
+    }
+}
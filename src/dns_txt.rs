@@ -0,0 +1,294 @@
+//! Publishing word addresses in DNS TXT records.
+//!
+//! A word address is published as a TXT record at `words.<domain>` whose
+//! value holds a version tag and the phrase's [`slug`]:
+//!
+//! ```text
+//! words.example.com. 3600 IN TXT "v=4wn1 words=ocean-thunder-falcon-star"
+//! ```
+//!
+//! [`to_txt`] and [`zone_entry`] render records, [`from_txt`] parses a value
+//! back, quoted or not. With the `dns` feature, [`TxtResolver`] looks the
+//! records up through the system resolver (or a given nameserver), so word
+//! addresses can be distributed through existing DNS infrastructure.
+//!
+//! ```rust
+//! use four_word_networking::WordSocketAddr;
+//! use four_word_networking::dns_txt::{from_txt, zone_entry};
+//!
+//! let address = WordSocketAddr::new(([192, 168, 1, 1], 443))?;
+//! let entry = zone_entry("example.com", &address, 3600);
+//! assert!(entry.starts_with("words.example.com. 3600 IN TXT \"v=4wn1 words="));
+//!
+//! let value = entry.split_once(" TXT ").unwrap().1;
+//! assert_eq!(from_txt(value)?, address);
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::slug::{from_slug, slug};
+use crate::word_address::WordSocketAddr;
+
+/// Version tag that starts every record value
+pub const TXT_VERSION: &str = "v=4wn1";
+
+/// Label under which records are published
+pub const RECORD_LABEL: &str = "words";
+
+/// Key of the field holding the phrase slug
+const WORDS_FIELD: &str = "words=";
+
+/// Returns the fully qualified record name for `domain`, e.g. `words.example.com.`
+pub fn record_name(domain: &str) -> String {
+    format!("{RECORD_LABEL}.{}.", domain.trim_end_matches('.'))
+}
+
+/// Renders the TXT record value for `address`, without quotes
+pub fn to_txt(address: &WordSocketAddr) -> String {
+    format!("{TXT_VERSION} {WORDS_FIELD}{}", slug(address.phrase()))
+}
+
+/// Renders a zone-file line publishing `address` for `domain`
+pub fn zone_entry(domain: &str, address: &WordSocketAddr, ttl: u32) -> String {
+    format!(
+        "{} {ttl} IN TXT \"{}\"",
+        record_name(domain),
+        to_txt(address)
+    )
+}
+
+/// Parses a TXT record value back to the word address.
+///
+/// Accepts the bare value or its zone-file form, including values split into
+/// several quoted strings (`"v=4wn1 " "words=..."`), which DNS concatenates.
+pub fn from_txt(txt: &str) -> Result<WordSocketAddr> {
+    let value = unquote(txt)?;
+    words_field(&value)
+        .ok_or_else(|| {
+            FourWordError::invalid_value(
+                "TXT record",
+                txt,
+                format!("not a {TXT_VERSION} word address record"),
+            )
+        })
+        .and_then(parse_words)
+}
+
+/// Returns the slug in a record value, or `None` if it is not a word address record
+fn words_field(value: &str) -> Option<&str> {
+    let mut fields = value.split_whitespace();
+    if fields.next() != Some(TXT_VERSION) {
+        return None;
+    }
+    fields.find_map(|field| field.strip_prefix(WORDS_FIELD))
+}
+
+/// Decodes the slug from a record's `words=` field
+fn parse_words(words: &str) -> Result<WordSocketAddr> {
+    WordSocketAddr::from_phrase(&from_slug(words)?)
+}
+
+/// Joins the quoted character-strings of a zone-file value; unquoted input is returned as is
+fn unquote(txt: &str) -> Result<String> {
+    let txt = txt.trim();
+    if !txt.starts_with('"') {
+        return Ok(txt.to_string());
+    }
+    let mut value = String::with_capacity(txt.len());
+    let mut chars = txt.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => value.extend(chars.next()),
+                    Some(c) => value.push(c),
+                    None => {
+                        return Err(FourWordError::invalid_value(
+                            "TXT record",
+                            txt,
+                            "unterminated quoted string",
+                        ));
+                    }
+                }
+            },
+            c if c.is_whitespace() => {}
+            _ => {
+                return Err(FourWordError::invalid_value(
+                    "TXT record",
+                    txt,
+                    "text outside quotes",
+                ));
+            }
+        }
+    }
+    Ok(value)
+}
+
+#[cfg(feature = "dns")]
+pub use resolver::TxtResolver;
+
+#[cfg(feature = "dns")]
+mod resolver {
+    use super::{parse_words, record_name, words_field};
+    use crate::error::{FourWordError, Result};
+    use crate::word_address::WordSocketAddr;
+    use hickory_resolver::config::{ConnectionConfig, NameServerConfig, ResolverConfig};
+    use hickory_resolver::net::runtime::TokioRuntimeProvider;
+    use hickory_resolver::proto::rr::RData;
+    use hickory_resolver::{Resolver, TokioResolver};
+    use std::net::SocketAddr;
+
+    /// Looks up word address records at `words.<domain>`
+    pub struct TxtResolver {
+        resolver: TokioResolver,
+    }
+
+    impl TxtResolver {
+        /// Uses the system's resolver configuration (`/etc/resolv.conf` on Unix)
+        pub fn system() -> Result<Self> {
+            let resolver = TokioResolver::builder_tokio()
+                .and_then(|builder| builder.build())
+                .map_err(dns_error)?;
+            Ok(TxtResolver { resolver })
+        }
+
+        /// Queries the nameserver at `nameserver` over UDP
+        pub fn with_nameserver(nameserver: SocketAddr) -> Result<Self> {
+            let mut connection = ConnectionConfig::udp();
+            connection.port = nameserver.port();
+            let config = ResolverConfig::from_name_servers(vec![NameServerConfig::new(
+                nameserver.ip(),
+                true,
+                vec![connection],
+            )]);
+            let resolver = Resolver::builder_with_config(config, TokioRuntimeProvider::default())
+                .build()
+                .map_err(dns_error)?;
+            Ok(TxtResolver { resolver })
+        }
+
+        /// Returns the word addresses published for `domain`.
+        ///
+        /// Other TXT records at the name are ignored and a missing name gives
+        /// an empty list; a word address record whose words do not decode is
+        /// an error.
+        pub async fn lookup(&self, domain: &str) -> Result<Vec<WordSocketAddr>> {
+            let lookup = match self.resolver.txt_lookup(record_name(domain)).await {
+                Ok(lookup) => lookup,
+                Err(e) if e.is_no_records_found() => return Ok(Vec::new()),
+                Err(e) => return Err(dns_error(e)),
+            };
+            let mut addresses = Vec::new();
+            for record in lookup.answers() {
+                let RData::TXT(txt) = &record.data else {
+                    continue;
+                };
+                let value: Vec<u8> = txt.txt_data.iter().flatten().copied().collect();
+                if let Some(words) = words_field(&String::from_utf8_lossy(&value)) {
+                    addresses.push(parse_words(words)?);
+                }
+            }
+            Ok(addresses)
+        }
+    }
+
+    /// Wraps a resolver failure
+    fn dns_error(e: impl std::fmt::Display) -> FourWordError {
+        FourWordError::Dns(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address() -> WordSocketAddr {
+        WordSocketAddr::new(([192, 168, 1, 1], 443)).unwrap()
+    }
+
+    #[test]
+    fn test_record_round_trip() {
+        let address = address();
+        let txt = to_txt(&address);
+        assert_eq!(txt, format!("v=4wn1 words={}", slug(address.phrase())));
+        assert_eq!(from_txt(&txt).unwrap(), address);
+        assert_eq!(from_txt(&format!("\"{txt}\"")).unwrap(), address);
+
+        // DNS concatenates split character-strings
+        let (version, words) = txt.split_once(' ').unwrap();
+        assert_eq!(
+            from_txt(&format!("\"{version} \" \"{words}\"")).unwrap(),
+            address
+        );
+    }
+
+    #[test]
+    fn test_record_name() {
+        assert_eq!(record_name("example.com"), "words.example.com.");
+        assert_eq!(record_name("example.com."), "words.example.com.");
+        assert!(
+            zone_entry("example.com", &address(), 300)
+                .starts_with("words.example.com. 300 IN TXT \"v=4wn1 ")
+        );
+    }
+
+    /// Answers one TXT query on a loopback UDP port with `values`
+    #[cfg(feature = "dns")]
+    async fn serve_txt(values: &[&str]) -> std::net::SocketAddr {
+        let socket = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+        tokio::spawn(async move {
+            let mut query = [0u8; 512];
+            let (len, peer) = socket.recv_from(&mut query).await.unwrap();
+            // The question runs from the header to the end of the name plus type and class
+            let mut end = 12;
+            while query[end] != 0 {
+                end += usize::from(query[end]) + 1;
+            }
+            end += 5;
+            assert!(end <= len);
+
+            let mut response = query[..2].to_vec();
+            response.extend_from_slice(&[0x81, 0x80, 0, 1, 0, values.len() as u8, 0, 0, 0, 0]);
+            response.extend_from_slice(&query[12..end]);
+            for value in &values {
+                // Name pointer to the question, TXT, IN, TTL 60
+                response.extend_from_slice(&[0xc0, 12, 0, 16, 0, 1, 0, 0, 0, 60]);
+                response.extend_from_slice(&(value.len() as u16 + 1).to_be_bytes());
+                response.push(value.len() as u8);
+                response.extend_from_slice(value.as_bytes());
+            }
+            socket.send_to(&response, peer).await.unwrap();
+        });
+        addr
+    }
+
+    #[cfg(feature = "dns")]
+    #[tokio::test]
+    async fn test_resolver_reads_published_records() {
+        let address = address();
+        let txt = to_txt(&address);
+        let nameserver = serve_txt(&["v=spf1 -all", &txt]).await;
+
+        let resolver = TxtResolver::with_nameserver(nameserver).unwrap();
+        assert_eq!(resolver.lookup("example.com").await.unwrap(), [address]);
+    }
+
+    #[test]
+    fn test_rejects_other_records() {
+        for txt in [
+            "v=spf1 -all",
+            "words=ocean-thunder",
+            "v=4wn1",
+            "v=4wn1 words=not-real-words",
+            "\"v=4wn1 words=",
+            "\"v=4wn1\" trailing",
+        ] {
+            assert!(from_txt(txt).is_err(), "{txt}");
+        }
+    }
+}
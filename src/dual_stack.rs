@@ -0,0 +1,154 @@
+//! Dual-stack hosts as one phrase.
+//!
+//! A [`DualStackAddr`] joins an IPv4 and an IPv6 endpoint of the same host.
+//! Its phrase is the IPv4 phrase (always four words) followed by the IPv6
+//! one, so a dual-stack node publishes a single string and the decoder
+//! splits it after the fourth word. IPv6 endpoints whose phrase would drop
+//! the port are carried as payload words instead, so both ports survive.
+//!
+//! ```rust
+//! use four_word_networking::dual_stack::DualStackAddr;
+//!
+//! let host = DualStackAddr::new("203.0.113.7:4001".parse()?, "[2001:db8::7]:4001".parse()?);
+//! let words = host.to_words()?;
+//!
+//! let decoded = DualStackAddr::from_words(&words)?;
+//! assert_eq!(decoded, host);
+//! assert_eq!(decoded.v4.port(), 4001);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::word_address::{exact_words, from_exact_words};
+use std::fmt;
+use std::io;
+use std::net::{SocketAddr, SocketAddrV4, SocketAddrV6, ToSocketAddrs};
+use std::str::FromStr;
+use std::vec;
+
+/// Words in the IPv4 half of the phrase
+const V4_WORDS: usize = 4;
+
+/// The IPv4 and IPv6 endpoints of one host
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DualStackAddr {
+    /// IPv4 endpoint
+    pub v4: SocketAddrV4,
+    /// IPv6 endpoint
+    pub v6: SocketAddrV6,
+}
+
+impl DualStackAddr {
+    /// Pairs the two endpoints of a host
+    pub fn new(v4: SocketAddrV4, v6: SocketAddrV6) -> Self {
+        DualStackAddr { v4, v6 }
+    }
+
+    /// Encodes both endpoints as one English phrase, IPv4 first
+    pub fn to_words(&self) -> Result<String> {
+        Ok(format!(
+            "{} {}",
+            exact_words(self.v4.into())?,
+            exact_words(self.v6.into())?
+        ))
+    }
+
+    /// Decodes a phrase produced by [`to_words`](Self::to_words)
+    pub fn from_words(words: &str) -> Result<Self> {
+        let tokens: Vec<&str> = words.split_whitespace().collect();
+        if tokens.len() <= V4_WORDS {
+            return Err(FourWordError::invalid_value(
+                "dual-stack phrase",
+                words,
+                "expected four IPv4 words followed by the IPv6 words",
+            ));
+        }
+        let (v4, v6) = tokens.split_at(V4_WORDS);
+        match (
+            from_exact_words(&v4.join(" "))?,
+            from_exact_words(&v6.join(" "))?,
+        ) {
+            (SocketAddr::V4(v4), SocketAddr::V6(v6)) => Ok(Self::new(v4, v6)),
+            _ => Err(FourWordError::NotEncoding {
+                expected: "an IPv4 then an IPv6 endpoint",
+            }),
+        }
+    }
+}
+
+impl fmt::Display for DualStackAddr {
+    /// Writes both endpoints as `v4,v6`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.v4, self.v6)
+    }
+}
+
+impl FromStr for DualStackAddr {
+    type Err = FourWordError;
+
+    /// Parses `v4,v6`, e.g. `203.0.113.7:4001,[2001:db8::7]:4001`
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            FourWordError::invalid_value("dual-stack address", s, "expected IPV4:PORT,[IPV6]:PORT")
+        };
+        let (v4, v6) = s.split_once(',').ok_or_else(invalid)?;
+        Ok(Self::new(
+            v4.trim().parse().map_err(|_| invalid())?,
+            v6.trim().parse().map_err(|_| invalid())?,
+        ))
+    }
+}
+
+impl ToSocketAddrs for DualStackAddr {
+    type Iter = vec::IntoIter<SocketAddr>;
+
+    /// Yields the IPv6 endpoint first, as Happy Eyeballs (RFC 8305) prefers
+    fn to_socket_addrs(&self) -> io::Result<Self::Iter> {
+        Ok(vec![SocketAddr::V6(self.v6), SocketAddr::V4(self.v4)].into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trips() {
+        for (host, words) in [
+            ("192.168.1.20:8080,[fe80::1]:8080", 10),
+            ("203.0.113.7:4001,[2001:db8:85a3::8a2e:370:7334]:4001", 16),
+            // The plain IPv6 phrase would drop this port
+            ("198.51.100.1:4433,[2606:4700:4700::1111]:4433", 17),
+        ] {
+            let host: DualStackAddr = host.parse().unwrap();
+            let phrase = host.to_words().unwrap();
+            assert_eq!(phrase.split(' ').count(), words, "{phrase}");
+            assert_eq!(
+                DualStackAddr::from_words(&phrase).unwrap(),
+                host,
+                "{phrase}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_display_and_addrs() {
+        let text = "203.0.113.7:4001,[2001:db8::7]:4001";
+        let host: DualStackAddr = text.parse().unwrap();
+        assert_eq!(host.to_string(), text);
+        let addrs: Vec<SocketAddr> = host.to_socket_addrs().unwrap().collect();
+        assert_eq!(addrs, [SocketAddr::V6(host.v6), SocketAddr::V4(host.v4)]);
+    }
+
+    #[test]
+    fn test_rejects_single_family_phrases() {
+        let v4 = crate::encode(([203, 0, 113, 7], 4001)).unwrap();
+        let v6 = crate::encode("[2001:db8::7]:4001".parse::<SocketAddr>().unwrap()).unwrap();
+        assert!(DualStackAddr::from_words(&v4).is_err());
+        assert!(DualStackAddr::from_words(&v6).is_err());
+        assert!(DualStackAddr::from_words(&format!("{v4} {v4}")).is_err());
+        assert!("203.0.113.7:4001".parse::<DualStackAddr>().is_err());
+    }
+}
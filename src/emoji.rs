@@ -0,0 +1,136 @@
+//! Emoji rendering of phrases.
+//!
+//! Consumer apps can share an address as a short row of emoji instead of
+//! words. Each word's 12-bit index is written as two emoji from the 64 in
+//! [`EMOJI`], high six bits first, so an IPv4 phrase becomes eight emoji in
+//! four pairs. The emoji stand for word indices, not words, so the same row
+//! decodes with any wordlist; words stay the canonical form and the emoji are
+//! only a rendering, selected with [`Alphabet::Emoji`](crate::format::Alphabet)
+//! on a [`Formatter`](crate::format::Formatter).
+//!
+//! The set is curated for phones: single code points with emoji presentation
+//! by default, from Unicode 9 or earlier, and nameable at a glance (animals,
+//! food, plants and a few objects).
+//!
+//! ```rust
+//! use four_word_networking::emoji::{from_emoji, to_emoji};
+//!
+//! let row = to_emoji(&[0, 4095, 64, 65]);
+//! assert_eq!(row, "🐶🐶 👑👑 🐱🐶 🐱🐱");
+//! assert_eq!(from_emoji(&row)?, [0, 4095, 64, 65]);
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+
+/// The emoji alphabet; the position of an emoji is its six-bit value
+///
+/// The order is part of the format: entries may never be reordered or replaced.
+pub const EMOJI: [char; 64] = [
+    '🐶', '🐱', '🐭', '🐰', '🦊', '🐻', '🐼', '🐨', '🐯', '🦁', '🐮', '🐷', '🐸', '🐵', '🐔', '🐧',
+    '🐦', '🦆', '🦉', '🐴', '🦄', '🐝', '🐛', '🦋', '🐌', '🐙', '🐢', '🐍', '🐳', '🐬', '🐟', '🦀',
+    '🍎', '🍌', '🍇', '🍓', '🍒', '🍍', '🥕', '🌽', '🍄', '🌵', '🌻', '🌲', '🍀', '🌙', '🌟', '🔥',
+    '🌈', '💧', '🍉', '🍕', '🍩', '🎂', '🎈', '🎁', '🔑', '🔔', '🎸', '🚀', '🚲', '🎩', '🏠', '👑',
+];
+
+/// Emoji per word index
+pub const EMOJI_PER_WORD: usize = 2;
+
+/// Variation selector some keyboards append to emoji; ignored when parsing
+const VARIATION_SELECTOR: char = '\u{FE0F}';
+
+/// Renders word indices as emoji pairs separated by spaces.
+///
+/// Only the low 12 bits of each index are used.
+pub fn to_emoji(indices: &[u16]) -> String {
+    let mut row = String::with_capacity(indices.len() * (EMOJI_PER_WORD * 4 + 1));
+    for (position, index) in indices.iter().enumerate() {
+        if position > 0 {
+            row.push(' ');
+        }
+        row.push(EMOJI[usize::from(index >> 6 & 0x3F)]);
+        row.push(EMOJI[usize::from(index & 0x3F)]);
+    }
+    row
+}
+
+/// Reads word indices back from emoji.
+///
+/// Whitespace and emoji variation selectors are ignored, so pairs may be
+/// spaced in any way or not at all.
+pub fn from_emoji(row: &str) -> Result<Vec<u16>> {
+    let values = row
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != VARIATION_SELECTOR)
+        .enumerate()
+        .map(|(position, c)| {
+            emoji_value(c).ok_or_else(|| FourWordError::unknown_word(position, &c.to_string()))
+        })
+        .collect::<Result<Vec<u16>>>()?;
+    if values.len() % EMOJI_PER_WORD != 0 {
+        return Err(FourWordError::out_of_range(
+            "emoji count",
+            format!("a multiple of {EMOJI_PER_WORD}"),
+            values.len(),
+        ));
+    }
+    Ok(values
+        .chunks(EMOJI_PER_WORD)
+        .map(|pair| pair[0] << 6 | pair[1])
+        .collect())
+}
+
+/// Whether `text` starts with an emoji from the alphabet, ignoring leading
+/// whitespace
+pub fn is_emoji_row(text: &str) -> bool {
+    text.trim_start()
+        .chars()
+        .next()
+        .and_then(emoji_value)
+        .is_some()
+}
+
+/// Six-bit value of `c`
+fn emoji_value(c: char) -> Option<u16> {
+    EMOJI
+        .iter()
+        .position(|&emoji| emoji == c)
+        .map(|value| value as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_index_round_trips() {
+        let indices: Vec<u16> = (0..4096).collect();
+        let row = to_emoji(&indices);
+        assert_eq!(row.split(' ').count(), 4096);
+        assert_eq!(from_emoji(&row).unwrap(), indices);
+    }
+
+    #[test]
+    fn test_alphabet_is_distinct_and_parsing_is_lenient() {
+        let mut sorted = EMOJI.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted.len(), EMOJI.len());
+
+        assert_eq!(from_emoji(" 🐶\u{FE0F}👑🐱🐶\n").unwrap(), [63, 64]);
+        assert!(is_emoji_row("  🍕🍕"));
+        assert!(!is_emoji_row("ocean 🍕"));
+
+        assert!(matches!(
+            from_emoji("🐶"),
+            Err(FourWordError::OutOfRange { .. })
+        ));
+        assert!(matches!(
+            from_emoji("🐶x"),
+            Err(FourWordError::UnknownWord { position: 1, .. })
+        ));
+        assert!(from_emoji("").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,53 @@
+//! The phrase conversion interface shared by real and test encoders.
+//!
+//! Application code that accepts a `&impl PhraseEncoder` (or a
+//! `Box<dyn PhraseEncoder>`) can run against
+//! [`FourWordAdaptiveEncoder`] in production and against a scripted
+//! [`MockEncoder`](crate::mock::MockEncoder) (`mock` feature) in its tests.
+
+#![forbid(unsafe_code)]
+
+use crate::error::Result;
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::fuzzy::FuzzyDecoding;
+use crate::wordlist::Wordlist;
+
+/// Converts between address strings and word phrases
+pub trait PhraseEncoder {
+    /// Encodes an IP address (with optional port) into words
+    fn encode(&self, input: &str) -> Result<String>;
+
+    /// Decodes words back to an address string
+    fn decode(&self, words: &str) -> Result<String>;
+
+    /// Decodes words, correcting typos and aliases where possible
+    fn decode_fuzzy(&self, words: &str, auto_correct: bool) -> Result<FuzzyDecoding>;
+}
+
+impl<W: Wordlist> PhraseEncoder for FourWordAdaptiveEncoder<W> {
+    fn encode(&self, input: &str) -> Result<String> {
+        FourWordAdaptiveEncoder::encode(self, input)
+    }
+
+    fn decode(&self, words: &str) -> Result<String> {
+        FourWordAdaptiveEncoder::decode(self, words)
+    }
+
+    fn decode_fuzzy(&self, words: &str, auto_correct: bool) -> Result<FuzzyDecoding> {
+        FourWordAdaptiveEncoder::decode_fuzzy(self, words, auto_correct)
+    }
+}
+
+impl<E: PhraseEncoder + ?Sized> PhraseEncoder for &E {
+    fn encode(&self, input: &str) -> Result<String> {
+        (**self).encode(input)
+    }
+
+    fn decode(&self, words: &str) -> Result<String> {
+        (**self).decode(words)
+    }
+
+    fn decode_fuzzy(&self, words: &str, auto_correct: bool) -> Result<FuzzyDecoding> {
+        (**self).decode_fuzzy(words, auto_correct)
+    }
+}
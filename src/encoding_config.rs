@@ -0,0 +1,492 @@
+//! Dictionaries of other sizes.
+//!
+//! The standard encoding spends 12 bits per word, so it needs a list of
+//! exactly 4,096 words, and some of those are too obscure for children,
+//! low-literacy or ESL audiences. An [`EncodingConfig`] picks another
+//! power-of-two list size instead, and [`SizedEncoder`] repacks the standard
+//! phrase's bits into words from that list: a 1,024-word list carries 10 bits
+//! per word, so IPv4 takes 5 words and IPv6 takes 8, 11 or 15.
+//!
+//! The repacked phrase holds exactly the bits of the standard one, padded
+//! with zeros to a whole word, so every address the standard encoding can
+//! express round-trips. Each phrase length maps back to one standard length
+//! for every supported size, which is how the decoder tells IPv4 from IPv6.
+//!
+//! [`SizedWordlist::simple_english`] is a built-in 1,024-word list of short
+//! words for such audiences:
+//!
+//! ```rust
+//! use four_word_networking::encoding_config::{EncodingConfig, SizedEncoder, SizedWordlist};
+//!
+//! let wordlist = SizedWordlist::simple_english();
+//! let config = EncodingConfig::for_wordlist(&wordlist)?;
+//! assert_eq!(config.bits_per_word(), 10);
+//!
+//! let encoder = SizedEncoder::new(wordlist, config)?;
+//! let addr = "192.168.1.1:443".parse()?;
+//! let words = encoder.encode(addr)?;
+//! assert_eq!(words.split(' ').count(), 5);
+//! assert_eq!(encoder.decode(&words)?, addr);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::dictionary_audit::OFFENSIVE_WORDS;
+use crate::dictionary4k::{DICTIONARY, DICTIONARY_SIZE, Dictionary4K};
+use crate::error::{FourWordError, Result};
+use crate::format::normalize_phrase;
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::four_word_ipv6_encoder::{read_index, write_index};
+use crate::phrase::write_words;
+use crate::wordlist::{Wordlist, fingerprint_words};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Fewest bits a word may carry (a 64-word list)
+pub const MIN_BITS_PER_WORD: u32 = 6;
+
+/// Most bits a word may carry (a 65,536-word list)
+pub const MAX_BITS_PER_WORD: u32 = 16;
+
+/// Bits per word of the standard encoding
+const STANDARD_BITS: u32 = 12;
+
+/// Word counts of standard phrases: IPv4, then the IPv6 lengths
+const STANDARD_LENGTHS: [usize; 4] = [4, 6, 9, 12];
+
+/// Words in [`SizedWordlist::simple_english`]
+const SIMPLE_SIZE: usize = 1024;
+
+/// Shortest and longest words taken into [`SizedWordlist::simple_english`]
+const SIMPLE_LENGTHS: std::ops::RangeInclusive<usize> = 3..=5;
+
+/// How many bits each word of a phrase carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EncodingConfig {
+    bits_per_word: u32,
+}
+
+impl EncodingConfig {
+    /// The standard encoding: 12 bits per word from a 4,096-word list
+    pub const STANDARD: Self = EncodingConfig {
+        bits_per_word: STANDARD_BITS,
+    };
+
+    /// Carries `bits_per_word` bits per word, from
+    /// [`MIN_BITS_PER_WORD`] to [`MAX_BITS_PER_WORD`]
+    pub fn new(bits_per_word: u32) -> Result<Self> {
+        if !(MIN_BITS_PER_WORD..=MAX_BITS_PER_WORD).contains(&bits_per_word) {
+            return Err(FourWordError::out_of_range(
+                "Bits per word",
+                format!("{MIN_BITS_PER_WORD}-{MAX_BITS_PER_WORD}"),
+                bits_per_word,
+            ));
+        }
+        Ok(EncodingConfig { bits_per_word })
+    }
+
+    /// Derives the bits per word from the size of `wordlist`, which must be
+    /// a power of two from 64 to 65,536 words
+    pub fn for_wordlist<W: Wordlist + ?Sized>(wordlist: &W) -> Result<Self> {
+        let len = wordlist.len();
+        if !len.is_power_of_two() {
+            return Err(FourWordError::DictionaryError(format!(
+                "wordlist size must be a power of two, got {len}"
+            )));
+        }
+        Self::new(len.trailing_zeros())
+    }
+
+    /// Bits each word carries
+    pub fn bits_per_word(&self) -> u32 {
+        self.bits_per_word
+    }
+
+    /// Number of words a list for this configuration must hold
+    pub fn dictionary_size(&self) -> usize {
+        1 << self.bits_per_word
+    }
+
+    /// Words needed for a phrase of `standard_words` 12-bit words
+    pub fn word_count(&self, standard_words: usize) -> usize {
+        (standard_words * STANDARD_BITS as usize).div_ceil(self.bits_per_word as usize)
+    }
+
+    /// Standard phrase length behind a phrase of `words` words
+    fn standard_length(&self, words: usize) -> Option<usize> {
+        STANDARD_LENGTHS
+            .into_iter()
+            .find(|&standard| self.word_count(standard) == words)
+    }
+}
+
+impl Default for EncodingConfig {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+/// Encodes addresses with a wordlist of any [`EncodingConfig`] size
+pub struct SizedEncoder<W = SizedWordlist> {
+    /// Produces the standard phrase whose bits are repacked
+    encoder: FourWordAdaptiveEncoder<Dictionary4K>,
+    wordlist: W,
+    config: EncodingConfig,
+}
+
+impl<W: Wordlist> SizedEncoder<W> {
+    /// Encodes with `wordlist`, which must hold
+    /// [`EncodingConfig::dictionary_size`] words
+    pub fn new(wordlist: W, config: EncodingConfig) -> Result<Self> {
+        if wordlist.len() != config.dictionary_size() {
+            return Err(FourWordError::DictionaryError(format!(
+                "wordlist must contain exactly {} words for {} bits per word, got {}",
+                config.dictionary_size(),
+                config.bits_per_word,
+                wordlist.len()
+            )));
+        }
+        Ok(SizedEncoder {
+            encoder: FourWordAdaptiveEncoder::new()?,
+            wordlist,
+            config,
+        })
+    }
+
+    /// Returns the configuration in use
+    pub fn config(&self) -> EncodingConfig {
+        self.config
+    }
+
+    /// Returns the wordlist this encoder reads and writes
+    pub fn wordlist(&self) -> &W {
+        &self.wordlist
+    }
+
+    /// Encodes `addr`; use port 65535 for "no port"
+    pub fn encode(&self, addr: SocketAddr) -> Result<String> {
+        let mut standard = String::new();
+        self.encoder.encode_unescaped_into(addr, &mut standard)?;
+        let standard = standard
+            .split(' ')
+            .enumerate()
+            .map(|(position, word)| {
+                DICTIONARY
+                    .get_index(word)
+                    .ok_or_else(|| FourWordError::unknown_word(position, word))
+            })
+            .collect::<Result<Vec<u16>>>()?;
+
+        let bits = self.config.bits_per_word as usize;
+        let count = self.config.word_count(standard.len());
+        let mut bytes = vec![0u8; (count * bits).div_ceil(8)];
+        for (word, &index) in standard.iter().enumerate() {
+            write_index(&mut bytes, word, index);
+        }
+        let indices: Vec<u16> = (0..count)
+            .map(|word| read_bits(&bytes, word * bits, bits))
+            .collect();
+
+        let mut words = String::new();
+        write_words(&self.wordlist, &indices, &mut words)?;
+        Ok(words)
+    }
+
+    /// Decodes a phrase from [`encode`](Self::encode).
+    ///
+    /// Separators, case and surrounding punctuation are folded away first.
+    pub fn decode(&self, words: &str) -> Result<SocketAddr> {
+        let normalized = normalize_phrase(words);
+        let indices = normalized
+            .split(' ')
+            .filter(|word| !word.is_empty())
+            .enumerate()
+            .map(|(position, word)| {
+                self.wordlist
+                    .get_index(word)
+                    .ok_or_else(|| FourWordError::unknown_word(position, word))
+            })
+            .collect::<Result<Vec<u16>>>()?;
+        let standard_words = self.config.standard_length(indices.len()).ok_or_else(|| {
+            let expected: Vec<usize> = STANDARD_LENGTHS
+                .iter()
+                .map(|&standard| self.config.word_count(standard))
+                .collect();
+            FourWordError::wrong_word_count(&expected, indices.len())
+        })?;
+
+        let bits = self.config.bits_per_word as usize;
+        let mut bytes = vec![0u8; (indices.len() * bits).div_ceil(8)];
+        for (word, &index) in indices.iter().enumerate() {
+            write_bits(&mut bytes, word * bits, bits, index);
+        }
+        // Padding past the standard phrase is always zero
+        let used = standard_words * STANDARD_BITS as usize;
+        if (used..indices.len() * bits).any(|offset| bytes[offset / 8] >> (offset % 8) & 1 == 1) {
+            return Err(FourWordError::NotEncoding {
+                expected: "an address at this dictionary size",
+            });
+        }
+
+        let standard: Vec<u16> = (0..standard_words)
+            .map(|word| read_index(&bytes, word))
+            .collect();
+        let mut phrase = String::new();
+        write_words(&DICTIONARY, &standard, &mut phrase)?;
+        self.encoder.decode_unescaped(&phrase)
+    }
+}
+
+/// Reads `len` bits starting at bit `offset` of `bytes`, least significant first
+fn read_bits(bytes: &[u8], offset: usize, len: usize) -> u16 {
+    (0..len).fold(0u16, |value, bit| {
+        let position = offset + bit;
+        let set = bytes
+            .get(position / 8)
+            .map_or(0, |byte| (byte >> (position % 8)) & 1);
+        value | (u16::from(set) << bit)
+    })
+}
+
+/// Writes the low `len` bits of `value` at bit `offset` of `bytes`
+fn write_bits(bytes: &mut [u8], offset: usize, len: usize, value: u16) {
+    for bit in 0..len {
+        if (value >> bit) & 1 == 1 {
+            let position = offset + bit;
+            bytes[position / 8] |= 1 << (position % 8);
+        }
+    }
+}
+
+/// A wordlist of any power-of-two size, for [`SizedEncoder`]
+///
+/// Cloning is cheap: the tables are shared.
+#[derive(Debug, Clone)]
+pub struct SizedWordlist {
+    tables: Arc<SizedTables>,
+}
+
+#[derive(Debug)]
+struct SizedTables {
+    /// Words indexed by their position
+    words: Vec<String>,
+    /// Word indices in lexicographic order, for reverse lookup
+    sorted_indices: Vec<u16>,
+    /// Fingerprint computed once at construction
+    fingerprint: [u8; 32],
+}
+
+impl SizedWordlist {
+    /// Builds a list from words in index order.
+    ///
+    /// The list must hold a power of two from 64 to 65,536 unique words made
+    /// only of ASCII letters (case is folded).
+    pub fn from_slice<S: AsRef<str>>(words: &[S]) -> Result<Self> {
+        if !words.len().is_power_of_two()
+            || !(MIN_BITS_PER_WORD..=MAX_BITS_PER_WORD).contains(&words.len().trailing_zeros())
+        {
+            return Err(FourWordError::DictionaryError(format!(
+                "wordlist size must be a power of two from {} to {}, got {}",
+                1usize << MIN_BITS_PER_WORD,
+                1usize << MAX_BITS_PER_WORD,
+                words.len()
+            )));
+        }
+
+        let words: Vec<String> = words
+            .iter()
+            .map(|word| word.as_ref().trim().to_ascii_lowercase())
+            .collect();
+        if let Some((index, word)) = words
+            .iter()
+            .enumerate()
+            .find(|(_, word)| word.is_empty() || !word.bytes().all(|b| b.is_ascii_lowercase()))
+        {
+            return Err(FourWordError::DictionaryError(format!(
+                "word {index} ({word:?}) must consist of ASCII letters only"
+            )));
+        }
+
+        let mut sorted_indices: Vec<u16> = (0..words.len()).map(|index| index as u16).collect();
+        sorted_indices.sort_by(|&a, &b| words[a as usize].cmp(&words[b as usize]));
+        if let Some(pair) = sorted_indices
+            .windows(2)
+            .find(|pair| words[pair[0] as usize] == words[pair[1] as usize])
+        {
+            return Err(FourWordError::DictionaryError(format!(
+                "duplicate word: {}",
+                words[pair[0] as usize]
+            )));
+        }
+
+        let fingerprint = fingerprint_words(words.iter().map(String::as_str));
+        Ok(SizedWordlist {
+            tables: Arc::new(SizedTables {
+                words,
+                sorted_indices,
+                fingerprint,
+            }),
+        })
+    }
+
+    /// 1,024 short words (three to five letters) from the English list, in
+    /// English index order, leaving out offensive words
+    pub fn simple_english() -> Self {
+        let dictionary = DICTIONARY;
+        let words: Vec<&str> = (0..DICTIONARY_SIZE as u16)
+            .filter_map(|index| dictionary.get_word(index))
+            .filter(|word| SIMPLE_LENGTHS.contains(&word.len()) && !OFFENSIVE_WORDS.contains(word))
+            .take(SIMPLE_SIZE)
+            .collect();
+        Self::from_slice(&words).expect("the English list has enough short words")
+    }
+}
+
+impl Wordlist for SizedWordlist {
+    fn get_word(&self, index: u16) -> Option<&str> {
+        self.tables.words.get(index as usize).map(String::as_str)
+    }
+
+    fn get_index(&self, word: &str) -> Option<u16> {
+        let word = word.to_ascii_lowercase();
+        let tables = &self.tables;
+        tables
+            .sorted_indices
+            .binary_search_by(|&index| tables.words[index as usize].cmp(&word))
+            .ok()
+            .map(|position| tables.sorted_indices[position])
+    }
+
+    fn len(&self) -> usize {
+        self.tables.words.len()
+    }
+
+    fn fingerprint(&self) -> [u8; 32] {
+        self.tables.fingerprint
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESSES: &[&str] = &[
+        "192.168.1.1:443",
+        "10.0.0.1:65535",
+        "[::1]:22",
+        "[fe80::1]:8080",
+        "[2001:db8::1]:65534",
+        "[2606:4700:4700::1111]:4433",
+        "[2001:db8:1:2:3:4:5:6]:443",
+    ];
+
+    #[test]
+    fn test_phrase_lengths_stay_distinct() {
+        for bits in MIN_BITS_PER_WORD..=MAX_BITS_PER_WORD {
+            let config = EncodingConfig::new(bits).unwrap();
+            for standard in STANDARD_LENGTHS {
+                let words = config.word_count(standard);
+                assert_eq!(config.standard_length(words), Some(standard), "{bits} bits");
+            }
+        }
+        assert!(EncodingConfig::new(MIN_BITS_PER_WORD - 1).is_err());
+        assert!(EncodingConfig::new(MAX_BITS_PER_WORD + 1).is_err());
+    }
+
+    #[test]
+    fn test_simple_english_round_trips() {
+        let wordlist = SizedWordlist::simple_english();
+        assert_eq!(wordlist.len(), SIMPLE_SIZE);
+        assert!((0..SIMPLE_SIZE as u16).all(|index| {
+            let word = wordlist.get_word(index).unwrap();
+            SIMPLE_LENGTHS.contains(&word.len()) && !OFFENSIVE_WORDS.contains(&word)
+        }));
+
+        let config = EncodingConfig::for_wordlist(&wordlist).unwrap();
+        let encoder = SizedEncoder::new(wordlist, config).unwrap();
+        for address in ADDRESSES {
+            let addr = standard_round_trip(address);
+            let words = encoder.encode(addr).unwrap();
+            assert_eq!(encoder.decode(&words).unwrap(), addr, "{words}");
+            assert_eq!(
+                encoder
+                    .decode(&words.to_uppercase().replace(' ', "-"))
+                    .unwrap(),
+                addr
+            );
+        }
+        let ipv4 = encoder.encode("192.168.1.1:443".parse().unwrap()).unwrap();
+        assert_eq!(ipv4.split(' ').count(), 5);
+    }
+
+    #[test]
+    fn test_every_size_round_trips() {
+        for bits in MIN_BITS_PER_WORD..=MAX_BITS_PER_WORD {
+            let words: Vec<String> = (0..1u32 << bits).map(synthetic_word).collect();
+            let wordlist = SizedWordlist::from_slice(&words).unwrap();
+            let config = EncodingConfig::for_wordlist(&wordlist).unwrap();
+            let encoder = SizedEncoder::new(wordlist, config).unwrap();
+            for address in ADDRESSES {
+                let addr = standard_round_trip(address);
+                let phrase = encoder.encode(addr).unwrap();
+                assert_eq!(encoder.decode(&phrase).unwrap(), addr, "{bits} bits");
+            }
+        }
+    }
+
+    #[test]
+    fn test_standard_size_matches_standard_encoder() {
+        let dictionary = DICTIONARY;
+        let words: Vec<&str> = (0..DICTIONARY_SIZE as u16)
+            .map(|index| dictionary.get_word(index).unwrap())
+            .collect();
+        let wordlist = SizedWordlist::from_slice(&words).unwrap();
+        assert_eq!(wordlist.fingerprint(), DICTIONARY.fingerprint());
+        let encoder = SizedEncoder::new(wordlist, EncodingConfig::default()).unwrap();
+        for address in ADDRESSES {
+            let addr: SocketAddr = address.parse().unwrap();
+            assert_eq!(encoder.encode(addr).unwrap(), crate::encode(addr).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_rejects_bad_lists_and_phrases() {
+        assert!(SizedWordlist::from_slice(&["one", "two", "six"]).is_err());
+        let doubled: Vec<&str> = (0..64).map(|_| "same").collect();
+        assert!(SizedWordlist::from_slice(&doubled).is_err());
+
+        let wordlist = SizedWordlist::simple_english();
+        assert!(SizedEncoder::new(wordlist.clone(), EncodingConfig::STANDARD).is_err());
+        let config = EncodingConfig::for_wordlist(&wordlist).unwrap();
+        let encoder = SizedEncoder::new(wordlist, config).unwrap();
+
+        let words = encoder.encode("192.168.1.1:443".parse().unwrap()).unwrap();
+        let mut split: Vec<&str> = words.split(' ').collect();
+        split.pop();
+        assert!(matches!(
+            encoder.decode(&split.join(" ")),
+            Err(FourWordError::WrongWordCount { got: 4, .. })
+        ));
+        // The last word of an IPv4 phrase holds 8 bits and 2 bits of padding
+        let last = encoder.wordlist().get_word(SIMPLE_SIZE as u16 - 1).unwrap();
+        split.push(last);
+        assert!(matches!(
+            encoder.decode(&split.join(" ")),
+            Err(FourWordError::NotEncoding { .. })
+        ));
+    }
+
+    /// What `address` decodes to under the standard encoding, which drops
+    /// ports it has no room for
+    fn standard_round_trip(address: &str) -> SocketAddr {
+        crate::decode(&crate::encode(address.parse::<SocketAddr>().unwrap()).unwrap()).unwrap()
+    }
+
+    /// Distinct lowercase word for `index`, in base 26
+    fn synthetic_word(index: u32) -> String {
+        (0..4)
+            .map(|digit| char::from(b'a' + (index / 26u32.pow(digit) % 26) as u8))
+            .collect()
+    }
+}
@@ -0,0 +1,194 @@
+//! Bootstrap and peer lists as a short paragraph of words.
+//!
+//! [`encode_endpoints`] writes an ordered list of 2 to 8 socket addresses as
+//! one phrase per line, followed by a line holding a single integrity word.
+//! The word is taken from a SHA-256 hash of the whole list, so a misheard
+//! word, a dropped line or two swapped lines are caught by
+//! [`decode_endpoints`] instead of yielding a wrong peer.
+//! [`encode_endpoints_inline`] gives the same list on one line, separated by
+//! commas, for places where line breaks are lost.
+//!
+//! Each address uses its usual phrase, except IPv6 addresses whose phrase
+//! would drop the port, which take 13 payload words.
+//!
+//! ```rust
+//! use four_word_networking::endpoint_list::{decode_endpoints, encode_endpoints};
+//! use std::net::SocketAddr;
+//!
+//! let peers: Vec<SocketAddr> = vec!["203.0.113.7:4001".parse()?, "[::1]:4001".parse()?];
+//! let block = encode_endpoints(&peers)?;
+//! assert_eq!(block.lines().count(), 3);
+//! assert_eq!(decode_endpoints(&block)?, peers);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::language::Language;
+use crate::word_address::{exact_words, from_exact_words};
+use sha2::{Digest, Sha256};
+use std::net::SocketAddr;
+
+/// Fewest addresses in a list
+pub const MIN_ENDPOINTS: usize = 2;
+
+/// Most addresses in a list
+pub const MAX_ENDPOINTS: usize = 8;
+
+/// Domain tag hashed ahead of the addresses
+const DOMAIN_TAG: &[u8] = b"four-word-networking endpoint list v1\0";
+
+/// Encodes `endpoints` as one phrase per line plus a final integrity word line
+pub fn encode_endpoints(endpoints: &[SocketAddr]) -> Result<String> {
+    Ok(encode_lines(endpoints)?.join("\n"))
+}
+
+/// Encodes `endpoints` on one line: the phrases and the integrity word, comma-separated
+pub fn encode_endpoints_inline(endpoints: &[SocketAddr]) -> Result<String> {
+    Ok(encode_lines(endpoints)?.join(", "))
+}
+
+/// Decodes a list from [`encode_endpoints`] or [`encode_endpoints_inline`].
+///
+/// Lines and commas both separate entries, and blank entries are skipped.
+/// Fails if the last entry is not the integrity word for the addresses
+/// before it.
+pub fn decode_endpoints(text: &str) -> Result<Vec<SocketAddr>> {
+    let mut entries: Vec<&str> = text
+        .split(['\n', ','])
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .collect();
+    let integrity = entries.pop().unwrap_or_default();
+    check_count(entries.len())?;
+
+    let endpoints = entries
+        .into_iter()
+        .map(from_exact_words)
+        .collect::<Result<Vec<SocketAddr>>>()?;
+    let expected = integrity_word(&endpoints)?;
+    if !integrity.eq_ignore_ascii_case(&expected) {
+        return Err(FourWordError::DecodingError(format!(
+            "Integrity word '{integrity}' does not match the list (expected '{expected}'); \
+             check each line and their order"
+        )));
+    }
+    Ok(endpoints)
+}
+
+/// Phrases for each endpoint followed by the integrity word
+fn encode_lines(endpoints: &[SocketAddr]) -> Result<Vec<String>> {
+    check_count(endpoints.len())?;
+    let mut lines = endpoints
+        .iter()
+        .map(|&addr| exact_words(addr))
+        .collect::<Result<Vec<String>>>()?;
+    lines.push(integrity_word(endpoints)?);
+    Ok(lines)
+}
+
+/// Fails unless a list of `count` addresses is within the supported range
+fn check_count(count: usize) -> Result<()> {
+    if !(MIN_ENDPOINTS..=MAX_ENDPOINTS).contains(&count) {
+        return Err(FourWordError::out_of_range(
+            "endpoint count",
+            format!("{MIN_ENDPOINTS} to {MAX_ENDPOINTS}"),
+            count,
+        ));
+    }
+    Ok(())
+}
+
+/// English word picked by the first 12 bits of the list's hash
+fn integrity_word(endpoints: &[SocketAddr]) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(DOMAIN_TAG);
+    for addr in endpoints {
+        match addr {
+            SocketAddr::V4(v4) => {
+                hasher.update([4]);
+                hasher.update(v4.ip().octets());
+            }
+            SocketAddr::V6(v6) => {
+                hasher.update([6]);
+                hasher.update(v6.ip().octets());
+            }
+        }
+        hasher.update(addr.port().to_be_bytes());
+    }
+    let hash = hasher.finalize();
+    let index = u16::from_le_bytes([hash[0], hash[1]]) & 0x0FFF;
+    let dictionary = Language::English.dictionary();
+    dictionary
+        .get_word(index)
+        .map(str::to_string)
+        .ok_or(FourWordError::InvalidWordIndex(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peers() -> Vec<SocketAddr> {
+        [
+            "203.0.113.7:4001",
+            "198.51.100.20:4001",
+            "[2001:db8::7]:4001",
+            "[2606:4700:4700::1111]:443",
+        ]
+        .iter()
+        .map(|addr| addr.parse().unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn test_block_and_inline_round_trip() {
+        let peers = peers();
+        let block = encode_endpoints(&peers).unwrap();
+        assert_eq!(block.lines().count(), peers.len() + 1);
+        assert_eq!(block.lines().last().unwrap().split(' ').count(), 1);
+        assert_eq!(decode_endpoints(&block).unwrap(), peers);
+
+        let inline = encode_endpoints_inline(&peers).unwrap();
+        assert!(!inline.contains('\n'));
+        assert_eq!(decode_endpoints(&inline).unwrap(), peers);
+
+        // Blank lines and surrounding whitespace are ignored
+        let padded = format!("\n  {}\n\n", block.replace('\n', "\n\n  "));
+        assert_eq!(decode_endpoints(&padded).unwrap(), peers);
+    }
+
+    #[test]
+    fn test_integrity_word_catches_mistakes() {
+        let peers = peers();
+        let block = encode_endpoints(&peers).unwrap();
+        let lines: Vec<&str> = block.lines().collect();
+
+        let mut swapped = lines.clone();
+        swapped.swap(0, 1);
+        assert!(decode_endpoints(&swapped.join("\n")).is_err());
+
+        let mut dropped = lines.clone();
+        dropped.remove(1);
+        assert!(decode_endpoints(&dropped.join("\n")).is_err());
+
+        let dictionary = Language::English.dictionary();
+        let mut wrong = lines.clone();
+        let index = dictionary.get_index(wrong[peers.len()]).unwrap();
+        wrong[peers.len()] = dictionary.get_word((index + 1) % 4096).unwrap();
+        assert!(decode_endpoints(&wrong.join("\n")).is_err());
+    }
+
+    #[test]
+    fn test_list_size_limits() {
+        let one = &peers()[..1];
+        assert!(encode_endpoints(one).is_err());
+        let nine: Vec<SocketAddr> = (1..=9)
+            .map(|host| SocketAddr::from(([10, 0, 0, host], 4001)))
+            .collect();
+        assert!(encode_endpoints(&nine).is_err());
+        assert!(encode_endpoints(&nine[..8]).is_ok());
+        assert!(decode_endpoints("").is_err());
+    }
+}
@@ -1,5 +1,20 @@
 //! Error types for four-word networking
+//!
+//! Failures a caller may want to act on carry structured fields:
+//! [`FourWordError::UnknownWord`] says which word of a phrase is wrong and
+//! what it may have meant, [`FourWordError::WrongWordCount`] lists the
+//! lengths that would have been accepted, and
+//! [`FourWordError::LossyEncoding`] and [`FourWordError::DictionaryMismatch`]
+//! report the two ways a phrase can decode to something other than what was
+//! meant. Rejected arguments are [`FourWordError::InvalidValue`],
+//! [`FourWordError::OutOfRange`], [`FourWordError::NotEncoding`] or
+//! [`FourWordError::Unsupported`], each naming what was rejected. The older
+//! string-only variants those replace are deprecated and no longer produced
+//! by this crate.
 
+#![forbid(unsafe_code)]
+
+use std::fmt;
 use thiserror::Error;
 
 /// Result type for four-word networking operations
@@ -11,6 +26,7 @@ pub enum FourWordError {
     #[error("Invalid word address format: {0}")]
     InvalidFourWordAddress(String),
 
+    #[deprecated(since = "2.3.0", note = "use `FourWordError::UnknownWord`")]
     #[error("Word not found in dictionary: {0}")]
     WordNotFound(String),
 
@@ -20,6 +36,10 @@ pub enum FourWordError {
     #[error("Numeric suffix out of range: {0}")]
     NumericSuffixOutOfRange(u32),
 
+    #[deprecated(
+        since = "2.3.0",
+        note = "use `InvalidValue`, `OutOfRange`, `NotEncoding` or `Unsupported`"
+    )]
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
@@ -32,6 +52,7 @@ pub enum FourWordError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    #[cfg(feature = "serde")]
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
@@ -44,12 +65,281 @@ pub enum FourWordError {
     #[error("Dictionary error: {0}")]
     DictionaryError(String),
 
+    #[deprecated(since = "2.3.0", note = "use `FourWordError::UnknownWord`")]
     #[error("Invalid word: {0}")]
     InvalidWord(String),
 
     #[error("Invalid word index: {0}")]
     InvalidWordIndex(u16),
 
+    #[deprecated(since = "2.3.0", note = "use `FourWordError::WrongWordCount`")]
     #[error("Invalid word count: expected {expected}, got {actual}")]
     InvalidWordCount { expected: usize, actual: usize },
+
+    #[error("Output error: {0}")]
+    Format(#[from] std::fmt::Error),
+
+    #[error("Input is already a word phrase: {0}")]
+    AlreadyEncoded(String),
+
+    #[error("Input is already an IP address: {0}")]
+    AlreadyDecoded(String),
+
+    #[error("Self-check failed: {0}")]
+    SelfCheckFailed(String),
+
+    #[error("Daemon error: {0}")]
+    Daemon(String),
+
+    #[error("Discovery error: {0}")]
+    Discovery(String),
+
+    #[error("DNS error: {0}")]
+    Dns(String),
+
+    #[error("Phrase expired: {0}")]
+    Expired(String),
+
+    #[error("Unknown word '{word}' at position {position}{}", format_suggestions(.suggestions))]
+    UnknownWord {
+        position: usize,
+        word: String,
+        suggestions: Vec<String>,
+    },
+
+    #[error(
+        "Phrase requires encoding version {required}; this decoder supports version {supported}"
+    )]
+    UnsupportedVersion { required: u16, supported: u16 },
+
+    /// A phrase had a length no encoding produces
+    #[error("Expected {} words, got {got}", format_counts(.expected))]
+    WrongWordCount {
+        /// Accepted lengths, shortest first
+        expected: Vec<usize>,
+        got: usize,
+    },
+
+    /// An address does not survive encoding unchanged
+    #[error("Encoding {input} is lossy: the words decode as {decoded}")]
+    LossyEncoding {
+        /// The address as given
+        input: String,
+        /// What its words decode to
+        decoded: String,
+    },
+
+    /// Words were made with a different wordlist than the one in use
+    #[error("Wordlist fingerprint {actual} does not match the expected {expected}")]
+    DictionaryMismatch {
+        /// Hex fingerprint the words were expected to have
+        expected: String,
+        /// Hex fingerprint of the wordlist actually found
+        actual: String,
+    },
+
+    /// Text given for some kind of value could not be used as one
+    #[error("Invalid {kind} '{value}': {reason}")]
+    InvalidValue {
+        /// What the text was meant to be, such as "socket address"
+        kind: &'static str,
+        /// The text as given
+        value: String,
+        /// Why it was rejected
+        reason: String,
+    },
+
+    /// A numeric argument fell outside the values accepted for it
+    #[error("{name} must be {allowed}, got {value}")]
+    OutOfRange {
+        /// What the number is, such as "IPv4 prefix length"
+        name: &'static str,
+        /// The accepted values, such as "at most 32"
+        allowed: String,
+        /// The value as given
+        value: String,
+    },
+
+    /// Valid words that do not encode the kind of value asked for
+    #[error("Words do not encode {expected}")]
+    NotEncoding {
+        /// What the caller expected, such as "an integer"
+        expected: &'static str,
+    },
+
+    /// An operation that cannot be applied to the value given
+    #[error("Cannot {operation}: {reason}")]
+    Unsupported {
+        /// What was attempted, such as "dial"
+        operation: &'static str,
+        /// Why it cannot be done
+        reason: String,
+    },
+}
+
+impl FourWordError {
+    /// An [`UnknownWord`](Self::UnknownWord) error without suggestions
+    pub fn unknown_word(position: usize, word: &str) -> Self {
+        FourWordError::UnknownWord {
+            position,
+            word: word.to_string(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    /// A [`WrongWordCount`](Self::WrongWordCount) error
+    pub fn wrong_word_count(expected: &[usize], got: usize) -> Self {
+        FourWordError::WrongWordCount {
+            expected: expected.to_vec(),
+            got,
+        }
+    }
+
+    /// An [`InvalidValue`](Self::InvalidValue) error
+    pub fn invalid_value(kind: &'static str, value: &str, reason: impl Into<String>) -> Self {
+        FourWordError::InvalidValue {
+            kind,
+            value: value.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    /// An [`OutOfRange`](Self::OutOfRange) error
+    pub fn out_of_range(
+        name: &'static str,
+        allowed: impl Into<String>,
+        value: impl fmt::Display,
+    ) -> Self {
+        FourWordError::OutOfRange {
+            name,
+            allowed: allowed.into(),
+            value: value.to_string(),
+        }
+    }
+
+    /// An [`Unsupported`](Self::Unsupported) error
+    pub fn unsupported(operation: &'static str, reason: impl Into<String>) -> Self {
+        FourWordError::Unsupported {
+            operation,
+            reason: reason.into(),
+        }
+    }
+
+    /// Stable snake_case name of the variant, for metrics labels and logs
+    #[allow(deprecated)]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FourWordError::InvalidFourWordAddress(_) => "invalid_address",
+            FourWordError::WordNotFound(_) | FourWordError::InvalidWord(_) => "unknown_word",
+            FourWordError::PositionOutOfRange(_) => "position_out_of_range",
+            FourWordError::NumericSuffixOutOfRange(_) => "numeric_suffix_out_of_range",
+            FourWordError::InvalidInput(_) => "invalid_input",
+            FourWordError::CompressionError(_) => "compression",
+            FourWordError::DecompressionError(_) => "decompression",
+            FourWordError::Io(_) => "io",
+            #[cfg(feature = "serde")]
+            FourWordError::Serialization(_) => "serialization",
+            FourWordError::EncodingError(_) => "encoding",
+            FourWordError::DecodingError(_) => "decoding",
+            FourWordError::DictionaryError(_) => "dictionary",
+            FourWordError::InvalidWordIndex(_) => "invalid_word_index",
+            FourWordError::InvalidWordCount { .. } => "wrong_word_count",
+            FourWordError::Format(_) => "format",
+            FourWordError::AlreadyEncoded(_) => "already_encoded",
+            FourWordError::AlreadyDecoded(_) => "already_decoded",
+            FourWordError::SelfCheckFailed(_) => "self_check_failed",
+            FourWordError::Daemon(_) => "daemon",
+            FourWordError::Discovery(_) => "discovery",
+            FourWordError::Dns(_) => "dns",
+            FourWordError::Expired(_) => "expired",
+            FourWordError::UnknownWord { .. } => "unknown_word",
+            FourWordError::UnsupportedVersion { .. } => "unsupported_version",
+            FourWordError::WrongWordCount { .. } => "wrong_word_count",
+            FourWordError::LossyEncoding { .. } => "lossy_encoding",
+            FourWordError::DictionaryMismatch { .. } => "dictionary_mismatch",
+            FourWordError::InvalidValue { .. } => "invalid_value",
+            FourWordError::OutOfRange { .. } => "out_of_range",
+            FourWordError::NotEncoding { .. } => "not_encoding",
+            FourWordError::Unsupported { .. } => "unsupported",
+        }
+    }
+}
+
+/// Formats accepted word counts as "4", "4 or 6" or "4, 6, 9 or 12"
+fn format_counts(counts: &[usize]) -> String {
+    match counts {
+        [] => "no".to_string(),
+        [only] => only.to_string(),
+        [rest @ .., last] => {
+            let rest: Vec<String> = rest.iter().map(usize::to_string).collect();
+            format!("{} or {last}", rest.join(", "))
+        }
+    }
+}
+
+/// Formats ranked suggestions for inclusion in an error message
+fn format_suggestions(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        String::new()
+    } else {
+        format!(" (did you mean: {}?)", suggestions.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structured_messages() {
+        assert_eq!(
+            FourWordError::wrong_word_count(&[4, 6, 9, 12], 5).to_string(),
+            "Expected 4, 6, 9 or 12 words, got 5"
+        );
+        assert_eq!(
+            FourWordError::wrong_word_count(&[8], 7).to_string(),
+            "Expected 8 words, got 7"
+        );
+        let unknown = FourWordError::UnknownWord {
+            position: 2,
+            word: "ocaen".to_string(),
+            suggestions: vec!["ocean".to_string()],
+        };
+        assert_eq!(
+            unknown.to_string(),
+            "Unknown word 'ocaen' at position 2 (did you mean: ocean?)"
+        );
+        let error: Box<dyn std::error::Error> = Box::new(FourWordError::unknown_word(0, "x"));
+        assert_eq!(error.to_string(), "Unknown word 'x' at position 0");
+        assert_eq!(FourWordError::unknown_word(0, "x").kind(), "unknown_word");
+        assert_eq!(
+            FourWordError::wrong_word_count(&[4], 3).kind(),
+            "wrong_word_count"
+        );
+    }
+
+    #[test]
+    fn test_rejected_argument_messages() {
+        let error = FourWordError::invalid_value("subnet", "10.0.0.0", "expected ADDRESS/LENGTH");
+        assert_eq!(
+            error.to_string(),
+            "Invalid subnet '10.0.0.0': expected ADDRESS/LENGTH"
+        );
+        assert_eq!(error.kind(), "invalid_value");
+        assert_eq!(
+            FourWordError::out_of_range("IPv4 prefix length", "at most 32", "/33").to_string(),
+            "IPv4 prefix length must be at most 32, got /33"
+        );
+        assert_eq!(
+            FourWordError::NotEncoding {
+                expected: "an integer"
+            }
+            .to_string(),
+            "Words do not encode an integer"
+        );
+        assert_eq!(
+            FourWordError::unsupported("dial", "10.0.0.1 has no port").to_string(),
+            "Cannot dial: 10.0.0.1 has no port"
+        );
+    }
 }
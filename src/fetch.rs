@@ -0,0 +1,124 @@
+//! Downloading language packs over HTTP(S).
+//!
+//! Packs are verified against the publisher's ed25519 key before they are
+//! returned, so the transport does not need to be trusted; HTTPS is still
+//! recommended to keep the requested language private.
+
+#![forbid(unsafe_code)]
+
+use crate::dictionary4k::Dictionary4K;
+use crate::error::{FourWordError, Result};
+use crate::language_pack::{LanguagePack, LanguageRegistry};
+use std::io::Read;
+use std::time::Duration;
+
+/// Largest pack accepted; 4,096 words of JSON is well under 200 KiB
+pub const MAX_PACK_BYTES: u64 = 1024 * 1024;
+
+/// Timeout for the whole download
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+impl Dictionary4K {
+    /// Downloads the language pack at `url` and verifies it was signed by `public_key`.
+    ///
+    /// See [`language_pack`](crate::language_pack) for the pack format.
+    pub fn fetch(url: &str, public_key: &[u8; 32]) -> Result<LanguagePack> {
+        let response = ureq::AgentBuilder::new()
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .get(url)
+            .call()
+            .map_err(|e| {
+                FourWordError::DictionaryError(format!("failed to fetch language pack {url}: {e}"))
+            })?;
+
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .take(MAX_PACK_BYTES + 1)
+            .read_to_end(&mut body)?;
+        if body.len() as u64 > MAX_PACK_BYTES {
+            return Err(FourWordError::DictionaryError(format!(
+                "language pack {url} exceeds {MAX_PACK_BYTES} bytes"
+            )));
+        }
+
+        LanguagePack::from_json(&body, public_key)
+    }
+}
+
+impl LanguageRegistry {
+    /// Downloads, verifies and installs the language pack at `url`
+    pub fn fetch(&mut self, url: &str, public_key: &[u8; 32]) -> Result<&LanguagePack> {
+        let pack = Dictionary4K::fetch(url, public_key)?;
+        let code = pack.code().to_string();
+        self.install(pack);
+        self.get(&code).ok_or_else(|| {
+            FourWordError::DictionaryError(format!("language pack '{code}' was not installed"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serves `body` once over plain HTTP on a loopback port
+    fn serve_once(body: Vec<u8>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/pack.json", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request);
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(header.as_bytes()).unwrap();
+            stream.write_all(&body).unwrap();
+        });
+        url
+    }
+
+    #[test]
+    fn test_fetch_installs_signed_pack() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        // Fixed-width words can never be prefixes of each other
+        let letter = |n: u32| char::from(b'a' + (n % 16) as u8);
+        let words: Vec<String> = (0..4096u32)
+            .map(|n| format!("z{}{}{}", letter(n), letter(n / 16), letter(n / 256)))
+            .collect();
+        let pack =
+            LanguagePack::new("zz", "Test", Dictionary4K::from_slice(&words).unwrap()).unwrap();
+        let key = SigningKey::from_bytes(&[3; 32]);
+        let json = pack
+            .to_json(&key.sign(&pack.signing_message()).to_bytes())
+            .unwrap();
+
+        let url = serve_once(json.into_bytes());
+        let mut registry = LanguageRegistry::new();
+        let installed = registry
+            .fetch(&url, key.verifying_key().as_bytes())
+            .unwrap();
+        assert!(installed.dictionary().is_compatible_with(pack.dictionary()));
+        assert_eq!(registry.codes(), ["zz"]);
+    }
+
+    #[test]
+    fn test_fetch_rejects_unsigned_garbage() {
+        let url = serve_once(b"{\"format\": 1}".to_vec());
+        let mut registry = LanguageRegistry::new();
+        assert!(registry.fetch(&url, &[0; 32]).is_err());
+        assert!(registry.codes().is_empty());
+    }
+
+    #[test]
+    fn test_fetch_unreachable() {
+        assert!(Dictionary4K::fetch("http://127.0.0.1:1/pack.json", &[0; 32]).is_err());
+    }
+}
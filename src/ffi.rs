@@ -0,0 +1,320 @@
+//! C interface for linking the encoder into C and C++ programs.
+//!
+//! Enabled by the `ffi` feature. The functions take caller-provided buffers
+//! and report failures through [`TwnStatus`], so no memory crosses the
+//! boundary in either direction. The matching header is
+//! `include/four_word_networking.h`, generated with
+//! `cbindgen --config cbindgen.toml --output include/four_word_networking.h`.
+//!
+//! ```c
+//! char words[TWN_PHRASE_CAPACITY];
+//! const uint8_t ip[4] = {192, 168, 1, 1};
+//! if (twn_encode_v4(ip, 443, words, sizeof words) != TWN_STATUS_OK) { ... }
+//!
+//! TwnAddress addr;
+//! if (twn_decode(words, &addr) == TWN_STATUS_OK && addr.has_port) { ... }
+//! ```
+//!
+//! Build a linkable library with
+//! `cargo rustc --lib --release --features ffi --crate-type staticlib` (or `cdylib`).
+//!
+//! This is the only module that may use `unsafe`: the crate root denies it
+//! and every other module forbids it.
+
+use crate::dictionary4k::DICTIONARY;
+use crate::error::FourWordError;
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::phrase::{DEFAULT_PHRASE_CAPACITY, WordPhrase};
+use std::ffi::{CStr, c_char};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::panic::{self, UnwindSafe};
+
+/// Size of an output buffer that fits any phrase plus its NUL terminator
+pub const TWN_PHRASE_CAPACITY: usize = 193;
+
+const _: () = assert!(TWN_PHRASE_CAPACITY == DEFAULT_PHRASE_CAPACITY + 1);
+
+/// Port value meaning "no port"
+pub const TWN_NO_PORT: u16 = 65535;
+
+/// Result of every `twn_*` call
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TwnStatus {
+    /// Success
+    Ok = 0,
+    /// A required pointer argument was NULL
+    NullPointer = 1,
+    /// The phrase is not valid UTF-8
+    InvalidUtf8 = 2,
+    /// The phrase does not decode
+    InvalidPhrase = 3,
+    /// The output buffer is too small; [`TWN_PHRASE_CAPACITY`] always suffices
+    BufferTooSmall = 4,
+    /// The input was already an IP address rather than a phrase
+    AlreadyDecoded = 5,
+    /// An unexpected internal failure
+    Internal = 6,
+}
+
+/// A decoded address
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TwnAddress {
+    /// 4 for IPv4, 6 for IPv6
+    pub family: u8,
+    /// Address bytes in network order; IPv4 uses the first four
+    pub ip: [u8; 16],
+    /// Port number, [`TWN_NO_PORT`] if the phrase carries none
+    pub port: u16,
+    /// Whether the phrase carries a port
+    pub has_port: bool,
+}
+
+/// Encodes a 4-byte IPv4 address and port into a NUL-terminated phrase.
+///
+/// Pass [`TWN_NO_PORT`] to encode the address alone.
+///
+/// # Safety
+///
+/// `ip` must point to 4 readable bytes and `out` to `out_len` writable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn twn_encode_v4(
+    ip: *const u8,
+    port: u16,
+    out: *mut c_char,
+    out_len: usize,
+) -> TwnStatus {
+    if ip.is_null() {
+        return TwnStatus::NullPointer;
+    }
+    // SAFETY: the caller guarantees `ip` points to 4 readable bytes
+    let octets = unsafe { ip.cast::<[u8; 4]>().read_unaligned() };
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::from(octets)), port);
+    // SAFETY: forwarded from the caller
+    unsafe { encode_to_buffer(addr, out, out_len) }
+}
+
+/// Encodes a 16-byte IPv6 address and port into a NUL-terminated phrase.
+///
+/// Pass [`TWN_NO_PORT`] to encode the address alone.
+///
+/// # Safety
+///
+/// `ip` must point to 16 readable bytes and `out` to `out_len` writable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn twn_encode_v6(
+    ip: *const u8,
+    port: u16,
+    out: *mut c_char,
+    out_len: usize,
+) -> TwnStatus {
+    if ip.is_null() {
+        return TwnStatus::NullPointer;
+    }
+    // SAFETY: the caller guarantees `ip` points to 16 readable bytes
+    let octets = unsafe { ip.cast::<[u8; 16]>().read_unaligned() };
+    let addr = SocketAddr::new(IpAddr::V6(Ipv6Addr::from(octets)), port);
+    // SAFETY: forwarded from the caller
+    unsafe { encode_to_buffer(addr, out, out_len) }
+}
+
+/// Decodes a NUL-terminated phrase into `out`.
+///
+/// # Safety
+///
+/// `words` must be a valid NUL-terminated string and `out` must point to a
+/// writable `TwnAddress`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn twn_decode(words: *const c_char, out: *mut TwnAddress) -> TwnStatus {
+    if words.is_null() || out.is_null() {
+        return TwnStatus::NullPointer;
+    }
+    // SAFETY: the caller guarantees a valid NUL-terminated string
+    let Ok(words) = unsafe { CStr::from_ptr(words) }.to_str() else {
+        return TwnStatus::InvalidUtf8;
+    };
+    let decoded =
+        guard(|| FourWordAdaptiveEncoder::with_wordlist(DICTIONARY)?.decode_socket_addr(words));
+    let addr = match decoded {
+        Ok(addr) => addr,
+        Err(status) => return status,
+    };
+
+    let (family, ip) = match addr.ip() {
+        IpAddr::V4(v4) => {
+            let mut ip = [0; 16];
+            ip[..4].copy_from_slice(&v4.octets());
+            (4, ip)
+        }
+        IpAddr::V6(v6) => (6, v6.octets()),
+    };
+    let address = TwnAddress {
+        family,
+        ip,
+        port: addr.port(),
+        has_port: addr.port() != TWN_NO_PORT,
+    };
+    // SAFETY: the caller guarantees `out` is writable
+    unsafe { out.write(address) };
+    TwnStatus::Ok
+}
+
+/// Returns a static, NUL-terminated description of `status`
+#[unsafe(no_mangle)]
+pub extern "C" fn twn_status_message(status: TwnStatus) -> *const c_char {
+    let message: &'static CStr = match status {
+        TwnStatus::Ok => c"success",
+        TwnStatus::NullPointer => c"a required pointer was NULL",
+        TwnStatus::InvalidUtf8 => c"phrase is not valid UTF-8",
+        TwnStatus::InvalidPhrase => c"phrase does not decode to an address",
+        TwnStatus::BufferTooSmall => c"output buffer is too small",
+        TwnStatus::AlreadyDecoded => c"input is already an IP address",
+        TwnStatus::Internal => c"internal error",
+    };
+    message.as_ptr()
+}
+
+/// Encodes `addr` and copies the phrase, NUL-terminated, into `out`
+///
+/// # Safety
+///
+/// `out` must point to `out_len` writable bytes.
+unsafe fn encode_to_buffer(addr: SocketAddr, out: *mut c_char, out_len: usize) -> TwnStatus {
+    if out.is_null() {
+        return TwnStatus::NullPointer;
+    }
+    let phrase = match guard(|| {
+        FourWordAdaptiveEncoder::with_wordlist(DICTIONARY)?
+            .encode_phrase::<DEFAULT_PHRASE_CAPACITY>(addr)
+    }) {
+        Ok(phrase) => phrase,
+        Err(status) => return status,
+    };
+    copy_phrase(&phrase, out, out_len)
+}
+
+/// Copies `phrase` and a NUL terminator into `out`
+fn copy_phrase(phrase: &WordPhrase, out: *mut c_char, out_len: usize) -> TwnStatus {
+    let bytes = phrase.as_bytes();
+    if bytes.len() >= out_len {
+        return TwnStatus::BufferTooSmall;
+    }
+    // SAFETY: `out` holds `out_len > bytes.len()` writable bytes, and a
+    // phrase held in Rust cannot overlap a caller buffer
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), out.cast::<u8>(), bytes.len());
+        out.add(bytes.len()).write(0);
+    }
+    TwnStatus::Ok
+}
+
+/// Runs `f`, mapping errors and panics to a status instead of unwinding into C
+fn guard<T>(f: impl FnOnce() -> crate::Result<T> + UnwindSafe) -> Result<T, TwnStatus> {
+    match panic::catch_unwind(f) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(error)) => Err(status_for(&error)),
+        Err(_) => Err(TwnStatus::Internal),
+    }
+}
+
+/// Maps an encoder error to the closest status code
+#[allow(deprecated)]
+fn status_for(error: &FourWordError) -> TwnStatus {
+    match error {
+        FourWordError::AlreadyDecoded(_) => TwnStatus::AlreadyDecoded,
+        FourWordError::Format(_) => TwnStatus::BufferTooSmall,
+        FourWordError::UnknownWord { .. }
+        | FourWordError::WrongWordCount { .. }
+        | FourWordError::InvalidInput(_)
+        | FourWordError::InvalidValue { .. }
+        | FourWordError::OutOfRange { .. }
+        | FourWordError::NotEncoding { .. }
+        | FourWordError::DecodingError(_)
+        | FourWordError::DecompressionError(_)
+        | FourWordError::InvalidFourWordAddress(_) => TwnStatus::InvalidPhrase,
+        _ => TwnStatus::Internal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_through_c_abi() {
+        let mut words = [0 as c_char; TWN_PHRASE_CAPACITY];
+        let ip = [192u8, 168, 1, 1];
+        let status = unsafe { twn_encode_v4(ip.as_ptr(), 443, words.as_mut_ptr(), words.len()) };
+        assert_eq!(status, TwnStatus::Ok);
+
+        let phrase = unsafe { CStr::from_ptr(words.as_ptr()) }.to_str().unwrap();
+        let expected = FourWordAdaptiveEncoder::new()
+            .unwrap()
+            .encode("192.168.1.1:443")
+            .unwrap();
+        assert_eq!(phrase, expected);
+
+        let mut addr = TwnAddress {
+            family: 0,
+            ip: [0; 16],
+            port: 0,
+            has_port: false,
+        };
+        assert_eq!(
+            unsafe { twn_decode(words.as_ptr(), &mut addr) },
+            TwnStatus::Ok
+        );
+        assert_eq!((addr.family, &addr.ip[..4], addr.port), (4, &ip[..], 443));
+        assert!(addr.has_port);
+    }
+
+    #[test]
+    fn test_ipv6_without_port() {
+        let mut words = [0 as c_char; TWN_PHRASE_CAPACITY];
+        let ip = Ipv6Addr::LOCALHOST.octets();
+        let status =
+            unsafe { twn_encode_v6(ip.as_ptr(), TWN_NO_PORT, words.as_mut_ptr(), words.len()) };
+        assert_eq!(status, TwnStatus::Ok);
+
+        let mut addr = TwnAddress {
+            family: 0,
+            ip: [0; 16],
+            port: 0,
+            has_port: true,
+        };
+        assert_eq!(
+            unsafe { twn_decode(words.as_ptr(), &mut addr) },
+            TwnStatus::Ok
+        );
+        assert_eq!((addr.family, addr.ip), (6, ip));
+        assert!(!addr.has_port);
+    }
+
+    #[test]
+    fn test_error_statuses() {
+        let ip = [10u8, 0, 0, 1];
+        let mut small = [0 as c_char; 8];
+        let status = unsafe { twn_encode_v4(ip.as_ptr(), 80, small.as_mut_ptr(), small.len()) };
+        assert_eq!(status, TwnStatus::BufferTooSmall);
+        let status = unsafe { twn_encode_v4(std::ptr::null(), 80, small.as_mut_ptr(), 8) };
+        assert_eq!(status, TwnStatus::NullPointer);
+
+        let mut addr = TwnAddress {
+            family: 0,
+            ip: [0; 16],
+            port: 0,
+            has_port: false,
+        };
+        let decode =
+            |words: &CStr, addr: &mut TwnAddress| unsafe { twn_decode(words.as_ptr(), addr) };
+        assert_eq!(
+            decode(c"not real words here", &mut addr),
+            TwnStatus::InvalidPhrase
+        );
+        assert_eq!(decode(c"10.0.0.1:80", &mut addr), TwnStatus::AlreadyDecoded);
+
+        let message = unsafe { CStr::from_ptr(twn_status_message(TwnStatus::BufferTooSmall)) };
+        assert_eq!(message.to_str().unwrap(), "output buffer is too small");
+    }
+}
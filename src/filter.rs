@@ -0,0 +1,265 @@
+//! Rewrites addresses in free text as phrases, and back.
+//!
+//! Logs and chat pastes are easier to read with phrases in place of raw
+//! addresses. [`TextFilter::addresses_to_words`] scans text for IPv4 and IPv6
+//! addresses, with or without a port (`10.0.0.1`, `10.0.0.1:443`, `fe80::1`,
+//! `[2001:db8::1]:443`), and replaces each with its phrase, leaving everything
+//! else byte for byte as it was. [`TextFilter::words_to_addresses`] does the
+//! reverse. `4wn filter` runs either over stdin a line at a time.
+//!
+//! Phrases are written with their words joined by `-`, so a rewritten address
+//! stays one token for tools that split on whitespace. Only joined phrases
+//! are turned back into addresses: any four dictionary words decode to some
+//! IPv4 address, so matching space-separated words would rewrite ordinary
+//! prose. Hyphenated prose of the right length, such as `state-of-the-art`,
+//! still reads as a phrase, so run the reverse direction on text that came
+//! out of the forward one.
+//!
+//! An address must stand alone: one touching a letter, digit or `_` is left
+//! alone, which keeps `std::vec` and `v1.2.3.4` intact. Trailing `.` and `:`
+//! are treated as punctuation.
+//!
+//! ```rust
+//! use four_word_networking::FourWordAdaptiveEncoder;
+//! use four_word_networking::filter::TextFilter;
+//!
+//! let filter = TextFilter::new(FourWordAdaptiveEncoder::new()?);
+//! let line = "accepted 192.168.1.1:443, then [::1]:80.";
+//! let words = filter.addresses_to_words(line);
+//! assert!(words.starts_with("accepted ") && words.ends_with('.'));
+//! assert!(!words.contains("192.168"));
+//! assert_eq!(filter.words_to_addresses(&words), line);
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::dictionary4k::Dictionary4K;
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::wordlist::Wordlist;
+use std::net::{IpAddr, SocketAddr};
+
+/// Port that marks an address without one
+const NO_PORT: u16 = 65535;
+
+/// Joins the words of a phrase written into text
+const JOINER: &str = "-";
+
+/// Phrase lengths in words: IPv4, then the IPv6 sizes
+const PHRASE_LENGTHS: [usize; 4] = [4, 6, 9, 12];
+
+/// Rewrites addresses in text with an encoder's phrases
+pub struct TextFilter<W = Dictionary4K> {
+    encoder: FourWordAdaptiveEncoder<W>,
+}
+
+impl<W: Wordlist> TextFilter<W> {
+    /// Creates a filter using `encoder`, including its language and profile
+    pub fn new(encoder: FourWordAdaptiveEncoder<W>) -> Self {
+        TextFilter { encoder }
+    }
+
+    /// The encoder phrases are made and read with
+    pub fn encoder(&self) -> &FourWordAdaptiveEncoder<W> {
+        &self.encoder
+    }
+
+    /// Replaces every standalone address in `text` with its joined phrase
+    pub fn addresses_to_words(&self, text: &str) -> String {
+        let bytes = text.as_bytes();
+        let mut out = String::with_capacity(text.len() * 2);
+        let mut copied = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            if !is_address_byte(bytes[i]) || (i > 0 && is_word_byte(bytes[i - 1])) {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < bytes.len() && is_address_byte(bytes[i]) {
+                i += 1;
+            }
+            if i < bytes.len() && is_word_byte(bytes[i]) {
+                continue;
+            }
+            // Address bytes are ASCII, so the run is on char boundaries
+            if let Some((len, phrase)) = self.longest_address(&text[start..i]) {
+                out.push_str(&text[copied..start]);
+                out.push_str(&phrase);
+                copied = start + len;
+            }
+        }
+        out.push_str(&text[copied..]);
+        out
+    }
+
+    /// Replaces every joined phrase in `text` with the address it decodes to.
+    ///
+    /// Words may be joined with `-` or `.`; addresses without a port are
+    /// written without one.
+    pub fn words_to_addresses(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut copied = 0;
+        let mut chars = text.char_indices().peekable();
+        let mut previous = None;
+        while let Some((start, c)) = chars.next() {
+            let boundary = !previous.is_some_and(is_word_char);
+            previous = Some(c);
+            if !c.is_alphabetic() || !boundary {
+                continue;
+            }
+            // Letters, with single separators between them
+            let mut end = start + c.len_utf8();
+            let mut separator = None;
+            while let Some(&(at, next)) = chars.peek() {
+                if next.is_alphabetic() {
+                    end = at + next.len_utf8();
+                } else if (next == '-' || next == '.')
+                    && separator.is_none_or(|separator| separator == next)
+                    && text[at + 1..].starts_with(char::is_alphabetic)
+                {
+                    separator = Some(next);
+                } else {
+                    break;
+                }
+                previous = Some(next);
+                chars.next();
+            }
+            if chars.peek().is_some_and(|&(_, next)| is_word_char(next)) {
+                continue;
+            }
+            let token = &text[start..end];
+            let Some(separator) = separator else {
+                continue;
+            };
+            if !PHRASE_LENGTHS.contains(&token.split(separator).count()) {
+                continue;
+            }
+            let phrase = token.replace(separator, " ");
+            if let Ok(addr) = self.encoder.decode_socket_addr(&phrase) {
+                out.push_str(&text[copied..start]);
+                if addr.port() == NO_PORT {
+                    out.push_str(&addr.ip().to_string());
+                } else {
+                    out.push_str(&addr.to_string());
+                }
+                copied = end;
+            }
+        }
+        out.push_str(&text[copied..]);
+        out
+    }
+
+    /// The longest prefix of `run` that is an address, dropping trailing `.`
+    /// and `:`, with its phrase
+    fn longest_address(&self, run: &str) -> Option<(usize, String)> {
+        let mut candidate = run;
+        loop {
+            if let Some(addr) = parse_address(candidate)
+                && let Ok(phrase) = self.joined_phrase(addr)
+            {
+                return Some((candidate.len(), phrase));
+            }
+            candidate = candidate.strip_suffix(['.', ':'])?;
+        }
+    }
+
+    fn joined_phrase(&self, addr: SocketAddr) -> crate::error::Result<String> {
+        let mut phrase = String::new();
+        self.encoder.encode_into(addr, &mut phrase)?;
+        Ok(phrase.replace(' ', JOINER))
+    }
+}
+
+/// Parses `ip`, `ip:port`, `[ipv6]` or `[ipv6]:port` (port 65535 for none)
+fn parse_address(candidate: &str) -> Option<SocketAddr> {
+    // Cheap rejection of hex words and bare "::"
+    if !candidate.contains([':', '.']) || !candidate.contains(|c: char| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    if let Ok(addr) = candidate.parse::<SocketAddr>() {
+        return Some(addr);
+    }
+    let ip = candidate
+        .strip_prefix('[')
+        .and_then(|inner| inner.strip_suffix(']'))
+        .unwrap_or(candidate);
+    ip.parse::<IpAddr>()
+        .ok()
+        .map(|ip| SocketAddr::new(ip, NO_PORT))
+}
+
+/// Bytes an address token is made of
+fn is_address_byte(byte: u8) -> bool {
+    byte.is_ascii_hexdigit() || matches!(byte, b'.' | b':' | b'[' | b']')
+}
+
+/// Bytes an address may not touch; non-ASCII bytes are parts of letters
+fn is_word_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || !byte.is_ascii()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter() -> TextFilter {
+        TextFilter::new(FourWordAdaptiveEncoder::new().unwrap())
+    }
+
+    #[test]
+    fn test_round_trip_log_lines() {
+        let filter = filter();
+        for line in [
+            "2024-05-01T10:00:00Z conn from 10.0.0.1:51234 to 192.168.1.1:443 ok",
+            "peer [2001:db8::1]:8080 (fe80::1%eth0) dropped",
+            "resolved example.com -> 93.184.216.34.",
+            "listening on [::1]:80, 127.0.0.1:80: ready",
+        ] {
+            let words = filter.addresses_to_words(line);
+            assert_ne!(words, line);
+            assert_eq!(filter.words_to_addresses(&words), line, "{words}");
+        }
+
+        let words = filter.addresses_to_words("a 10.0.0.1 b");
+        let phrase = filter
+            .joined_phrase("10.0.0.1:65535".parse().unwrap())
+            .unwrap();
+        assert_eq!(words, format!("a {phrase} b"));
+        assert_eq!(phrase.split(JOINER).count(), 4);
+    }
+
+    #[test]
+    fn test_leaves_non_addresses_alone() {
+        let filter = filter();
+        for line in [
+            "std::vec::Vec and Foo::bar",
+            "version v1.2.3.4 and 1.2.3 at 12:30:45",
+            "mac aa:bb:cc:dd:ee:ff deadbeef :: cafe",
+            "ünicode10.0.0.1 and 10.0.0.1x",
+            "",
+        ] {
+            assert_eq!(filter.addresses_to_words(line), line);
+        }
+    }
+
+    #[test]
+    fn test_words_to_addresses_needs_joined_phrases() {
+        let filter = filter();
+        let addr: SocketAddr = "10.1.2.3:22".parse().unwrap();
+        let spaced = filter.encoder().encode("10.1.2.3:22").unwrap();
+        assert_eq!(filter.words_to_addresses(&spaced), spaced);
+
+        let dotted = format!("ssh {}.", spaced.replace(' ', "."));
+        assert_eq!(filter.words_to_addresses(&dotted), format!("ssh {addr}."));
+
+        // Too few words, or words touching digits, are not phrases
+        assert_eq!(filter.words_to_addresses("ocean-thunder"), "ocean-thunder");
+        let glued = format!("x{}", spaced.replace(' ', "-"));
+        assert_eq!(filter.words_to_addresses(&glued), glued);
+    }
+}
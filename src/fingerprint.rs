@@ -0,0 +1,196 @@
+//! Key fingerprints as words for verbal verification.
+//!
+//! [`fingerprint_words`] turns a TLS certificate or SSH host key digest into
+//! a fixed [`FINGERPRINT_WORDS`]-word phrase, an alternative to hex strings
+//! and randomart that can be read out over the phone. The digest is hashed
+//! again with SHA-256 under a domain tag, so SHA-1, SHA-256 and SHA-512
+//! digests all give phrases of the same length, and the first 96 bits pick
+//! the words. Only the English dictionary is used, so the words meet the
+//! same length and confusability rules as address phrases.
+//!
+//! [`parse_fingerprint`] accepts the textual forms tools print, and
+//! [`compare_fingerprints`] reports which words a listener heard differently:
+//!
+//! ```rust
+//! use four_word_networking::fingerprint::{
+//!     compare_fingerprints, fingerprint_words, parse_fingerprint,
+//! };
+//!
+//! let digest = parse_fingerprint("SHA256:uNiVztksCsDhcc0u9e8BujQXVUpKZIDTMczCvj3tD2s")?;
+//! let words = fingerprint_words(&digest)?;
+//! assert_eq!(words.split(' ').count(), 8);
+//!
+//! // The other side reads back what they see
+//! let heard = words.to_uppercase();
+//! assert!(compare_fingerprints(&words, &heard)?.is_empty());
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::four_word_ipv6_encoder::read_index;
+use crate::language::Language;
+use crate::phrase::write_words;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use sha2::{Digest, Sha256};
+
+/// Words in a fingerprint phrase (96 bits)
+pub const FINGERPRINT_WORDS: usize = 8;
+
+/// Domain tag hashed ahead of the digest, so phrases never collide with other uses
+const DOMAIN_TAG: &[u8] = b"four-word-networking fingerprint v1\0";
+
+/// Encodes a key or certificate digest as a [`FINGERPRINT_WORDS`]-word phrase
+pub fn fingerprint_words(digest: &[u8]) -> Result<String> {
+    if digest.is_empty() {
+        return Err(FourWordError::unsupported(
+            "fingerprint",
+            "the digest is empty",
+        ));
+    }
+    let hash = Sha256::new()
+        .chain_update(DOMAIN_TAG)
+        .chain_update(digest)
+        .finalize();
+    let indices: Vec<u16> = (0..FINGERPRINT_WORDS)
+        .map(|word| read_index(&hash, word))
+        .collect();
+    let mut words = String::new();
+    write_words(&Language::English.dictionary(), &indices, &mut words)?;
+    Ok(words)
+}
+
+/// Parses a digest as printed by common tools.
+///
+/// Accepts OpenSSH's `SHA256:<base64>` (and `MD5:aa:bb:...`), colon-separated
+/// hex as shown for TLS certificates (`AB:CD:...`), and plain hex.
+pub fn parse_fingerprint(text: &str) -> Result<Vec<u8>> {
+    let text = text.trim();
+    let invalid =
+        || FourWordError::invalid_value("fingerprint", text, "expected SHA256:<base64> or hex");
+    let (algorithm, value) = match text.split_once(':') {
+        Some((algorithm, value))
+            if algorithm.starts_with(|c: char| c.is_ascii_uppercase()) && algorithm.len() > 2 =>
+        {
+            (Some(algorithm), value)
+        }
+        _ => (None, text),
+    };
+    let digest = match algorithm {
+        Some(algorithm) if algorithm.starts_with("SHA") => STANDARD_NO_PAD
+            .decode(value.trim_end_matches('='))
+            .map_err(|_| invalid())?,
+        _ => hex::decode(value.replace(':', "")).map_err(|_| invalid())?,
+    };
+    if digest.is_empty() {
+        return Err(invalid());
+    }
+    Ok(digest)
+}
+
+/// Compares an expected fingerprint phrase with one read back by a person.
+///
+/// Case and separators are ignored. Returns the positions of the words that
+/// differ, so an empty list means the fingerprints match. A phrase with the
+/// wrong number of words or a word outside the dictionary is an error rather
+/// than a mismatch, because it was most likely misheard.
+pub fn compare_fingerprints(expected: &str, heard: &str) -> Result<Vec<usize>> {
+    let expected = fingerprint_indices(expected)?;
+    let heard = fingerprint_indices(heard)?;
+    Ok(expected
+        .iter()
+        .zip(&heard)
+        .enumerate()
+        .filter(|(_, (a, b))| a != b)
+        .map(|(position, _)| position)
+        .collect())
+}
+
+/// Looks up the words of a fingerprint phrase
+fn fingerprint_indices(phrase: &str) -> Result<Vec<u16>> {
+    let dictionary = Language::English.dictionary();
+    let indices = phrase
+        .split(|c: char| c.is_whitespace() || c == '.' || c == '-')
+        .filter(|word| !word.is_empty())
+        .enumerate()
+        .map(|(position, word)| {
+            dictionary
+                .get_index(word)
+                .ok_or_else(|| FourWordError::unknown_word(position, word))
+        })
+        .collect::<Result<Vec<u16>>>()?;
+    if indices.len() != FINGERPRINT_WORDS {
+        return Err(FourWordError::wrong_word_count(
+            &[FINGERPRINT_WORDS],
+            indices.len(),
+        ));
+    }
+    Ok(indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SSH: &str = "SHA256:uNiVztksCsDhcc0u9e8BujQXVUpKZIDTMczCvj3tD2s";
+
+    #[test]
+    fn test_phrase_is_fixed_length_and_stable() {
+        let sha1 = [0x5a; 20];
+        let sha512 = [0x5a; 64];
+        let words = fingerprint_words(&sha1).unwrap();
+        assert_eq!(words.split(' ').count(), FINGERPRINT_WORDS);
+        assert_eq!(
+            fingerprint_words(&sha512).unwrap().split(' ').count(),
+            FINGERPRINT_WORDS
+        );
+        assert_eq!(fingerprint_words(&sha1).unwrap(), words);
+        assert_ne!(fingerprint_words(&sha512).unwrap(), words);
+        assert!(fingerprint_words(&[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_tool_formats() {
+        let digest = parse_fingerprint(SSH).unwrap();
+        assert_eq!(digest.len(), 32);
+
+        let colon_hex = digest
+            .iter()
+            .map(|byte| format!("{byte:02X}"))
+            .collect::<Vec<_>>()
+            .join(":");
+        assert_eq!(parse_fingerprint(&colon_hex).unwrap(), digest);
+        assert_eq!(parse_fingerprint(&hex::encode(&digest)).unwrap(), digest);
+        assert_eq!(
+            parse_fingerprint("MD5:16:27:ac:a5:76:28:2d:36:63:1b:56:4d:eb:df:a6:48")
+                .unwrap()
+                .len(),
+            16
+        );
+
+        for text in ["", "SHA256:", "SHA256:!!", "zz:yy"] {
+            assert!(parse_fingerprint(text).is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_compare_reports_differing_words() {
+        let words = fingerprint_words(&parse_fingerprint(SSH).unwrap()).unwrap();
+        assert!(
+            compare_fingerprints(&words, &words.replace(' ', "-"))
+                .unwrap()
+                .is_empty()
+        );
+
+        let dictionary = Language::English.dictionary();
+        let mut heard: Vec<&str> = words.split(' ').collect();
+        let index = dictionary.get_index(heard[3]).unwrap();
+        heard[3] = dictionary.get_word((index + 1) % 4096).unwrap();
+        assert_eq!(compare_fingerprints(&words, &heard.join(" ")).unwrap(), [3]);
+
+        assert!(compare_fingerprints(&words, &heard[..7].join(" ")).is_err());
+        assert!(compare_fingerprints(&words, "not real words at all in this phrase").is_err());
+    }
+}
@@ -0,0 +1,601 @@
+//! Configurable rendering of encoded addresses.
+//!
+//! Words are the canonical form of an address; a [`Formatter`] chooses how
+//! one is shown to people. [`Alphabet::Words`] gives the usual phrase,
+//! [`Alphabet::Emoji`] the [emoji rendering](crate::emoji) of the same word
+//! indices and [`Alphabet::Phonetic`] the [spoken read-back](crate::phonetic).
+//! [`Formatter::parse`] reads any of them back whatever the formatter is set
+//! to, so a receiver never needs to know which one was sent.
+//!
+//! [`Alphabet::Numeric`] writes each word as its dictionary index, joined by
+//! dashes (`1492-88-2051-7`), for keyboards and locales where the words are
+//! hard to type. It is not a separate encoding: every decoder reads a
+//! decimal index anywhere a word may stand, so words and indices can be
+//! mixed (`ocean-88-falcon-7`), and [`WordSocketAddr`](crate::WordSocketAddr)
+//! takes a port suffix on a port-less one (`1492-88-2051-7:443`). Indices
+//! must be separated by dashes or whitespace: dotted numbers are left to
+//! IPv4, so a mistyped address such as `300.1.1.1` is never read as a
+//! phrase.
+//!
+//! Phrases copied between apps pick up cosmetic differences: `Ocean.Thunder`,
+//! `ocean-thunder`, quotes or a trailing full stop. Decoding goes through
+//! [`normalize_phrase`], which folds these away, and a formatter writes words
+//! with the [`Separator`] and [`Case`] a UI wants; [`Formatter::canonicalize`]
+//! rewrites any accepted form in the formatter's style. Decoded addresses are
+//! written with [`Formatter::format_addr`] in the configured [`PortStyle`].
+//!
+//! ```rust
+//! use four_word_networking::FourWordAdaptiveEncoder;
+//! use four_word_networking::format::{Alphabet, Case, Formatter, Separator};
+//!
+//! let encoder = FourWordAdaptiveEncoder::new()?;
+//! let addr = "192.168.1.1:443".parse()?;
+//! let emoji = Formatter::new().with_alphabet(Alphabet::Emoji);
+//!
+//! let row = emoji.format(&encoder, addr)?;
+//! assert_eq!(row.chars().filter(|c| *c != ' ').count(), 8);
+//! assert_eq!(emoji.parse(&encoder, &row)?, addr);
+//!
+//! let words = Formatter::new().format(&encoder, addr)?;
+//! assert_eq!(words, encoder.encode("192.168.1.1:443")?);
+//! assert_eq!(Formatter::new().parse(&encoder, &row)?, addr);
+//!
+//! let dashed = Formatter::new().with_separator(Separator::Dash).with_case(Case::Upper);
+//! let styled = dashed.canonicalize(&encoder, &format!("\"{}.\"", words.replace(' ', ".")))?;
+//! assert_eq!(styled, words.replace(' ', "-").to_uppercase());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::emoji::{from_emoji, is_emoji_row, to_emoji};
+use crate::error::{FourWordError, Result};
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::phonetic::{is_read_back, parse_read_back, read_back};
+use crate::phrase::{DEFAULT_PHRASE_CAPACITY, WordPhrase};
+use crate::ports::display_socket_addr;
+use crate::wordlist::Wordlist;
+use std::borrow::Cow;
+use std::net::SocketAddr;
+
+/// Port that marks an address without one
+const NO_PORT: u16 = 65535;
+
+/// The symbols an address is written in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Alphabet {
+    /// Dictionary words, the canonical form
+    #[default]
+    Words,
+    /// Two emoji per word index (see [`emoji`](crate::emoji))
+    Emoji,
+    /// Each word spelled in the NATO alphabet, then the port's digits, for
+    /// dictation (see [`phonetic`](crate::phonetic))
+    Phonetic,
+    /// Each word's dictionary index in decimal, joined by dashes
+    Numeric,
+}
+
+/// What goes between the words of a phrase
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Separator {
+    /// `ocean thunder`, the canonical form
+    #[default]
+    Space,
+    /// `ocean.thunder`
+    Dot,
+    /// `ocean-thunder`, one token for tools that split on whitespace
+    Dash,
+}
+
+impl Separator {
+    /// The separator character
+    pub fn as_char(self) -> char {
+        match self {
+            Separator::Space => ' ',
+            Separator::Dot => '.',
+            Separator::Dash => '-',
+        }
+    }
+}
+
+/// Letter case of written words
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Case {
+    /// `ocean thunder`, the canonical form
+    #[default]
+    Lower,
+    /// `OCEAN THUNDER`
+    Upper,
+    /// `Ocean Thunder`
+    Title,
+}
+
+/// How the port of a decoded address is written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PortStyle {
+    /// `192.168.1.1:443`
+    #[default]
+    Number,
+    /// `192.168.1.1:https`, for well-known ports
+    Service,
+}
+
+/// How encoded addresses are written out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Formatter {
+    alphabet: Alphabet,
+    separator: Separator,
+    case: Case,
+    port_style: PortStyle,
+}
+
+impl Formatter {
+    /// Creates a formatter that writes canonical phrases
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the alphabet addresses are written in
+    pub fn with_alphabet(mut self, alphabet: Alphabet) -> Self {
+        self.alphabet = alphabet;
+        self
+    }
+
+    /// Returns the alphabet addresses are written in
+    pub fn alphabet(&self) -> Alphabet {
+        self.alphabet
+    }
+
+    /// Sets the separator between words ([`Alphabet::Words`] only)
+    pub fn with_separator(mut self, separator: Separator) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Returns the separator between words
+    pub fn separator(&self) -> Separator {
+        self.separator
+    }
+
+    /// Sets the letter case of words ([`Alphabet::Words`] only)
+    pub fn with_case(mut self, case: Case) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Returns the letter case of words
+    pub fn case(&self) -> Case {
+        self.case
+    }
+
+    /// Sets how [`format_addr`](Self::format_addr) writes ports
+    pub fn with_port_style(mut self, port_style: PortStyle) -> Self {
+        self.port_style = port_style;
+        self
+    }
+
+    /// Returns how ports are written
+    pub fn port_style(&self) -> PortStyle {
+        self.port_style
+    }
+
+    /// Encodes `addr` with `encoder` and writes it in this formatter's style
+    /// (port 65535 for "no port")
+    pub fn format<W: Wordlist>(
+        &self,
+        encoder: &FourWordAdaptiveEncoder<W>,
+        addr: SocketAddr,
+    ) -> Result<String> {
+        match self.alphabet {
+            Alphabet::Words => {
+                let mut phrase = String::new();
+                encoder.encode_into(addr, &mut phrase)?;
+                Ok(self.style_words(&phrase))
+            }
+            Alphabet::Emoji => {
+                // Emoji carry word indices, so reserved-word escapes are not needed
+                let mut phrase = WordPhrase::<DEFAULT_PHRASE_CAPACITY>::new();
+                encoder.encode_unescaped_into(addr, &mut phrase)?;
+                Ok(to_emoji(&phrase_indices(encoder.wordlist(), &phrase)?))
+            }
+            Alphabet::Numeric => {
+                // Indices stand in for the words, escapes included, and decode like them
+                let mut phrase = WordPhrase::<DEFAULT_PHRASE_CAPACITY>::new();
+                encoder.encode_into(addr, &mut phrase)?;
+                let indices = phrase_indices(encoder.wordlist(), &phrase)?;
+                Ok(indices
+                    .iter()
+                    .map(u16::to_string)
+                    .collect::<Vec<_>>()
+                    .join("-"))
+            }
+            Alphabet::Phonetic => {
+                let mut phrase = String::new();
+                encoder.encode_into(addr, &mut phrase)?;
+                // Say the port the phrase carries, which is the one a listener gets
+                let port = Some(encoder.decode_socket_addr(&phrase)?.port())
+                    .filter(|&port| port != NO_PORT);
+                let words: Vec<&str> = phrase.split(' ').collect();
+                Ok(read_back(&words, port))
+            }
+        }
+    }
+
+    /// Decodes a phrase, an emoji row or a read-back, whichever `text` is.
+    ///
+    /// A read-back's spoken port must match the port its words decode to.
+    pub fn parse<W: Wordlist>(
+        &self,
+        encoder: &FourWordAdaptiveEncoder<W>,
+        text: &str,
+    ) -> Result<SocketAddr> {
+        if is_read_back(text) {
+            let (words, port) = parse_read_back(text)?;
+            let addr = encoder.decode_socket_addr(&words.join(" "))?;
+            if let Some(port) = port
+                && port != addr.port()
+            {
+                return Err(FourWordError::DecodingError(format!(
+                    "The words decode to port {} but port {port} was read back",
+                    addr.port()
+                )));
+            }
+            return Ok(addr);
+        }
+        if !is_emoji_row(text) {
+            return encoder.decode_socket_addr(text);
+        }
+        let words = from_emoji(text)?
+            .into_iter()
+            .map(|index| {
+                encoder
+                    .wordlist()
+                    .get_word(index)
+                    .ok_or(FourWordError::InvalidWordIndex(index))
+            })
+            .collect::<Result<Vec<&str>>>()?;
+        encoder.decode_unescaped(&words.join(" "))
+    }
+
+    /// Rewrites a phrase, emoji row or read-back in this formatter's style.
+    ///
+    /// Phrases that differ only in separators, case or surrounding
+    /// punctuation canonicalize to the same string.
+    pub fn canonicalize<W: Wordlist>(
+        &self,
+        encoder: &FourWordAdaptiveEncoder<W>,
+        text: &str,
+    ) -> Result<String> {
+        self.format(encoder, self.parse(encoder, text)?)
+    }
+
+    /// Writes a decoded address, leaving out port 65535 ("no port")
+    pub fn format_addr(&self, addr: SocketAddr) -> String {
+        if addr.port() == NO_PORT {
+            return addr.ip().to_string();
+        }
+        match self.port_style {
+            PortStyle::Number => addr.to_string(),
+            PortStyle::Service => display_socket_addr(addr),
+        }
+    }
+
+    /// Applies the separator and case to a canonical phrase
+    fn style_words(&self, phrase: &str) -> String {
+        let separator = self.separator.as_char();
+        let mut styled = String::with_capacity(phrase.len());
+        for (position, word) in phrase.split(' ').enumerate() {
+            if position > 0 {
+                styled.push(separator);
+            }
+            match self.case {
+                Case::Lower => styled.push_str(word),
+                Case::Upper => styled.push_str(&word.to_uppercase()),
+                Case::Title => {
+                    let mut chars = word.chars();
+                    styled.extend(chars.next().into_iter().flat_map(char::to_uppercase));
+                    styled.push_str(chars.as_str());
+                }
+            }
+        }
+        styled
+    }
+}
+
+/// Looks up the index of every word of a canonical `phrase`
+fn phrase_indices<W: Wordlist + ?Sized>(wordlist: &W, phrase: &str) -> Result<Vec<u16>> {
+    phrase
+        .split(' ')
+        .enumerate()
+        .map(|(position, word)| {
+            wordlist
+                .get_index(word)
+                .ok_or_else(|| FourWordError::unknown_word(position, word))
+        })
+        .collect()
+}
+
+/// Whether `token` is a word written as its decimal dictionary index
+pub(crate) fn is_index(token: &str) -> bool {
+    !token.is_empty() && token.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// [Normalizes](normalize_phrase) `text` and replaces the indices in it with
+/// the words of `wordlist` they stand for.
+///
+/// Indices in dot-separated text are refused, since dotted numbers are IPv4.
+pub(crate) fn resolve_indices<'a, W: Wordlist + ?Sized>(
+    wordlist: &W,
+    text: &'a str,
+) -> Result<Cow<'a, str>> {
+    let phrase = normalize_phrase(text);
+    if !phrase.split(' ').any(is_index) {
+        return Ok(phrase);
+    }
+    if text
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .contains('.')
+    {
+        return Err(FourWordError::invalid_value(
+            "phrase",
+            text,
+            "separate word indices with '-' or spaces; dotted numbers read as an IPv4 address",
+        ));
+    }
+    let mut words = String::with_capacity(phrase.len() * 2);
+    for (position, token) in phrase.split(' ').enumerate() {
+        if position > 0 {
+            words.push(' ');
+        }
+        if !is_index(token) {
+            words.push_str(token);
+            continue;
+        }
+        let index = token
+            .parse::<u16>()
+            .map_err(|_| FourWordError::unknown_word(position, token))?;
+        let word = wordlist
+            .get_word(index)
+            .ok_or(FourWordError::InvalidWordIndex(index))?;
+        words.push_str(word);
+    }
+    Ok(Cow::Owned(words))
+}
+
+/// Folds a phrase as copied from another UI onto the canonical form: words
+/// separated by whitespace, `.` or `-` (mixed freely), any case, and
+/// punctuation around the whole phrase such as quotes, brackets or a full
+/// stop, become lower-case words separated by single spaces.
+///
+/// Canonical input is returned as is, without allocating.
+pub fn normalize_phrase(text: &str) -> Cow<'_, str> {
+    let is_canonical = !text.is_empty()
+        && text.split(' ').all(|word| {
+            !word.is_empty()
+                && word
+                    .chars()
+                    .all(|c| c.is_alphanumeric() && !c.is_uppercase())
+        });
+    if is_canonical {
+        return Cow::Borrowed(text);
+    }
+    let trimmed = text.trim_matches(|c: char| !c.is_alphanumeric());
+    let mut phrase = String::with_capacity(trimmed.len());
+    for word in trimmed
+        .split(|c: char| c.is_whitespace() || c == '.' || c == '-')
+        .filter(|word| !word.is_empty())
+    {
+        if !phrase.is_empty() {
+            phrase.push(' ');
+        }
+        phrase.extend(word.chars().flat_map(char::to_lowercase));
+    }
+    Cow::Owned(phrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emoji_round_trip_every_family() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let emoji = Formatter::new().with_alphabet(Alphabet::Emoji);
+        for addr in [
+            "10.0.0.1:65535",
+            "[::1]:8080",
+            "[fe80::1]:22",
+            "[2001:db8::1]:443",
+            "[2606:4700:4700::1111]:53",
+        ] {
+            let addr: SocketAddr = addr.parse().unwrap();
+            let row = emoji.format(&encoder, addr).unwrap();
+            let words = Formatter::new().format(&encoder, addr).unwrap();
+            assert_eq!(row.split(' ').count(), words.split(' ').count());
+            assert_eq!(
+                emoji.parse(&encoder, &row).unwrap(),
+                emoji.parse(&encoder, &words).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_emoji_ignores_reserved_word_escapes() {
+        let mut profile = crate::EncodingProfile::new();
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let addr: SocketAddr = "192.168.1.1:443".parse().unwrap();
+        let words = encoder.encode("192.168.1.1:443").unwrap();
+        let first = words.split(' ').next().unwrap().to_string();
+        let escape = encoder.wordlist().get_word(1).unwrap();
+        profile.reserve_words(escape, &[&first]).unwrap();
+        let escaping = FourWordAdaptiveEncoder::new()
+            .unwrap()
+            .with_profile(profile);
+        assert_ne!(escaping.encode("192.168.1.1:443").unwrap(), words);
+
+        let emoji = Formatter::new().with_alphabet(Alphabet::Emoji);
+        let row = emoji.format(&escaping, addr).unwrap();
+        assert_eq!(row, emoji.format(&encoder, addr).unwrap());
+        assert_eq!(emoji.parse(&escaping, &row).unwrap(), addr);
+    }
+
+    #[test]
+    fn test_numeric_indices_mix_with_words() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let numeric = Formatter::new().with_alphabet(Alphabet::Numeric);
+        for addr in ["10.0.0.1:80", "[2001:db8::1]:443", "[::1]:8080"] {
+            let addr: SocketAddr = addr.parse().unwrap();
+            let row = numeric.format(&encoder, addr).unwrap();
+            assert!(row.split('-').all(is_index), "{row}");
+            assert_eq!(numeric.parse(&encoder, &row).unwrap(), addr);
+            assert_eq!(
+                encoder.decode_socket_addr(&row.replace('-', " ")).unwrap(),
+                addr
+            );
+
+            // Every other word written as its index
+            let words = Formatter::new().format(&encoder, addr).unwrap();
+            let mixed: Vec<&str> = words
+                .split(' ')
+                .zip(row.split('-'))
+                .enumerate()
+                .map(|(position, (word, index))| if position % 2 == 0 { index } else { word })
+                .collect();
+            assert_eq!(encoder.decode_socket_addr(&mixed.join("-")).unwrap(), addr);
+            assert_eq!(
+                Formatter::new().canonicalize(&encoder, &row).unwrap(),
+                words
+            );
+            let fuzzy = encoder.decode_fuzzy(&row, true).unwrap();
+            assert!(fuzzy.corrections.is_empty());
+            assert_eq!(fuzzy.address, encoder.decode(&words).unwrap());
+        }
+
+        // A port suffix on a port-less phrase
+        let bare = numeric
+            .format(&encoder, "10.0.0.1:65535".parse().unwrap())
+            .unwrap();
+        let with_port: crate::WordSocketAddr = format!("{bare}:443").parse().unwrap();
+        assert_eq!(with_port.addr(), "10.0.0.1:443".parse().unwrap());
+
+        assert!(matches!(
+            encoder.decode_socket_addr("4096 1 2 3"),
+            Err(FourWordError::InvalidWordIndex(4096))
+        ));
+        assert!(matches!(
+            encoder.decode_socket_addr("99999 1 2 3"),
+            Err(FourWordError::UnknownWord { position: 0, .. })
+        ));
+        // Dotted numbers are an address, or a mistyped one, never indices
+        assert!(matches!(
+            encoder.decode_socket_addr("10.0.0.1"),
+            Err(FourWordError::AlreadyDecoded(_))
+        ));
+        assert!(matches!(
+            encoder.decode_socket_addr("300.1.1.1"),
+            Err(FourWordError::InvalidValue { kind: "phrase", .. })
+        ));
+        let words = encoder.encode("10.0.0.1:80").unwrap();
+        let (_, rest) = words.split_once(' ').unwrap();
+        let dotted = format!("7.{}", rest.replace(' ', "."));
+        assert!(encoder.decode_socket_addr(&dotted).is_err(), "{dotted}");
+    }
+
+    #[test]
+    fn test_cosmetic_variants_decode_and_canonicalize() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        for addr in ["10.0.0.1:80", "[2001:db8::1]:443"] {
+            let addr: SocketAddr = addr.parse().unwrap();
+            let words = Formatter::new().format(&encoder, addr).unwrap();
+            let title = Formatter::new().with_case(Case::Title);
+            for variant in [
+                words.to_uppercase(),
+                words.replace(' ', "-"),
+                words.replace(' ', "."),
+                format!("\"{}.\"", title.format(&encoder, addr).unwrap()),
+                format!(
+                    "({})\n",
+                    words.replacen(' ', "-", 1).replacen(' ', ".\t", 1)
+                ),
+            ] {
+                assert_eq!(
+                    encoder.decode_socket_addr(&variant).unwrap(),
+                    addr,
+                    "{variant}"
+                );
+                assert_eq!(
+                    Formatter::new().canonicalize(&encoder, &variant).unwrap(),
+                    words
+                );
+            }
+        }
+
+        assert!(matches!(
+            normalize_phrase("ocean thunder"),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(normalize_phrase("¡Ocean--Thunder!"), "ocean thunder");
+        assert_eq!(normalize_phrase("  "), "");
+    }
+
+    #[test]
+    fn test_word_and_port_styles() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let addr: SocketAddr = "192.168.1.1:443".parse().unwrap();
+        let words = Formatter::new().format(&encoder, addr).unwrap();
+        let styled = Formatter::new()
+            .with_separator(Separator::Dot)
+            .with_case(Case::Title)
+            .format(&encoder, addr)
+            .unwrap();
+        assert_eq!(styled.to_lowercase(), words.replace(' ', "."));
+        assert!(
+            styled
+                .split('.')
+                .all(|word| word.starts_with(char::is_uppercase))
+        );
+
+        // Styling applies to words only
+        let emoji = Formatter::new().with_alphabet(Alphabet::Emoji);
+        assert_eq!(
+            emoji
+                .with_separator(Separator::Dash)
+                .format(&encoder, addr)
+                .unwrap(),
+            emoji.format(&encoder, addr).unwrap()
+        );
+
+        let service = Formatter::new().with_port_style(PortStyle::Service);
+        assert_eq!(Formatter::new().format_addr(addr), "192.168.1.1:443");
+        assert_eq!(service.format_addr(addr), "192.168.1.1:https");
+        assert_eq!(service.format_addr("[::1]:65535".parse().unwrap()), "::1");
+    }
+
+    #[test]
+    fn test_phonetic_round_trip_and_port_check() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let phonetic = Formatter::new().with_alphabet(Alphabet::Phonetic);
+        for addr in [
+            "10.0.0.1:65535",
+            "192.168.1.1:8080",
+            "[::1]:443",
+            "[2001:db8::1]:22",
+        ] {
+            let addr: SocketAddr = addr.parse().unwrap();
+            let spoken = phonetic.format(&encoder, addr).unwrap();
+            assert_eq!(Formatter::new().parse(&encoder, &spoken).unwrap(), addr);
+            assert_eq!(spoken.contains("port:"), addr.port() != NO_PORT);
+        }
+
+        let spoken = phonetic
+            .format(&encoder, "192.168.1.1:8080".parse().unwrap())
+            .unwrap();
+        assert!(spoken.ends_with(", port: Eight Zero - Eight Zero"));
+        let misheard = spoken.replace("port: Eight Zero - Eight Zero", "port: Eight Zero - Eight");
+        assert!(matches!(
+            phonetic.parse(&encoder, &misheard),
+            Err(FourWordError::DecodingError(_))
+        ));
+    }
+}
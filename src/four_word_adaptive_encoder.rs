@@ -2,47 +2,348 @@
 //!
 //! This is the main public API for four-word networking.
 
+#![forbid(unsafe_code)]
+
+use crate::confusability;
+use crate::dictionary4k::{DICTIONARY_SIZE, Dictionary4K};
 use crate::error::{FourWordError, Result};
+use crate::format::{is_index, resolve_indices};
 use crate::four_word_encoder::FourWordEncoder;
 use crate::four_word_ipv6_encoder::{FourWordIpv6Encoder, Ipv6FourWordGroupEncoding};
-use std::net::{IpAddr, SocketAddr};
+use crate::fuzzy::{
+    FuzzyDecoding, MAX_SUGGESTIONS, WordCorrection, edit_distance, suggest_words_in,
+    unambiguous_correction,
+};
+use crate::language::Language;
+use crate::normalize::VoiceNormalizer;
+use crate::phrase::{DEFAULT_PHRASE_CAPACITY, WordPhrase};
+use crate::ports;
+use crate::profile::EncodingProfile;
+use crate::reserved;
+use crate::transliteration::AliasTable;
+use crate::version::{self, ENCODING_VERSION};
+use crate::wordlist::Wordlist;
+use std::fmt;
+use std::io::Read;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
 
 /// The main four-word networking encoder interface
-pub struct FourWordAdaptiveEncoder {
-    ipv4_encoder: FourWordEncoder,
-    ipv6_encoder: FourWordIpv6Encoder,
+///
+/// Generic over the [`Wordlist`] backend; the built-in [`Dictionary4K`] is the default.
+pub struct FourWordAdaptiveEncoder<W = Dictionary4K> {
+    ipv4_encoder: FourWordEncoder<W>,
+    ipv6_encoder: FourWordIpv6Encoder<W>,
+    /// `None` when encoding with a custom wordlist
+    language: Option<Language>,
+    /// Spellings folded onto dictionary words during lenient decoding
+    aliases: AliasTable,
+    /// Whether phrases with confusable neighbours get a selector word
+    avoid_confusion: bool,
 }
 
 impl FourWordAdaptiveEncoder {
-    /// Creates a new four-word adaptive encoder
+    /// Creates a new four-word adaptive encoder using the English wordlist
     pub fn new() -> Result<Self> {
+        Self::with_language(Language::English)
+    }
+
+    /// Creates an encoder that encodes to and decodes from `language`'s wordlist
+    pub fn with_language(language: Language) -> Result<Self> {
+        let mut encoder = Self::with_wordlist(language.dictionary())?;
+        encoder.language = Some(language);
+        encoder.aliases = AliasTable::builtin(language);
+        Ok(encoder)
+    }
+}
+
+impl<W: Wordlist> FourWordAdaptiveEncoder<W> {
+    /// Creates an encoder using a custom wordlist backend.
+    ///
+    /// The list must hold exactly 4,096 words. Backends that are expensive to
+    /// clone can be passed by reference or behind an `Arc`. Both sides of an
+    /// exchange must use the same list; compare [`Wordlist::fingerprint`]s
+    /// before trusting decoded addresses.
+    pub fn with_wordlist(wordlist: W) -> Result<Self>
+    where
+        W: Clone,
+    {
+        if wordlist.len() != DICTIONARY_SIZE {
+            return Err(FourWordError::DictionaryError(format!(
+                "wordlist must contain exactly {DICTIONARY_SIZE} words, got {}",
+                wordlist.len()
+            )));
+        }
         Ok(FourWordAdaptiveEncoder {
-            ipv4_encoder: FourWordEncoder::new(),
-            ipv6_encoder: FourWordIpv6Encoder::new(),
+            ipv4_encoder: FourWordEncoder::with_dictionary(wordlist.clone()),
+            ipv6_encoder: FourWordIpv6Encoder::with_dictionary(wordlist),
+            language: None,
+            aliases: AliasTable::new(),
+            avoid_confusion: false,
         })
     }
 
+    /// Uses `profile` for deployment-specific compression.
+    ///
+    /// Phrases that rely on profile entries (such as a registered NAT64 prefix)
+    /// only decode under a profile with the same entries.
+    pub fn with_profile(mut self, profile: EncodingProfile) -> Self {
+        self.ipv6_encoder = self.ipv6_encoder.with_profile(profile);
+        self
+    }
+
+    /// Rewrites phrases whose adjacent words are confusable.
+    ///
+    /// Such phrases gain a leading selector word and have their words
+    /// shifted (see [`confusability`](crate::confusability)); every encoder
+    /// decodes them, with or without this option.
+    pub fn with_confusion_avoidance(mut self, avoid: bool) -> Self {
+        self.avoid_confusion = avoid;
+        self
+    }
+
+    /// Whether phrases with confusable neighbours are rewritten
+    pub fn avoids_confusion(&self) -> bool {
+        self.avoid_confusion
+    }
+
+    /// Returns the encoding profile in use
+    pub fn profile(&self) -> &EncodingProfile {
+        self.ipv6_encoder.profile()
+    }
+
+    /// Returns the alias table applied by [`decode_fuzzy`](Self::decode_fuzzy)
+    pub fn aliases(&self) -> &AliasTable {
+        &self.aliases
+    }
+
+    /// Teaches lenient decoding that `heard` means the dictionary word `canonical`
+    pub fn add_alias(&mut self, heard: &str, canonical: &str) -> Result<()> {
+        let wordlist = self.ipv4_encoder.wordlist();
+        self.aliases.insert(wordlist, heard, canonical)
+    }
+
+    /// Adds `heard,canonical` alias lines from `reader`, returning how many were added.
+    ///
+    /// See [`AliasTable::extend_from_reader`] for the format.
+    pub fn load_aliases(&mut self, reader: impl Read) -> Result<usize> {
+        let wordlist = self.ipv4_encoder.wordlist();
+        self.aliases.extend_from_reader(wordlist, reader)
+    }
+
+    /// Returns the built-in language this encoder reads and writes, or `None`
+    /// for a custom wordlist
+    pub fn language(&self) -> Option<Language> {
+        self.language
+    }
+
+    /// Returns the wordlist this encoder reads and writes
+    pub fn wordlist(&self) -> &W {
+        self.ipv4_encoder.wordlist()
+    }
+
     /// Encodes any IP address into words
     /// - IPv4: Always exactly 4 words
     /// - IPv6: 6, 9, or 12 words based on compression
+    ///
+    /// Input that is already a valid phrase fails with
+    /// [`FourWordError::AlreadyEncoded`], so running a conversion twice is
+    /// caught instead of reported as a malformed address.
     pub fn encode(&self, input: &str) -> Result<String> {
-        let addr = self.parse_address(input)?;
+        let addr = self.parse_address(input).map_err(|e| {
+            let phrase = input.trim();
+            if self.decode_socket_addr(phrase).is_ok() {
+                FourWordError::AlreadyEncoded(phrase.to_string())
+            } else {
+                e
+            }
+        })?;
 
-        match addr {
-            SocketAddr::V4(_) => {
-                let encoded = self.ipv4_encoder.encode(addr)?;
-                Ok(encoded.to_string())
+        let words = match addr {
+            SocketAddr::V4(_) => self.ipv4_encoder.encode(addr)?.to_string(),
+            // IPv6 phrases can tell a port of 65535 given explicitly from
+            // none; IPv4 phrases cannot
+            SocketAddr::V6(v6) if v6.port() == 65535 && ports::parse_socket_addr(input).is_ok() => {
+                let mut words = String::new();
+                self.ipv6_encoder
+                    .encode_port_into(*v6.ip(), Some(65535), &mut words)?;
+                words
             }
-            SocketAddr::V6(v6) => {
-                let encoded = self.ipv6_encoder.encode(&v6)?;
-                Ok(encoded.to_string())
+            SocketAddr::V6(v6) => self.ipv6_encoder.encode(&v6)?.to_string(),
+        };
+        self.finish_phrase(words)
+    }
+
+    /// Writes the space-separated words for `addr` to `out`.
+    ///
+    /// The allocation-free counterpart of [`encode`](Self::encode) for callers
+    /// that already hold a parsed address; use port 65535 for "no port".
+    pub fn encode_into(&self, addr: SocketAddr, out: &mut impl fmt::Write) -> Result<()> {
+        if self.avoid_confusion {
+            let mut phrase = WordPhrase::<DEFAULT_PHRASE_CAPACITY>::new();
+            self.encode_unescaped_into(addr, &mut phrase)?;
+            if self.profile().escape_word().is_none() {
+                return confusability::separate(self.wordlist(), &phrase, out);
             }
+            let mut separated = String::with_capacity(phrase.len());
+            confusability::separate(self.wordlist(), &phrase, &mut separated)?;
+            return reserved::escape(self.wordlist(), self.profile(), &separated, out);
+        }
+        if self.profile().escape_word().is_some() {
+            // Reserved words are replaced after encoding
+            let mut phrase = WordPhrase::<DEFAULT_PHRASE_CAPACITY>::new();
+            self.encode_unescaped_into(addr, &mut phrase)?;
+            return reserved::escape(self.wordlist(), self.profile(), &phrase, out);
+        }
+        self.encode_unescaped_into(addr, out)
+    }
+
+    /// Writes the words for `addr` without applying reserved-word escapes
+    pub(crate) fn encode_unescaped_into(
+        &self,
+        addr: SocketAddr,
+        out: &mut impl fmt::Write,
+    ) -> Result<()> {
+        match addr {
+            SocketAddr::V4(v4) => self.ipv4_encoder.encode_into(*v4.ip(), v4.port(), out),
+            SocketAddr::V6(v6) => self.ipv6_encoder.encode_into(&v6, out),
+        }
+    }
+
+    /// Encodes `addr` with a leading tag word for [`ENCODING_VERSION`].
+    ///
+    /// Tagged phrases are one word longer and decode like plain ones; see
+    /// [`version`](crate::version) for why a deployment may want them. With
+    /// [confusion avoidance](Self::with_confusion_avoidance), a phrase that
+    /// needs a selector leads with it in place of the tag, since selectors
+    /// also mark version 1.
+    pub fn encode_tagged(&self, addr: SocketAddr) -> Result<String> {
+        let mut words = String::new();
+        self.encode_unescaped_into(addr, &mut words)?;
+        let mut phrase = String::with_capacity(words.len() + 8);
+        if self.avoid_confusion {
+            confusability::separate(self.wordlist(), &words, &mut phrase)?;
+        }
+        if phrase.len() <= words.len() {
+            phrase.clear();
+            version::write_tag(self.wordlist(), ENCODING_VERSION, &mut phrase)?;
+            phrase.push(' ');
+            phrase.push_str(&words);
+        }
+        if self.profile().escape_word().is_none() {
+            return Ok(phrase);
+        }
+        let mut escaped = String::with_capacity(phrase.len());
+        reserved::escape(self.wordlist(), self.profile(), &phrase, &mut escaped)?;
+        Ok(escaped)
+    }
+
+    /// Encodes `addr`, failing with [`FourWordError::LossyEncoding`] unless
+    /// the words decode back to exactly `addr`.
+    ///
+    /// [`encode`](Self::encode) keeps only the /64 of unique-local addresses
+    /// and drops ports that do not fit a 12-word phrase; use this where a
+    /// silently different address would be worse than an error.
+    pub fn encode_lossless(&self, addr: SocketAddr) -> Result<String> {
+        let mut words = String::new();
+        self.encode_into(addr, &mut words)?;
+        let decoded = self.decode_socket_addr(&words)?;
+        if decoded != addr {
+            return Err(FourWordError::LossyEncoding {
+                input: addr.to_string(),
+                decoded: decoded.to_string(),
+            });
         }
+        Ok(words)
+    }
+
+    /// Encodes `addr` into a stack-allocated phrase of capacity `N` bytes.
+    ///
+    /// Fails with [`FourWordError::Format`] if the phrase does not fit.
+    pub fn encode_phrase<const N: usize>(&self, addr: SocketAddr) -> Result<WordPhrase<N>> {
+        let mut phrase = WordPhrase::new();
+        self.encode_into(addr, &mut phrase)?;
+        Ok(phrase)
     }
 
     /// Decodes words back to an IP address
-    /// Port 65535 is treated as "no port specified" and omitted from output
+    /// Port 65535 is treated as "no port specified" and omitted from output,
+    /// unless an IPv6 phrase was encoded with the port given explicitly
     pub fn decode(&self, words: &str) -> Result<String> {
+        let (addr, has_port) = self.decode_endpoint(words)?;
+        if has_port {
+            Ok(addr.to_string())
+        } else {
+            Ok(addr.ip().to_string())
+        }
+    }
+
+    /// Decodes words back to a socket address.
+    ///
+    /// Phrases encoded without a port decode with port 65535. Input that is
+    /// already an IP address fails with [`FourWordError::AlreadyDecoded`].
+    /// Separators, case and surrounding punctuation are folded away first
+    /// (see [`normalize_phrase`](crate::format::normalize_phrase)), and any
+    /// word may be written as its dictionary index
+    /// ([`Alphabet::Numeric`](crate::format::Alphabet::Numeric)).
+    pub fn decode_socket_addr(&self, words: &str) -> Result<SocketAddr> {
+        self.decode_endpoint(words).map(|(addr, _)| addr)
+    }
+
+    /// Decodes words to a socket address and whether they carry a port
+    fn decode_endpoint(&self, words: &str) -> Result<(SocketAddr, bool)> {
+        self.with_plain_words(words, |words| self.decode_plain_endpoint(words))
+    }
+
+    /// Runs `decode` on `words` once they are reduced to plain address words.
+    ///
+    /// Every decoder goes through here: input that is already an address is
+    /// refused, indices are resolved to words and reserved words the encoder
+    /// escaped are restored, then [`with_unescaped_words`](Self::with_unescaped_words)
+    /// takes over.
+    fn with_plain_words<T>(
+        &self,
+        words: &str,
+        decode: impl FnOnce(&str) -> Result<T>,
+    ) -> Result<T> {
+        self.reject_address(words)?;
+        let normalized = resolve_indices(self.wordlist(), words)?;
+        let words = normalized.as_ref();
+        let unescaped =
+            reserved::unescape(self.wordlist(), self.profile(), &Self::split_words(words))?;
+        self.with_unescaped_words(unescaped.as_deref().unwrap_or(words), decode)
+    }
+
+    /// Runs `decode` on `words` after checking and dropping the version tag
+    /// and undoing a confusion-avoidance selector
+    fn with_unescaped_words<T>(
+        &self,
+        words: &str,
+        decode: impl FnOnce(&str) -> Result<T>,
+    ) -> Result<T> {
+        let untagged = version::strip_tag(self.wordlist(), words, &Self::split_words(words))?;
+        let words = untagged.as_ref();
+        let unshifted = confusability::unshift(self.wordlist(), words, &Self::split_words(words))?;
+        decode(&unshifted)
+    }
+
+    /// Decodes words that contain no reserved-word escapes.
+    ///
+    /// A version tag is checked and dropped first, so phrases from another
+    /// encoding version fail with [`FourWordError::UnsupportedVersion`]; a
+    /// confusion-avoidance selector is undone next.
+    pub(crate) fn decode_unescaped(&self, words: &str) -> Result<SocketAddr> {
+        self.decode_unescaped_endpoint(words).map(|(addr, _)| addr)
+    }
+
+    /// [`decode_unescaped`](Self::decode_unescaped), also telling whether
+    /// the words carry a port
+    fn decode_unescaped_endpoint(&self, words: &str) -> Result<(SocketAddr, bool)> {
+        self.with_unescaped_words(words, |words| self.decode_plain_endpoint(words))
+    }
+
+    /// Decodes plain address words to a socket address and whether they
+    /// carry a port
+    fn decode_plain_endpoint(&self, words: &str) -> Result<(SocketAddr, bool)> {
         // Determine separator and count words appropriately
         let word_count = if words.contains(' ') {
             // For space-separated words, filter out empty strings from trailing spaces
@@ -59,33 +360,167 @@ impl FourWordAdaptiveEncoder {
         };
 
         match word_count {
+            // IPv4
             4 => {
-                // IPv4
                 let addr = self.ipv4_encoder.decode(words)?;
-                // If port is 65535, omit it (special marker for "no port specified")
-                if addr.port() == 65535 {
-                    Ok(addr.ip().to_string())
-                } else {
-                    Ok(addr.to_string())
-                }
+                Ok((addr, addr.port() != 65535))
             }
             6 | 9 | 12 => {
                 // IPv6 (6, 9, or 12 words including padding)
                 let groups = self.parse_ipv6_groups(words)?;
-                let decoded = self.ipv6_encoder.decode(&groups)?;
-                let socket_addr = SocketAddr::V6(decoded);
-                // If port is 65535, omit it (special marker for "no port specified")
-                if socket_addr.port() == 65535 {
-                    Ok(socket_addr.ip().to_string())
-                } else {
-                    Ok(socket_addr.to_string())
+                let (addr, has_port) = self.ipv6_encoder.decode_endpoint(&groups)?;
+                Ok((SocketAddr::V6(addr), has_port))
+            }
+            _ => Err(FourWordError::wrong_word_count(&[4, 6, 9, 12], word_count)),
+        }
+    }
+
+    /// Encodes the /48, /56 or /64 network prefix of `ip` as 6 or 9 words.
+    ///
+    /// Host bits are dropped, so any address inside the prefix gives the same
+    /// words. Decode with [`decode_prefix`](Self::decode_prefix); the plain
+    /// decoders return just the network address.
+    pub fn encode_prefix(&self, ip: Ipv6Addr, prefix_len: u8) -> Result<String> {
+        let words = self.ipv6_encoder.encode_prefix(ip, prefix_len)?.to_string();
+        self.finish_phrase(words)
+    }
+
+    /// Applies confusion avoidance and reserved-word escapes to plain `words`
+    fn finish_phrase(&self, words: String) -> Result<String> {
+        let words = if self.avoid_confusion {
+            let mut separated = String::with_capacity(words.len());
+            confusability::separate(self.wordlist(), &words, &mut separated)?;
+            separated
+        } else {
+            words
+        };
+        if self.profile().escape_word().is_none() {
+            return Ok(words);
+        }
+        let mut escaped = String::with_capacity(words.len());
+        reserved::escape(self.wordlist(), self.profile(), &words, &mut escaped)?;
+        Ok(escaped)
+    }
+
+    /// Decodes words from [`encode_prefix`](Self::encode_prefix) to the network
+    /// address and prefix length.
+    ///
+    /// Input is read as by [`decode_socket_addr`](Self::decode_socket_addr),
+    /// so indices, version tags and escapes are handled the same way.
+    pub fn decode_prefix(&self, words: &str) -> Result<(Ipv6Addr, u8)> {
+        self.with_plain_words(words, |words| {
+            let groups = self.parse_ipv6_groups(words)?;
+            self.ipv6_encoder.decode_prefix(&groups)
+        })
+    }
+
+    /// Decodes words in whichever compiled-in language they belong to.
+    ///
+    /// The encoder's own language is tried first; otherwise the language is
+    /// identified with [`Language::detect`]. Returns the language alongside
+    /// the decoded address. Encoders with a custom dictionary only consider
+    /// the built-in languages.
+    pub fn decode_any_language(&self, words: &str) -> Result<(Language, String)> {
+        if let Some(language) = self.language
+            && Self::split_words(words)
+                .iter()
+                .all(|word| is_index(word) || self.wordlist().contains(word))
+        {
+            return Ok((language, self.decode(words)?));
+        }
+
+        let language = Language::detect(words).ok_or_else(|| {
+            FourWordError::invalid_value(
+                "phrase",
+                words,
+                "words do not belong to a single supported language",
+            )
+        })?;
+        let address = FourWordAdaptiveEncoder::with_language(language)?
+            .with_profile(self.profile().clone())
+            .decode(words)?;
+        Ok((language, address))
+    }
+
+    /// Decodes a spoken or transcribed phrase after normalizing it.
+    ///
+    /// Casing, punctuation, plurals and homophones are folded onto words of
+    /// this encoder's wordlist according to `normalizer`, and words it leaves
+    /// unknown are looked up in the [alias table](Self::aliases), before the
+    /// regular decode runs.
+    pub fn decode_normalized(&self, words: &str, normalizer: &VoiceNormalizer) -> Result<String> {
+        let wordlist = self.wordlist();
+        let normalized = normalizer.normalize(wordlist, words);
+        let resolved: Vec<String> = normalized
+            .split(' ')
+            .map(|word| {
+                self.aliases
+                    .resolve(wordlist, word)
+                    .unwrap_or_else(|| word.to_string())
+            })
+            .collect();
+        self.decode(&resolved.join(" "))
+    }
+
+    /// Decodes words, tolerating typos in individual words.
+    ///
+    /// Words found in the [alias table](Self::aliases) are replaced by their
+    /// canonical word and reported as corrections. Other words not found in
+    /// the dictionary are matched against their nearest
+    /// dictionary words by edit distance. When `auto_correct` is set and a word
+    /// has a single closest match, it is replaced and the correction reported;
+    /// otherwise decoding fails with [`FourWordError::UnknownWord`] carrying the
+    /// ranked candidates.
+    pub fn decode_fuzzy(&self, words: &str, auto_correct: bool) -> Result<FuzzyDecoding> {
+        // Digits would otherwise be reported as unknown words
+        self.reject_address(words)?;
+
+        let mut corrected = Vec::new();
+        let mut corrections = Vec::new();
+
+        for (position, word) in Self::split_words(words).into_iter().enumerate() {
+            // Indices are resolved by the decode below
+            if self.wordlist().contains(word) || is_index(word) {
+                corrected.push(word.to_string());
+                continue;
+            }
+            if let Some(canonical) = self.aliases.resolve(self.wordlist(), word) {
+                corrections.push(WordCorrection {
+                    position,
+                    original: word.to_string(),
+                    distance: edit_distance(word, &canonical),
+                    corrected: canonical.clone(),
+                });
+                corrected.push(canonical);
+                continue;
+            }
+
+            let suggestions = suggest_words_in(self.wordlist(), word, MAX_SUGGESTIONS);
+            match unambiguous_correction(&suggestions).filter(|_| auto_correct) {
+                Some(best) => {
+                    corrections.push(WordCorrection {
+                        position,
+                        original: word.to_string(),
+                        corrected: best.word.clone(),
+                        distance: best.distance,
+                    });
+                    corrected.push(best.word.clone());
+                }
+                None => {
+                    return Err(FourWordError::UnknownWord {
+                        position,
+                        word: word.to_string(),
+                        suggestions: suggestions.into_iter().map(|s| s.word).collect(),
+                    });
                 }
             }
-            _ => Err(FourWordError::InvalidWordCount {
-                expected: 4, // or 6/8/12 for IPv6
-                actual: word_count,
-            }),
         }
+
+        let address = self.decode(&corrected.join(" "))?;
+        Ok(FuzzyDecoding {
+            address,
+            corrections,
+        })
     }
 
     /// Returns information about the encoding
@@ -107,11 +542,34 @@ impl FourWordAdaptiveEncoder {
         }
     }
 
+    /// Category `addr` encodes under, or `None` for IPv4
+    pub(crate) fn ipv6_category(
+        &self,
+        addr: SocketAddr,
+    ) -> Result<Option<crate::ipv6_compression::Ipv6Category>> {
+        match addr {
+            SocketAddr::V4(_) => Ok(None),
+            SocketAddr::V6(v6) => Ok(Some(self.ipv6_encoder.encode(&v6)?.category())),
+        }
+    }
+
+    /// Splits a phrase into words using the same separator rules as `decode`
+    pub(crate) fn split_words(words: &str) -> Vec<&str> {
+        let separator = if words.contains(' ') {
+            ' '
+        } else if words.contains('.') {
+            '.'
+        } else {
+            '-'
+        };
+        words.split(separator).filter(|s| !s.is_empty()).collect()
+    }
+
     /// Parses an IP address string into a SocketAddr
     /// Uses port 65535 as a special marker for "no port specified"
-    fn parse_address(&self, input: &str) -> Result<SocketAddr> {
-        // Try parsing as socket address first
-        if let Ok(addr) = input.parse::<SocketAddr>() {
+    pub(crate) fn parse_address(&self, input: &str) -> Result<SocketAddr> {
+        // Try parsing as socket address first, with a port number or service name
+        if let Ok(addr) = ports::parse_socket_addr(input) {
             return Ok(addr);
         }
 
@@ -123,9 +581,20 @@ impl FourWordAdaptiveEncoder {
             });
         }
 
-        Err(FourWordError::InvalidInput(format!(
-            "Invalid IP address format: {input}"
-        )))
+        Err(FourWordError::invalid_value(
+            "IP address",
+            input,
+            "expected an address with an optional port",
+        ))
+    }
+
+    /// Fails with [`FourWordError::AlreadyDecoded`] if `words` parses as an address
+    fn reject_address(&self, words: &str) -> Result<()> {
+        let input = words.trim();
+        match self.parse_address(input) {
+            Ok(_) => Err(FourWordError::AlreadyDecoded(input.to_string())),
+            Err(_) => Ok(()),
+        }
     }
 
     /// Parses IPv6 word groups from a string
@@ -135,23 +604,35 @@ impl FourWordAdaptiveEncoder {
 
         // Parse words and filter out empty strings
         let all_words: Vec<String> = if words.contains(' ') {
-            words.split(' ').filter(|s| !s.is_empty()).map(|s| s.to_string()).collect()
+            words
+                .split(' ')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect()
         } else if words.contains('.') {
-            words.split('.').filter(|s| !s.is_empty()).map(|s| s.to_string()).collect()
+            words
+                .split('.')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect()
         } else {
-            words.split('-').filter(|s| !s.is_empty()).map(|s| s.to_string()).collect()
+            words
+                .split('-')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect()
         };
 
         // IPv6 can have 6, 9, or 12 words
         if all_words.len() != 6 && all_words.len() != 9 && all_words.len() != 12 {
-            return Err(FourWordError::InvalidWordCount {
-                expected: 6, // or 9/12
-                actual: all_words.len(),
-            });
+            return Err(FourWordError::wrong_word_count(
+                &[6, 9, 12],
+                all_words.len(),
+            ));
         }
 
         let mut groups = Vec::new();
-        
+
         // Create groups of 4 words, handling 6 and 9 word cases properly
         match all_words.len() {
             6 => {
@@ -200,17 +681,19 @@ impl FourWordAdaptiveEncoder {
                 // For 12 words, create groups of 4
                 for chunk in all_words.chunks(4) {
                     groups.push(FourWordGroup::new(
-                        chunk.get(0).cloned().unwrap_or_default(),
+                        chunk.first().cloned().unwrap_or_default(),
                         chunk.get(1).cloned().unwrap_or_default(),
                         chunk.get(2).cloned().unwrap_or_default(),
                         chunk.get(3).cloned().unwrap_or_default(),
                     ));
                 }
             }
-            _ => return Err(FourWordError::InvalidWordCount {
-                expected: 6,
-                actual: all_words.len(),
-            }),
+            _ => {
+                return Err(FourWordError::wrong_word_count(
+                    &[6, 9, 12],
+                    all_words.len(),
+                ));
+            }
         };
 
         // For decoding, we don't know the category yet, so use a placeholder
@@ -274,6 +757,72 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_decode_fuzzy_auto_corrects_typo() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let encoded = encoder.encode("192.168.1.1:443").unwrap();
+        let mut words: Vec<String> = encoded.split(' ').map(String::from).collect();
+
+        // Replace the second word with "ocean" misspelled, re-encoding the expected address
+        words[1] = "ocean".to_string();
+        let expected = encoder.decode(&words.join(" ")).unwrap();
+        words[1] = "oceen".to_string();
+        let typo = words.join(" ");
+
+        let result = encoder.decode_fuzzy(&typo, true).unwrap();
+        assert_eq!(result.address, expected);
+        assert_eq!(result.corrections.len(), 1);
+        assert_eq!(result.corrections[0].position, 1);
+        assert_eq!(result.corrections[0].corrected, "ocean");
+
+        // Without auto-correct the candidates come back in the error
+        match encoder.decode_fuzzy(&typo, false) {
+            Err(FourWordError::UnknownWord {
+                position,
+                word,
+                suggestions,
+            }) => {
+                assert_eq!(position, 1);
+                assert_eq!(word, "oceen");
+                assert_eq!(suggestions.first().map(String::as_str), Some("ocean"));
+            }
+            other => panic!("expected UnknownWord, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_fuzzy_exact_input() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let encoded = encoder.encode("10.0.0.1:80").unwrap();
+        let result = encoder.decode_fuzzy(&encoded, true).unwrap();
+        assert_eq!(result.address, "10.0.0.1:80");
+        assert!(result.corrections.is_empty());
+    }
+
+    #[test]
+    fn test_decode_normalized_transcript() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let encoded = encoder.encode("192.168.1.1:443").unwrap();
+
+        // Simulate speech-to-text output: capitalised, punctuated, dot-free
+        let transcript = encoded
+            .split(' ')
+            .map(|w| {
+                let mut chars = w.chars();
+                let first = chars.next().unwrap().to_uppercase().collect::<String>();
+                format!("{first}{},", chars.as_str())
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let normalizer = VoiceNormalizer::new();
+        assert!(encoder.decode(&transcript).is_err());
+        assert_eq!(
+            encoder.decode_normalized(&transcript, &normalizer).unwrap(),
+            "192.168.1.1:443"
+        );
+    }
+
     #[test]
     fn test_analyze() {
         let encoder = FourWordAdaptiveEncoder::new().unwrap();
@@ -286,4 +835,478 @@ mod tests {
         assert!(analysis.contains("IPv6"));
         assert!(analysis.contains("words"));
     }
+
+    #[test]
+    fn test_every_language_round_trips() {
+        let english = FourWordAdaptiveEncoder::new().unwrap();
+        for &language in Language::ALL {
+            let encoder = FourWordAdaptiveEncoder::with_language(language).unwrap();
+            assert_eq!(encoder.language(), Some(language));
+            for addr in ["192.168.1.1:443", "[::1]:8080", "[2001:db8::1]:443"] {
+                let words = encoder.encode(addr).unwrap();
+                assert_eq!(encoder.decode(&words).unwrap(), addr, "{language}");
+                assert_eq!(
+                    english.decode_any_language(&words).unwrap(),
+                    (language, addr.to_string())
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "lang-es")]
+    #[test]
+    fn test_languages_use_different_words() {
+        let english = FourWordAdaptiveEncoder::new().unwrap();
+        let spanish = FourWordAdaptiveEncoder::with_language(Language::Spanish).unwrap();
+
+        let words = spanish.encode("10.0.0.1:80").unwrap();
+        assert_ne!(words, english.encode("10.0.0.1:80").unwrap());
+        assert!(english.decode(&words).is_err());
+    }
+
+    #[test]
+    fn test_custom_dictionary_encoder() {
+        // Fixed-width words can never be prefixes of each other
+        let letter = |n: u32| char::from(b'a' + (n % 16) as u8);
+        let words: Vec<String> = (0..4096u32)
+            .map(|n| format!("x{}{}{}", letter(n), letter(n / 16), letter(n / 256)))
+            .collect();
+        let dictionary = Dictionary4K::from_slice(&words).unwrap();
+        let encoder = FourWordAdaptiveEncoder::with_wordlist(dictionary).unwrap();
+        assert_eq!(encoder.language(), None);
+
+        let encoded = encoder.encode("192.168.1.1:443").unwrap();
+        assert!(encoded.split(' ').all(|w| words.contains(&w.to_string())));
+        assert_eq!(encoder.decode(&encoded).unwrap(), "192.168.1.1:443");
+        assert!(
+            FourWordAdaptiveEncoder::new()
+                .unwrap()
+                .decode(&encoded)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_nat64_prefix_from_profile() {
+        let mut profile = EncodingProfile::new();
+        profile
+            .add_nat64_prefix("2001:db8:64::/96".parse().unwrap())
+            .unwrap();
+        let encoder = FourWordAdaptiveEncoder::new()
+            .unwrap()
+            .with_profile(profile);
+
+        let address = "[2001:db8:64::c633:6401]:443";
+        let encoded = encoder.encode(address).unwrap();
+        assert_eq!(encoded.split(' ').count(), 6);
+        assert_eq!(encoder.decode(&encoded).unwrap(), address);
+
+        // The prefix index means nothing to a decoder without the profile
+        assert!(
+            FourWordAdaptiveEncoder::new()
+                .unwrap()
+                .decode(&encoded)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_nat64_well_known_prefix() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let address = "[64:ff9b::c633:6401]:443";
+        let encoded = encoder.encode(address).unwrap();
+        assert_eq!(encoded.split(' ').count(), 6);
+        assert_eq!(encoder.decode(&encoded).unwrap(), address);
+    }
+
+    #[test]
+    fn test_well_known_ports() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        for (address, words, decoded) in [
+            // A well-known port saves a word
+            ("[2001:db8:85a3::1]:443", 6, "[2001:db8:85a3::1]:443"),
+            ("[2001:db8:85a3::1]:4242", 9, "[2001:db8:85a3::1]:4242"),
+            // and survives the 16-byte fallback, which drops other ports
+            (
+                "[2606:4700:4700::1111]:443",
+                12,
+                "[2606:4700:4700::1111]:443",
+            ),
+            ("[2606:4700:4700::1111]:4433", 12, "2606:4700:4700::1111"),
+        ] {
+            let encoded = encoder.encode(address).unwrap();
+            assert_eq!(encoded.split(' ').count(), words, "{address}");
+            assert_eq!(encoder.decode(&encoded).unwrap(), decoded);
+        }
+
+        // Service names encode like the port number
+        assert_eq!(
+            encoder.encode("[2001:db8:85a3::1]:https").unwrap(),
+            encoder.encode("[2001:db8:85a3::1]:443").unwrap()
+        );
+        assert_eq!(
+            encoder.encode("192.0.2.1:SSH").unwrap(),
+            encoder.encode("192.0.2.1:22").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_provider_prefix_from_profile() {
+        let mut profile = EncodingProfile::new();
+        profile
+            .add_provider_prefix("2a00:1450::/32".parse().unwrap())
+            .unwrap();
+        let encoder = FourWordAdaptiveEncoder::new()
+            .unwrap()
+            .with_profile(profile);
+
+        for (address, words) in [
+            ("[2a00:1450:4001::1]:443", 6),
+            ("[2a00:1450:4001:81c::200e]:443", 9),
+        ] {
+            let encoded = encoder.encode(address).unwrap();
+            assert_eq!(encoded.split(' ').count(), words, "{address}");
+            assert_eq!(encoder.decode(&encoded).unwrap(), address);
+            assert!(
+                FourWordAdaptiveEncoder::new()
+                    .unwrap()
+                    .decode(&encoded)
+                    .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_v4_mapped_keeps_ipv6_form() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        for address in [
+            "[::ffff:203.0.113.7]:443",
+            "::ffff:192.168.1.1",
+            "[::ffff:0:a00:1]:80",
+        ] {
+            let encoded = encoder.encode(address).unwrap();
+            assert_eq!(encoded.split(' ').count(), 6, "{address}");
+            assert_eq!(encoder.decode(&encoded).unwrap(), address);
+        }
+    }
+
+    #[test]
+    fn test_transition_addresses_keep_port() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        for (address, words) in [
+            ("[2001:0:4136:e378:8000:63bf:3fff:fdd2]:3544", 12),
+            ("[2002:c000:204::1]:443", 9),
+            ("[2002:c000:204:1:a00:27ff:fe12:3456]:443", 12),
+            ("[2600:1:2:3:0:5efe:c000:201]:443", 12),
+        ] {
+            let encoded = encoder.encode(address).unwrap();
+            assert_eq!(encoded.split(' ').count(), words, "{address}");
+            assert_eq!(encoder.decode(&encoded).unwrap(), address);
+        }
+    }
+
+    #[test]
+    fn test_multicast_groups() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        for (address, words) in [
+            ("ff02::1", 6),
+            ("[ff02::fb]:5353", 6),
+            ("[ff02::1:ff12:3456]:547", 6),
+            ("[ff0e::1234]:9000", 6),
+        ] {
+            let encoded = encoder.encode(address).unwrap();
+            assert_eq!(encoded.split(' ').count(), words, "{address}");
+            assert_eq!(encoder.decode(&encoded).unwrap(), address);
+        }
+    }
+
+    #[test]
+    fn test_delegated_prefixes() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let host: Ipv6Addr = "2001:db8:1234:5678:9abc::1".parse().unwrap();
+        for (prefix_len, network, words) in [
+            (48, "2001:db8:1234::", 6),
+            (56, "2001:db8:1234:5600::", 6),
+            (64, "2001:db8:1234:5678::", 9),
+        ] {
+            let encoded = encoder.encode_prefix(host, prefix_len).unwrap();
+            assert_eq!(encoded.split(' ').count(), words, "/{prefix_len}");
+            let network: Ipv6Addr = network.parse().unwrap();
+            assert_eq!(
+                encoder.decode_prefix(&encoded).unwrap(),
+                (network, prefix_len)
+            );
+            // Plain decoding gives the network address without a port
+            assert_eq!(encoder.decode(&encoded).unwrap(), network.to_string());
+        }
+
+        let address = encoder.encode("[2001:db8::1]:443").unwrap();
+        assert!(encoder.decode_prefix(&address).is_err());
+    }
+
+    #[test]
+    fn test_decode_prefix_shares_the_decode_pipeline() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let host: Ipv6Addr = "2001:db8:abcd:12::1".parse().unwrap();
+        let network: Ipv6Addr = "2001:db8:abcd::".parse().unwrap();
+        let encoded = encoder.encode_prefix(host, 48).unwrap();
+
+        // Words written as their dictionary indices
+        let numeric = encoded
+            .split(' ')
+            .map(|word| encoder.wordlist().get_index(word).unwrap().to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert_eq!(encoder.decode_prefix(&numeric).unwrap(), (network, 48));
+
+        // A prefix tagged with another version is refused, not misread
+        let tag = encoder.wordlist().get_word(2).unwrap();
+        assert!(matches!(
+            encoder.decode_prefix(&format!("{tag} {encoded}")),
+            Err(FourWordError::UnsupportedVersion { required: 2, .. })
+        ));
+        assert!(matches!(
+            encoder.decode_prefix("2001:db8::1"),
+            Err(FourWordError::AlreadyDecoded(_))
+        ));
+    }
+
+    #[test]
+    fn test_reserved_words_never_emitted() {
+        let plain = FourWordAdaptiveEncoder::new().unwrap();
+        let address = "192.168.1.1:443";
+        let unescaped = plain.encode(address).unwrap();
+        let reserved = unescaped.split(' ').nth(1).unwrap().to_string();
+
+        let mut profile = EncodingProfile::new();
+        profile.reserve_words("over", &[&reserved]).unwrap();
+        let encoder = FourWordAdaptiveEncoder::new()
+            .unwrap()
+            .with_profile(profile);
+
+        let encoded = encoder.encode(address).unwrap();
+        assert!(!encoded.split(' ').any(|word| word == reserved));
+        // The tag and one escape pair, then padding past the lengths plain
+        // phrases use
+        assert_eq!(encoded.split(' ').count(), 8);
+        assert_eq!(encoder.decode(&encoded).unwrap(), address);
+        let phrase: WordPhrase = encoder.encode_phrase(address.parse().unwrap()).unwrap();
+        assert_eq!(phrase, encoded.as_str());
+
+        // The unescaped phrase uses a word this profile forbids
+        assert!(encoder.decode(&unescaped).is_err());
+        // Without the reservation the tag refuses the escaped phrase instead
+        // of decoding it to another address
+        assert!(matches!(
+            plain.decode(&encoded),
+            Err(FourWordError::Unsupported { .. })
+        ));
+    }
+
+    #[test]
+    fn test_encode_lossless() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        for address in ["10.0.0.1:80", "[2001:db8::1]:443", "[fe80::1]:22"] {
+            let addr: SocketAddr = address.parse().unwrap();
+            let words = encoder.encode_lossless(addr).unwrap();
+            assert_eq!(words, encoder.encode(address).unwrap());
+        }
+        // Unique-local addresses keep only their /64
+        let addr: SocketAddr = "[fd00:1234:5678:9abc::1]:8080".parse().unwrap();
+        match encoder.encode_lossless(addr) {
+            Err(FourWordError::LossyEncoding { input, decoded }) => {
+                assert_eq!(input, addr.to_string());
+                assert_ne!(decoded, input);
+            }
+            other => panic!("expected LossyEncoding, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_structured_decode_errors() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        match encoder.decode_socket_addr("ocean zzzz ocean ocean") {
+            Err(FourWordError::UnknownWord { position, word, .. }) => {
+                assert_eq!((position, word.as_str()), (1, "zzzz"));
+            }
+            other => panic!("expected UnknownWord, got {other:?}"),
+        }
+        for count in [3, 5] {
+            match encoder.decode_socket_addr(&vec!["ocean"; count].join(" ")) {
+                Err(FourWordError::WrongWordCount { expected, got }) => {
+                    assert_eq!((expected.as_slice(), got), (&[4, 6, 9, 12][..], count));
+                }
+                other => panic!("expected WrongWordCount, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_version_tags() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        for address in ["10.0.0.1:80", "[2001:db8::1]:443", "[fe80::1]:22"] {
+            let addr: SocketAddr = address.parse().unwrap();
+            let plain = encoder.encode(address).unwrap();
+            let tagged = encoder.encode_tagged(addr).unwrap();
+            assert_eq!(tagged.split(' ').count(), plain.split(' ').count() + 1);
+            assert_eq!(encoder.decode_socket_addr(&tagged).unwrap(), addr);
+
+            let tag = encoder.wordlist().get_word(9).unwrap();
+            match encoder.decode_socket_addr(&format!("{tag} {plain}")) {
+                Err(FourWordError::UnsupportedVersion {
+                    required: 9,
+                    supported: ENCODING_VERSION,
+                }) => {}
+                other => panic!("{address}: {other:?}"),
+            }
+        }
+
+        // The tag word is escaped like any other when the profile reserves it
+        let tag = encoder
+            .wordlist()
+            .get_word(ENCODING_VERSION)
+            .unwrap()
+            .to_string();
+        let mut profile = EncodingProfile::new();
+        profile.reserve_words("over", &[&tag]).unwrap();
+        let encoder = encoder.with_profile(profile);
+        let addr: SocketAddr = "192.168.1.1:443".parse().unwrap();
+        let tagged = encoder.encode_tagged(addr).unwrap();
+        assert!(!tagged.split(' ').any(|word| word == tag));
+        assert_eq!(tagged.split(' ').count(), 8);
+        assert_eq!(encoder.decode_socket_addr(&tagged).unwrap(), addr);
+    }
+
+    #[test]
+    fn test_version_tags_with_selectors_and_escapes() {
+        let encoder = FourWordAdaptiveEncoder::new()
+            .unwrap()
+            .with_confusion_avoidance(true);
+        let plain = FourWordAdaptiveEncoder::new().unwrap();
+        for address in ["0.0.0.0:0", "[::1]:443", "192.168.1.1:443", "[fe80::1]:22"] {
+            let addr: SocketAddr = address.parse().unwrap();
+            let words = encoder.encode(address).unwrap();
+            let tagged = encoder.encode_tagged(addr).unwrap();
+            // A selector stands in for the tag, so the length stays plain + 1
+            let plain_len = plain.encode(address).unwrap().split(' ').count();
+            assert_eq!(tagged.split(' ').count(), plain_len + 1, "{tagged}");
+            if words.split(' ').count() > plain_len {
+                assert_eq!(tagged, words);
+            }
+            assert_eq!(
+                version::phrase_version(encoder.wordlist(), &tagged.split(' ').collect::<Vec<_>>()),
+                Some(ENCODING_VERSION)
+            );
+            assert_eq!(encoder.decode_socket_addr(&tagged).unwrap(), addr);
+            assert_eq!(plain.decode_socket_addr(&tagged).unwrap(), addr);
+        }
+
+        // Reserving the selector and the tag escapes both
+        let zeros = encoder.encode_tagged("0.0.0.0:0".parse().unwrap()).unwrap();
+        let selector = zeros.split(' ').next().unwrap().to_string();
+        let tag = encoder
+            .wordlist()
+            .get_word(ENCODING_VERSION)
+            .unwrap()
+            .to_string();
+        let mut profile = EncodingProfile::new();
+        profile.reserve_words("over", &[&selector, &tag]).unwrap();
+        let escaping = encoder.with_profile(profile);
+        for address in ["0.0.0.0:0", "192.168.1.1:443", "[2001:db8::1]:443"] {
+            let addr: SocketAddr = address.parse().unwrap();
+            let tagged = escaping.encode_tagged(addr).unwrap();
+            assert!(
+                !tagged
+                    .split(' ')
+                    .any(|word| word == selector || word == tag)
+            );
+            assert_eq!(escaping.decode_socket_addr(&tagged).unwrap(), addr);
+            assert!(plain.decode_socket_addr(&tagged).is_err());
+        }
+    }
+
+    #[test]
+    fn test_decode_fuzzy_applies_aliases() {
+        let mut encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let encoded = encoder.encode("192.168.1.1:443").unwrap();
+        let mut words: Vec<String> = encoded.split(' ').map(str::to_string).collect();
+        let original = words[1].clone();
+        words[1] = "zzqx".to_string();
+        encoder.add_alias("zzqx", &original).unwrap();
+
+        let result = encoder.decode_fuzzy(&words.join(" "), false).unwrap();
+        assert_eq!(result.address, "192.168.1.1:443");
+        assert_eq!(result.corrections.len(), 1);
+        assert_eq!(result.corrections[0].corrected, original);
+        // Strict decoding is unaffected
+        assert!(encoder.decode(&words.join(" ")).is_err());
+
+        // Normalized decoding consults the same table
+        let normalizer = VoiceNormalizer::new();
+        assert_eq!(
+            encoder
+                .decode_normalized(&words.join(" "), &normalizer)
+                .unwrap(),
+            "192.168.1.1:443"
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_and_normalized_agree_on_homophones() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let normalizer = VoiceNormalizer::new();
+        let phrase = "eight one night color";
+        let heard = "ate won knight colour";
+        assert_eq!(
+            encoder.decode_fuzzy(heard, false).unwrap().address,
+            encoder.decode(phrase).unwrap()
+        );
+        assert_eq!(
+            encoder.decode_normalized(heard, &normalizer).unwrap(),
+            encoder.decode(phrase).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encode_phrase_matches_encode() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        for addr in [
+            "192.168.1.1:443",
+            "[::1]:22",
+            "[fe80::1]:80",
+            "[2606:4700::1111]:53",
+        ] {
+            let phrase: WordPhrase = encoder.encode_phrase(addr.parse().unwrap()).unwrap();
+            assert_eq!(phrase, encoder.encode(addr).unwrap().as_str());
+        }
+
+        let too_small = encoder.encode_phrase::<8>("10.0.0.1:80".parse().unwrap());
+        assert!(matches!(too_small, Err(FourWordError::Format(_))));
+    }
+
+    #[test]
+    fn test_double_conversion_is_detected() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        for address in ["192.168.1.1:443", "10.0.0.1", "[2001:db8::1]:8080"] {
+            let words = encoder.encode(address).unwrap();
+            assert!(matches!(
+                encoder.encode(&words),
+                Err(FourWordError::AlreadyEncoded(phrase)) if phrase == words
+            ));
+            assert!(matches!(
+                encoder.decode(address),
+                Err(FourWordError::AlreadyDecoded(input)) if input == address
+            ));
+            assert!(matches!(
+                encoder.decode_fuzzy(&format!(" {address} "), true),
+                Err(FourWordError::AlreadyDecoded(_))
+            ));
+        }
+
+        // Input that is neither stays an ordinary error
+        assert!(matches!(
+            encoder.encode("not an address"),
+            Err(FourWordError::InvalidValue { .. })
+        ));
+    }
 }
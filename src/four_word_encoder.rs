@@ -3,8 +3,13 @@
 //! This module provides encoding and decoding of IPv4 addresses and ports
 //! into exactly four memorable words using a 4,096-word dictionary.
 
-use crate::dictionary4k::DICTIONARY;
+#![forbid(unsafe_code)]
+
+use crate::dictionary4k::{DICTIONARY, Dictionary4K};
 use crate::error::{FourWordError, Result};
+use crate::phrase::write_words;
+use crate::wordlist::Wordlist;
+use std::fmt;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
 /// Represents an encoded four-word address
@@ -40,12 +45,26 @@ impl std::fmt::Display for FourWordEncoding {
 }
 
 /// Four-word encoder for IPv4 addresses
-pub struct FourWordEncoder;
+pub struct FourWordEncoder<W = Dictionary4K> {
+    dictionary: W,
+}
 
 impl FourWordEncoder {
-    /// Creates a new four-word encoder
+    /// Creates a new four-word encoder using the English dictionary
     pub fn new() -> Self {
-        FourWordEncoder
+        Self::with_dictionary(DICTIONARY)
+    }
+}
+
+impl<W: Wordlist> FourWordEncoder<W> {
+    /// Creates an encoder that maps word indices through `dictionary`
+    pub fn with_dictionary(dictionary: W) -> Self {
+        FourWordEncoder { dictionary }
+    }
+
+    /// Returns the wordlist this encoder maps through
+    pub fn wordlist(&self) -> &W {
+        &self.dictionary
     }
 
     /// Encodes an IPv4 address and port into four words using simple algorithm
@@ -55,32 +74,14 @@ impl FourWordEncoder {
         // Total: 48 bits
         // With 4 words at 12 bits each, we have exactly 48 bits
 
-        let octets = addr.octets();
-
-        // Pack the 48 bits: IPv4 (32 bits) + port (16 bits)
-        // Convert to big-endian bytes then to integer
-        let mut bytes = [0u8; 6];
-        bytes[0..4].copy_from_slice(&octets);
-        bytes[4..6].copy_from_slice(&port.to_be_bytes());
-
-        // Convert to 48-bit integer
-        let mut n = 0u64;
-        for byte in bytes {
-            n = (n << 8) | (byte as u64);
-        }
-
-        // Simple modulo-based encoding (like the Python version)
         let mut words = Vec::with_capacity(4);
-        let mut remaining = n;
-
-        for _ in 0..4 {
-            let index = (remaining % 4096) as u16;
-            let word = DICTIONARY
+        for index in pack_ipv4(addr, port) {
+            let word = self
+                .dictionary
                 .get_word(index)
                 .ok_or(FourWordError::InvalidWordIndex(index))?
                 .to_string();
             words.push(word);
-            remaining /= 4096;
         }
 
         Ok(FourWordEncoding::new(
@@ -93,45 +94,33 @@ impl FourWordEncoder {
 
     /// Decodes four words back to an IPv4 address and port using simple algorithm
     pub fn decode_ipv4(&self, encoding: &FourWordEncoding) -> Result<(Ipv4Addr, u16)> {
-        // Reconstruct the 48-bit number from words
-        let mut n = 0u64;
-
-        for (i, word) in encoding.words.iter().enumerate() {
-            let index = DICTIONARY
+        let mut indices = [0u16; 4];
+        for (position, (index, word)) in indices.iter_mut().zip(&encoding.words).enumerate() {
+            *index = self
+                .dictionary
                 .get_index(word)
-                .ok_or_else(|| FourWordError::InvalidWord(word.clone()))?;
-
-            // Each word contributes its index * 4096^position
-            n += (index as u64) * 4096u64.pow(i as u32);
+                .ok_or_else(|| FourWordError::unknown_word(position, word))?;
         }
 
-        // Convert back to bytes (big-endian)
-        let bytes = n.to_be_bytes();
-
-        // Extract IPv4 address (first 4 bytes of the 48-bit value)
-        // Since we have 8 bytes from u64, we need the last 6 bytes
-        let octet1 = bytes[2];
-        let octet2 = bytes[3];
-        let octet3 = bytes[4];
-        let octet4 = bytes[5];
-
-        // Extract port (last 2 bytes)
-        let port = ((bytes[6] as u16) << 8) | (bytes[7] as u16);
-
-        let addr = Ipv4Addr::new(octet1, octet2, octet3, octet4);
-        Ok((addr, port))
+        Ok(unpack_ipv4(indices))
     }
 
     /// Encodes a socket address
     pub fn encode(&self, addr: SocketAddr) -> Result<FourWordEncoding> {
         match addr {
             SocketAddr::V4(v4) => self.encode_ipv4(*v4.ip(), v4.port()),
-            SocketAddr::V6(_) => Err(FourWordError::InvalidInput(
-                "IPv6 addresses require more than 4 words".to_string(),
+            SocketAddr::V6(_) => Err(FourWordError::unsupported(
+                "encode IPv6 in 4 words",
+                "IPv6 addresses require more than 4 words",
             )),
         }
     }
 
+    /// Writes the four space-separated words for `addr` to `out` without allocating
+    pub fn encode_into(&self, addr: Ipv4Addr, port: u16, out: &mut impl fmt::Write) -> Result<()> {
+        write_words(&self.dictionary, &pack_ipv4(addr, port), out)
+    }
+
     /// Decodes words to a socket address
     pub fn decode(&self, words: &str) -> Result<SocketAddr> {
         // First try space-separated
@@ -142,10 +131,7 @@ impl FourWordEncoder {
             // Try dot-separated for backward compatibility
             let dot_parts: Vec<&str> = words.split('.').collect();
             if dot_parts.len() != 4 {
-                return Err(FourWordError::InvalidWordCount {
-                    expected: 4,
-                    actual: dot_parts.len(),
-                });
+                return Err(FourWordError::wrong_word_count(&[4], dot_parts.len()));
             }
             dot_parts
         };
@@ -162,6 +148,27 @@ impl FourWordEncoder {
     }
 }
 
+/// Packs an IPv4 address and port into four 12-bit word indices.
+///
+/// The 48 bits (address then port, big-endian) are split least significant
+/// group first, so `indices[0]` holds the low 12 bits of the port.
+pub(crate) fn pack_ipv4(addr: Ipv4Addr, port: u16) -> [u16; 4] {
+    let n = (u64::from(u32::from(addr)) << 16) | u64::from(port);
+    let mut indices = [0u16; 4];
+    for (i, index) in indices.iter_mut().enumerate() {
+        *index = ((n >> (12 * i)) & 0xFFF) as u16;
+    }
+    indices
+}
+
+/// Inverse of [`pack_ipv4`]; bits above the low 12 of each index are ignored
+pub(crate) fn unpack_ipv4(indices: [u16; 4]) -> (Ipv4Addr, u16) {
+    let n = indices.iter().enumerate().fold(0u64, |n, (i, &index)| {
+        n | (u64::from(index & 0xFFF) << (12 * i))
+    });
+    (Ipv4Addr::from((n >> 16) as u32), n as u16)
+}
+
 impl Default for FourWordEncoder {
     fn default() -> Self {
         Self::new()
@@ -197,6 +204,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pack_ipv4_layout() {
+        // 1.2.3.4:0x5678 is the 48-bit value 0x0102_0304_5678
+        let indices = pack_ipv4(Ipv4Addr::new(1, 2, 3, 4), 0x5678);
+        assert_eq!(indices, [0x678, 0x045, 0x203, 0x010]);
+        assert_eq!(unpack_ipv4(indices), (Ipv4Addr::new(1, 2, 3, 4), 0x5678));
+    }
+
     #[test]
     fn test_socket_addr_encoding() {
         let encoder = FourWordEncoder::new();
@@ -3,10 +3,42 @@
 //! This module provides encoding and decoding of IPv6 addresses
 //! into groups of four words using a 4,096-word dictionary.
 
-use crate::dictionary4k::DICTIONARY;
+#![forbid(unsafe_code)]
+
+use crate::dictionary4k::{DICTIONARY, Dictionary4K};
 use crate::error::{FourWordError, Result};
 use crate::ipv6_compression::{CompressedIpv6, Ipv6Category, Ipv6Compressor};
-use std::net::SocketAddrV6;
+use crate::phrase::write_words;
+use crate::ports;
+use crate::profile::EncodingProfile;
+use crate::wordlist::Wordlist;
+use std::fmt;
+use std::net::{Ipv6Addr, SocketAddrV6};
+
+/// Port that marks a phrase encoded without one
+const NO_PORT: u16 = 65535;
+
+/// One-byte port code for "no port"; other codes index [`ports::WELL_KNOWN_PORTS`]
+const NO_PORT_CODE: u8 = 0xFF;
+
+/// One-byte port code for a port of 65535 given explicitly
+const MAX_PORT_CODE: u8 = 0xFE;
+
+/// Bit after a 16-bit port field of 65535 that marks the port as given
+/// rather than absent
+const MAX_PORT_FLAG: u32 = 1 << 16;
+
+/// Data bytes of a unique local /64 in the compact 6-word layout: the global
+/// ID and subnet, without the fc/fd byte
+const COMPACT_ULA_LEN: usize = 7;
+
+/// Compact unique local port codes for no port and an explicit 65535; lower
+/// codes index [`ports::WELL_KNOWN_PORTS`]
+const COMPACT_NO_PORT_CODE: u8 = 0x7F;
+const COMPACT_MAX_PORT_CODE: u8 = 0x7E;
+
+/// Phrase lengths an IPv6 address can take, shortest first
+const IPV6_WORD_COUNTS: [usize; 3] = [6, 9, 12];
 
 /// Represents a group of four words
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -30,7 +62,14 @@ impl FourWordGroup {
 
 impl std::fmt::Display for FourWordGroup {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.words.join(" "))
+        // Partial trailing groups (6 and 9 word encodings) carry empty slots
+        let words: Vec<&str> = self
+            .words
+            .iter()
+            .map(|w| w.as_str())
+            .filter(|w| !w.is_empty())
+            .collect();
+        write!(f, "{}", words.join(" "))
     }
 }
 
@@ -54,9 +93,13 @@ impl Ipv6FourWordGroupEncoding {
         &self.groups
     }
 
-    /// Returns the total word count
+    /// Returns the total word count, excluding empty slots in a partial final group
     pub fn word_count(&self) -> usize {
-        self.groups.len() * 4
+        self.groups
+            .iter()
+            .flat_map(|g| g.words().iter())
+            .filter(|w| !w.is_empty())
+            .count()
     }
 
     /// Returns the IPv6 category
@@ -68,7 +111,9 @@ impl Ipv6FourWordGroupEncoding {
     pub fn to_dashed_string(&self) -> String {
         self.groups
             .iter()
-            .map(|g| g.words().join("-"))
+            .flat_map(|g| g.words().iter())
+            .filter(|w| !w.is_empty())
+            .map(|w| w.as_str())
             .collect::<Vec<_>>()
             .join("-")
     }
@@ -80,6 +125,7 @@ impl std::fmt::Display for Ipv6FourWordGroupEncoding {
             .groups
             .iter()
             .map(|g| g.to_string())
+            .filter(|g| !g.is_empty())
             .collect::<Vec<_>>()
             .join(" ");
         write!(f, "{groups_str}")
@@ -87,115 +133,138 @@ impl std::fmt::Display for Ipv6FourWordGroupEncoding {
 }
 
 /// Four-word encoder for IPv6 addresses
-pub struct FourWordIpv6Encoder {
+pub struct FourWordIpv6Encoder<W = Dictionary4K> {
     compressor: Ipv6Compressor,
+    dictionary: W,
 }
 
 impl FourWordIpv6Encoder {
-    /// Creates a new IPv6 four-word encoder
+    /// Creates a new IPv6 four-word encoder using the English dictionary
     pub fn new() -> Self {
+        Self::with_dictionary(DICTIONARY)
+    }
+}
+
+impl<W: Wordlist> FourWordIpv6Encoder<W> {
+    /// Creates an encoder that maps word indices through `dictionary`
+    pub fn with_dictionary(dictionary: W) -> Self {
         FourWordIpv6Encoder {
             compressor: Ipv6Compressor::new(),
+            dictionary,
         }
     }
 
+    /// Uses `profile` for deployment-specific compression such as NAT64 prefixes
+    pub fn with_profile(mut self, profile: EncodingProfile) -> Self {
+        self.compressor = Ipv6Compressor::with_profile(profile);
+        self
+    }
+
+    /// Returns the encoding profile in use
+    pub fn profile(&self) -> &EncodingProfile {
+        self.compressor.profile()
+    }
+
     /// Encodes an IPv6 socket address into groups of four words
     pub fn encode(&self, addr: &SocketAddrV6) -> Result<Ipv6FourWordGroupEncoding> {
         // Compress the IPv6 address
         let compressed = self.compressor.compress(*addr.ip(), Some(addr.port()))?;
         let category = compressed.category;
-        let compressed_data = compressed.as_bytes();
 
         // Each group of 4 words encodes 48 bits (4 * 12 bits)
         // We need to handle variable-length compressed data
-        let groups = self.encode_bytes_to_groups(&compressed_data, addr.port(), category)?;
+        let groups =
+            self.encode_bytes_to_groups(compressed.as_bytes(), given_port(addr.port()), category)?;
 
         Ok(Ipv6FourWordGroupEncoding::new(groups, category))
     }
 
+    /// Writes the space-separated words for `addr` to `out`.
+    ///
+    /// Produces the same phrase as `encode(addr).to_string()` without building
+    /// word strings or groups.
+    pub fn encode_into(&self, addr: &SocketAddrV6, out: &mut impl fmt::Write) -> Result<()> {
+        self.encode_port_into(*addr.ip(), given_port(addr.port()), out)
+    }
+
+    /// Writes the words for `ip` with `port`, where `Some(65535)` is a port
+    /// given explicitly and `None` no port
+    pub(crate) fn encode_port_into(
+        &self,
+        ip: Ipv6Addr,
+        port: Option<u16>,
+        out: &mut impl fmt::Write,
+    ) -> Result<()> {
+        let compressed = self
+            .compressor
+            .compress(ip, Some(port.unwrap_or(NO_PORT)))?;
+        let (indices, count) = pack_ipv6(compressed.as_bytes(), port, compressed.category)?;
+        write_words(&self.dictionary, &indices[..count], out)
+    }
+
     /// Decodes groups of four words back to an IPv6 socket address
     pub fn decode(&self, encoding: &Ipv6FourWordGroupEncoding) -> Result<SocketAddrV6> {
-        // Decode groups back to bytes, port, and actual category
-        let (decoded_bytes, decoded_port, actual_category) =
-            self.decode_groups_to_bytes(&encoding.groups, encoding.category)?;
+        self.decode_endpoint(encoding).map(|(addr, _)| addr)
+    }
 
-        // Create compressed IPv6 from bytes and actual category
-        let mut compressed = CompressedIpv6::from_bytes(&decoded_bytes, actual_category)?;
-        compressed.port = Some(decoded_port);
+    /// Decodes groups of four words along with whether they carry a port,
+    /// which tells an explicit port 65535 from none
+    pub(crate) fn decode_endpoint(
+        &self,
+        encoding: &Ipv6FourWordGroupEncoding,
+    ) -> Result<(SocketAddrV6, bool)> {
+        // Decode groups back to the compressed address and port
+        let (compressed, given) = self.decode_groups(&encoding.groups)?;
+
+        // Decompress to get the original address; prefixes keep data in the port slot
+        let (addr, port) = self.compressor.decompress(&compressed)?;
+
+        let port = port.unwrap_or(NO_PORT);
+        Ok((
+            SocketAddrV6::new(addr, port, 0, 0),
+            port != NO_PORT || given == Some(NO_PORT),
+        ))
+    }
 
-        // Decompress to get the original address
-        let (addr, _) = self.compressor.decompress(&compressed)?;
+    /// Encodes the /48, /56 or /64 network prefix of `ip`, dropping the host bits
+    pub fn encode_prefix(&self, ip: Ipv6Addr, prefix_len: u8) -> Result<Ipv6FourWordGroupEncoding> {
+        let compressed = self.compressor.compress_prefix(ip, prefix_len)?;
+        let groups = self.encode_bytes_to_groups(
+            compressed.as_bytes(),
+            given_port(compressed.port.unwrap_or(NO_PORT)),
+            compressed.category,
+        )?;
+        Ok(Ipv6FourWordGroupEncoding::new(groups, compressed.category))
+    }
 
-        Ok(SocketAddrV6::new(addr, decoded_port, 0, 0))
+    /// Decodes a prefix encoding back to the network address and prefix length.
+    ///
+    /// Fails if the words encode an address rather than a prefix.
+    pub fn decode_prefix(&self, encoding: &Ipv6FourWordGroupEncoding) -> Result<(Ipv6Addr, u8)> {
+        let (compressed, _) = self.decode_groups(&encoding.groups)?;
+        let prefix_len = compressed.prefix_len().ok_or(FourWordError::NotEncoding {
+            expected: "a network prefix",
+        })?;
+        let (network, _) = self.compressor.decompress(&compressed)?;
+        Ok((network, prefix_len))
     }
 
     /// Encodes bytes into groups of four words
-    fn encode_bytes_to_groups(&self, data: &[u8], port: u16, category: Ipv6Category) -> Result<Vec<FourWordGroup>> {
-        let mut groups = Vec::new();
-
-        // Store the category (3 bits) + data length (5 bits) in the first byte, then data, then port
-        // This way the decoder knows the category and exactly how many bytes to extract
-        let data_len = data.len() as u8;
-        if data_len > 31 {
-            return Err(FourWordError::InvalidInput(
-                format!("Data too large: {} bytes (max 31)", data_len)
-            ));
-        }
-
-        // Calculate total bits: 8 bits for category+length + data bits + 16 bits for port
-        let total_bits = 8 + (data.len() * 8) + 16;
-
-        // Determine number of words needed
-        // Each word encodes 12 bits
-        // For IPv6: minimum 6 words (72 bits), can be 9 (108 bits) or 12 (144 bits)
-        let words_needed = if total_bits <= 72 {
-            6 // 6 words for simple patterns
-        } else if total_bits <= 108 {
-            9 // 9 words for medium complexity addresses
-        } else {
-            12 // 12 words for complex addresses
-        };
-
-        // Calculate padding needed (for potential future use)
-        let bits_to_encode: usize = words_needed * 12;
-        let _padding_bits = bits_to_encode.saturating_sub(total_bits);
-
-        // For large data or when using 12 words, use byte array approach to avoid overflow
-        if data.len() >= 14 || words_needed >= 12 {
-            return self.encode_large_data_to_groups(data, port, words_needed, category);
-        }
-
-        // Build the number: category+length (8 bits) + data + port (16 bits)
-        let mut n = 0u128;
-
-        // Put category (3 bits) and data length (5 bits) in the lowest 8 bits
-        let category_and_length = (category.to_bits() << 5) | (data_len & 0x1F);
-        n |= category_and_length as u128;
-
-        // Put compressed data in the next bits
-        for (i, &byte) in data.iter().enumerate() {
-            n |= (byte as u128) << (8 + (i * 8));
-        }
-
-        // Add port in the next 16 bits (after length + data)
-        n |= (port as u128) << (8 + (data.len() * 8));
-
-        // Extract words using modulo (similar to IPv4 approach)
-        let mut word_indices = Vec::with_capacity(words_needed);
-        let mut remaining = n;
-
-        for _ in 0..words_needed {
-            let index = (remaining % 4096) as u16;
-            word_indices.push(index);
-            remaining /= 4096;
-        }
+    fn encode_bytes_to_groups(
+        &self,
+        data: &[u8],
+        port: Option<u16>,
+        category: Ipv6Category,
+    ) -> Result<Vec<FourWordGroup>> {
+        let (indices, count) = pack_ipv6(data, port, category)?;
 
         // Always create groups of 4 words, padding as needed
-        for chunk in word_indices.chunks(4) {
+        let mut groups = Vec::new();
+        for chunk in indices[..count].chunks(4) {
             let words: Result<Vec<String>> = chunk
                 .iter()
                 .map(|&idx| {
-                    DICTIONARY
+                    self.dictionary
                         .get_word(idx)
                         .ok_or(FourWordError::InvalidWordIndex(idx))
                         .map(|s| s.to_string())
@@ -214,218 +283,302 @@ impl FourWordIpv6Encoder {
         Ok(groups)
     }
 
-    /// Encodes large data (>14 bytes) using byte array approach to avoid overflow
-    fn encode_large_data_to_groups(
-        &self,
-        data: &[u8],
-        port: u16,
-        words_needed: usize,
-        category: Ipv6Category,
-    ) -> Result<Vec<FourWordGroup>> {
-        // Create a byte array with category+length prefix, data, and port
-        let mut all_bytes = Vec::new();
-        let data_len = data.len() as u8;
-        if data_len > 31 {
-            return Err(FourWordError::InvalidInput(
-                format!("Data too large: {} bytes (max 31)", data_len)
-            ));
-        }
-        
-        // Pack category (3 bits) and length (5 bits) into first byte
-        let category_and_length = (category.to_bits() << 5) | (data_len & 0x1F);
-        all_bytes.push(category_and_length); // Category+length prefix
-        all_bytes.extend_from_slice(data); // Data
-        all_bytes.extend_from_slice(&port.to_le_bytes()); // Port in little-endian
-
-        // Pad to required number of bytes
-        let bytes_needed = (words_needed * 12).div_ceil(8); // Round up to bytes
-        while all_bytes.len() < bytes_needed {
-            all_bytes.push(0);
+    /// Decodes groups of words back to the compressed address and port
+    fn decode_groups(&self, groups: &[FourWordGroup]) -> Result<(CompressedIpv6, Option<u16>)> {
+        // Filter out empty words and special markers (from potential padding)
+        let words = groups
+            .iter()
+            .flat_map(|group| group.words().iter())
+            .filter(|w| !w.is_empty() && !w.starts_with("__MARKER_"));
+
+        let mut indices = [0u16; MAX_IPV6_WORDS];
+        let mut count = 0;
+        for word in words {
+            let index = self
+                .dictionary
+                .get_index(word)
+                .ok_or_else(|| FourWordError::unknown_word(count, word))?;
+            let slot = indices
+                .get_mut(count)
+                .ok_or_else(|| FourWordError::wrong_word_count(&IPV6_WORD_COUNTS, count + 1))?;
+            *slot = index;
+            count += 1;
         }
 
-        // Extract word indices directly from byte array
-        let mut word_indices = Vec::with_capacity(words_needed);
+        unpack_ipv6(&indices[..count])
+    }
+}
 
-        // Extract 12-bit chunks as word indices
-        let mut bit_offset = 0;
-        for _ in 0..words_needed {
-            let mut word_index = 0u16;
+/// Number of words in the longest IPv6 encoding
+pub(crate) const MAX_IPV6_WORDS: usize = 12;
+
+/// Packs header, compressed data and port into 6, 9 or 12 word indices.
+///
+/// The port takes 16 bits, or a one-byte code when it is well-known (or
+/// absent) and the full port would need more words or not fit at all. The
+/// decoder tells the two apart by the bits left after the data. `None` packs
+/// no port; `Some(65535)` packs an explicit port 65535, which sets the bit
+/// after a 16-bit field or uses its own code.
+///
+/// Returns the indices in a fixed buffer along with how many are used.
+pub(crate) fn pack_ipv6(
+    data: &[u8],
+    port: Option<u16>,
+    category: Ipv6Category,
+) -> Result<([u16; MAX_IPV6_WORDS], usize)> {
+    // Store the category (3 bits) + data length (5 bits) in the first byte, then data, then port
+    // This way the decoder knows the category and exactly how many bytes to extract
+    let data_len = data.len();
+    if data_len > 31 {
+        return Err(FourWordError::out_of_range(
+            "IPv6 data length",
+            "at most 31 bytes",
+            data_len,
+        ));
+    }
+    if let Some(indices) = pack_compact_unique_local(data, port, category) {
+        return Ok((indices, 6));
+    }
 
-            // Extract 12 bits starting at bit_offset
-            for bit in 0..12 {
-                let byte_idx = (bit_offset + bit) / 8;
-                let bit_idx = (bit_offset + bit) % 8;
+    // Pick the fewest words (6 = 72 bits, 9 = 108, 12 = 144) that hold the
+    // 8-bit category+length header, the data and the port field
+    let code = match port {
+        None => Some(NO_PORT_CODE),
+        Some(NO_PORT) => Some(MAX_PORT_CODE),
+        Some(port) => ports::port_code(port),
+    };
+    let explicit_max = port == Some(NO_PORT);
+    let (words_needed, port_field, port_bytes) = IPV6_WORD_COUNTS
+        .into_iter()
+        .find_map(|words| match spare_bits(words, data_len)? {
+            17.. if explicit_max => Some((words, u32::from(NO_PORT) | MAX_PORT_FLAG, 3)),
+            16.. if !explicit_max => Some((words, u32::from(port.unwrap_or(NO_PORT)), 2)),
+            8.. => code.map(|code| (words, u32::from(code), 1)),
+            _ => None,
+        })
+        .unwrap_or_else(|| {
+            // No room for this port: mark it absent if a byte is left
+            let room = spare_bits(MAX_IPV6_WORDS, data_len).is_some_and(|spare| spare >= 8);
+            (MAX_IPV6_WORDS, u32::from(NO_PORT_CODE), usize::from(room))
+        });
+
+    let mut indices = [0u16; MAX_IPV6_WORDS];
+
+    // For large data or when using 12 words, use byte array approach to avoid overflow
+    if data_len >= 14 || words_needed >= 12 {
+        // Header, up to 31 data bytes and the port in little-endian
+        let mut bytes = [0u8; 34];
+        bytes[0] = pack_header(category, data_len as u8);
+        bytes[1..1 + data_len].copy_from_slice(data);
+        bytes[1 + data_len..1 + data_len + port_bytes]
+            .copy_from_slice(&port_field.to_le_bytes()[..port_bytes]);
+        for (word, index) in indices[..words_needed].iter_mut().enumerate() {
+            *index = read_index(&bytes, word);
+        }
+        return Ok((indices, words_needed));
+    }
 
-                if byte_idx < all_bytes.len() {
-                    let bit_val = (all_bytes[byte_idx] >> bit_idx) & 1;
-                    word_index |= (bit_val as u16) << bit;
-                }
-            }
+    // Build the number: category+length (8 bits) + data + port (16 bits)
+    let mut n = pack_header(category, data_len as u8) as u128;
+    for (i, &byte) in data.iter().enumerate() {
+        n |= (byte as u128) << (8 + (i * 8));
+    }
+    n |= (port_field as u128) << (8 + (data_len * 8));
 
-            // Ensure word index is within dictionary range
-            word_index %= 4096;
-            word_indices.push(word_index);
-            bit_offset += 12;
-        }
+    // Extract words using modulo (similar to IPv4 approach)
+    for index in &mut indices[..words_needed] {
+        *index = (n % 4096) as u16;
+        n /= 4096;
+    }
 
-        // Create groups of 4 words
-        let mut groups = Vec::new();
-        for chunk in word_indices.chunks(4) {
-            let words: Result<Vec<String>> = chunk
-                .iter()
-                .map(|&idx| {
-                    DICTIONARY
-                        .get_word(idx)
-                        .ok_or(FourWordError::InvalidWordIndex(idx))
-                        .map(|s| s.to_string())
-                })
-                .collect();
+    Ok((indices, words_needed))
+}
 
-            let words = words?;
-            groups.push(FourWordGroup::new(
-                words.first().cloned().unwrap_or_default(),
-                words.get(1).cloned().unwrap_or_default(),
-                words.get(2).cloned().unwrap_or_default(),
-                words.get(3).cloned().unwrap_or_default(),
-            ));
-        }
+/// Packs a unique local /64 into 6 words when its port is well-known or absent.
+///
+/// The fc/fd byte is implied by the category apart from its L bit, which
+/// takes the top bit of a 7-bit port code after the global ID and subnet.
+fn pack_compact_unique_local(
+    data: &[u8],
+    port: Option<u16>,
+    category: Ipv6Category,
+) -> Option<[u16; MAX_IPV6_WORDS]> {
+    let (&prefix, rest) = data.split_first()?;
+    if category != Ipv6Category::UniqueLocal
+        || rest.len() != COMPACT_ULA_LEN
+        || prefix & 0xFE != 0xFC
+    {
+        return None;
+    }
+    let code = match port {
+        None => COMPACT_NO_PORT_CODE,
+        Some(NO_PORT) => COMPACT_MAX_PORT_CODE,
+        Some(port) => ports::port_code(port).filter(|&code| code < COMPACT_MAX_PORT_CODE)?,
+    };
+
+    let mut n = u128::from(pack_header(category, COMPACT_ULA_LEN as u8));
+    for (i, &byte) in rest.iter().enumerate() {
+        n |= u128::from(byte) << (8 + i * 8);
+    }
+    n |= u128::from(((prefix & 1) << 7) | code) << (8 + COMPACT_ULA_LEN * 8);
 
-        Ok(groups)
+    let mut indices = [0u16; MAX_IPV6_WORDS];
+    for index in &mut indices[..6] {
+        *index = (n % 4096) as u16;
+        n /= 4096;
     }
+    Some(indices)
+}
 
-    /// Decodes groups of words back to bytes, port, and actual category
-    fn decode_groups_to_bytes(
-        &self,
-        groups: &[FourWordGroup],
-        _encoding_category: Ipv6Category,
-    ) -> Result<(Vec<u8>, u16, Ipv6Category)> {
-        // Flatten all words from groups
-        let mut all_words = Vec::new();
-        for group in groups {
-            all_words.extend_from_slice(group.words());
+/// Inverse of [`pack_ipv6`]: recovers the compressed address and port from
+/// word indices, with `None` for no port
+pub(crate) fn unpack_ipv6(indices: &[u16]) -> Result<(CompressedIpv6, Option<u16>)> {
+    // Header, up to 31 data bytes and the port fit in the 18 bytes of 12 words
+    let mut bytes = [0u8; MAX_IPV6_WORDS * 12 / 8];
+    if indices.len() >= MAX_IPV6_WORDS {
+        // Large encodings (12 words) are packed as a little-endian bit string
+        for (word, &index) in indices.iter().enumerate() {
+            write_index(&mut bytes, word, index);
         }
+    } else {
+        // Smaller encodings are a base-4096 number: header, data, then port
+        let n = indices
+            .iter()
+            .rev()
+            .fold(0u128, |n, &index| (n << 12) | u128::from(index & 0xFFF));
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = (n.checked_shr(8 * i as u32).unwrap_or(0) & 0xFF) as u8;
+        }
+    }
 
-        // Filter out empty words and special markers (from potential padding)
-        let all_words: Vec<&String> = all_words.iter()
-            .filter(|w| !w.is_empty() && !w.starts_with("__MARKER_"))
-            .collect();
-
-        // For large encodings (12 words), use byte array approach
-        if all_words.len() >= 12 {
-            return self.decode_large_data_from_groups(&all_words);
+    // Extract category (3 bits) and data length (5 bits) from the first byte
+    let (category_bits, data_len) = unpack_header(bytes[0]);
+    let field = bytes.get(1 + data_len..).unwrap_or_default();
+    let port = match (port_field_bits(indices.len(), data_len), field) {
+        (16, [low, high, rest @ ..]) => match u16::from_le_bytes([*low, *high]) {
+            // The bit after the field tells an explicit 65535 from no port
+            NO_PORT if rest.first().is_some_and(|flag| flag & 1 == 1) => Some(NO_PORT),
+            NO_PORT => None,
+            port => Some(port),
+        },
+        (8, [code, ..])
+            if category_bits == Ipv6Category::UniqueLocal.to_bits()
+                && data_len == COMPACT_ULA_LEN =>
+        {
+            return unpack_compact_unique_local(&bytes[1..=data_len], *code);
         }
+        (8, [code, ..]) => code_port(*code),
+        _ => None,
+    };
+
+    let data = bytes.get(1..1 + data_len).ok_or_else(|| {
+        FourWordError::DecompressionError(format!(
+            "Encoded data length {data_len} exceeds the words"
+        ))
+    })?;
+    let category = Ipv6Category::from_encoded(category_bits, data)?;
+
+    let mut compressed = CompressedIpv6::from_bytes(data, category)?;
+    compressed.port = Some(port.unwrap_or(NO_PORT));
+    Ok((compressed, port))
+}
 
-        // Reconstruct the number using iterative multiplication to avoid overflow
-        let mut n = 0u128;
-        let mut base = 1u128;
+/// Rebuilds a unique local /64 from the compact layout of [`pack_compact_unique_local`]
+fn unpack_compact_unique_local(rest: &[u8], code: u8) -> Result<(CompressedIpv6, Option<u16>)> {
+    let mut data = [0u8; COMPACT_ULA_LEN + 1];
+    data[0] = 0xFC | (code >> 7);
+    data[1..].copy_from_slice(rest);
+    let port = match code & 0x7F {
+        COMPACT_NO_PORT_CODE => None,
+        COMPACT_MAX_PORT_CODE => Some(NO_PORT),
+        code => ports::code_port(code),
+    };
+
+    let mut compressed = CompressedIpv6::from_bytes(&data, Ipv6Category::UniqueLocal)?;
+    compressed.port = Some(port.unwrap_or(NO_PORT));
+    Ok((compressed, port))
+}
 
-        for word in all_words.iter() {
-            let index = DICTIONARY
-                .get_index(word)
-                .ok_or_else(|| FourWordError::InvalidWord(word.to_string()))?;
-
-            // Check for potential overflow before multiplication
-            if let Some(contribution) = base.checked_mul(index as u128) {
-                n = n.checked_add(contribution).ok_or_else(|| {
-                    FourWordError::InvalidInput("Numeric overflow in decoding".to_string())
-                })?;
-
-                // Update base for next iteration, but stop if it would overflow
-                if base.checked_mul(4096).is_none() {
-                    break;
-                }
-                base *= 4096;
-            } else {
-                return Err(FourWordError::InvalidInput(
-                    "Numeric overflow in decoding".to_string(),
-                ));
-            }
-        }
+/// The port to pack for a socket address port, where 65535 means none
+fn given_port(port: u16) -> Option<u16> {
+    (port != NO_PORT).then_some(port)
+}
 
-        // Extract category (3 bits) and data length (5 bits) from the lowest 8 bits
-        let category_and_length = (n & 0xFF) as u8;
-        let data_len = (category_and_length & 0x1F) as usize; // Lower 5 bits
-        let decoded_category_bits = (category_and_length >> 5) & 0x07; // Upper 3 bits
+/// Bits left for the port in `words` words after the header and `data_len` data bytes
+fn spare_bits(words: usize, data_len: usize) -> Option<usize> {
+    (words * 12).checked_sub(8 + 8 * data_len)
+}
 
-        // Extract compressed data from the next data_len bytes
-        let mut compressed_bytes = Vec::new();
-        for i in 0..data_len {
-            let byte = ((n >> (8 + (i * 8))) & 0xFF) as u8;
-            compressed_bytes.push(byte);
-        }
+/// Width of the port field in `words` words after `data_len` data bytes: 16
+/// for the full port, 8 for a port code, or 0 when the port was dropped
+pub(crate) fn port_field_bits(words: usize, data_len: usize) -> usize {
+    match spare_bits(words, data_len) {
+        Some(16..) => 16,
+        Some(8..) => 8,
+        _ => 0,
+    }
+}
 
-        // Extract port from the next 16 bits
-        let port = ((n >> (8 + (data_len * 8))) & 0xFFFF) as u16;
-        
-        // Decode the actual category from the bits
-        let actual_category = Ipv6Category::from_bits(decoded_category_bits)?;
-        
-        // Special handling for GlobalUnicast with provider patterns
-        // If the decoded category is GlobalUnicast and we have 13 bytes,
-        // the first byte is a pattern ID, not part of the category/length encoding
-        if actual_category == Ipv6Category::GlobalUnicast && compressed_bytes.len() == 13 {
-            // This is a provider pattern encoding - prepend the category bits
-            // to match what the decompressor expects
-            Ok((compressed_bytes, port, actual_category))
-        } else {
-            Ok((compressed_bytes, port, actual_category))
-        }
+/// Port for a one-byte port code.
+///
+/// The no-port code, and codes outside the table (left by encoders that
+/// dropped the port), decode as no port.
+fn code_port(code: u8) -> Option<u16> {
+    match code {
+        MAX_PORT_CODE => Some(NO_PORT),
+        code => ports::code_port(code),
     }
+}
 
-    /// Decodes large data (12 words) using byte array approach to avoid overflow
-    fn decode_large_data_from_groups(&self, all_words: &[&String]) -> Result<(Vec<u8>, u16, Ipv6Category)> {
-        // Convert words back to indices
-        let mut word_indices = Vec::new();
-        for word in all_words {
-            let index = DICTIONARY
-                .get_index(word)
-                .ok_or_else(|| FourWordError::InvalidWord(word.to_string()))?;
-            word_indices.push(index);
-        }
+/// Packs the category (upper 3 bits) and data length (lower 5 bits) into a header byte
+pub(crate) fn pack_header(category: Ipv6Category, data_len: u8) -> u8 {
+    (category.to_bits() << 5) | (data_len & 0x1F)
+}
 
-        // Convert word indices back to byte array
-        let bytes_needed = (all_words.len() * 12).div_ceil(8); // Round up to bytes
-        let mut all_bytes = vec![0u8; bytes_needed];
-
-        // Pack 12-bit word indices into byte array
-        let mut bit_offset = 0;
-        for &word_index in word_indices.iter() {
-            // Pack 12 bits starting at bit_offset
-            for bit in 0..12 {
-                let byte_idx = (bit_offset + bit) / 8;
-                let bit_idx = (bit_offset + bit) % 8;
-
-                if byte_idx < all_bytes.len() {
-                    let bit_val = (word_index >> bit) & 1;
-                    if bit_val == 1 {
-                        all_bytes[byte_idx] |= 1 << bit_idx;
-                    }
-                }
-            }
-            bit_offset += 12;
-        }
+/// Splits a header byte back into its category bits and data length.
+///
+/// The bits are resolved with [`Ipv6Category::from_encoded`] once the data is
+/// available, since extended categories keep their subtype in the first byte.
+pub(crate) fn unpack_header(header: u8) -> (u8, usize) {
+    ((header >> 5) & 0x07, (header & 0x1F) as usize)
+}
 
-        // Extract category (3 bits) and data length (5 bits) from first byte
-        let category_and_length = all_bytes[0];
-        let data_len = (category_and_length & 0x1F) as usize; // Lower 5 bits
-        let decoded_category_bits = (category_and_length >> 5) & 0x07; // Upper 3 bits
+/// Reads `count` 12-bit word indices from `bytes`, least significant bit first.
+///
+/// Bits past the end of `bytes` read as zero.
+#[cfg(any(test, kani))]
+pub(crate) fn bytes_to_indices(bytes: &[u8], count: usize) -> Vec<u16> {
+    (0..count).map(|word| read_index(bytes, word)).collect()
+}
 
-        // Extract compressed data
-        let compressed_bytes = all_bytes[1..1 + data_len].to_vec();
+/// Reads the `word`th 12-bit index from `bytes`, least significant bit first
+pub(crate) fn read_index(bytes: &[u8], word: usize) -> u16 {
+    (0..12).fold(0u16, |index, bit| {
+        let offset = word * 12 + bit;
+        let value = bytes
+            .get(offset / 8)
+            .map_or(0, |byte| (byte >> (offset % 8)) & 1);
+        index | (u16::from(value) << bit)
+    })
+}
 
-        // Extract port from little-endian bytes
-        let port_start = 1 + data_len;
-        let port = if port_start + 2 <= all_bytes.len() {
-            u16::from_le_bytes([all_bytes[port_start], all_bytes[port_start + 1]])
-        } else {
-            // If we can't read a full 2-byte port, use the special marker for "no port specified"
-            65535
-        };
-        
-        // Decode the actual category from the bits
-        let actual_category = Ipv6Category::from_bits(decoded_category_bits)?;
+/// Inverse of [`bytes_to_indices`]: packs 12-bit indices into `ceil(12n / 8)` bytes
+#[cfg(any(test, kani))]
+pub(crate) fn indices_to_bytes(indices: &[u16]) -> Vec<u8> {
+    let mut bytes = vec![0u8; (indices.len() * 12).div_ceil(8)];
+    for (word, &index) in indices.iter().enumerate() {
+        write_index(&mut bytes, word, index);
+    }
+    bytes
+}
 
-        Ok((compressed_bytes, port, actual_category))
+/// Sets the `word`th 12-bit index in `bytes`, least significant bit first
+pub(crate) fn write_index(bytes: &mut [u8], word: usize, index: u16) {
+    for bit in 0..12 {
+        if (index >> bit) & 1 == 1 {
+            let offset = word * 12 + bit;
+            bytes[offset / 8] |= 1 << (offset % 8);
+        }
     }
 }
 
@@ -458,11 +611,7 @@ mod tests {
             if let SocketAddr::V6(v6) = addr {
                 println!("\nTesting: {}", addr_str);
                 let encoded = encoder.encode(&v6).unwrap();
-                println!(
-                    "Encoded: {} ({} words)",
-                    encoded.to_string(),
-                    encoded.word_count()
-                );
+                println!("Encoded: {} ({} words)", encoded, encoded.word_count());
                 println!("Category: {:?}", encoded.category());
                 let decoded = encoder.decode(&encoded).unwrap();
                 println!("Decoded: {}", decoded);
@@ -514,4 +663,104 @@ mod tests {
         );
         assert_eq!(encoding.word_count(), 8);
     }
+
+    #[test]
+    fn test_header_round_trip() {
+        let header = pack_header(Ipv6Category::UniqueLocal, 8);
+        assert_eq!(header, 0b010_01000);
+        assert_eq!(unpack_header(header), (2, 8));
+        // Category bits 7 defer to the subtype in the first data byte
+        let (bits, _) = unpack_header(pack_header(Ipv6Category::Nat64, 6));
+        assert_eq!(
+            Ipv6Category::from_encoded(bits, &[0]).unwrap(),
+            Ipv6Category::Nat64
+        );
+        assert!(Ipv6Category::from_encoded(bits, &[0xff]).is_err());
+        assert!(Ipv6Category::from_encoded(bits, &[]).is_err());
+    }
+
+    #[test]
+    fn test_twelve_bit_packing_round_trip() {
+        let indices: Vec<u16> = (0..12).map(|i| (i * 337 + 5) % 4096).collect();
+        let bytes = indices_to_bytes(&indices);
+        assert_eq!(bytes.len(), 18);
+        assert_eq!(bytes_to_indices(&bytes, indices.len()), indices);
+        // Missing trailing bytes read as zero
+        assert_eq!(bytes_to_indices(&[0xFF], 2), vec![0x0FF, 0]);
+    }
+
+    #[test]
+    fn test_well_known_ports_use_a_byte() {
+        // Seven data bytes leave 8 spare bits in 6 words: room for a port code only
+        let data = [0x21; 7];
+        for (port, words) in [
+            (Some(443), 6),
+            (Some(22), 6),
+            (None, 6),
+            (Some(NO_PORT), 6),
+            (Some(4242), 9),
+        ] {
+            let (indices, count) = pack_ipv6(&data, port, Ipv6Category::GlobalUnicast).unwrap();
+            assert_eq!(count, words, "port {port:?}");
+            let (compressed, decoded) = unpack_ipv6(&indices[..count]).unwrap();
+            assert_eq!((compressed.as_bytes(), decoded), (&data[..], port));
+        }
+
+        // Sixteen data bytes keep a well-known port but drop any other
+        let data = [0x21; 16];
+        for (port, decoded) in [(Some(443), Some(443)), (Some(4242), None), (None, None)] {
+            let (indices, count) = pack_ipv6(&data, port, Ipv6Category::GlobalUnicast).unwrap();
+            assert_eq!(count, 12);
+            assert_eq!(
+                unpack_ipv6(&indices[..count]).unwrap().1,
+                decoded,
+                "port {port:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_explicit_max_port_differs_from_no_port() {
+        // Eight data bytes leave room for the full port and the flag bit in 9 words
+        let data = [0x21; 8];
+        let (absent, count) = pack_ipv6(&data, None, Ipv6Category::GlobalUnicast).unwrap();
+        let (explicit, _) = pack_ipv6(&data, Some(NO_PORT), Ipv6Category::GlobalUnicast).unwrap();
+        assert_eq!(count, 9);
+        assert_ne!(absent, explicit);
+        assert_eq!(unpack_ipv6(&absent[..count]).unwrap().1, None);
+        assert_eq!(unpack_ipv6(&explicit[..count]).unwrap().1, Some(NO_PORT));
+    }
+
+    #[test]
+    fn test_unique_local_prefix_fits_six_words() {
+        assert!(ports::WELL_KNOWN_PORTS.len() < usize::from(COMPACT_MAX_PORT_CODE));
+        for prefix in [0xFC, 0xFD] {
+            let data = [prefix, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE];
+            for port in [Some(443), None, Some(NO_PORT)] {
+                let (indices, count) = pack_ipv6(&data, port, Ipv6Category::UniqueLocal).unwrap();
+                assert_eq!(count, 6, "port {port:?}");
+                let (compressed, decoded) = unpack_ipv6(&indices[..count]).unwrap();
+                assert_eq!((compressed.as_bytes(), decoded), (&data[..], port));
+                assert_eq!(compressed.category, Ipv6Category::UniqueLocal);
+            }
+            // Other ports keep the full layout
+            let (indices, count) = pack_ipv6(&data, Some(4242), Ipv6Category::UniqueLocal).unwrap();
+            assert_eq!(count, 9);
+            assert_eq!(unpack_ipv6(&indices[..count]).unwrap().1, Some(4242));
+        }
+    }
+
+    #[test]
+    fn test_unpack_rejects_overlong_length() {
+        // A header claiming 31 data bytes cannot be satisfied by 12 words
+        let mut indices = [0u16; MAX_IPV6_WORDS];
+        indices[0] = u16::from(pack_header(Ipv6Category::GlobalUnicast, 31));
+        assert!(unpack_ipv6(&indices).is_err());
+        assert!(unpack_ipv6(&indices[..6]).is_err());
+
+        let addr: SocketAddrV6 = "[2001:db8:1:2:3:4:5:6]:443".parse().unwrap();
+        let encoder = FourWordIpv6Encoder::new();
+        let encoding = encoder.encode(&addr).unwrap();
+        assert_eq!(encoder.decode(&encoding).unwrap(), addr);
+    }
 }
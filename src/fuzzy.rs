@@ -0,0 +1,211 @@
+//! Fuzzy word matching for typo-tolerant decoding.
+//!
+//! This module finds the dictionary words closest to a misspelled input
+//! (e.g. "oceen" -> "ocean") using edit distance, so decoding can suggest
+//! corrections or fix unambiguous typos automatically.
+
+#![forbid(unsafe_code)]
+
+use crate::dictionary4k::DICTIONARY;
+use crate::wordlist::Wordlist;
+
+/// Maximum edit distance considered when suggesting corrections
+pub const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Maximum number of suggestions returned for a single word
+pub const MAX_SUGGESTIONS: usize = 5;
+
+/// A candidate correction for a word that is not in the dictionary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordSuggestion {
+    /// The dictionary word being suggested
+    pub word: String,
+    /// Edit distance between the input and the suggested word
+    pub distance: usize,
+}
+
+/// A correction applied during fuzzy decoding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordCorrection {
+    /// Zero-based position of the word in the phrase
+    pub position: usize,
+    /// The word as it was supplied
+    pub original: String,
+    /// The dictionary word it was replaced with
+    pub corrected: String,
+    /// Edit distance between the two
+    pub distance: usize,
+}
+
+/// Result of a fuzzy decode: the address plus any corrections that were applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyDecoding {
+    /// The decoded address
+    pub address: String,
+    /// Corrections applied to the input words, in phrase order
+    pub corrections: Vec<WordCorrection>,
+}
+
+/// Computes the edit distance between two words.
+///
+/// Uses the optimal string alignment variant of Damerau-Levenshtein, so an
+/// adjacent transposition ("ocaen" vs "ocean") counts as a single edit.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.is_empty() {
+        return b.len();
+    }
+    if b.is_empty() {
+        return a.len();
+    }
+
+    let width = b.len() + 1;
+    let mut d = vec![0usize; (a.len() + 1) * width];
+    for i in 0..=a.len() {
+        d[i * width] = i;
+    }
+    for (j, cell) in d.iter_mut().enumerate().take(width) {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (d[(i - 1) * width + j] + 1)
+                .min(d[i * width + j - 1] + 1)
+                .min(d[(i - 1) * width + j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(d[(i - 2) * width + j - 2] + 1);
+            }
+            d[i * width + j] = best;
+        }
+    }
+
+    d[a.len() * width + b.len()]
+}
+
+/// Returns the dictionary words nearest to `word`, ranked by edit distance.
+///
+/// Only words within [`MAX_SUGGESTION_DISTANCE`] are returned. Ties are broken
+/// by dictionary order so results are deterministic.
+pub fn suggest_words(word: &str, limit: usize) -> Vec<WordSuggestion> {
+    suggest_words_in(&DICTIONARY, word, limit)
+}
+
+/// Same as [`suggest_words`] but against an explicit wordlist
+pub fn suggest_words_in<W: Wordlist + ?Sized>(
+    dict: &W,
+    word: &str,
+    limit: usize,
+) -> Vec<WordSuggestion> {
+    nearest_indices(dict, word, limit)
+        .into_iter()
+        .filter_map(|(distance, index)| {
+            dict.get_word(index).map(|w| WordSuggestion {
+                word: w.to_string(),
+                distance,
+            })
+        })
+        .collect()
+}
+
+/// Edit distance and index of the words nearest to `word`, closest first
+pub(crate) fn nearest_indices<W: Wordlist + ?Sized>(
+    dict: &W,
+    word: &str,
+    limit: usize,
+) -> Vec<(usize, u16)> {
+    let needle = word.to_lowercase();
+    let needle_len = needle.chars().count();
+    let mut candidates: Vec<(usize, u16)> = Vec::new();
+
+    for index in 0..dict.len() as u16 {
+        let Some(candidate) = dict.get_word(index) else {
+            continue;
+        };
+        // Length difference is a lower bound on edit distance
+        if candidate.chars().count().abs_diff(needle_len) > MAX_SUGGESTION_DISTANCE {
+            continue;
+        }
+        let distance = edit_distance(&needle, candidate);
+        if distance <= MAX_SUGGESTION_DISTANCE {
+            candidates.push((distance, index));
+        }
+    }
+
+    candidates.sort();
+    candidates.truncate(limit);
+    candidates
+}
+
+/// Picks the suggestion to auto-correct to, if exactly one word is closest.
+pub(crate) fn unambiguous_correction(suggestions: &[WordSuggestion]) -> Option<&WordSuggestion> {
+    let best = suggestions.first()?;
+    let tied = suggestions
+        .iter()
+        .filter(|s| s.distance == best.distance)
+        .count();
+    (tied == 1).then_some(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("ocean", "ocean"), 0);
+        assert_eq!(edit_distance("oceen", "ocean"), 1);
+        assert_eq!(edit_distance("ocaen", "ocean"), 1); // transposition
+        assert_eq!(edit_distance("ocan", "ocean"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_suggest_ranks_closest_first() {
+        let suggestions = suggest_words("oceen", MAX_SUGGESTIONS);
+        assert!(!suggestions.is_empty());
+        assert_eq!(suggestions[0].word, "ocean");
+        assert_eq!(suggestions[0].distance, 1);
+        assert!(
+            suggestions
+                .windows(2)
+                .all(|w| w[0].distance <= w[1].distance)
+        );
+    }
+
+    #[test]
+    fn test_no_suggestions_for_garbage() {
+        assert!(suggest_words("xqzzvkjw", MAX_SUGGESTIONS).is_empty());
+    }
+
+    #[test]
+    fn test_unambiguous_correction() {
+        let one = vec![
+            WordSuggestion {
+                word: "ocean".to_string(),
+                distance: 1,
+            },
+            WordSuggestion {
+                word: "open".to_string(),
+                distance: 2,
+            },
+        ];
+        assert_eq!(unambiguous_correction(&one).unwrap().word, "ocean");
+
+        let tied = vec![
+            WordSuggestion {
+                word: "cat".to_string(),
+                distance: 1,
+            },
+            WordSuggestion {
+                word: "car".to_string(),
+                distance: 1,
+            },
+        ];
+        assert!(unambiguous_correction(&tied).is_none());
+    }
+}
@@ -0,0 +1,428 @@
+//! Minimal HTTP/JSON API over TCP.
+//!
+//! Services written in other languages can call the canonical encoder over
+//! localhost instead of porting the algorithm. `4wn serve` runs an
+//! [`HttpServer`] with the CLI's wordlist and encoding profile.
+//!
+//! ## Endpoints
+//!
+//! ```text
+//! POST /encode  {"address": "192.168.1.1:443"}  ->  200 conversion
+//! POST /decode  {"words": "<phrase>"}           ->  200 conversion
+//! GET  /health                                  ->  200 {"status": "ok", "version": ...}
+//! GET  /metrics                                 ->  200 Prometheus text format
+//! ```
+//!
+//! A conversion is the JSON of a [`Conversion`](crate::conversion::Conversion):
+//! the input, words, ip, port, category and lossless flag, as printed by
+//! `4wn --format json`. A plain text body is accepted in place of the JSON
+//! object. Failures return a 4xx status with `{"error": "<message>"}`.
+//!
+//! Every conversion and request is recorded in the server's
+//! [`Metrics`]: conversions by category, lossy encodings, error kinds,
+//! latency and requests by endpoint and status. Pass a handle in with
+//! [`HttpServer::with_metrics`] to share it with the rest of a service.
+//!
+//! The server speaks just enough HTTP/1.1 for that: `Content-Length` bodies
+//! (no chunked uploads) and persistent connections, one thread per
+//! connection. Bind it to a loopback address; it has no authentication or TLS.
+
+#![forbid(unsafe_code)]
+
+use crate::conversion::Direction;
+use crate::error::Result;
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::metrics::Metrics;
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Address `4wn serve` listens on by default
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:8080";
+
+/// Largest request line plus headers accepted, in bytes
+pub const MAX_HEAD_BYTES: u64 = 8 * 1024;
+
+/// Largest request body accepted, in bytes
+pub const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+/// Idle connections are closed after this long
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Status and JSON body of a response
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    /// HTTP status code
+    pub status: u16,
+    /// Response body; a JSON string is sent as plain text
+    pub body: Value,
+}
+
+impl HttpResponse {
+    fn ok(body: Value) -> Self {
+        HttpResponse { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        HttpResponse {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+
+    /// Reason phrase for the status line
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Content Too Large",
+            431 => "Request Header Fields Too Large",
+            500 => "Internal Server Error",
+            _ => "Error",
+        }
+    }
+}
+
+/// HTTP server answering encode/decode requests from a shared encoder
+#[derive(Clone)]
+pub struct HttpServer {
+    encoder: Arc<FourWordAdaptiveEncoder>,
+    metrics: Arc<Metrics>,
+}
+
+impl HttpServer {
+    /// Creates a server that converts with `encoder`
+    pub fn new(encoder: FourWordAdaptiveEncoder) -> Self {
+        HttpServer {
+            encoder: Arc::new(encoder),
+            metrics: Arc::new(Metrics::new()),
+        }
+    }
+
+    /// Records into `metrics` instead of the server's own handle
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// The encoder requests are converted with
+    pub fn encoder(&self) -> &FourWordAdaptiveEncoder {
+        &self.encoder
+    }
+
+    /// The metrics served at `GET /metrics`
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Binds a listener at `addr`
+    pub fn bind(addr: impl ToSocketAddrs) -> Result<TcpListener> {
+        Ok(TcpListener::bind(addr)?)
+    }
+
+    /// Accepts connections forever, serving each on its own thread
+    pub fn serve(&self, listener: TcpListener) -> Result<()> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = self.clone();
+            thread::spawn(move || {
+                // A client hanging up mid-request is not a server failure
+                let _ = server.serve_connection(stream);
+            });
+        }
+        Ok(())
+    }
+
+    /// Serves requests on a single connection until either side closes it
+    pub fn serve_connection(&self, stream: TcpStream) -> Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut writer = stream.try_clone()?;
+        let mut reader = BufReader::new(stream);
+
+        loop {
+            let (response, keep_alive) = match read_request(&mut reader)? {
+                None => return Ok(()),
+                Some(Err(response)) => (response, false),
+                Some(Ok(request)) => (
+                    self.handle(&request.method, &request.path, &request.body),
+                    request.keep_alive,
+                ),
+            };
+            let (body, content_type) = match &response.body {
+                Value::String(text) => (text.clone(), "text/plain; version=0.0.4"),
+                body => (body.to_string(), "application/json"),
+            };
+            write!(
+                writer,
+                "HTTP/1.1 {} {}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n{}\r\n{body}",
+                response.status,
+                response.reason(),
+                body.len(),
+                if keep_alive {
+                    ""
+                } else {
+                    "Connection: close\r\n"
+                },
+            )?;
+            writer.flush()?;
+            if !keep_alive {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Handles one request and returns the response to send
+    pub fn handle(&self, method: &str, path: &str, body: &[u8]) -> HttpResponse {
+        let path = path.split_once('?').map_or(path, |(path, _)| path);
+        let response = self.respond(method, path, body);
+        let endpoint = match path {
+            "/encode" => "/encode",
+            "/decode" => "/decode",
+            "/health" => "/health",
+            "/metrics" => "/metrics",
+            _ => "other",
+        };
+        self.metrics.record_request(endpoint, response.status);
+        response
+    }
+
+    /// The response to a request for `path`, without its query string
+    fn respond(&self, method: &str, path: &str, body: &[u8]) -> HttpResponse {
+        let (expected, field) = match path {
+            "/health" => {
+                return match method {
+                    "GET" => HttpResponse::ok(json!({
+                        "status": "ok",
+                        "version": crate::VERSION,
+                    })),
+                    _ => HttpResponse::error(405, "use GET /health"),
+                };
+            }
+            "/metrics" => {
+                return match method {
+                    "GET" => HttpResponse::ok(Value::String(self.metrics.render())),
+                    _ => HttpResponse::error(405, "use GET /metrics"),
+                };
+            }
+            "/encode" => (Direction::Encode, "address"),
+            "/decode" => (Direction::Decode, "words"),
+            _ => return HttpResponse::error(404, format!("no endpoint at {path}")),
+        };
+        if method != "POST" {
+            return HttpResponse::error(405, format!("use POST {path}"));
+        }
+
+        let input = match request_input(body, field) {
+            Ok(input) => input,
+            Err(message) => return HttpResponse::error(400, message),
+        };
+        match self.metrics.convert(&self.encoder, &input) {
+            Ok(conversion) if conversion.direction == expected => {
+                match serde_json::to_value(conversion) {
+                    Ok(body) => HttpResponse::ok(body),
+                    Err(e) => HttpResponse::error(500, e.to_string()),
+                }
+            }
+            Ok(_) => HttpResponse::error(
+                400,
+                match expected {
+                    Direction::Encode => "expected an address; POST phrases to /decode",
+                    Direction::Decode => "expected words; POST addresses to /encode",
+                },
+            ),
+            Err(e) => HttpResponse::error(400, e.to_string()),
+        }
+    }
+}
+
+/// A parsed request
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+    keep_alive: bool,
+}
+
+/// Reads the next request, `None` at end of stream, or the error response
+/// for a request that cannot be served
+fn read_request(
+    reader: &mut BufReader<TcpStream>,
+) -> Result<Option<std::result::Result<Request, HttpResponse>>> {
+    let mut head = reader.by_ref().take(MAX_HEAD_BYTES);
+    let mut line = String::new();
+    if head.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Ok(Some(Err(HttpResponse::error(
+            400,
+            "malformed request line",
+        ))));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+    let mut keep_alive = version == "HTTP/1.1";
+
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        if head.read_line(&mut line)? == 0 || !line.ends_with('\n') {
+            return Ok(Some(Err(HttpResponse::error(
+                431,
+                "request head too large",
+            ))));
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            return Ok(Some(Err(HttpResponse::error(400, "malformed header"))));
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            match value.parse::<u64>() {
+                Ok(length) if length <= MAX_BODY_BYTES => content_length = length,
+                Ok(_) => return Ok(Some(Err(HttpResponse::error(413, "body too large")))),
+                Err(_) => return Ok(Some(Err(HttpResponse::error(400, "bad Content-Length")))),
+            }
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            return Ok(Some(Err(HttpResponse::error(
+                400,
+                "chunked bodies are not supported; send Content-Length",
+            ))));
+        } else if name.eq_ignore_ascii_case("connection") {
+            keep_alive = !value.eq_ignore_ascii_case("close");
+        }
+    }
+
+    let mut body = Vec::new();
+    reader.take(content_length).read_to_end(&mut body)?;
+    Ok(Some(Ok(Request {
+        method,
+        path,
+        body,
+        keep_alive,
+    })))
+}
+
+/// The address or phrase in a body: `{"<field>": "..."}` or plain text
+fn request_input(body: &[u8], field: &str) -> std::result::Result<String, String> {
+    let text = std::str::from_utf8(body).map_err(|_| "body is not UTF-8".to_string())?;
+    let text = text.trim();
+    if !text.starts_with('{') {
+        return match text {
+            "" => Err(format!("missing body; send {{\"{field}\": \"...\"}}")),
+            text => Ok(text.to_string()),
+        };
+    }
+    let value: Value = serde_json::from_str(text).map_err(|e| format!("invalid JSON: {e}"))?;
+    value[field]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| format!("expected {{\"{field}\": \"...\"}}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle() {
+        let server = HttpServer::new(FourWordAdaptiveEncoder::new().unwrap());
+        let health = server.handle("GET", "/health", b"");
+        assert_eq!((health.status, &health.body["status"]), (200, &json!("ok")));
+
+        let encoded = server.handle("POST", "/encode", br#"{"address": "192.168.1.1:443"}"#);
+        assert_eq!(encoded.status, 200);
+        assert_eq!(encoded.body["category"], "ipv4");
+        let words = encoded.body["words"].as_str().unwrap();
+
+        let decoded = server.handle("POST", "/decode", words.as_bytes());
+        assert_eq!(decoded.status, 200);
+        assert_eq!(
+            (&decoded.body["ip"], &decoded.body["port"]),
+            (&json!("192.168.1.1"), &json!(443))
+        );
+
+        assert_eq!(server.handle("POST", "/decode", b"10.0.0.1").status, 400);
+        assert_eq!(
+            server
+                .handle("POST", "/encode", br#"{"words": "x"}"#)
+                .status,
+            400
+        );
+        assert_eq!(server.handle("POST", "/encode", b"999.1.1.1").status, 400);
+        assert_eq!(server.handle("GET", "/encode", b"").status, 405);
+        assert_eq!(server.handle("GET", "/nope", b"").status, 404);
+
+        // The address POSTed to /decode was still converted
+        let metrics = server.metrics();
+        assert_eq!(metrics.conversions(Direction::Encode, "ipv4"), 2);
+        assert_eq!(metrics.conversions(Direction::Decode, "ipv4"), 1);
+        assert_eq!(metrics.requests("/encode", 400), 2);
+        assert_eq!(metrics.requests("other", 404), 1);
+        assert_eq!(metrics.errors("invalid_value"), 1);
+
+        let scrape = server.handle("GET", "/metrics", b"");
+        assert_eq!(scrape.status, 200);
+        let text = scrape.body.as_str().unwrap();
+        assert!(
+            text.contains(r#"fourword_http_requests_total{endpoint="/decode",status="200"} 1"#)
+        );
+        assert_eq!(server.handle("POST", "/metrics", b"").status, 405);
+    }
+
+    #[test]
+    fn test_shared_metrics() {
+        let metrics = Arc::new(Metrics::new());
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let server = HttpServer::new(encoder).with_metrics(Arc::clone(&metrics));
+        server.clone().handle("POST", "/encode", b"[::1]:8080");
+        assert_eq!(metrics.conversions(Direction::Encode, "loopback"), 1);
+        assert_eq!(metrics.requests("/encode", 200), 1);
+    }
+
+    #[test]
+    fn test_serve_over_tcp() {
+        let listener = HttpServer::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = HttpServer::new(FourWordAdaptiveEncoder::new().unwrap());
+        thread::spawn(move || server.serve(listener));
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let body = r#"{"address": "[::1]:8080"}"#;
+        // Two requests on one connection; the second asks to close it
+        write!(
+            stream,
+            "POST /encode HTTP/1.1\r\nContent-Length: {}\r\n\r\n{body}\
+             GET /health HTTP/1.1\r\n\r\n\
+             GET /metrics HTTP/1.1\r\nConnection: close\r\n\r\n",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert_eq!(
+            response.matches("HTTP/1.1 200 OK\r\n").count(),
+            3,
+            "{response}"
+        );
+        assert!(response.contains(r#""category":"loopback""#));
+        assert!(response.contains(&format!(r#""version":"{}"}}"#, crate::VERSION)));
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4\r\n"));
+        assert!(
+            response.contains(
+                r#"fourword_conversions_total{direction="encode",category="loopback"} 1"#
+            )
+        );
+        assert!(response.ends_with("fourword_conversion_duration_seconds_count 1\n"));
+    }
+}
@@ -0,0 +1,423 @@
+//! Explains how an address maps to its words.
+//!
+//! Similar addresses can produce very different phrases: a link-local and a
+//! global address fall into different categories, a well-known port takes one
+//! byte instead of two, and a changed byte moves every bit after it. The
+//! [`EncodingInspector`] lays an encoding out field by field so the reason is
+//! visible: the detected category, the bit layout of the packed value, the
+//! bits each word carries and whether the phrase reproduces the input.
+//!
+//! Words carry 12 bits each, least significant bit first: word 1 holds bits
+//! 0-11 of the packed value, word 2 bits 12-23 and so on. `4wn explain`
+//! prints the same report.
+//!
+//! ```rust
+//! use four_word_networking::{EncodingInspector, FourWordAdaptiveEncoder};
+//!
+//! let inspector = EncodingInspector::new(FourWordAdaptiveEncoder::new()?);
+//! let inspection = inspector.inspect("[fe80::1]:443")?;
+//! assert_eq!(inspection.description, "Link-Local (fe80::)");
+//! assert_eq!(inspection.bits, 72);
+//! assert!(inspection.lossless);
+//!
+//! let port = inspection.fields.last().unwrap();
+//! assert_eq!((port.name, port.start, port.len), ("port", 56, 16));
+//! assert_eq!(port.value, "443 https");
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::conversion::category_name;
+use crate::dictionary4k::Dictionary4K;
+use crate::error::{FourWordError, Result};
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::four_word_ipv6_encoder::{port_field_bits, unpack_ipv6};
+use crate::ipv6_compression::EXTENDED_CATEGORY_BITS;
+use crate::ports::service_name;
+use crate::wordlist::Wordlist;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+
+/// Port that marks an address without one
+const NO_PORT: u16 = 65535;
+
+/// Bits each word carries
+pub const BITS_PER_WORD: usize = 12;
+
+/// A field of the packed value
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct BitField {
+    /// Field name, such as `category`, `data` or `port`
+    pub name: &'static str,
+    /// First bit of the field
+    pub start: usize,
+    /// Width in bits
+    pub len: usize,
+    /// The field's value as read back from the words
+    pub value: String,
+}
+
+/// The bits one word carries
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct WordBits {
+    /// The word, before any reserved-word escape
+    pub word: String,
+    /// Its dictionary index, which is the 12 bits it carries
+    pub index: u16,
+    /// First bit the word carries
+    pub start: usize,
+    /// Names of the fields the word's bits belong to
+    pub fields: Vec<&'static str>,
+}
+
+/// How one address is encoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Inspection {
+    /// The address or phrase as given, trimmed
+    pub input: String,
+    /// The address that was encoded
+    pub ip: IpAddr,
+    /// Its port, or `None` for an address without one
+    pub port: Option<u16>,
+    /// Machine-readable category, as in [`Conversion`](crate::conversion::Conversion)
+    pub category: &'static str,
+    /// Human-readable category, such as `Link-Local (fe80::)`
+    pub description: &'static str,
+    /// The phrase, with any reserved-word escapes applied
+    pub phrase: String,
+    /// Size of the packed value: the word count times [`BITS_PER_WORD`]
+    pub bits: usize,
+    /// Fields of the packed value, lowest bits first
+    pub fields: Vec<BitField>,
+    /// The words in phrase order
+    pub words: Vec<WordBits>,
+    /// What the phrase decodes to
+    pub decoded: String,
+    /// Whether the phrase decodes to exactly the input address and port
+    pub lossless: bool,
+}
+
+/// Lays out how addresses are encoded by an encoder
+pub struct EncodingInspector<W = Dictionary4K> {
+    encoder: FourWordAdaptiveEncoder<W>,
+}
+
+impl<W: Wordlist> EncodingInspector<W> {
+    /// Creates an inspector for `encoder`, including its language and profile
+    pub fn new(encoder: FourWordAdaptiveEncoder<W>) -> Self {
+        EncodingInspector { encoder }
+    }
+
+    /// Inspects the encoding of an address, or of the address a phrase
+    /// decodes to.
+    ///
+    /// As with [`convert_entry`](FourWordAdaptiveEncoder::convert_entry),
+    /// input containing a digit or colon is an address.
+    pub fn inspect(&self, input: &str) -> Result<Inspection> {
+        let input = input.trim();
+        let addr = if input.contains(|c: char| c.is_ascii_digit() || c == ':') {
+            self.encoder.parse_address(input)?
+        } else {
+            self.encoder.decode_socket_addr(input)?
+        };
+        let mut inspection = self.inspect_addr(addr)?;
+        inspection.input = input.to_string();
+        Ok(inspection)
+    }
+
+    /// Inspects the encoding of `addr`; use port 65535 for "no port"
+    pub fn inspect_addr(&self, addr: SocketAddr) -> Result<Inspection> {
+        let mut unescaped = String::new();
+        self.encoder.encode_unescaped_into(addr, &mut unescaped)?;
+        let words: Vec<&str> = unescaped.split(' ').collect();
+        let indices = words
+            .iter()
+            .enumerate()
+            .map(|(position, word)| {
+                self.encoder
+                    .wordlist()
+                    .get_index(word)
+                    .ok_or_else(|| FourWordError::unknown_word(position, word))
+            })
+            .collect::<Result<Vec<u16>>>()?;
+        let bits = indices.len() * BITS_PER_WORD;
+
+        let (category, description, fields) = match addr {
+            SocketAddr::V4(_) => (None, "IPv4", ipv4_fields(&indices)),
+            SocketAddr::V6(_) => {
+                let (compressed, port) = unpack_ipv6(&indices)?;
+                let fields = ipv6_fields(&indices, compressed.as_bytes(), port);
+                (
+                    Some(compressed.category),
+                    compressed.category_description(),
+                    fields,
+                )
+            }
+        };
+        let words = words
+            .iter()
+            .zip(&indices)
+            .enumerate()
+            .map(|(i, (word, &index))| {
+                let start = i * BITS_PER_WORD;
+                WordBits {
+                    word: word.to_string(),
+                    index,
+                    start,
+                    fields: fields
+                        .iter()
+                        .filter(|f| f.start < start + BITS_PER_WORD && start < f.start + f.len)
+                        .map(|f| f.name)
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let mut phrase = String::new();
+        self.encoder.encode_into(addr, &mut phrase)?;
+        let decoded = self.encoder.decode_socket_addr(&phrase)?;
+        Ok(Inspection {
+            input: display_addr(addr),
+            ip: addr.ip(),
+            port: (addr.port() != NO_PORT).then_some(addr.port()),
+            category: category_name(category),
+            description,
+            phrase,
+            bits,
+            fields,
+            words,
+            decoded: display_addr(decoded),
+            lossless: decoded == addr,
+        })
+    }
+
+    /// Returns the encoder being inspected
+    pub fn encoder(&self) -> &FourWordAdaptiveEncoder<W> {
+        &self.encoder
+    }
+}
+
+/// IPv4: the port in the low 16 bits, the address in the 32 above
+fn ipv4_fields(indices: &[u16]) -> Vec<BitField> {
+    let port = read_bits(indices, 0, 16) as u16;
+    let ip = std::net::Ipv4Addr::from(read_bits(indices, 16, 32) as u32);
+    vec![
+        field("port", 0, 16, port_text((port != NO_PORT).then_some(port))),
+        field("address", 16, 32, ip.to_string()),
+    ]
+}
+
+/// IPv6: the header (data length, then category), the compressed data, the
+/// port field and any padding
+fn ipv6_fields(indices: &[u16], data: &[u8], port: Option<u16>) -> Vec<BitField> {
+    let length = read_bits(indices, 0, 5) as usize;
+    let category = read_bits(indices, 5, 3) as u8;
+    let mut fields = vec![
+        field("length", 0, 5, format!("{length} bytes")),
+        field("category", 5, 3, category.to_string()),
+    ];
+    let mut start = 8;
+    // Compact unique local phrases keep only the L bit of the fc/fd byte,
+    // beside a 7-bit port code
+    let compact = length < data.len();
+    let mut data = &data[data.len() - length.min(data.len())..];
+    if category == EXTENDED_CATEGORY_BITS
+        && let Some((subtype, rest)) = data.split_first()
+    {
+        fields.push(field("subtype", start, 8, subtype.to_string()));
+        start += 8;
+        data = rest;
+    }
+    if !data.is_empty() {
+        fields.push(field("data", start, 8 * data.len(), hex::encode(data)));
+        start += 8 * data.len();
+    }
+    match port_field_bits(indices.len(), (start - 8) / 8) {
+        16 => fields.push(field("port", start, 16, port_text(port))),
+        8 if compact => {
+            let code = read_bits(indices, start, 7);
+            fields.push(field(
+                "port code",
+                start,
+                7,
+                format!("{code} ({})", port_text(port)),
+            ));
+            let l_bit = read_bits(indices, start + 7, 1);
+            fields.push(field("L bit", start + 7, 1, l_bit.to_string()));
+        }
+        8 => {
+            let code = read_bits(indices, start, 8);
+            fields.push(field(
+                "port code",
+                start,
+                8,
+                format!("{code} ({})", port_text(port)),
+            ));
+        }
+        _ => {}
+    }
+    let end = fields.last().map_or(0, |f| f.start + f.len);
+    let bits = indices.len() * BITS_PER_WORD;
+    if end < bits {
+        fields.push(field(
+            "padding",
+            end,
+            bits - end,
+            read_bits(indices, end, bits - end).to_string(),
+        ));
+    }
+    fields
+}
+
+/// Shorthand for a [`BitField`]
+fn field(name: &'static str, start: usize, len: usize, value: String) -> BitField {
+    BitField {
+        name,
+        start,
+        len,
+        value,
+    }
+}
+
+/// Reads `len` (at most 64) bits from the words, starting at bit `start`
+fn read_bits(indices: &[u16], start: usize, len: usize) -> u64 {
+    (0..len).fold(0, |value, bit| {
+        let offset = start + bit;
+        let set = indices
+            .get(offset / BITS_PER_WORD)
+            .map_or(0, |index| (index >> (offset % BITS_PER_WORD)) & 1);
+        value | (u64::from(set) << bit)
+    })
+}
+
+/// A port with its service name, or `none`
+fn port_text(port: Option<u16>) -> String {
+    match port.map(|port| (port, service_name(port))) {
+        None => "none".to_string(),
+        Some((port, Some(name))) => format!("{port} {name}"),
+        Some((port, None)) => port.to_string(),
+    }
+}
+
+/// An address without the no-port marker
+fn display_addr(addr: SocketAddr) -> String {
+    if addr.port() == NO_PORT {
+        addr.ip().to_string()
+    } else {
+        addr.to_string()
+    }
+}
+
+impl fmt::Display for Inspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Input:      {}", self.input)?;
+        writeln!(f, "Category:   {}", self.description)?;
+        writeln!(f, "Phrase:     {}", self.phrase)?;
+        writeln!(
+            f,
+            "Size:       {} words x {BITS_PER_WORD} bits = {} bits, least significant first",
+            self.words.len(),
+            self.bits
+        )?;
+        writeln!(f)?;
+        writeln!(f, "  Bits      Field      Value")?;
+        for field in &self.fields {
+            let bits = bit_range(field.start, field.len);
+            writeln!(f, "  {bits:<9} {:<10} {}", field.name, field.value)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "  Word  Bits      Index  Carries")?;
+        for (i, word) in self.words.iter().enumerate() {
+            let bits = bit_range(word.start, BITS_PER_WORD);
+            writeln!(
+                f,
+                "  {:<5} {bits:<9} {:<6} {} ({})",
+                i + 1,
+                format!("{:#05x}", word.index),
+                word.word,
+                word.fields.join(", ")
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(f, "Decodes to: {}", self.decoded)?;
+        write!(
+            f,
+            "Lossless:   {}",
+            if self.lossless { "yes" } else { "no" }
+        )
+    }
+}
+
+/// `start-end` (inclusive) for a run of bits
+fn bit_range(start: usize, len: usize) -> String {
+    format!("{start}-{}", start + len - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inspector() -> EncodingInspector {
+        EncodingInspector::new(FourWordAdaptiveEncoder::new().unwrap())
+    }
+
+    #[test]
+    fn test_ipv4_layout() {
+        let inspection = inspector().inspect("1.2.3.4:22").unwrap();
+        assert_eq!((inspection.category, inspection.bits), ("ipv4", 48));
+        assert_eq!(
+            inspection.fields[0],
+            field("port", 0, 16, "22 ssh".to_string())
+        );
+        assert_eq!(inspection.fields[1].value, "1.2.3.4");
+        let carried: Vec<Vec<&str>> = inspection.words.iter().map(|w| w.fields.clone()).collect();
+        assert_eq!(
+            carried,
+            [
+                vec!["port"],
+                vec!["port", "address"],
+                vec!["address"],
+                vec!["address"]
+            ]
+        );
+        assert!(inspection.lossless);
+
+        let phrase = inspection.phrase.clone();
+        let from_phrase = inspector().inspect(&phrase).unwrap();
+        assert_eq!(from_phrase.input, phrase);
+        assert_eq!(from_phrase.decoded, "1.2.3.4:22");
+    }
+
+    #[test]
+    fn test_ipv6_layout() {
+        let inspector = inspector();
+        let inspection = inspector.inspect("[2001:db8:85a3::1]:4242").unwrap();
+        let names: Vec<&str> = inspection.fields.iter().map(|f| f.name).collect();
+        assert_eq!(names[..3], ["length", "category", "data"]);
+        assert!(names.contains(&"port"));
+        assert_eq!(inspection.words.len() * BITS_PER_WORD, inspection.bits);
+        // Fields tile the packed value without gaps
+        for pair in inspection.fields.windows(2) {
+            assert_eq!(pair[0].start + pair[0].len, pair[1].start);
+        }
+        let last = inspection.fields.last().unwrap();
+        assert_eq!(last.start + last.len, inspection.bits);
+
+        let extended = inspector.inspect("[64:ff9b::c000:201]:443").unwrap();
+        assert_eq!(extended.category, "nat64");
+        assert_eq!(extended.fields[2].name, "subtype");
+
+        let lossy = inspector.inspect("[2606:4700:4700::1111]:4433").unwrap();
+        assert!(!lossy.lossless);
+        assert_eq!(lossy.decoded, "2606:4700:4700::1111");
+        assert!(lossy.to_string().ends_with("Lossless:   no"));
+
+        assert!(inspector.inspect("ocean thunder").is_err());
+    }
+}
@@ -0,0 +1,196 @@
+//! Integers as words, for room codes, invite tokens and node IDs.
+//!
+//! A value takes as few 12-bit words as it needs, most significant first,
+//! and is followed by a check word: 12 bits of a SHA-256 hash of the value
+//! words under a domain tag. Small numbers therefore give short codes (any
+//! value below 4,096 is two words), a `u64` takes at most 7 words and a
+//! `u128` at most 12. A misheard, swapped, dropped or extra word passes the
+//! check only about once in 4,096 tries.
+//!
+//! Each integer has exactly one phrase: decoding rejects leading zero words
+//! as well as a bad check word.
+//!
+//! ```rust
+//! use four_word_networking::integer::{decode_u64, encode_u64, word_count};
+//!
+//! let code = encode_u64(1_234_567)?;
+//! assert_eq!(code.split(' ').count(), word_count(1_234_567));
+//! assert_eq!(decode_u64(&code)?, 1_234_567);
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::four_word_ipv6_encoder::read_index;
+use crate::language::Language;
+use crate::phrase::write_words;
+use crate::wordlist::Wordlist;
+use sha2::{Digest, Sha256};
+
+/// Domain tag hashed ahead of the value words, so check words never match other uses
+const DOMAIN_TAG: &[u8] = b"four-word-networking integer v1\0";
+
+/// Number of words, check word included, encoding `value`
+pub const fn word_count(value: u128) -> usize {
+    let bits = (u128::BITS - value.leading_zeros()) as usize;
+    let value_words = if bits == 0 { 1 } else { bits.div_ceil(12) };
+    value_words + 1
+}
+
+/// Encodes `value` as English words
+pub fn encode_u64(value: u64) -> Result<String> {
+    encode_u128(u128::from(value))
+}
+
+/// Decodes English words produced by [`encode_u64`]
+pub fn decode_u64(words: &str) -> Result<u64> {
+    decode_u64_with(&Language::English.dictionary(), words)
+}
+
+/// Encodes `value` as English words
+pub fn encode_u128(value: u128) -> Result<String> {
+    encode_u128_with(&Language::English.dictionary(), value)
+}
+
+/// Decodes English words produced by [`encode_u128`] (or [`encode_u64`])
+pub fn decode_u128(words: &str) -> Result<u128> {
+    decode_u128_with(&Language::English.dictionary(), words)
+}
+
+/// Decodes words from `wordlist` to a `u64`
+pub fn decode_u64_with<W: Wordlist + ?Sized>(wordlist: &W, words: &str) -> Result<u64> {
+    let value = decode_u128_with(wordlist, words)?;
+    u64::try_from(value)
+        .map_err(|_| FourWordError::out_of_range("Integer", "at most u64::MAX", value))
+}
+
+/// Encodes `value` as space-separated words from `wordlist`
+pub fn encode_u128_with<W: Wordlist + ?Sized>(wordlist: &W, value: u128) -> Result<String> {
+    let value_words = word_count(value) - 1;
+    let mut indices: Vec<u16> = (0..value_words)
+        .rev()
+        .map(|word| ((value >> (12 * word)) & 0xFFF) as u16)
+        .collect();
+    indices.push(check_index(&indices));
+    let mut words = String::new();
+    write_words(wordlist, &indices, &mut words)?;
+    Ok(words)
+}
+
+/// Decodes words from `wordlist`, separated by whitespace, dots or dashes
+pub fn decode_u128_with<W: Wordlist + ?Sized>(wordlist: &W, words: &str) -> Result<u128> {
+    let mut indices = words
+        .split(|c: char| c.is_whitespace() || c == '.' || c == '-')
+        .filter(|word| !word.is_empty())
+        .enumerate()
+        .map(|(position, word)| {
+            wordlist
+                .get_index(word)
+                .ok_or_else(|| FourWordError::unknown_word(position, word))
+        })
+        .collect::<Result<Vec<u16>>>()?;
+
+    let max_words = word_count(u128::MAX);
+    if !(2..=max_words).contains(&indices.len()) {
+        return Err(FourWordError::wrong_word_count(
+            &(2..=max_words).collect::<Vec<_>>(),
+            indices.len(),
+        ));
+    }
+    let check = indices.pop().unwrap_or_default();
+    if check != check_index(&indices) {
+        return Err(FourWordError::DecodingError(
+            "Check word does not match; a word was probably misheard".to_string(),
+        ));
+    }
+    let value = indices.iter().try_fold(0u128, |value, &index| {
+        value
+            .checked_mul(4096)
+            .map(|value| value | u128::from(index & 0xFFF))
+    });
+    match value {
+        Some(value) if word_count(value) == indices.len() + 1 => Ok(value),
+        _ => Err(FourWordError::NotEncoding {
+            expected: "an integer",
+        }),
+    }
+}
+
+/// Check word for the value word `indices`
+fn check_index(indices: &[u16]) -> u16 {
+    let mut hasher = Sha256::new().chain_update(DOMAIN_TAG);
+    for index in indices {
+        hasher.update(index.to_le_bytes());
+    }
+    read_index(&hasher.finalize(), 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_lengths() {
+        for (value, words) in [
+            (0, 2),
+            (1, 2),
+            (4095, 2),
+            (4096, 3),
+            (1 << 24, 4),
+            (u64::MAX as u128, 7),
+            (u128::MAX, 12),
+        ] {
+            let phrase = encode_u128(value).unwrap();
+            assert_eq!(phrase.split(' ').count(), words, "{value}");
+            assert_eq!(word_count(value), words);
+            assert_eq!(decode_u128(&phrase).unwrap(), value);
+        }
+        for value in [0, 42, 1_234_567, u64::MAX] {
+            let phrase = encode_u64(value).unwrap();
+            assert_eq!(phrase, encode_u128(u128::from(value)).unwrap());
+            assert_eq!(decode_u64(&phrase).unwrap(), value);
+        }
+        let dashed = encode_u64(987_654_321).unwrap().replace(' ', "-");
+        assert_eq!(decode_u64(&dashed.to_uppercase()).unwrap(), 987_654_321);
+    }
+
+    #[test]
+    fn test_check_word_and_canonical_form() {
+        let phrase = encode_u64(1_234_567).unwrap();
+        let words: Vec<&str> = phrase.split(' ').collect();
+
+        // Swapped value words
+        let swapped = [words[1], words[0], words[2]].join(" ");
+        assert!(matches!(
+            decode_u64(&swapped),
+            Err(FourWordError::DecodingError(_))
+        ));
+        // Leading zero word with a valid check word
+        let dictionary = Language::English.dictionary();
+        let zero = dictionary.get_word(0).unwrap();
+        let padded: Vec<u16> = std::iter::once(0)
+            .chain(
+                words[..2]
+                    .iter()
+                    .map(|word| dictionary.get_index(word).unwrap()),
+            )
+            .collect();
+        let check = dictionary.get_word(check_index(&padded)).unwrap();
+        let padded = format!("{zero} {} {} {check}", words[0], words[1]);
+        assert!(matches!(
+            decode_u64(&padded),
+            Err(FourWordError::NotEncoding { .. })
+        ));
+
+        assert!(matches!(
+            decode_u64(words[0]),
+            Err(FourWordError::WrongWordCount { got: 1, .. })
+        ));
+        assert!(decode_u64(&encode_u128(u128::from(u64::MAX) + 1).unwrap()).is_err());
+        assert!(matches!(
+            decode_u64("notaword ocean"),
+            Err(FourWordError::UnknownWord { position: 0, .. })
+        ));
+    }
+}
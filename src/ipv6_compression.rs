@@ -4,15 +4,35 @@
 //! for IPv6 addresses, taking advantage of their hierarchical structure and
 //! common patterns to achieve optimal compression ratios.
 
+#![forbid(unsafe_code)]
+
 use crate::error::FourWordError;
-use std::net::Ipv6Addr;
+use crate::multicast::{self, WELL_KNOWN_GROUPS};
+use crate::nat64::Nat64Prefix;
+use crate::profile::EncodingProfile;
+use crate::provider_prefix::ProviderPrefix;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Category bits marking an extended category, whose subtype is the first data byte
+pub const EXTENDED_CATEGORY_BITS: u8 = 7;
+
+/// Subtype of NAT64 addresses under the well-known prefix `64:ff9b::/96`,
+/// which need no prefix index because every decoder knows the prefix
+const NAT64_WELL_KNOWN_CODE: u8 = 9;
+
+/// Solicited-node group prefix `ff02::1:ff00:0/104` (RFC 4291 section 2.7.1)
+const SOLICITED_NODE: [u8; 13] = [0xFF, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01, 0xFF];
+
+/// Prefix lengths [`Ipv6Compressor::compress_prefix`] accepts, as delegated by
+/// DHCPv6-PD and used for ULA network plans
+pub const PREFIX_LENGTHS: [u8; 3] = [48, 56, 64];
 
 /// IPv6 address categories for compression optimization
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Ipv6Category {
     /// ::1 - IPv6 loopback (4 words)
     Loopback,
-    /// fe80::/10 - Link-local addresses (3-4 words)
+    /// fe80::/64 - Link-local addresses (3-4 words)
     LinkLocal,
     /// fc00::/7 - Unique local addresses (4-5 words)
     UniqueLocal,
@@ -22,8 +42,23 @@ pub enum Ipv6Category {
     GlobalUnicast,
     /// ::/128 - Unspecified address (4 words)
     Unspecified,
-    /// Multicast and other special addresses (5-6 words)
+    /// Reserved and other special addresses (12 words)
     Special,
+    /// IPv4 embedded under the well-known `64:ff9b::/96` or a
+    /// profile-configured NAT64 prefix (6 words)
+    Nat64,
+    /// A /48, /56 or /64 network prefix with the host bits dropped (6-9 words)
+    Prefix,
+    /// IPv4-mapped `::ffff:0:0/96` or IPv4-translated `::ffff:0:0:0/96` (6 words)
+    V4Mapped,
+    /// 2001::/32 - Teredo, server IPv4 and obfuscated client (12 words, keeps the port)
+    Teredo,
+    /// 2002::/16 - 6to4, embedded IPv4, subnet and interface ID (9-12 words)
+    SixToFour,
+    /// ff00::/8 - Multicast, scope plus a well-known or trimmed group ID (6-12 words)
+    Multicast,
+    /// Global unicast under a profile-registered provider prefix (6-12 words)
+    Provider,
 }
 
 impl Ipv6Category {
@@ -37,6 +72,55 @@ impl Ipv6Category {
             Ipv6Category::GlobalUnicast => 4,
             Ipv6Category::Unspecified => 5,
             Ipv6Category::Special => 6,
+            Ipv6Category::Nat64
+            | Ipv6Category::Prefix
+            | Ipv6Category::V4Mapped
+            | Ipv6Category::Teredo
+            | Ipv6Category::SixToFour
+            | Ipv6Category::Multicast
+            | Ipv6Category::Provider => EXTENDED_CATEGORY_BITS,
+        }
+    }
+
+    /// Subtype stored in the first data byte for extended categories.
+    ///
+    /// Prefixes use one subtype per length, IPv4-translated addresses the
+    /// code after IPv4-mapped ones, 6to4 addresses with a short interface
+    /// ID the code after full ones, and multicast groups one code each for
+    /// well-known, solicited-node and other groups, starting at this code. NAT64 addresses
+    /// under the well-known prefix use [`NAT64_WELL_KNOWN_CODE`].
+    pub fn extended_code(&self) -> Option<u8> {
+        match self {
+            Ipv6Category::Nat64 => Some(0),
+            Ipv6Category::Prefix => Some(1),
+            Ipv6Category::V4Mapped => Some(4),
+            Ipv6Category::Teredo => Some(6),
+            Ipv6Category::SixToFour => Some(7),
+            Ipv6Category::Multicast => Some(10),
+            Ipv6Category::Provider => Some(13),
+            _ => None,
+        }
+    }
+
+    /// Resolves category bits, reading the subtype byte for extended categories
+    pub fn from_encoded(bits: u8, data: &[u8]) -> Result<Self, FourWordError> {
+        if bits != EXTENDED_CATEGORY_BITS {
+            return Self::from_bits(bits);
+        }
+        match data.first().copied() {
+            Some(0 | NAT64_WELL_KNOWN_CODE) => Ok(Ipv6Category::Nat64),
+            Some(1..=3) => Ok(Ipv6Category::Prefix),
+            Some(4..=5) => Ok(Ipv6Category::V4Mapped),
+            Some(6) => Ok(Ipv6Category::Teredo),
+            Some(7..=8) => Ok(Ipv6Category::SixToFour),
+            Some(10..=12) => Ok(Ipv6Category::Multicast),
+            Some(13) => Ok(Ipv6Category::Provider),
+            Some(code) => Err(FourWordError::DecompressionError(format!(
+                "Invalid extended category: {code}"
+            ))),
+            None => Err(FourWordError::DecompressionError(
+                "Extended category is missing its subtype".to_string(),
+            )),
         }
     }
 
@@ -50,18 +134,151 @@ impl Ipv6Category {
             4 => Ok(Ipv6Category::GlobalUnicast),
             5 => Ok(Ipv6Category::Unspecified),
             6 => Ok(Ipv6Category::Special),
-            _ => Err(FourWordError::InvalidInput(
-                format!("Invalid category bits: {}", bits)
-            )),
+            _ => Err(FourWordError::DecompressionError(format!(
+                "Invalid category bits: {}",
+                bits
+            ))),
+        }
+    }
+}
+
+impl Ipv6Category {
+    /// Every category, in bit order then extended-code order
+    pub const ALL: [Ipv6Category; 14] = [
+        Ipv6Category::Loopback,
+        Ipv6Category::LinkLocal,
+        Ipv6Category::UniqueLocal,
+        Ipv6Category::Documentation,
+        Ipv6Category::GlobalUnicast,
+        Ipv6Category::Unspecified,
+        Ipv6Category::Special,
+        Ipv6Category::Nat64,
+        Ipv6Category::Prefix,
+        Ipv6Category::V4Mapped,
+        Ipv6Category::Teredo,
+        Ipv6Category::SixToFour,
+        Ipv6Category::Multicast,
+        Ipv6Category::Provider,
+    ];
+
+    /// Builds an address in this category from `random` bytes, for generators.
+    ///
+    /// The category's fixed bits overwrite the start of `random`. Prefix and
+    /// provider addresses are plain global unicast addresses, and global
+    /// unicast may land in a more specific category such as 6to4.
+    #[cfg(any(feature = "arbitrary", feature = "proptest"))]
+    pub(crate) fn sample_address(self, mut random: [u8; 16]) -> Ipv6Addr {
+        let fixed: &[u8] = match self {
+            Ipv6Category::Loopback => return Ipv6Addr::LOCALHOST,
+            Ipv6Category::Unspecified => return Ipv6Addr::UNSPECIFIED,
+            Ipv6Category::LinkLocal => &[0xFE, 0x80, 0, 0, 0, 0, 0, 0],
+            Ipv6Category::UniqueLocal => &[0xFD],
+            Ipv6Category::Documentation => &[0x20, 0x01, 0x0D, 0xB8],
+            Ipv6Category::Teredo => &[0x20, 0x01, 0, 0],
+            Ipv6Category::SixToFour => &[0x20, 0x02],
+            Ipv6Category::Nat64 => &[0, 0x64, 0xFF, 0x9B, 0, 0, 0, 0, 0, 0, 0, 0],
+            Ipv6Category::V4Mapped => &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xFF, 0xFF],
+            Ipv6Category::Multicast => &[0xFF],
+            // IPv4-compatible addresses, deprecated and kept verbatim; a
+            // nonzero first octet keeps clear of :: and ::1
+            Ipv6Category::Special => {
+                random[12] = random[12].max(1);
+                &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+            }
+            Ipv6Category::GlobalUnicast | Ipv6Category::Prefix | Ipv6Category::Provider => {
+                random[0] = 0x20 | (random[0] & 0x1F);
+                &[]
+            }
+        };
+        random[..fixed.len()].copy_from_slice(fixed);
+        Ipv6Addr::from(random)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls {
+    use super::{CompressedIpv6, Ipv6Category, Ipv6Compressor, PREFIX_LENGTHS};
+    use arbitrary::{Arbitrary, Error, Unstructured};
+
+    impl<'a> Arbitrary<'a> for Ipv6Category {
+        fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+            u.choose(&Ipv6Category::ALL).copied()
+        }
+    }
+
+    /// Compresses an address from a random category; prefix categories
+    /// compress a /48, /56 or /64 and provider ones plain global unicast
+    impl<'a> Arbitrary<'a> for CompressedIpv6 {
+        fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+            let category = Ipv6Category::arbitrary(u)?;
+            let ip = category.sample_address(u.arbitrary()?);
+            let compressor = Ipv6Compressor::new();
+            let compressed = match category {
+                Ipv6Category::Prefix => compressor.compress_prefix(ip, *u.choose(&PREFIX_LENGTHS)?),
+                _ => compressor.compress(ip, u.arbitrary()?),
+            };
+            compressed.map_err(|_| Error::IncorrectFormat)
         }
     }
 }
 
+#[cfg(feature = "proptest")]
+mod proptest_impls {
+    use super::{CompressedIpv6, Ipv6Category, Ipv6Compressor, PREFIX_LENGTHS};
+    use proptest::prelude::*;
+    use proptest::sample::{Select, select};
+
+    impl Arbitrary for Ipv6Category {
+        type Parameters = ();
+        type Strategy = Select<Ipv6Category>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            select(&Ipv6Category::ALL[..])
+        }
+    }
+
+    /// Same distribution as the `arbitrary` implementation
+    impl Arbitrary for CompressedIpv6 {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<CompressedIpv6>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            (
+                any::<Ipv6Category>(),
+                any::<[u8; 16]>(),
+                any::<Option<u16>>(),
+                select(&PREFIX_LENGTHS[..]),
+            )
+                .prop_filter_map(
+                    "address failed to compress",
+                    |(category, random, port, length)| {
+                        let ip = category.sample_address(random);
+                        let compressor = Ipv6Compressor::new();
+                        match category {
+                            Ipv6Category::Prefix => compressor.compress_prefix(ip, length),
+                            _ => compressor.compress(ip, port),
+                        }
+                        .ok()
+                    },
+                )
+                .boxed()
+        }
+    }
+}
+
+/// Largest compressed payload; with the header byte it fills the 144 bits of
+/// a 12-word encoding
+pub const MAX_COMPRESSED_BYTES: usize = 17;
+
 /// Compressed representation of an IPv6 address
-#[derive(Debug, Clone)]
+///
+/// The payload is stored inline, so compressing and decompressing never
+/// allocate and the value is `Copy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CompressedIpv6 {
     pub category: Ipv6Category,
-    pub compressed_data: Vec<u8>,
+    data: [u8; MAX_COMPRESSED_BYTES],
+    len: u8,
     pub original_bits: usize,
     pub compressed_bits: usize,
     pub port: Option<u16>,
@@ -71,23 +288,61 @@ impl CompressedIpv6 {
     /// Creates compressed IPv6 from bytes and category
     pub fn from_bytes(data: &[u8], category: Ipv6Category) -> Result<Self, FourWordError> {
         if data.is_empty() {
-            return Err(FourWordError::InvalidInput(
+            return Err(FourWordError::DecompressionError(
                 "Empty compressed data".to_string(),
             ));
         }
 
-        Ok(CompressedIpv6 {
+        let mut compressed = Self::empty(category, None);
+        compressed.extend_from_slice(data)?;
+        compressed.compressed_bits = data.len() * 8;
+        Ok(compressed)
+    }
+
+    /// Creates an empty payload for the compressor to fill
+    fn empty(category: Ipv6Category, port: Option<u16>) -> Self {
+        CompressedIpv6 {
             category,
-            compressed_data: data.to_vec(),
+            data: [0; MAX_COMPRESSED_BYTES],
+            len: 0,
             original_bits: 128,
-            compressed_bits: data.len() * 8,
-            port: None,
-        })
+            compressed_bits: 0,
+            port,
+        }
+    }
+
+    /// Appends a byte to the payload
+    fn push(&mut self, byte: u8) -> Result<(), FourWordError> {
+        self.extend_from_slice(&[byte])
+    }
+
+    /// Appends bytes to the payload, failing if it would exceed [`MAX_COMPRESSED_BYTES`]
+    fn extend_from_slice(&mut self, bytes: &[u8]) -> Result<(), FourWordError> {
+        let start = usize::from(self.len);
+        let end = start + bytes.len();
+        if end > MAX_COMPRESSED_BYTES {
+            return Err(FourWordError::CompressionError(format!(
+                "Compressed data too large: {end} bytes (max {MAX_COMPRESSED_BYTES})"
+            )));
+        }
+        self.data[start..end].copy_from_slice(bytes);
+        self.len = end as u8;
+        Ok(())
     }
 
     /// Get bytes representation
-    pub fn as_bytes(&self) -> Vec<u8> {
-        self.compressed_data.clone()
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data[..usize::from(self.len)]
+    }
+
+    /// Returns the prefix length for the [`Ipv6Category::Prefix`] category
+    pub fn prefix_len(&self) -> Option<u8> {
+        let first = self.category.extended_code()?;
+        if self.category != Ipv6Category::Prefix {
+            return None;
+        }
+        let code = self.as_bytes().first()?.checked_sub(first)?;
+        PREFIX_LENGTHS.get(usize::from(code)).copied()
     }
 
     /// Get the total compressed size including port
@@ -124,24 +379,38 @@ impl CompressedIpv6 {
             Ipv6Category::Documentation => "Documentation (2001:db8::)",
             Ipv6Category::GlobalUnicast => "Global Unicast",
             Ipv6Category::Unspecified => "Unspecified (::)",
-            Ipv6Category::Special => "Special/Multicast",
+            Ipv6Category::Special => "Special",
+            Ipv6Category::Multicast => "Multicast (ff00::/8)",
+            Ipv6Category::Provider => "Global Unicast (provider prefix)",
+            Ipv6Category::Nat64 => "NAT64 (IPv4-embedded, RFC 6052)",
+            Ipv6Category::Prefix => "Network prefix (/48, /56, /64)",
+            Ipv6Category::V4Mapped => "IPv4-mapped or translated (::ffff:0:0/96)",
+            Ipv6Category::Teredo => "Teredo (2001::/32)",
+            Ipv6Category::SixToFour => "6to4 (2002::/16)",
         }
     }
 }
 
 /// Advanced IPv6 compression engine
-pub struct Ipv6Compressor;
-
-impl Default for Ipv6Compressor {
-    fn default() -> Self {
-        Ipv6Compressor
-    }
+#[derive(Debug, Clone, Default)]
+pub struct Ipv6Compressor {
+    profile: EncodingProfile,
 }
 
 impl Ipv6Compressor {
     /// Creates a new IPv6 compressor
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Creates a compressor that also uses the network facts in `profile`
+    pub fn with_profile(profile: EncodingProfile) -> Self {
+        Ipv6Compressor { profile }
+    }
+
+    /// Returns the profile this compressor uses
+    pub fn profile(&self) -> &EncodingProfile {
+        &self.profile
     }
 
     /// Compress an IPv6 address with optional port
@@ -150,7 +419,18 @@ impl Ipv6Compressor {
         ip: Ipv6Addr,
         port: Option<u16>,
     ) -> Result<CompressedIpv6, FourWordError> {
+        // Profile prefixes are operator-specific, so they take precedence over
+        // the built-in decision table
+        if let Some((prefix_id, v4)) = self.profile.match_nat64(&ip) {
+            return Self::compress_nat64(prefix_id, v4, port);
+        }
+
         let category = Self::categorize_address(&ip);
+        if category == Ipv6Category::GlobalUnicast
+            && let Some((prefix_id, prefix)) = self.profile.match_provider(&ip)
+        {
+            return Self::compress_provider(prefix_id, prefix, ip, port);
+        }
 
         match category {
             Ipv6Category::Loopback => Self::compress_loopback(ip, port),
@@ -160,35 +440,83 @@ impl Ipv6Compressor {
             Ipv6Category::GlobalUnicast => Self::compress_global_unicast(ip, port),
             Ipv6Category::Unspecified => Self::compress_unspecified(ip, port),
             Ipv6Category::Special => Self::compress_special(ip, port),
+            Ipv6Category::V4Mapped => Self::compress_v4_mapped(ip, port),
+            Ipv6Category::Nat64 => Self::compress_nat64_well_known(ip, port),
+            Ipv6Category::Teredo => Self::compress_teredo(ip, port),
+            Ipv6Category::SixToFour => Self::compress_six_to_four(ip, port),
+            Ipv6Category::Multicast => Self::compress_multicast(ip, port),
+            // Never returned by categorize_address
+            Ipv6Category::Prefix | Ipv6Category::Provider => Self::compress_special(ip, port),
         }
     }
 
-    /// Decompress back to IPv6 address and port
+    /// Compresses the network prefix of `ip`, dropping the host bits.
+    ///
+    /// The category comes from the prefix length alone, never from the host
+    /// part, and the length must be one of [`PREFIX_LENGTHS`]. The last two
+    /// prefix bytes travel in the port slot, which a prefix has no use for,
+    /// so /48 and /56 prefixes fit in 6 words and /64 in 9.
+    pub fn compress_prefix(
+        &self,
+        ip: Ipv6Addr,
+        prefix_len: u8,
+    ) -> Result<CompressedIpv6, FourWordError> {
+        let code = PREFIX_LENGTHS
+            .iter()
+            .position(|&len| len == prefix_len)
+            .ok_or_else(|| {
+                FourWordError::out_of_range(
+                    "IPv6 prefix length",
+                    format!("one of {PREFIX_LENGTHS:?}"),
+                    format!("/{prefix_len}"),
+                )
+            })?;
+        let first = Ipv6Category::Prefix.extended_code().unwrap_or(1);
+        let len = usize::from(prefix_len / 8);
+        let bytes = ip.octets();
+        let (network, tail) = bytes[..len].split_at(len - 2);
+
+        let port = u16::from_be_bytes([tail[0], tail[1]]);
+        let mut compressed = CompressedIpv6::empty(Ipv6Category::Prefix, Some(port));
+        compressed.push(first + code as u8)?;
+        compressed.extend_from_slice(network)?;
+        compressed.original_bits = usize::from(prefix_len);
+        compressed.compressed_bits = 8 + network.len() * 8; // subtype + prefix bytes
+        Ok(compressed)
+    }
+
+    /// Decompress back to IPv6 address and port.
+    ///
+    /// Prefixes decompress to their network address with no port, since the
+    /// port slot holds prefix bytes.
     pub fn decompress(
         &self,
         compressed: &CompressedIpv6,
     ) -> Result<(Ipv6Addr, Option<u16>), FourWordError> {
         let ip = match compressed.category {
-            Ipv6Category::Loopback => Self::decompress_loopback(&compressed.compressed_data)?,
-            Ipv6Category::LinkLocal => Self::decompress_link_local(&compressed.compressed_data)?,
-            Ipv6Category::UniqueLocal => {
-                Self::decompress_unique_local(&compressed.compressed_data)?
-            }
-            Ipv6Category::Documentation => {
-                Self::decompress_documentation(&compressed.compressed_data)?
-            }
-            Ipv6Category::GlobalUnicast => {
-                Self::decompress_global_unicast(&compressed.compressed_data)?
-            }
-            Ipv6Category::Unspecified => Self::decompress_unspecified(&compressed.compressed_data)?,
-            Ipv6Category::Special => Self::decompress_special(&compressed.compressed_data)?,
+            Ipv6Category::Loopback => Self::decompress_loopback(compressed.as_bytes())?,
+            Ipv6Category::LinkLocal => Self::decompress_link_local(compressed.as_bytes())?,
+            Ipv6Category::UniqueLocal => Self::decompress_unique_local(compressed.as_bytes())?,
+            Ipv6Category::Documentation => Self::decompress_documentation(compressed.as_bytes())?,
+            Ipv6Category::GlobalUnicast => Self::decompress_global_unicast(compressed.as_bytes())?,
+            Ipv6Category::Unspecified => Self::decompress_unspecified(compressed.as_bytes())?,
+            Ipv6Category::Special => Self::decompress_special(compressed.as_bytes())?,
+            Ipv6Category::Nat64 => self.decompress_nat64(compressed.as_bytes())?,
+            Ipv6Category::Prefix => return Ok((Self::decompress_prefix(compressed)?, None)),
+            Ipv6Category::V4Mapped => Self::decompress_v4_mapped(compressed.as_bytes())?,
+            Ipv6Category::Teredo => Self::decompress_teredo(compressed.as_bytes())?,
+            Ipv6Category::SixToFour => Self::decompress_six_to_four(compressed.as_bytes())?,
+            Ipv6Category::Multicast => Self::decompress_multicast(compressed.as_bytes())?,
+            Ipv6Category::Provider => self.decompress_provider(compressed.as_bytes())?,
         };
 
         Ok((ip, compressed.port))
     }
 
     /// Categorize an IPv6 address for optimal compression
-    fn categorize_address(ip: &Ipv6Addr) -> Ipv6Category {
+    ///
+    /// Must agree with [`crate::categorization::decision_table`].
+    pub(crate) fn categorize_address(ip: &Ipv6Addr) -> Ipv6Category {
         let segments = ip.segments();
 
         // Check for loopback ::1
@@ -201,8 +529,19 @@ impl Ipv6Compressor {
             return Ipv6Category::Unspecified;
         }
 
-        // Check for link-local fe80::/10
-        if segments[0] & 0xFFC0 == 0xFE80 {
+        // Check for IPv4-mapped ::ffff:0:0/96 and IPv4-translated ::ffff:0:0:0/96
+        if segments[..4] == [0, 0, 0, 0] && matches!(segments[4..6], [0xFFFF, 0] | [0, 0xFFFF]) {
+            return Ipv6Category::V4Mapped;
+        }
+
+        // Check for the NAT64 well-known prefix 64:ff9b::/96
+        if segments[..6] == [0x64, 0xFF9B, 0, 0, 0, 0] {
+            return Ipv6Category::Nat64;
+        }
+
+        // Check for link-local fe80::/64 - the rest of fe80::/10 is rarely used
+        // and falls through to Special so it still round-trips exactly
+        if segments[0] == 0xFE80 && segments[1..4] == [0, 0, 0] {
             return Ipv6Category::LinkLocal;
         }
 
@@ -216,12 +555,27 @@ impl Ipv6Compressor {
             return Ipv6Category::Documentation;
         }
 
+        // Check for Teredo 2001::/32
+        if segments[0] == 0x2001 && segments[1] == 0 {
+            return Ipv6Category::Teredo;
+        }
+
+        // Check for 6to4 2002::/16
+        if segments[0] == 0x2002 {
+            return Ipv6Category::SixToFour;
+        }
+
         // Check for global unicast 2000::/3
         if segments[0] & 0xE000 == 0x2000 {
             return Ipv6Category::GlobalUnicast;
         }
 
-        // Everything else (multicast, etc.)
+        // Check for multicast ff00::/8
+        if ip.is_multicast() {
+            return Ipv6Category::Multicast;
+        }
+
+        // Everything else (reserved, IPv4-compatible, etc.)
         Ipv6Category::Special
     }
 
@@ -232,14 +586,10 @@ impl Ipv6Compressor {
     ) -> Result<CompressedIpv6, FourWordError> {
         // Loopback is just ::1, but we ensure 4 words minimum for IPv6
         // Add padding bytes to ensure we reach 4 words (56 bits total)
-        let padding = vec![0x00, 0x00, 0x01, 0x00, 0x00, 0x00]; // 48 bits of padding
-        Ok(CompressedIpv6 {
-            category: Ipv6Category::Loopback,
-            compressed_data: padding,
-            original_bits: 128,
-            compressed_bits: 48, // Ensure 4 words minimum
-            port,
-        })
+        let mut compressed = CompressedIpv6::empty(Ipv6Category::Loopback, port);
+        compressed.extend_from_slice(&[0x00, 0x00, 0x01, 0x00, 0x00, 0x00])?; // 48 bits of padding
+        compressed.compressed_bits = 48; // Ensure 4 words minimum
+        Ok(compressed)
     }
 
     /// Compress link-local address fe80::/10
@@ -253,31 +603,31 @@ impl Ipv6Compressor {
         // Optimize for common patterns
 
         // Check for simple patterns (fe80::1, fe80::2, etc.)
-        let non_zero_segments: Vec<(usize, u16)> = segments[4..8]
-            .iter()
-            .enumerate()
-            .filter(|&(_, &seg)| seg != 0)
-            .map(|(i, &seg)| (i + 4, seg))
-            .collect();
+        let non_zero_segments = (4..8).filter(|&pos| segments[pos] != 0);
+        let non_zero_count = non_zero_segments.clone().count();
 
-        let mut compressed = Vec::new();
-        let compressed_bits;
+        let mut compressed = CompressedIpv6::empty(Ipv6Category::LinkLocal, port);
 
-        if non_zero_segments.is_empty() {
+        if non_zero_count == 0 {
             // fe80:: - all zeros in interface ID
             // Use 6 bytes to match loopback and other simple patterns
-            compressed = vec![0, 0, 0, 0, 0, 0]; // Marker + padding for 48 bits
-            compressed_bits = 48; // 6 bytes
-        } else if non_zero_segments.len() == 1 && non_zero_segments[0].1 <= 255 {
+            compressed.extend_from_slice(&[0, 0, 0, 0, 0, 0])?; // Marker + padding for 48 bits
+            compressed.compressed_bits = 48; // 6 bytes
+        } else if let Some(pos) = non_zero_segments
+            .clone()
+            .next()
+            .filter(|&pos| non_zero_count == 1 && segments[pos] <= 255)
+        {
             // Single small value like fe80::1 - store position + value
-            let (pos, val) = non_zero_segments[0];
             // Use 6 bytes to match loopback and other simple patterns
-            compressed = vec![1, (pos - 4) as u8, val as u8, 0, 0, 0]; // Marker + data + padding
-            compressed_bits = 48; // 6 bytes
-        } else if segments[4] & 0x0200 == 0x0200 && segments[7] == 0 {
+            let val = segments[pos];
+            compressed.extend_from_slice(&[1, (pos - 4) as u8, val as u8, 0, 0, 0])?; // Marker + data + padding
+            compressed.compressed_bits = 48; // 6 bytes
+        } else if segments[4] & 0x0200 == 0x0200 && segments[6] <= 0xFF && segments[7] == 0 {
             // EUI-64 derived address - only use this pattern if segment[7] is 0
-            // since the reconstruction doesn't preserve segment[7]
-            compressed = vec![2]; // Marker for EUI-64
+            // and segment[6] fits a byte, since the reconstruction keeps only
+            // the low byte of segment[6] and none of segment[7]
+            compressed.push(2)?; // Marker for EUI-64
             let mac_derived = [
                 (segments[4] ^ 0x0200) as u8, // Remove universal/local bit
                 (segments[4] >> 8) as u8,
@@ -285,26 +635,20 @@ impl Ipv6Compressor {
                 (segments[5] >> 8) as u8,
                 (segments[6]) as u8,
             ];
-            compressed.extend_from_slice(&mac_derived);
-            compressed_bits = 48; // 6 bytes total
+            compressed.extend_from_slice(&mac_derived)?;
+            compressed.compressed_bits = 48; // 6 bytes total
         } else {
             // Complex pattern - store efficiently with RLE
-            compressed.push(3); // Marker for complex pattern
-            for &(pos, val) in &non_zero_segments {
-                compressed.push((pos - 4) as u8);
-                compressed.extend_from_slice(&val.to_be_bytes());
+            compressed.push(3)?; // Marker for complex pattern
+            for pos in non_zero_segments {
+                compressed.push((pos - 4) as u8)?;
+                compressed.extend_from_slice(&segments[pos].to_be_bytes())?;
             }
-            compressed.push(255); // End marker
-            compressed_bits = 3 + (compressed.len() * 8); // category + data
+            compressed.push(255)?; // End marker
+            compressed.compressed_bits = 3 + (compressed.as_bytes().len() * 8); // category + data
         }
 
-        Ok(CompressedIpv6 {
-            category: Ipv6Category::LinkLocal,
-            compressed_data: compressed,
-            original_bits: 128,
-            compressed_bits,
-            port,
-        })
+        Ok(compressed)
     }
 
     /// Compress unique local address fc00::/7
@@ -317,24 +661,17 @@ impl Ipv6Compressor {
         // Unique local: fcxx:xxxx:xxxx:xxxx:xxxx:xxxx:xxxx:xxxx
         // ULA compression is always lossy - only preserve the first 64 bits (4 segments)
         // Interface ID (segments 4-7) is always dropped as per design
-        let mut compressed = vec![];
+        let mut compressed = CompressedIpv6::empty(Ipv6Category::UniqueLocal, port);
 
         // Store only segments[0-3] as 8 bytes (prefix + global ID + subnet)
-        compressed.extend_from_slice(&segments[0].to_be_bytes()); // segments[0] (includes fc/fd prefix)
-        compressed.extend_from_slice(&segments[1].to_be_bytes()); // segments[1]
-        compressed.extend_from_slice(&segments[2].to_be_bytes()); // segments[2]
-        compressed.extend_from_slice(&segments[3].to_be_bytes()); // segments[3] (subnet)
+        for segment in &segments[..4] {
+            compressed.extend_from_slice(&segment.to_be_bytes())?;
+        }
 
         // ULA compression always uses only 64 bits (4 segments) + category
-        let compressed_bits = 3 + 64; // category + 4 segments (8 bytes)
+        compressed.compressed_bits = 3 + 64; // category + 4 segments (8 bytes)
 
-        Ok(CompressedIpv6 {
-            category: Ipv6Category::UniqueLocal,
-            compressed_data: compressed,
-            original_bits: 128,
-            compressed_bits,
-            port,
-        })
+        Ok(compressed)
     }
 
     /// Compress documentation address 2001:db8::/32
@@ -348,49 +685,48 @@ impl Ipv6Compressor {
         // For documentation addresses, we need to preserve interface ID segments
         // to avoid losing data like in 2001:db8:85a3::8a2e:370:7334
 
-        let mut compressed = Vec::new();
+        let mut compressed = CompressedIpv6::empty(Ipv6Category::Documentation, port);
 
         // Store segments 2-3 (routing prefix after 2001:db8)
-        compressed.extend_from_slice(&segments[2].to_be_bytes());
-        compressed.extend_from_slice(&segments[3].to_be_bytes());
+        compressed.extend_from_slice(&segments[2].to_be_bytes())?;
+        compressed.extend_from_slice(&segments[3].to_be_bytes())?;
 
         // Check for non-zero segments in the interface ID (segments 4-7)
-        let non_zero_interface: Vec<(usize, u16)> = segments[4..8]
-            .iter()
-            .enumerate()
-            .filter(|&(_, &seg)| seg != 0)
-            .map(|(i, &seg)| (i + 4, seg))
-            .collect();
+        let non_zero_interface = (4..8).filter(|&pos| segments[pos] != 0);
+        let non_zero_count = non_zero_interface.clone().count();
 
-        if non_zero_interface.is_empty() {
+        if non_zero_count == 0 {
             // No interface ID - use marker for empty interface
-            compressed.push(0); // Marker
-        } else if non_zero_interface.len() == 1 && non_zero_interface[0].1 <= 255 {
+            compressed.push(0)?; // Marker
+        } else if let Some(pos) = non_zero_interface
+            .clone()
+            .next()
+            .filter(|&pos| non_zero_count == 1 && segments[pos] <= 255)
+        {
             // Single small value in interface ID - compact encoding
-            let (pos, val) = non_zero_interface[0];
-            compressed.push(1); // Marker
-            compressed.push((pos - 4) as u8); // Position in interface ID
-            compressed.push(val as u8); // Value (single byte)
-        } else {
+            compressed.push(1)?; // Marker
+            compressed.push((pos - 4) as u8)?; // Position in interface ID
+            compressed.push(segments[pos] as u8)?; // Value (single byte)
+        } else if non_zero_count < 4 {
             // Complex interface ID - store all non-zero segments with position
-            compressed.push(2); // Marker for complex pattern
-            for &(pos, val) in &non_zero_interface {
-                compressed.push((pos - 4) as u8); // Position relative to interface ID start
-                compressed.extend_from_slice(&val.to_be_bytes()); // Full 16-bit value
+            compressed.push(2)?; // Marker for complex pattern
+            for pos in non_zero_interface {
+                compressed.push((pos - 4) as u8)?; // Position relative to interface ID start
+                compressed.extend_from_slice(&segments[pos].to_be_bytes())?; // Full 16-bit value
+            }
+            compressed.push(255)?; // End marker
+        } else {
+            // Fully populated interface ID - position/value pairs would be longer
+            compressed.push(3)?; // Marker for full interface ID
+            for segment in &segments[4..] {
+                compressed.extend_from_slice(&segment.to_be_bytes())?;
             }
-            compressed.push(255); // End marker
         }
 
         // Variable length depending on complexity
-        let compressed_bits = compressed.len() * 8;
+        compressed.compressed_bits = compressed.as_bytes().len() * 8;
 
-        Ok(CompressedIpv6 {
-            category: Ipv6Category::Documentation,
-            compressed_data: compressed,
-            original_bits: 128,
-            compressed_bits,
-            port,
-        })
+        Ok(compressed)
     }
 
     /// Compress global unicast address 2000::/3
@@ -402,31 +738,38 @@ impl Ipv6Compressor {
 
         // Global unicast is the most challenging to compress
         // We'll use statistical compression based on common patterns
+        let mut compressed = CompressedIpv6::empty(Ipv6Category::GlobalUnicast, port);
 
         // Check for common provider patterns
-        if let Some(compressed) = Self::try_provider_patterns(&segments) {
-            return Ok(CompressedIpv6 {
-                category: Ipv6Category::GlobalUnicast,
-                compressed_data: compressed,
-                original_bits: 128,
-                compressed_bits: 3 + 48, // category + pattern data
-                port,
-            });
+        if let Some(pattern_id) = Self::try_provider_patterns(&segments) {
+            // Store pattern ID + the 6 segments after the /32 prefix
+            compressed.push(pattern_id)?;
+            for segment in &segments[2..] {
+                compressed.extend_from_slice(&segment.to_be_bytes())?;
+            }
+            compressed.compressed_bits = 3 + 48; // category + pattern data
+            return Ok(compressed);
+        }
+
+        // ISATAP: the /64 prefix + the IPv4 from a ::0:5efe:a.b.c.d or
+        // ::200:5efe:a.b.c.d interface ID. Global unicast prefixes start with
+        // 001, so the top bit of the first byte is free to carry the 0x200
+        if let Some(universal) = Self::isatap_interface(&segments) {
+            let octets = ip.octets();
+            compressed.push(octets[0] | u8::from(universal) << 7)?;
+            compressed.extend_from_slice(&octets[1..8])?;
+            compressed.extend_from_slice(&octets[12..])?;
+            compressed.compressed_bits = 3 + 96; // category + prefix + IPv4
+            return Ok(compressed);
         }
 
         // Fallback: store all segments (full 128 bits)
-        let mut compressed = Vec::new();
         for segment in segments {
-            compressed.extend_from_slice(&segment.to_be_bytes());
+            compressed.extend_from_slice(&segment.to_be_bytes())?;
         }
+        compressed.compressed_bits = 3 + 128; // category + full address
 
-        Ok(CompressedIpv6 {
-            category: Ipv6Category::GlobalUnicast,
-            compressed_data: compressed,
-            original_bits: 128,
-            compressed_bits: 3 + 128, // category + full address
-            port,
-        })
+        Ok(compressed)
     }
 
     /// Compress unspecified address ::
@@ -436,70 +779,169 @@ impl Ipv6Compressor {
     ) -> Result<CompressedIpv6, FourWordError> {
         // Unspecified is all zeros, but we ensure 4 words minimum for IPv6
         // Add padding bytes to ensure we reach 4 words (56 bits total)
-        let padding = vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00]; // 48 bits of padding
-        Ok(CompressedIpv6 {
-            category: Ipv6Category::Unspecified,
-            compressed_data: padding,
-            original_bits: 128,
-            compressed_bits: 48, // Ensure 4 words minimum
-            port,
-        })
+        let mut compressed = CompressedIpv6::empty(Ipv6Category::Unspecified, port);
+        compressed.extend_from_slice(&[0x00; 6])?; // 48 bits of padding
+        compressed.compressed_bits = 48; // Ensure 4 words minimum
+        Ok(compressed)
     }
 
-    /// Compress special addresses (multicast, etc.)
+    /// Compress special addresses (reserved, IPv4-compatible, etc.)
     fn compress_special(ip: Ipv6Addr, port: Option<u16>) -> Result<CompressedIpv6, FourWordError> {
-        let segments = ip.segments();
-
         // For special addresses, store all segments but mark as special
-        let mut compressed = Vec::new();
-        for segment in segments {
-            compressed.extend_from_slice(&segment.to_be_bytes());
-        }
+        let mut compressed = CompressedIpv6::empty(Ipv6Category::Special, port);
+        compressed.extend_from_slice(&ip.octets())?;
+        compressed.compressed_bits = 3 + 128; // category + full address
+        Ok(compressed)
+    }
 
-        Ok(CompressedIpv6 {
-            category: Ipv6Category::Special,
-            compressed_data: compressed,
-            original_bits: 128,
-            compressed_bits: 3 + 128, // category + full address
-            port,
-        })
+    /// Compress a NAT64 address to its subtype, prefix index and embedded IPv4
+    fn compress_nat64(
+        prefix_id: u8,
+        v4: Ipv4Addr,
+        port: Option<u16>,
+    ) -> Result<CompressedIpv6, FourWordError> {
+        let mut compressed = CompressedIpv6::empty(Ipv6Category::Nat64, port);
+        compressed.push(Ipv6Category::Nat64.extended_code().unwrap_or(0))?;
+        compressed.push(prefix_id)?;
+        compressed.extend_from_slice(&v4.octets())?;
+        compressed.compressed_bits = 3 + 48; // category + subtype + prefix index + IPv4
+        Ok(compressed)
     }
 
-    /// Try to compress using common provider patterns
-    fn try_provider_patterns(segments: &[u16; 8]) -> Option<Vec<u8>> {
-        // Common patterns from major IPv6 providers
-        let patterns = [
-            // Google: 2001:4860::/32
-            ([0x2001, 0x4860], 32),
-            // Hurricane Electric: 2001:470::/32
-            ([0x2001, 0x0470], 32),
-            // Comcast: 2001:558::/32
-            ([0x2001, 0x0558], 32),
-        ];
+    /// Compress an address under the NAT64 well-known prefix to its subtype and IPv4
+    fn compress_nat64_well_known(
+        ip: Ipv6Addr,
+        port: Option<u16>,
+    ) -> Result<CompressedIpv6, FourWordError> {
+        let mut compressed = CompressedIpv6::empty(Ipv6Category::Nat64, port);
+        compressed.push(NAT64_WELL_KNOWN_CODE)?;
+        compressed.extend_from_slice(&ip.octets()[12..])?;
+        compressed.compressed_bits = 3 + 40; // category + subtype + IPv4
+        Ok(compressed)
+    }
 
-        for (pattern, prefix_bits) in patterns {
-            if segments[0] == pattern[0] && segments[1] == pattern[1] {
-                // Store pattern ID + remaining bits
-                let pattern_id = match pattern {
-                    [0x2001, 0x4860] => 0u8,
-                    [0x2001, 0x0470] => 1u8,
-                    [0x2001, 0x0558] => 2u8,
-                    _ => continue,
-                };
+    /// Compress a global unicast address under a provider prefix to its subtype,
+    /// prefix index, a byte holding both field lengths, the subnet bytes after
+    /// the prefix without trailing zeros and the interface ID without leading zeros
+    fn compress_provider(
+        prefix_id: u8,
+        prefix: &ProviderPrefix,
+        ip: Ipv6Addr,
+        port: Option<u16>,
+    ) -> Result<CompressedIpv6, FourWordError> {
+        let octets = ip.octets();
+        let start = prefix.byte_len();
+        let subnet_end = octets[start..8]
+            .iter()
+            .rposition(|&byte| byte != 0)
+            .map_or(start, |i| start + i + 1);
+        let interface = octets[8..]
+            .iter()
+            .position(|&byte| byte != 0)
+            .map_or(16, |i| i + 8);
+        let lengths = ((subnet_end - start) << 4 | (16 - interface)) as u8;
+        let mut compressed = CompressedIpv6::empty(Ipv6Category::Provider, port);
+        compressed.push(Ipv6Category::Provider.extended_code().unwrap_or(13))?;
+        compressed.extend_from_slice(&[prefix_id, lengths])?;
+        compressed.extend_from_slice(&octets[start..subnet_end])?;
+        compressed.extend_from_slice(&octets[interface..])?;
+        compressed.compressed_bits = 8 * compressed.as_bytes().len(); // subtype + fields
+        Ok(compressed)
+    }
+
+    /// Compress an IPv4-mapped or IPv4-translated address to its subtype and IPv4
+    fn compress_v4_mapped(
+        ip: Ipv6Addr,
+        port: Option<u16>,
+    ) -> Result<CompressedIpv6, FourWordError> {
+        let first = Ipv6Category::V4Mapped.extended_code().unwrap_or(4);
+        let octets = ip.octets();
+        // ::ffff:a.b.c.d has 0xffff in bytes 10-11; ::ffff:0:a.b.c.d in bytes 8-9
+        let translated = octets[8..10] == [0xFF, 0xFF];
+        let mut compressed = CompressedIpv6::empty(Ipv6Category::V4Mapped, port);
+        compressed.push(first + u8::from(translated))?;
+        compressed.extend_from_slice(&octets[12..])?;
+        compressed.compressed_bits = 3 + 40; // category + subtype + IPv4
+        Ok(compressed)
+    }
 
-                let mut compressed = vec![pattern_id];
+    /// Compress a Teredo address to its subtype and the 96 bits after 2001::/32:
+    /// server IPv4, flags, obfuscated client port and obfuscated client IPv4
+    fn compress_teredo(ip: Ipv6Addr, port: Option<u16>) -> Result<CompressedIpv6, FourWordError> {
+        let mut compressed = CompressedIpv6::empty(Ipv6Category::Teredo, port);
+        compressed.push(Ipv6Category::Teredo.extended_code().unwrap_or(6))?;
+        compressed.extend_from_slice(&ip.octets()[4..])?;
+        compressed.compressed_bits = 3 + 104; // category + subtype + Teredo fields
+        Ok(compressed)
+    }
 
-                // Store the remaining segments after the pattern
-                let remaining_segments = 8 - (prefix_bits / 16);
-                for segment in segments.iter().skip(8 - remaining_segments) {
-                    compressed.extend_from_slice(&segment.to_be_bytes());
-                }
+    /// Compress a 6to4 address to its subtype, embedded IPv4, subnet ID and
+    /// interface ID, keeping only the last two bytes of interface IDs below 2^16
+    fn compress_six_to_four(
+        ip: Ipv6Addr,
+        port: Option<u16>,
+    ) -> Result<CompressedIpv6, FourWordError> {
+        let first = Ipv6Category::SixToFour.extended_code().unwrap_or(7);
+        let octets = ip.octets();
+        let short = octets[8..14] == [0; 6];
+        let mut compressed = CompressedIpv6::empty(Ipv6Category::SixToFour, port);
+        compressed.push(first + u8::from(short))?;
+        compressed.extend_from_slice(&octets[2..8])?;
+        compressed.extend_from_slice(if short { &octets[14..] } else { &octets[8..] })?;
+        compressed.compressed_bits = 8 * compressed.as_bytes().len(); // subtype + fields
+        Ok(compressed)
+    }
 
-                return Some(compressed);
-            }
+    /// Compress a multicast address to its subtype, flags/scope byte and group:
+    /// a [`WELL_KNOWN_GROUPS`] index, the low 24 bits of a solicited-node
+    /// group, or the group ID without its leading zero bytes
+    fn compress_multicast(
+        ip: Ipv6Addr,
+        port: Option<u16>,
+    ) -> Result<CompressedIpv6, FourWordError> {
+        let first = Ipv6Category::Multicast.extended_code().unwrap_or(10);
+        let octets = ip.octets();
+        let mut compressed = CompressedIpv6::empty(Ipv6Category::Multicast, port);
+        let group = multicast::group_id(&ip).and_then(multicast::group_index);
+        if let Some(index) = group {
+            compressed.extend_from_slice(&[first, octets[1], index])?;
+        } else if octets[2..13] == SOLICITED_NODE[2..13] {
+            compressed.extend_from_slice(&[first + 1, octets[1]])?;
+            compressed.extend_from_slice(&octets[13..])?;
+        } else {
+            let start = octets[2..]
+                .iter()
+                .position(|&byte| byte != 0)
+                .map_or(16, |i| i + 2);
+            compressed.extend_from_slice(&[first + 2, octets[1]])?;
+            compressed.extend_from_slice(&octets[start..])?;
         }
+        compressed.compressed_bits = 8 * compressed.as_bytes().len(); // subtype + fields
+        Ok(compressed)
+    }
+
+    /// Returns whether the universal/local bit is set if the interface ID is
+    /// an ISATAP one (RFC 5214)
+    fn isatap_interface(segments: &[u16; 8]) -> Option<bool> {
+        match segments[4..6] {
+            [0, 0x5EFE] => Some(false),
+            [0x0200, 0x5EFE] => Some(true),
+            _ => None,
+        }
+    }
 
-        None
+    /// Returns the pattern ID if the address belongs to a common provider /32
+    fn try_provider_patterns(segments: &[u16; 8]) -> Option<u8> {
+        // Common patterns from major IPv6 providers
+        match [segments[0], segments[1]] {
+            // Google: 2001:4860::/32
+            [0x2001, 0x4860] => Some(0),
+            // Hurricane Electric: 2001:470::/32
+            [0x2001, 0x0470] => Some(1),
+            // Comcast: 2001:558::/32
+            [0x2001, 0x0558] => Some(2),
+            _ => None,
+        }
     }
 
     // Decompression methods (implementations would mirror compression logic)
@@ -509,7 +951,7 @@ impl Ipv6Compressor {
 
     fn decompress_link_local(data: &[u8]) -> Result<Ipv6Addr, FourWordError> {
         if data.is_empty() {
-            return Err(FourWordError::InvalidInput(
+            return Err(FourWordError::DecompressionError(
                 "Empty link-local data".to_string(),
             ));
         }
@@ -561,7 +1003,7 @@ impl Ipv6Compressor {
                 }
             }
             _ => {
-                return Err(FourWordError::InvalidInput(
+                return Err(FourWordError::DecompressionError(
                     "Invalid link-local pattern".to_string(),
                 ));
             }
@@ -598,7 +1040,7 @@ impl Ipv6Compressor {
             ];
             Ok(Ipv6Addr::from(segments))
         } else {
-            Err(FourWordError::InvalidInput(format!(
+            Err(FourWordError::DecompressionError(format!(
                 "Invalid unique local data length: {} (expected 8 or 16 bytes)",
                 data.len()
             )))
@@ -607,7 +1049,7 @@ impl Ipv6Compressor {
 
     fn decompress_documentation(data: &[u8]) -> Result<Ipv6Addr, FourWordError> {
         if data.len() < 5 {
-            return Err(FourWordError::InvalidInput(
+            return Err(FourWordError::DecompressionError(
                 "Documentation data too short - expected at least 5 bytes".to_string(),
             ));
         }
@@ -657,8 +1099,19 @@ impl Ipv6Compressor {
                     }
                 }
             }
+            3 => {
+                // Full interface ID - segments 4-7 stored in order
+                let interface = data.get(5..13).ok_or_else(|| {
+                    FourWordError::DecompressionError(
+                        "Documentation interface ID truncated".to_string(),
+                    )
+                })?;
+                for (segment, bytes) in segments[4..].iter_mut().zip(interface.chunks_exact(2)) {
+                    *segment = u16::from_be_bytes([bytes[0], bytes[1]]);
+                }
+            }
             _ => {
-                return Err(FourWordError::InvalidInput(format!(
+                return Err(FourWordError::DecompressionError(format!(
                     "Invalid documentation marker: {marker}"
                 )));
             }
@@ -675,11 +1128,23 @@ impl Ipv6Compressor {
                 segments[i] = ((data[i * 2] as u16) << 8) | (data[i * 2 + 1] as u16);
             }
             Ok(Ipv6Addr::from(segments))
+        } else if data.len() == 12 {
+            // ISATAP case: /64 prefix with the universal/local flag in the top bit + IPv4
+            let mut bytes = [0u8; 16];
+            bytes[0] = data[0] & 0x7F;
+            bytes[1..8].copy_from_slice(&data[1..8]);
+            bytes[8..12].copy_from_slice(if data[0] & 0x80 != 0 {
+                &[0x02, 0x00, 0x5E, 0xFE]
+            } else {
+                &[0x00, 0x00, 0x5E, 0xFE]
+            });
+            bytes[12..].copy_from_slice(&data[8..]);
+            Ok(Ipv6Addr::from(bytes))
         } else if data.len() == 13 {
             // Provider pattern case: 1 byte pattern ID + 12 bytes (6 segments)
             let pattern_id = data[0];
             let mut segments = [0u16; 8];
-            
+
             // Set the prefix based on pattern ID
             match pattern_id {
                 0 => {
@@ -698,23 +1163,26 @@ impl Ipv6Compressor {
                     segments[1] = 0x0558;
                 }
                 _ => {
-                    return Err(FourWordError::InvalidInput(
-                        format!("Invalid provider pattern ID: {}", pattern_id)
-                    ))
+                    return Err(FourWordError::DecompressionError(format!(
+                        "Invalid provider pattern ID: {}",
+                        pattern_id
+                    )));
                 }
             }
-            
+
             // Decode the remaining 6 segments from the 12 bytes
             for i in 0..6 {
                 let byte_offset = 1 + (i * 2); // Skip pattern ID byte
-                segments[i + 2] = ((data[byte_offset] as u16) << 8) | (data[byte_offset + 1] as u16);
+                segments[i + 2] =
+                    ((data[byte_offset] as u16) << 8) | (data[byte_offset + 1] as u16);
             }
-            
+
             Ok(Ipv6Addr::from(segments))
         } else {
-            Err(FourWordError::InvalidInput(
-                format!("Invalid global unicast data length: {} bytes", data.len())
-            ))
+            Err(FourWordError::DecompressionError(format!(
+                "Invalid global unicast data length: {} bytes",
+                data.len()
+            )))
         }
     }
 
@@ -722,6 +1190,133 @@ impl Ipv6Compressor {
         Ok(Ipv6Addr::UNSPECIFIED)
     }
 
+    fn decompress_nat64(&self, data: &[u8]) -> Result<Ipv6Addr, FourWordError> {
+        if let &[NAT64_WELL_KNOWN_CODE, a, b, c, d] = data {
+            return Ok(Nat64Prefix::WELL_KNOWN.embed(Ipv4Addr::new(a, b, c, d)));
+        }
+        let [_subtype, prefix_id, a, b, c, d] = data else {
+            return Err(FourWordError::DecompressionError(
+                "Invalid NAT64 address data".to_string(),
+            ));
+        };
+        let prefix = self.profile.nat64_prefix(*prefix_id)?;
+        Ok(prefix.embed(Ipv4Addr::new(*a, *b, *c, *d)))
+    }
+
+    fn decompress_provider(&self, data: &[u8]) -> Result<Ipv6Addr, FourWordError> {
+        let invalid = || {
+            FourWordError::DecompressionError("Invalid provider prefix address data".to_string())
+        };
+        let [_subtype, prefix_id, lengths, fields @ ..] = data else {
+            return Err(invalid());
+        };
+        let prefix = self.profile.provider_prefix(*prefix_id)?;
+        let start = prefix.byte_len();
+        let (subnet_len, interface_len) = (usize::from(lengths >> 4), usize::from(lengths & 0x0F));
+        if start + subnet_len > 8 || interface_len > 8 || fields.len() != subnet_len + interface_len
+        {
+            return Err(invalid());
+        }
+        let (subnet, interface) = fields.split_at(subnet_len);
+        let mut bytes = [0u8; 16];
+        bytes[..start].copy_from_slice(&prefix.prefix().octets()[..start]);
+        bytes[start..start + subnet_len].copy_from_slice(subnet);
+        bytes[16 - interface_len..].copy_from_slice(interface);
+        let ip = Ipv6Addr::from(bytes);
+        if !prefix.contains(&ip) {
+            return Err(invalid());
+        }
+        Ok(ip)
+    }
+
+    fn decompress_prefix(compressed: &CompressedIpv6) -> Result<Ipv6Addr, FourWordError> {
+        let invalid = || FourWordError::DecompressionError("Invalid IPv6 prefix data".to_string());
+        let prefix_len = compressed.prefix_len().ok_or_else(invalid)?;
+        let network = &compressed.as_bytes()[1..];
+        let len = usize::from(prefix_len / 8);
+        if network.len() + 2 != len {
+            return Err(invalid());
+        }
+        let mut bytes = [0u8; 16];
+        bytes[..len - 2].copy_from_slice(network);
+        bytes[len - 2..len].copy_from_slice(&compressed.port.ok_or_else(invalid)?.to_be_bytes());
+        Ok(Ipv6Addr::from(bytes))
+    }
+
+    fn decompress_v4_mapped(data: &[u8]) -> Result<Ipv6Addr, FourWordError> {
+        let first = Ipv6Category::V4Mapped.extended_code().unwrap_or(4);
+        let &[code, a, b, c, d] = data else {
+            return Err(FourWordError::DecompressionError(
+                "Invalid IPv4-mapped address data".to_string(),
+            ));
+        };
+        let v4 = Ipv4Addr::new(a, b, c, d);
+        if code == first {
+            Ok(v4.to_ipv6_mapped())
+        } else {
+            let [high, low] = [u16::from_be_bytes([a, b]), u16::from_be_bytes([c, d])];
+            Ok(Ipv6Addr::new(0, 0, 0, 0, 0xFFFF, 0, high, low))
+        }
+    }
+
+    fn decompress_teredo(data: &[u8]) -> Result<Ipv6Addr, FourWordError> {
+        if data.len() != 13 {
+            return Err(FourWordError::DecompressionError(
+                "Invalid Teredo address data".to_string(),
+            ));
+        }
+        let mut bytes = [0u8; 16];
+        bytes[..2].copy_from_slice(&[0x20, 0x01]);
+        bytes[4..].copy_from_slice(&data[1..]);
+        Ok(Ipv6Addr::from(bytes))
+    }
+
+    fn decompress_six_to_four(data: &[u8]) -> Result<Ipv6Addr, FourWordError> {
+        let first = Ipv6Category::SixToFour.extended_code().unwrap_or(7);
+        let (code, fields) = data.split_first().ok_or_else(|| {
+            FourWordError::DecompressionError("Invalid 6to4 address data".to_string())
+        })?;
+        let interface_len = if *code == first { 8 } else { 2 };
+        if fields.len() != 6 + interface_len {
+            return Err(FourWordError::DecompressionError(
+                "Invalid 6to4 address data".to_string(),
+            ));
+        }
+        let mut bytes = [0u8; 16];
+        bytes[..2].copy_from_slice(&[0x20, 0x02]);
+        bytes[2..8].copy_from_slice(&fields[..6]);
+        bytes[16 - interface_len..].copy_from_slice(&fields[6..]);
+        Ok(Ipv6Addr::from(bytes))
+    }
+
+    fn decompress_multicast(data: &[u8]) -> Result<Ipv6Addr, FourWordError> {
+        let first = Ipv6Category::Multicast.extended_code().unwrap_or(10);
+        let invalid =
+            || FourWordError::DecompressionError("Invalid multicast address data".to_string());
+        let [code, flags_scope, group @ ..] = data else {
+            return Err(invalid());
+        };
+        let mut bytes = [0u8; 16];
+        bytes[..2].copy_from_slice(&[0xFF, *flags_scope]);
+        match (code.checked_sub(first), group) {
+            (Some(0), &[index]) => {
+                let group = WELL_KNOWN_GROUPS.get(usize::from(index)).ok_or_else(|| {
+                    FourWordError::DecompressionError(format!("Unknown multicast group #{index}"))
+                })?;
+                bytes[12..].copy_from_slice(&group.id.to_be_bytes());
+            }
+            (Some(1), &[a, b, c]) => {
+                bytes[11..13].copy_from_slice(&SOLICITED_NODE[11..13]);
+                bytes[13..].copy_from_slice(&[a, b, c]);
+            }
+            (Some(2), group) if group.len() <= 14 => {
+                bytes[16 - group.len()..].copy_from_slice(group);
+            }
+            _ => return Err(invalid()),
+        }
+        Ok(Ipv6Addr::from(bytes))
+    }
+
     fn decompress_special(data: &[u8]) -> Result<Ipv6Addr, FourWordError> {
         if data.len() >= 16 {
             let mut segments = [0u16; 8];
@@ -730,7 +1325,7 @@ impl Ipv6Compressor {
             }
             Ok(Ipv6Addr::from(segments))
         } else {
-            Err(FourWordError::InvalidInput(
+            Err(FourWordError::DecompressionError(
                 "Invalid special address data".to_string(),
             ))
         }
@@ -742,6 +1337,226 @@ mod tests {
     use super::*;
     use std::str::FromStr;
 
+    #[cfg(any(feature = "arbitrary", feature = "proptest"))]
+    #[test]
+    fn test_sample_address_lands_in_category() {
+        for category in Ipv6Category::ALL {
+            for seed in 0..32u8 {
+                let random = [seed.wrapping_mul(37); 16];
+                let ip = category.sample_address(random);
+                let expected = match category {
+                    Ipv6Category::GlobalUnicast | Ipv6Category::Prefix | Ipv6Category::Provider => {
+                        continue;
+                    }
+                    other => other,
+                };
+                assert_eq!(Ipv6Compressor::categorize_address(&ip), expected, "{ip}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_nat64_profile_compression() {
+        let mut profile = EncodingProfile::new();
+        profile
+            .add_nat64_prefix("2001:db8:122::/48".parse().unwrap())
+            .unwrap();
+        let compressor = Ipv6Compressor::with_profile(profile);
+
+        let ip = Ipv6Addr::from_str("2001:db8:122:c000:2:2100::").unwrap();
+        let compressed = compressor.compress(ip, Some(80)).unwrap();
+        assert_eq!(compressed.category, Ipv6Category::Nat64);
+        assert_eq!(compressed.as_bytes(), [0, 0, 192, 0, 2, 33]);
+        assert_eq!(compressor.decompress(&compressed).unwrap(), (ip, Some(80)));
+
+        // Without the prefix the address is plain documentation space
+        assert_eq!(
+            Ipv6Compressor::new().compress(ip, None).unwrap().category,
+            Ipv6Category::Documentation
+        );
+        assert!(Ipv6Compressor::new().decompress(&compressed).is_err());
+    }
+
+    #[test]
+    fn test_nat64_well_known_prefix() {
+        let compressor = Ipv6Compressor::new();
+        let ip = Ipv6Addr::from_str("64:ff9b::192.0.2.33").unwrap();
+        let compressed = compressor.compress(ip, Some(443)).unwrap();
+        assert_eq!(compressed.category, Ipv6Category::Nat64);
+        assert_eq!(
+            compressed.as_bytes(),
+            [NAT64_WELL_KNOWN_CODE, 192, 0, 2, 33]
+        );
+        assert_eq!(compressor.decompress(&compressed).unwrap(), (ip, Some(443)));
+
+        // Only the /96 is built in; the RFC 8215 local-use /48 needs a profile
+        let local = Ipv6Addr::from_str("64:ff9b:1::c000:221").unwrap();
+        assert_eq!(
+            compressor.compress(local, None).unwrap().category,
+            Ipv6Category::Special
+        );
+    }
+
+    #[test]
+    fn test_provider_prefix_compression() {
+        let mut profile = EncodingProfile::new();
+        profile
+            .add_provider_prefix("2a00:1450::/32".parse().unwrap())
+            .unwrap();
+        profile
+            .add_provider_prefix("2a03:2880::/29".parse().unwrap())
+            .unwrap();
+        let compressor = Ipv6Compressor::with_profile(profile);
+
+        for (address, bytes) in [
+            (
+                "2a00:1450:4001:81c::200e",
+                &[13, 0, 0x42, 0x40, 0x01, 0x08, 0x1c, 0x20, 0x0e][..],
+            ),
+            ("2a00:1450:4001::1", &[13, 0, 0x21, 0x40, 0x01, 0x01]),
+            ("2a00:1450::", &[13, 0, 0x00]),
+            (
+                "2a03:2881:1:2::1",
+                &[13, 1, 0x51, 0x81, 0, 0x01, 0, 0x02, 0x01],
+            ),
+        ] {
+            let ip = Ipv6Addr::from_str(address).unwrap();
+            let compressed = compressor.compress(ip, Some(443)).unwrap();
+            assert_eq!(compressed.category, Ipv6Category::Provider, "{address}");
+            assert_eq!(compressed.as_bytes(), bytes, "{address}");
+            assert_eq!(
+                compressor.decompress(&compressed).unwrap(),
+                (ip, Some(443)),
+                "{address}"
+            );
+            assert!(Ipv6Compressor::new().decompress(&compressed).is_err());
+        }
+
+        // Other categories are unaffected by provider prefixes
+        let other = Ipv6Addr::from_str("2606:4700::1111").unwrap();
+        assert_eq!(
+            compressor.compress(other, None).unwrap().category,
+            Ipv6Category::GlobalUnicast
+        );
+    }
+
+    #[test]
+    fn test_prefix_compression() {
+        let compressor = Ipv6Compressor::new();
+        let host = Ipv6Addr::from_str("2001:db8:1234:5678:9abc::1").unwrap();
+        for (prefix_len, network, bytes) in [
+            (48, "2001:db8:1234::", 5),
+            (56, "2001:db8:1234:5600::", 6),
+            (64, "2001:db8:1234:5678::", 7),
+        ] {
+            let compressed = compressor.compress_prefix(host, prefix_len).unwrap();
+            assert_eq!(compressed.category, Ipv6Category::Prefix);
+            assert_eq!(compressed.prefix_len(), Some(prefix_len));
+            assert_eq!(compressed.as_bytes().len(), bytes);
+
+            // Host bits are dropped, whatever category the host would have had
+            let network = Ipv6Addr::from_str(network).unwrap();
+            assert_eq!(compressor.decompress(&compressed).unwrap(), (network, None));
+        }
+
+        assert!(compressor.compress_prefix(host, 32).is_err());
+        assert!(compressor.compress_prefix(host, 128).is_err());
+        let ula = compressor.compress(host, Some(80)).unwrap();
+        assert_eq!(ula.prefix_len(), None);
+    }
+
+    #[test]
+    fn test_v4_mapped_compression() {
+        let compressor = Ipv6Compressor::new();
+        for (address, code) in [("::ffff:203.0.113.7", 4), ("::ffff:0:203.0.113.7", 5)] {
+            let ip = Ipv6Addr::from_str(address).unwrap();
+            let compressed = compressor.compress(ip, Some(443)).unwrap();
+            assert_eq!(compressed.category, Ipv6Category::V4Mapped, "{address}");
+            assert_eq!(compressed.as_bytes(), [code, 203, 0, 113, 7]);
+            assert_eq!(compressor.decompress(&compressed).unwrap(), (ip, Some(443)));
+        }
+
+        // IPv4-compatible ::a.b.c.d is deprecated and not downgraded
+        let compatible = Ipv6Addr::from_str("::203.0.113.7").unwrap();
+        assert_eq!(
+            compressor.compress(compatible, None).unwrap().category,
+            Ipv6Category::Special
+        );
+    }
+
+    #[test]
+    fn test_transition_compression() {
+        let compressor = Ipv6Compressor::new();
+        for (address, category, bytes) in [
+            // RFC 4380: server 65.54.227.120, client 192.0.2.45:40000
+            (
+                "2001:0:4136:e378:8000:63bf:3fff:fdd2",
+                Ipv6Category::Teredo,
+                13,
+            ),
+            ("2002:c000:204::1", Ipv6Category::SixToFour, 9),
+            (
+                "2002:c000:204:1:a00:27ff:fe12:3456",
+                Ipv6Category::SixToFour,
+                15,
+            ),
+            (
+                "2600:1:2:3::5efe:192.0.2.1",
+                Ipv6Category::GlobalUnicast,
+                12,
+            ),
+            (
+                "2600:1:2:3:200:5efe:192.0.2.1",
+                Ipv6Category::GlobalUnicast,
+                12,
+            ),
+        ] {
+            let ip = Ipv6Addr::from_str(address).unwrap();
+            let compressed = compressor.compress(ip, Some(3544)).unwrap();
+            assert_eq!(compressed.category, category, "{address}");
+            assert_eq!(compressed.as_bytes().len(), bytes, "{address}");
+            assert_eq!(
+                compressor.decompress(&compressed).unwrap(),
+                (ip, Some(3544)),
+                "{address}"
+            );
+        }
+
+        let teredo = Ipv6Addr::from_str("2001:0:4136:e378:8000:63bf:3fff:fdd2").unwrap();
+        let compressed = compressor.compress(teredo, None).unwrap();
+        assert_eq!(compressed.as_bytes()[..5], [6, 0x41, 0x36, 0xe3, 0x78]);
+    }
+
+    #[test]
+    fn test_multicast_compression() {
+        let compressor = Ipv6Compressor::new();
+        // Unicast-prefix-based groups embed a prefix, so little can be trimmed
+        let ssm = Ipv6Addr::from_str("ff3e:30:2001:db8::1").unwrap();
+        let ssm_bytes = [&[12, 0x3e], &ssm.octets()[3..]].concat();
+        for (address, bytes) in [
+            ("ff02::1", &[10, 0x02, 0][..]),
+            ("ff05::1:3", &[10, 0x05, 16]),
+            ("ff02::1:ff12:3456", &[11, 0x02, 0x12, 0x34, 0x56]),
+            ("ff0e::1234", &[12, 0x0e, 0x12, 0x34]),
+            ("ff15::", &[12, 0x15]),
+            ("ff3e:30:2001:db8::1", &ssm_bytes),
+        ] {
+            let ip = Ipv6Addr::from_str(address).unwrap();
+            let compressed = compressor.compress(ip, Some(5353)).unwrap();
+            assert_eq!(compressed.category, Ipv6Category::Multicast, "{address}");
+            assert_eq!(compressed.as_bytes(), bytes, "{address}");
+            assert_eq!(
+                compressor.decompress(&compressed).unwrap(),
+                (ip, Some(5353)),
+                "{address}"
+            );
+        }
+
+        let unknown =
+            CompressedIpv6::from_bytes(&[10, 0x02, 255], Ipv6Category::Multicast).unwrap();
+        assert!(compressor.decompress(&unknown).is_err());
+    }
+
     #[test]
     fn test_loopback_compression() {
         let compressor = Ipv6Compressor::new();
@@ -749,7 +1564,7 @@ mod tests {
         let compressed = compressor.compress(ip, Some(443)).unwrap();
 
         assert_eq!(compressed.category, Ipv6Category::Loopback);
-        assert_eq!(compressed.compressed_data.len(), 6); // Padded to 6 bytes
+        assert_eq!(compressed.as_bytes().len(), 6); // Padded to 6 bytes
         // With category byte + 6 bytes data = 56 bits total = 4 words
         assert!(compressed.recommended_word_count() >= 4); // IPv6 minimum 4 words
 
@@ -765,7 +1580,7 @@ mod tests {
         let compressed = compressor.compress(ip, None).unwrap();
 
         assert_eq!(compressed.category, Ipv6Category::Unspecified);
-        assert_eq!(compressed.compressed_data.len(), 6); // Padded to 6 bytes
+        assert_eq!(compressed.as_bytes().len(), 6); // Padded to 6 bytes
         assert!(compressed.recommended_word_count() >= 4); // IPv6 minimum 4 words
     }
 
@@ -780,6 +1595,17 @@ mod tests {
         assert!(compressed.compression_ratio() > 0.3); // Adjusted for padding
     }
 
+    #[test]
+    fn test_link_local_eui64_pattern_is_lossless() {
+        let compressor = Ipv6Compressor::new();
+        for text in ["fe80::2ff:fe00:12:0", "fe80::53df:0:91d3:0"] {
+            let ip = Ipv6Addr::from_str(text).unwrap();
+            let compressed = compressor.compress(ip, None).unwrap();
+            let (decompressed, _) = compressor.decompress(&compressed).unwrap();
+            assert_eq!(decompressed, ip, "{text}");
+        }
+    }
+
     #[test]
     fn test_documentation_compression() {
         let compressor = Ipv6Compressor::new();
@@ -816,4 +1642,21 @@ mod tests {
             assert!(ratio > 0.0, "{name} should have some compression");
         }
     }
+
+    #[test]
+    fn test_payload_fits_inline() {
+        let compressor = Ipv6Compressor::new();
+        // Dense documentation addresses keep the whole interface ID
+        let ip = Ipv6Addr::from_str("2001:db8:1:2:3:4:5:6").unwrap();
+        let compressed = compressor.compress(ip, Some(443)).unwrap();
+        assert_eq!(compressed.as_bytes().len(), 13);
+        assert_eq!(compressor.decompress(&compressed).unwrap(), (ip, Some(443)));
+
+        let ip = Ipv6Addr::from_str("2606:4700:1:2:3:4:5:6").unwrap();
+        let compressed = compressor.compress(ip, None).unwrap();
+        assert!(compressed.as_bytes().len() <= MAX_COMPRESSED_BYTES);
+
+        let oversize = [0u8; MAX_COMPRESSED_BYTES + 1];
+        assert!(CompressedIpv6::from_bytes(&oversize, Ipv6Category::GlobalUnicast).is_err());
+    }
 }
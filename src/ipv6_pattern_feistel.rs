@@ -16,6 +16,8 @@
 //! - No cryptographic dependencies
 //! - Deterministic behavior
 
+#![forbid(unsafe_code)]
+
 use crate::{FourWordError, Result};
 
 /// IPv6 pattern IDs for Feistel encoding
@@ -81,7 +83,7 @@ impl IPv6PatternFeistel {
     /// returns mixed data with pattern information embedded.
     pub fn encode(pattern_id: IPv6PatternId, data: u64, data_bits: usize) -> Result<u64> {
         if data_bits > 61 {
-            return Err(FourWordError::InvalidInput(
+            return Err(FourWordError::CompressionError(
                 "Data too large for Feistel encoding (max 61 bits)".to_string(),
             ));
         }
@@ -101,7 +103,7 @@ impl IPv6PatternFeistel {
     /// Takes mixed data and returns the original pattern ID and data.
     pub fn decode(mixed_data: u64, original_data_bits: usize) -> Result<(IPv6PatternId, u64)> {
         if original_data_bits > 61 {
-            return Err(FourWordError::InvalidInput(
+            return Err(FourWordError::DecompressionError(
                 "Data too large for Feistel decoding (max 61 bits)".to_string(),
             ));
         }
@@ -185,7 +187,7 @@ impl IPv6PatternFeistel {
 
         // Reserve the top 3 bits for pattern ID
         if data_bits > 61 {
-            return Err(FourWordError::InvalidInput(
+            return Err(FourWordError::CompressionError(
                 "Data too large for pattern encoding (max 61 bits)".to_string(),
             ));
         }
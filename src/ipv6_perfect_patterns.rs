@@ -4,6 +4,8 @@
 //! perfect compression in 4-5 words. It leverages real-world IPv6 allocation patterns
 //! from BGP data and provider-specific address structures.
 
+#![forbid(unsafe_code)]
+
 use crate::error::FourWordError;
 use std::net::Ipv6Addr;
 
@@ -0,0 +1,483 @@
+//! Keyed phrases that only holders of a shared secret can read.
+//!
+//! A plain phrase is a public encoding: anyone with the wordlist can turn it
+//! back into the address. [`KeyedEncoder`] runs the phrase's bits through a
+//! keyed permutation before picking the words, so a rendezvous phrase can be
+//! posted on a public channel while only peers holding the same [`PhraseKey`]
+//! recover the exact address.
+//!
+//! The permutation is an eight-round Feistel network over all of the phrase's
+//! bits with HMAC-SHA256 (the `hmac` crate) as the round function. It keeps
+//! the word count, so a keyed phrase still shows whether it holds IPv4 (4
+//! words) or IPv6 (6, 9 or 12 words). It is deterministic, so the same
+//! address under the same key always gives the same phrase, and it is not
+//! authenticated: a wrong key decodes to some other address or fails, rather
+//! than reporting a mismatch.
+//!
+//! [`EphemeralEncoder`] adds rotation for one-time invites: phrases are keyed
+//! for a time window of a chosen period and carry check words, so they
+//! change every period and stop decoding with [`FourWordError::Expired`]
+//! once they are more than one window old. The caller passes the time, so
+//! the module reads no clock of its own.
+//!
+//! ```rust
+//! use four_word_networking::FourWordAdaptiveEncoder;
+//! use four_word_networking::keyed::{KeyedEncoder, PhraseKey};
+//! use std::net::SocketAddr;
+//!
+//! let key = PhraseKey::from_secret(b"7f3a9c1e5b2d8f4a6c0e1b3d5f7a9c2e")?;
+//! let keyed = KeyedEncoder::new(FourWordAdaptiveEncoder::new()?, key);
+//! let addr: SocketAddr = "203.0.113.7:4001".parse()?;
+//! let words = keyed.encode(addr)?;
+//! assert_ne!(words, four_word_networking::encode(addr)?);
+//! assert_eq!(keyed.decode(&words)?, addr);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::dictionary4k::Dictionary4K;
+use crate::error::{FourWordError, Result};
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::phrase::write_words;
+use crate::reserved;
+use crate::wordlist::Wordlist;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::net::SocketAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Domain tag hashed ahead of secrets and round inputs
+const DOMAIN_TAG: &[u8] = b"four-word-networking keyed phrase v1\0";
+
+/// Feistel rounds; four already make a pseudorandom permutation
+const ROUNDS: u8 = 8;
+
+/// Check words closing an ephemeral phrase, 12 bits each
+pub const CHECK_WORDS: usize = 2;
+
+/// Shortest rotation period [`EphemeralEncoder`] accepts
+pub const MIN_ROTATION_PERIOD: Duration = Duration::from_secs(60);
+
+/// Secret shared by everyone allowed to read keyed phrases
+#[derive(Clone)]
+pub struct PhraseKey {
+    key: [u8; 32],
+}
+
+impl PhraseKey {
+    /// Uses 32 random bytes as the key
+    pub fn new(key: [u8; 32]) -> Self {
+        PhraseKey { key }
+    }
+
+    /// Derives a key from a shared secret of any length.
+    ///
+    /// The secret is hashed once, so it should be random (a generated token
+    /// or key file) rather than a password someone could guess.
+    pub fn from_secret(secret: &[u8]) -> Result<Self> {
+        if secret.is_empty() {
+            return Err(FourWordError::invalid_value(
+                "phrase key secret",
+                "",
+                "must not be empty",
+            ));
+        }
+        let digest = Sha256::new()
+            .chain_update(DOMAIN_TAG)
+            .chain_update(secret)
+            .finalize();
+        Ok(PhraseKey::new(digest.into()))
+    }
+
+    /// HMAC-SHA256 (RFC 2104) of the concatenated `parts`
+    fn mac(&self, parts: &[&[u8]]) -> [u8; 32] {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.key)
+            .unwrap_or_else(|_| unreachable!("HMAC accepts keys of any length"));
+        for part in parts {
+            mac.update(part);
+        }
+        mac.finalize().into_bytes().into()
+    }
+}
+
+impl fmt::Debug for PhraseKey {
+    /// Never prints the key
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PhraseKey(..)")
+    }
+}
+
+/// A [`FourWordAdaptiveEncoder`] whose phrases need the key to read
+pub struct KeyedEncoder<W = Dictionary4K> {
+    encoder: FourWordAdaptiveEncoder<W>,
+    key: PhraseKey,
+}
+
+impl<W: Wordlist> KeyedEncoder<W> {
+    /// Wraps `encoder`, keying its phrases with `key`
+    pub fn new(encoder: FourWordAdaptiveEncoder<W>, key: PhraseKey) -> Self {
+        KeyedEncoder { encoder, key }
+    }
+
+    /// Encodes `addr` as a keyed phrase; use port 65535 for "no port"
+    pub fn encode(&self, addr: SocketAddr) -> Result<String> {
+        let mut indices = self.plain_indices(addr)?;
+        permute(&self.key, &[], &mut indices, Direction::Encrypt);
+        self.write_phrase(&indices)
+    }
+
+    /// Decodes a phrase from [`encode`](Self::encode) made with the same key.
+    ///
+    /// Words may be separated by whitespace, dots or dashes.
+    pub fn decode(&self, words: &str) -> Result<SocketAddr> {
+        let mut indices = self.read_phrase(words, 0)?;
+        permute(&self.key, &[], &mut indices, Direction::Decrypt);
+        self.decode_indices(&indices)
+    }
+
+    /// Returns the wrapped encoder
+    pub fn encoder(&self) -> &FourWordAdaptiveEncoder<W> {
+        &self.encoder
+    }
+
+    /// Word indices of the plain phrase for `addr`
+    fn plain_indices(&self, addr: SocketAddr) -> Result<Vec<u16>> {
+        let mut plain = String::new();
+        self.encoder.encode_unescaped_into(addr, &mut plain)?;
+        plain
+            .split(' ')
+            .enumerate()
+            .map(|(position, word)| self.index_of(position, word))
+            .collect()
+    }
+
+    /// Writes `indices` as words, escaping any the profile reserves
+    fn write_phrase(&self, indices: &[u16]) -> Result<String> {
+        let mut words = String::new();
+        write_words(self.encoder.wordlist(), indices, &mut words)?;
+        let mut escaped = String::with_capacity(words.len());
+        reserved::escape(
+            self.encoder.wordlist(),
+            self.encoder.profile(),
+            &words,
+            &mut escaped,
+        )?;
+        Ok(escaped)
+    }
+
+    /// Reads the word indices of a keyed phrase that ends in `extra` words
+    /// after the address, undoing reserved-word escapes
+    fn read_phrase(&self, words: &str, extra: usize) -> Result<Vec<u16>> {
+        let words: Vec<&str> = words
+            .split(|c: char| c.is_whitespace() || c == '.' || c == '-')
+            .filter(|word| !word.is_empty())
+            .collect();
+        let unescaped =
+            reserved::unescape(self.encoder.wordlist(), self.encoder.profile(), &words)?;
+        let words: Vec<&str> = match &unescaped {
+            Some(restored) => restored.split(' ').collect(),
+            None => words,
+        };
+        if !matches!(words.len().checked_sub(extra), Some(4 | 6 | 9 | 12)) {
+            let expected: Vec<usize> = [4, 6, 9, 12].iter().map(|len| len + extra).collect();
+            return Err(FourWordError::wrong_word_count(&expected, words.len()));
+        }
+        words
+            .iter()
+            .enumerate()
+            .map(|(position, word)| self.index_of(position, word))
+            .collect()
+    }
+
+    /// Decodes the plain phrase with word `indices`
+    fn decode_indices(&self, indices: &[u16]) -> Result<SocketAddr> {
+        let mut plain = String::new();
+        write_words(self.encoder.wordlist(), indices, &mut plain)?;
+        self.encoder.decode_unescaped(&plain)
+    }
+
+    /// Looks up `word`, the phrase's word at `position`, in the encoder's wordlist
+    fn index_of(&self, position: usize, word: &str) -> Result<u16> {
+        self.encoder
+            .wordlist()
+            .get_index(word)
+            .ok_or_else(|| FourWordError::unknown_word(position, word))
+    }
+}
+
+/// Keyed phrases that rotate every period and then expire.
+///
+/// A phrase is keyed for the window `now / period` and ends with
+/// [`CHECK_WORDS`] check words tied to that window, so it is two words
+/// longer than the plain phrase. It decodes during its own window and the
+/// next one, living between one and two periods; older phrases, phrases from
+/// a later window (a peer whose clock is ahead) and phrases under another
+/// key fail with [`FourWordError::Expired`]. The check words hold 24 bits,
+/// and each phrase is tried against two windows, so about one such phrase in
+/// eight million still passes and decodes to a wrong address.
+///
+/// Every method takes the time as `now`; pass `SystemTime::now()` outside
+/// tests.
+pub struct EphemeralEncoder<W = Dictionary4K> {
+    keyed: KeyedEncoder<W>,
+    period: Duration,
+}
+
+impl<W: Wordlist> EphemeralEncoder<W> {
+    /// Rotates `keyed`'s phrases every `period`: whole seconds, at least
+    /// [`MIN_ROTATION_PERIOD`]
+    pub fn new(keyed: KeyedEncoder<W>, period: Duration) -> Result<Self> {
+        if period < MIN_ROTATION_PERIOD || period.subsec_nanos() != 0 {
+            return Err(FourWordError::out_of_range(
+                "Rotation period",
+                format!(
+                    "whole seconds and at least {}s",
+                    MIN_ROTATION_PERIOD.as_secs()
+                ),
+                format!("{period:?}"),
+            ));
+        }
+        Ok(EphemeralEncoder { keyed, period })
+    }
+
+    /// Encodes `addr` for the window containing `now`
+    pub fn encode_at(&self, addr: SocketAddr, now: SystemTime) -> Result<String> {
+        let window = self.window(now)?;
+        let mut indices = self.keyed.plain_indices(addr)?;
+        let check = check_indices(&self.keyed.key, window, &indices);
+        permute(
+            &self.keyed.key,
+            &window.to_le_bytes(),
+            &mut indices,
+            Direction::Encrypt,
+        );
+        indices.extend(check);
+        self.keyed.write_phrase(&indices)
+    }
+
+    /// Decodes a phrase from the window containing `now` or the one before it
+    pub fn decode_at(&self, words: &str, now: SystemTime) -> Result<SocketAddr> {
+        let mut indices = self.keyed.read_phrase(words, CHECK_WORDS)?;
+        let check = indices.split_off(indices.len() - CHECK_WORDS);
+        let current = self.window(now)?;
+        for window in [Some(current), current.checked_sub(1)]
+            .into_iter()
+            .flatten()
+        {
+            let mut plain = indices.clone();
+            permute(
+                &self.keyed.key,
+                &window.to_le_bytes(),
+                &mut plain,
+                Direction::Decrypt,
+            );
+            if check_indices(&self.keyed.key, window, &plain)[..] == check[..] {
+                return self.keyed.decode_indices(&plain);
+            }
+        }
+        Err(FourWordError::Expired(format!(
+            "phrase is not from the last {}s, or was made with a different key",
+            self.period.as_secs() * 2
+        )))
+    }
+
+    /// Returns the rotation period
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Returns the wrapped keyed encoder
+    pub fn keyed(&self) -> &KeyedEncoder<W> {
+        &self.keyed
+    }
+
+    /// Number of the window containing `now`
+    fn window(&self, now: SystemTime) -> Result<u64> {
+        let elapsed = now.duration_since(UNIX_EPOCH).map_err(|_| {
+            FourWordError::out_of_range("Time", "after the Unix epoch", format!("{now:?}"))
+        })?;
+        Ok(elapsed.as_secs() / self.period.as_secs())
+    }
+}
+
+/// Check words binding the plain phrase `indices` to `window`
+fn check_indices(key: &PhraseKey, window: u64, indices: &[u16]) -> [u16; CHECK_WORDS] {
+    let plain: Vec<u8> = indices
+        .iter()
+        .flat_map(|index| index.to_le_bytes())
+        .collect();
+    let mac = key.mac(&[DOMAIN_TAG, b"check", &window.to_le_bytes(), &plain]);
+    std::array::from_fn(|word| u16::from_le_bytes([mac[2 * word], mac[2 * word + 1]]) & 0x0FFF)
+}
+
+/// Which way [`permute`] runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Encrypt,
+    Decrypt,
+}
+
+/// Permutes the bits of up to 21 word `indices` in place.
+///
+/// A balanced Feistel network over the two halves of the 12-bit-per-word
+/// payload; `tweak` selects an independent permutation under the same key.
+fn permute(key: &PhraseKey, tweak: &[u8], indices: &mut [u16], direction: Direction) {
+    let width = indices.len() * 6;
+    let mask = u128::MAX >> (128 - width);
+    let header = [indices.len() as u8];
+    let round = |round: u8, half: u128| {
+        let mac = key.mac(&[DOMAIN_TAG, &header, &[round], tweak, &half.to_le_bytes()]);
+        let mut low = [0u8; 16];
+        low.copy_from_slice(&mac[..16]);
+        u128::from_le_bytes(low) & mask
+    };
+
+    let mut left = read_bits(indices, 0, width);
+    let mut right = read_bits(indices, width, width);
+    match direction {
+        Direction::Encrypt => {
+            for r in 0..ROUNDS {
+                (left, right) = (right, left ^ round(r, right));
+            }
+        }
+        Direction::Decrypt => {
+            for r in (0..ROUNDS).rev() {
+                (left, right) = (right ^ round(r, left), left);
+            }
+        }
+    }
+    write_bits(indices, 0, width, left);
+    write_bits(indices, width, width, right);
+}
+
+/// Reads `len` payload bits starting at bit `start`, least significant first
+fn read_bits(indices: &[u16], start: usize, len: usize) -> u128 {
+    (0..len).fold(0u128, |value, bit| {
+        let offset = start + bit;
+        let set = (indices[offset / 12] >> (offset % 12)) & 1;
+        value | (u128::from(set) << bit)
+    })
+}
+
+/// Inverse of [`read_bits`]
+fn write_bits(indices: &mut [u16], start: usize, len: usize, value: u128) {
+    for bit in 0..len {
+        let offset = start + bit;
+        let word = &mut indices[offset / 12];
+        let set = ((value >> bit) & 1) as u16;
+        *word = (*word & !(1 << (offset % 12))) | (set << (offset % 12));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::EncodingProfile;
+
+    fn keyed(secret: &[u8]) -> KeyedEncoder {
+        let key = PhraseKey::from_secret(secret).unwrap();
+        KeyedEncoder::new(FourWordAdaptiveEncoder::new().unwrap(), key)
+    }
+
+    #[test]
+    fn test_hmac_matches_rfc_4231() {
+        // Test case 2: a short key is zero-padded exactly like ours
+        let mut key = [0u8; 32];
+        key[..4].copy_from_slice(b"Jefe");
+        let mac = PhraseKey::new(key).mac(&[b"what do ya want ", b"for nothing?"]);
+        assert_eq!(
+            hex::encode(mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_round_trip_hides_address() {
+        let keyed = keyed(b"shared rendezvous secret");
+        for addr in [
+            "192.168.1.1:443",
+            "10.0.0.1:65535",
+            "[::1]:8080",
+            "[2001:db8:85a3::1]:4242",
+            "[2606:4700:4700::1111]:443",
+        ] {
+            let addr: SocketAddr = addr.parse().unwrap();
+            let words = keyed.encode(addr).unwrap();
+            let plain = crate::encode(addr).unwrap();
+            assert_eq!(words.split(' ').count(), plain.split(' ').count());
+            assert_ne!(words, plain);
+            assert_eq!(keyed.decode(&words).unwrap(), addr, "{words}");
+            assert_eq!(
+                keyed
+                    .decode(&words.to_uppercase().replace(' ', "-"))
+                    .unwrap(),
+                addr
+            );
+        }
+    }
+
+    #[test]
+    fn test_wrong_key_does_not_recover_address() {
+        let addr: SocketAddr = "203.0.113.7:4001".parse().unwrap();
+        let words = keyed(b"one secret").encode(addr).unwrap();
+        let other = keyed(b"another secret");
+        assert_ne!(other.encode(addr).unwrap(), words);
+        assert_ne!(other.decode(&words).ok(), Some(addr));
+        assert!(PhraseKey::from_secret(b"").is_err());
+        assert_eq!(format!("{:?}", PhraseKey::new([7; 32])), "PhraseKey(..)");
+    }
+
+    #[test]
+    fn test_reserved_words_stay_escaped() {
+        let addr: SocketAddr = "203.0.113.7:4001".parse().unwrap();
+        let key = PhraseKey::from_secret(b"profile secret").unwrap();
+        let unescaped = KeyedEncoder::new(FourWordAdaptiveEncoder::new().unwrap(), key.clone())
+            .encode(addr)
+            .unwrap();
+        let words: Vec<&str> = unescaped.split(' ').collect();
+        let (escape, reserved) = (words[0], words[1]);
+
+        // Both the escape word and the reserved word are replaced by pairs
+        let mut profile = EncodingProfile::new();
+        profile.reserve_words(escape, &[reserved]).unwrap();
+        let encoder = FourWordAdaptiveEncoder::new()
+            .unwrap()
+            .with_profile(profile);
+        let keyed = KeyedEncoder::new(encoder, key);
+        let words = keyed.encode(addr).unwrap();
+        assert_eq!(words.split(' ').count(), 8);
+        assert!(!words.split(' ').any(|word| word == reserved));
+        assert_eq!(keyed.decode(&words).unwrap(), addr);
+    }
+
+    #[test]
+    fn test_ephemeral_phrases_rotate_and_expire() {
+        let period = Duration::from_secs(600);
+        let ephemeral = EphemeralEncoder::new(keyed(b"invite secret"), period).unwrap();
+        let start = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let addr: SocketAddr = "[2001:db8:85a3::1]:4242".parse().unwrap();
+
+        let words = ephemeral.encode_at(addr, start).unwrap();
+        assert_eq!(words.split(' ').count(), 9 + CHECK_WORDS);
+        assert_ne!(ephemeral.encode_at(addr, start + period).unwrap(), words);
+
+        // Valid for the rest of its window and the whole next one
+        for later in [Duration::ZERO, period, period * 2 - Duration::from_secs(1)] {
+            assert_eq!(ephemeral.decode_at(&words, start + later).unwrap(), addr);
+        }
+        for when in [start + period * 2, start - period] {
+            let error = ephemeral.decode_at(&words, when).unwrap_err();
+            assert!(matches!(error, FourWordError::Expired(_)), "{error}");
+        }
+
+        let other = EphemeralEncoder::new(keyed(b"other secret"), period).unwrap();
+        assert!(matches!(
+            other.decode_at(&words, start),
+            Err(FourWordError::Expired(_))
+        ));
+        assert!(EphemeralEncoder::new(keyed(b"s"), Duration::from_secs(59)).is_err());
+        assert!(EphemeralEncoder::new(keyed(b"s"), Duration::from_millis(60_500)).is_err());
+    }
+}
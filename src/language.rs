@@ -0,0 +1,193 @@
+//! Wordlist languages.
+//!
+//! Every language maps the same 12-bit word indices onto its own 4,096-word
+//! list, so an address encodes to the same positions in every language and
+//! only the words differ. English is always available; other lists are
+//! compiled in with their `lang-*` feature (`lang-es`, `lang-fr`, or
+//! `all-languages`).
+//!
+//! The language is not stored in the words themselves. It travels alongside
+//! the phrase as its ISO 639-1 code (see [`Language::code`]), or is recovered
+//! from the words with [`Language::detect`].
+
+#![forbid(unsafe_code)]
+
+use crate::dictionary4k::Dictionary4K;
+use crate::error::{FourWordError, Result};
+use crate::format::is_index;
+use std::fmt;
+use std::str::FromStr;
+
+/// A language with a built-in 4,096-word list.
+///
+/// Languages are added over time, so matches need a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum Language {
+    /// English, the default wordlist
+    #[default]
+    English,
+    /// Spanish (accents folded to ASCII)
+    #[cfg(feature = "lang-es")]
+    Spanish,
+    /// French (accents folded to ASCII)
+    #[cfg(feature = "lang-fr")]
+    French,
+}
+
+impl Language {
+    /// Every language compiled into this build, English first
+    pub const ALL: &'static [Language] = &[
+        Language::English,
+        #[cfg(feature = "lang-es")]
+        Language::Spanish,
+        #[cfg(feature = "lang-fr")]
+        Language::French,
+    ];
+
+    /// Returns the ISO 639-1 code identifying this language
+    pub const fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            #[cfg(feature = "lang-es")]
+            Language::Spanish => "es",
+            #[cfg(feature = "lang-fr")]
+            Language::French => "fr",
+        }
+    }
+
+    /// Looks up a language by its ISO 639-1 code, ignoring case
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|language| language.code().eq_ignore_ascii_case(code))
+    }
+
+    /// Returns the 4,096-word dictionary for this language
+    pub const fn dictionary(self) -> Dictionary4K {
+        Dictionary4K::for_language(self)
+    }
+
+    /// Identifies the language of a phrase from its words.
+    ///
+    /// Returns the single language whose dictionary contains every word, or
+    /// `None` if no language does or the words are valid in several. Words
+    /// written as dictionary indices say nothing about the language and are
+    /// skipped.
+    pub fn detect(phrase: &str) -> Option<Self> {
+        let words: Vec<&str> = phrase
+            .split(|c: char| c.is_whitespace() || ".-".contains(c))
+            .filter(|word| !word.is_empty() && !is_index(word))
+            .collect();
+        if words.is_empty() {
+            return None;
+        }
+
+        let mut matches = Self::ALL.iter().copied().filter(|language| {
+            let dictionary = language.dictionary();
+            words.iter().all(|word| dictionary.contains(word))
+        });
+        let language = matches.next()?;
+        matches.next().is_none().then_some(language)
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Language {
+    type Err = FourWordError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_code(s).ok_or_else(|| {
+            let available: Vec<&str> = Self::ALL.iter().map(|l| l.code()).collect();
+            FourWordError::invalid_value(
+                "language",
+                s,
+                format!("available: {}", available.join(", ")),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_round_trip() {
+        for &language in Language::ALL {
+            assert_eq!(language.code().parse::<Language>().unwrap(), language);
+            assert_eq!(language.to_string(), language.code());
+        }
+        assert_eq!(Language::from_code("EN"), Some(Language::English));
+        assert!("xx".parse::<Language>().is_err());
+    }
+
+    #[test]
+    fn test_every_dictionary_is_complete() {
+        for &language in Language::ALL {
+            let dictionary = language.dictionary();
+            assert_eq!(dictionary.len(), 4096);
+            for index in 0..4096u16 {
+                let word = dictionary.get_word(index).unwrap();
+                assert_eq!(
+                    dictionary.get_index(word),
+                    Some(index),
+                    "{language}: {word}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect_english() {
+        let dictionary = Language::English.dictionary();
+        let phrase: Vec<&str> = (0..4)
+            .map(|i| dictionary.get_word(i * 1000).unwrap())
+            .collect();
+        assert_eq!(Language::detect(&phrase.join(" ")), Some(Language::English));
+        assert_eq!(Language::detect("xqzzvkjw"), None);
+        assert_eq!(Language::detect(""), None);
+    }
+
+    #[cfg(feature = "lang-es")]
+    #[test]
+    fn test_detect_spanish() {
+        let dictionary = Language::Spanish.dictionary();
+        let phrase: Vec<&str> = (0..4)
+            .map(|i| dictionary.get_word(i * 1000 + 7).unwrap())
+            .collect();
+        assert_eq!(Language::detect(&phrase.join("-")), Some(Language::Spanish));
+    }
+
+    #[cfg(feature = "lang-fr")]
+    #[test]
+    fn test_detect_french() {
+        let dictionary = Language::French.dictionary();
+        let phrase: Vec<&str> = (0..4)
+            .map(|i| dictionary.get_word(i * 1000 + 11).unwrap())
+            .collect();
+        assert_eq!(Language::detect(&phrase.join(" ")), Some(Language::French));
+    }
+
+    #[test]
+    fn test_dictionaries_share_no_words() {
+        // A shared word would leave `detect` unable to tell the languages apart
+        for (i, &language) in Language::ALL.iter().enumerate() {
+            let dictionary = language.dictionary();
+            for &other in &Language::ALL[i + 1..] {
+                let other_dictionary = other.dictionary();
+                let shared: Vec<&str> = (0..4096)
+                    .filter_map(|index| dictionary.get_word(index))
+                    .filter(|word| other_dictionary.contains(word))
+                    .collect();
+                assert!(shared.is_empty(), "{language} and {other} share {shared:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,369 @@
+//! Signed language packs installed at runtime.
+//!
+//! A language pack is a 4,096-word list distributed separately from the
+//! binary, so applications can offer languages without compiling every
+//! wordlist in. Packs are JSON documents signed with ed25519 by their
+//! publisher:
+//!
+//! ```json
+//! {
+//!   "format": 1,
+//!   "code": "de",
+//!   "name": "Deutsch",
+//!   "fingerprint": "<SHA-256 of the words, hex>",
+//!   "signature": "<ed25519 signature of the signing message, hex>",
+//!   "words": ["...", "..."]
+//! }
+//! ```
+//!
+//! The signature covers the code, name and word fingerprint (see
+//! [`LanguagePack::signing_message`]), so neither the words nor the language
+//! they are offered as can be swapped without invalidating it. Verified packs
+//! are kept in a [`LanguageRegistry`] owned by the application.
+
+#![forbid(unsafe_code)]
+
+use crate::dictionary4k::Dictionary4K;
+use crate::error::{FourWordError, Result};
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::wordlist::fingerprint_words;
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Pack format version understood by this release
+pub const PACK_FORMAT: u32 = 1;
+
+/// Domain separator prefixed to every signing message
+const SIGNING_CONTEXT: &[u8] = b"four-word-networking language pack v1\0";
+
+/// On-the-wire representation of a pack
+#[derive(Serialize, Deserialize)]
+struct PackFile {
+    format: u32,
+    code: String,
+    name: String,
+    fingerprint: String,
+    signature: String,
+    words: Vec<String>,
+}
+
+/// A verified wordlist together with the language it is published as
+#[derive(Debug, Clone)]
+pub struct LanguagePack {
+    code: String,
+    name: String,
+    dictionary: Dictionary4K,
+}
+
+impl LanguagePack {
+    /// Creates an unsigned pack, for publishers preparing a pack to sign.
+    ///
+    /// `code` must be 2-8 lowercase ASCII letters (an ISO 639 code or similar).
+    pub fn new(code: &str, name: &str, dictionary: Dictionary4K) -> Result<Self> {
+        validate_code(code)?;
+        Ok(LanguagePack {
+            code: code.to_string(),
+            name: name.to_string(),
+            dictionary,
+        })
+    }
+
+    /// Parses a pack and verifies it was signed by `public_key`.
+    ///
+    /// The words must hash to the declared fingerprint and the signature
+    /// must verify before they are validated and indexed like any custom
+    /// wordlist, so an unauthenticated pack never gets dictionary tables.
+    pub fn from_json(json: &[u8], public_key: &[u8; 32]) -> Result<Self> {
+        let file: PackFile = serde_json::from_slice(json)?;
+        if file.format != PACK_FORMAT {
+            return Err(FourWordError::DictionaryError(format!(
+                "unsupported language pack format {}",
+                file.format
+            )));
+        }
+        validate_code(&file.code)?;
+
+        // Hash the words as Dictionary4K::from_slice folds them
+        let words: Vec<String> = file
+            .words
+            .iter()
+            .map(|word| word.trim().to_ascii_lowercase())
+            .collect();
+        let fingerprint = fingerprint_words(words.iter().map(String::as_str));
+        let actual = hex::encode(fingerprint);
+        if !actual.eq_ignore_ascii_case(file.fingerprint.trim()) {
+            return Err(FourWordError::DictionaryMismatch {
+                expected: file.fingerprint.trim().to_ascii_lowercase(),
+                actual,
+            });
+        }
+
+        let key = VerifyingKey::from_bytes(public_key).map_err(|e| {
+            FourWordError::DictionaryError(format!("invalid language pack public key: {e}"))
+        })?;
+        let signature: [u8; 64] = hex::decode(&file.signature)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| {
+                FourWordError::DictionaryError(format!(
+                    "language pack '{}' has a malformed signature",
+                    file.code
+                ))
+            })?;
+        let message = signing_message(&file.code, &file.name, &fingerprint);
+        key.verify_strict(&message, &Signature::from_bytes(&signature))
+            .map_err(|_| {
+                FourWordError::DictionaryError(format!(
+                    "language pack '{}' signature does not verify",
+                    file.code
+                ))
+            })?;
+
+        Self::new(&file.code, &file.name, Dictionary4K::from_slice(&words)?)
+    }
+
+    /// Serializes the pack with a `signature` over [`signing_message`](Self::signing_message)
+    pub fn to_json(&self, signature: &[u8; 64]) -> Result<String> {
+        let file = PackFile {
+            format: PACK_FORMAT,
+            code: self.code.clone(),
+            name: self.name.clone(),
+            fingerprint: self.dictionary.fingerprint_hex(),
+            signature: hex::encode(signature),
+            words: (0..self.dictionary.len() as u16)
+                .filter_map(|index| self.dictionary.get_word(index))
+                .map(str::to_string)
+                .collect(),
+        };
+        Ok(serde_json::to_string(&file)?)
+    }
+
+    /// Bytes the publisher signs: context, code, name and word fingerprint
+    pub fn signing_message(&self) -> Vec<u8> {
+        signing_message(&self.code, &self.name, &self.dictionary.fingerprint())
+    }
+
+    /// Returns the language code
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Returns the human-readable language name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the pack's wordlist
+    pub fn dictionary(&self) -> &Dictionary4K {
+        &self.dictionary
+    }
+
+    /// Creates an encoder that reads and writes this pack's words
+    pub fn encoder(&self) -> Result<FourWordAdaptiveEncoder> {
+        FourWordAdaptiveEncoder::with_wordlist(self.dictionary.clone())
+    }
+}
+
+/// Fails unless `code` is 2-8 lowercase ASCII letters
+fn validate_code(code: &str) -> Result<()> {
+    let valid_code = (2..=8).contains(&code.len()) && code.bytes().all(|b| b.is_ascii_lowercase());
+    if !valid_code {
+        return Err(FourWordError::DictionaryError(format!(
+            "language code must be 2-8 lowercase ASCII letters, got '{code}'"
+        )));
+    }
+    Ok(())
+}
+
+/// Context, code, name and word fingerprint, each separated by a NUL
+fn signing_message(code: &str, name: &str, fingerprint: &[u8; 32]) -> Vec<u8> {
+    let mut message = SIGNING_CONTEXT.to_vec();
+    message.extend_from_slice(code.as_bytes());
+    message.push(0);
+    message.extend_from_slice(name.as_bytes());
+    message.push(0);
+    message.extend_from_slice(fingerprint);
+    message
+}
+
+/// Language packs installed by the application, keyed by language code
+#[derive(Debug, Clone, Default)]
+pub struct LanguageRegistry {
+    packs: HashMap<String, LanguagePack>,
+}
+
+impl LanguageRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Installs `pack`, returning the pack it replaced for the same code, if any
+    pub fn install(&mut self, pack: LanguagePack) -> Option<LanguagePack> {
+        self.packs.insert(pack.code.clone(), pack)
+    }
+
+    /// Removes the pack for `code`
+    pub fn remove(&mut self, code: &str) -> Option<LanguagePack> {
+        self.packs.remove(code)
+    }
+
+    /// Looks up the pack for `code`
+    pub fn get(&self, code: &str) -> Option<&LanguagePack> {
+        self.packs.get(code)
+    }
+
+    /// Returns the installed language codes, sorted
+    pub fn codes(&self) -> Vec<&str> {
+        let mut codes: Vec<&str> = self.packs.keys().map(String::as_str).collect();
+        codes.sort_unstable();
+        codes
+    }
+
+    /// Finds the pack whose wordlist contains every word of `phrase`, if exactly one does
+    pub fn detect(&self, phrase: &str) -> Option<&LanguagePack> {
+        let words: Vec<&str> = phrase
+            .split(|c: char| c.is_whitespace() || c == '.' || c == '-')
+            .filter(|word| !word.is_empty())
+            .collect();
+        if words.is_empty() {
+            return None;
+        }
+        let mut matches = self
+            .packs
+            .values()
+            .filter(|pack| words.iter().all(|word| pack.dictionary.contains(word)));
+        let pack = matches.next()?;
+        matches.next().is_none().then_some(pack)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn test_dictionary(prefix: char) -> Dictionary4K {
+        // Fixed-width words can never be prefixes of each other
+        let letter = |n: u32| char::from(b'a' + (n % 16) as u8);
+        let words: Vec<String> = (0..4096u32)
+            .map(|n| format!("{prefix}{}{}{}", letter(n), letter(n / 16), letter(n / 256)))
+            .collect();
+        Dictionary4K::from_slice(&words).unwrap()
+    }
+
+    fn signed_pack(key: &SigningKey) -> String {
+        let pack = LanguagePack::new("xx", "Test", test_dictionary('x')).unwrap();
+        let signature = key.sign(&pack.signing_message());
+        pack.to_json(&signature.to_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_signed_pack_round_trip() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let json = signed_pack(&key);
+
+        let pack =
+            LanguagePack::from_json(json.as_bytes(), key.verifying_key().as_bytes()).unwrap();
+        assert_eq!(pack.code(), "xx");
+        assert!(pack.dictionary().is_compatible_with(&test_dictionary('x')));
+
+        let encoder = pack.encoder().unwrap();
+        let words = encoder.encode("10.0.0.1:80").unwrap();
+        assert_eq!(encoder.decode(&words).unwrap(), "10.0.0.1:80");
+    }
+
+    #[test]
+    fn test_rejects_wrong_key_and_tampering() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let other = SigningKey::from_bytes(&[8; 32]);
+        let json = signed_pack(&key);
+        let public_key = key.verifying_key().to_bytes();
+
+        assert!(
+            LanguagePack::from_json(json.as_bytes(), other.verifying_key().as_bytes()).is_err()
+        );
+
+        // Swapping a word breaks the fingerprint
+        let tampered = json.replacen("xaaa", "yaaa", 1);
+        assert!(matches!(
+            LanguagePack::from_json(tampered.as_bytes(), &public_key),
+            Err(FourWordError::DictionaryMismatch { .. })
+        ));
+
+        // Relabelling the language breaks the signature
+        let relabelled = json.replacen("\"code\":\"xx\"", "\"code\":\"yy\"", 1);
+        assert!(LanguagePack::from_json(relabelled.as_bytes(), &public_key).is_err());
+    }
+
+    #[test]
+    fn test_verifies_before_building_the_dictionary() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let public_key = key.verifying_key().to_bytes();
+
+        // Three words with a matching fingerprint would fail word validation,
+        // but the signature is checked first
+        let words = ["oops", "oops", "oops"];
+        let fingerprint = hex::encode(fingerprint_words(words.into_iter()));
+        let file = PackFile {
+            format: PACK_FORMAT,
+            code: "xx".to_string(),
+            name: "Test".to_string(),
+            fingerprint,
+            signature: hex::encode([0u8; 64]),
+            words: words.map(str::to_string).to_vec(),
+        };
+        let json = serde_json::to_string(&file).unwrap();
+        match LanguagePack::from_json(json.as_bytes(), &public_key) {
+            Err(FourWordError::DictionaryError(message)) => {
+                assert!(message.contains("signature"), "{message}");
+            }
+            other => panic!("expected a signature error, got {other:?}"),
+        }
+
+        // Signed by the right key, the same words reach validation
+        let message = signing_message(
+            &file.code,
+            &file.name,
+            &fingerprint_words(words.into_iter()),
+        );
+        let signed = PackFile {
+            signature: hex::encode(key.sign(&message).to_bytes()),
+            ..file
+        };
+        let json = serde_json::to_string(&signed).unwrap();
+        match LanguagePack::from_json(json.as_bytes(), &public_key) {
+            Err(FourWordError::DictionaryError(message)) => {
+                assert!(message.contains("4096 words"), "{message}");
+            }
+            other => panic!("expected a word count error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_registry_install_and_detect() {
+        let mut registry = LanguageRegistry::new();
+        assert!(
+            registry
+                .install(LanguagePack::new("xx", "X", test_dictionary('x')).unwrap())
+                .is_none()
+        );
+        registry.install(LanguagePack::new("yy", "Y", test_dictionary('y')).unwrap());
+        assert_eq!(registry.codes(), ["xx", "yy"]);
+
+        assert_eq!(
+            registry.detect("yaaa ybbb").map(LanguagePack::code),
+            Some("yy")
+        );
+        assert!(registry.detect("xaaa ybbb").is_none());
+        assert!(registry.remove("xx").is_some());
+        assert!(registry.get("xx").is_none());
+    }
+
+    #[test]
+    fn test_invalid_code() {
+        assert!(LanguagePack::new("EN", "English", test_dictionary('x')).is_err());
+        assert!(LanguagePack::new("x", "X", test_dictionary('x')).is_err());
+    }
+}
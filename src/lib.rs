@@ -16,6 +16,17 @@
 //!
 //! ## Example
 //!
+//! The [`encode`] and [`decode`] functions cover the common case:
+//!
+//! ```rust
+//! let addr = "192.168.1.1:443".parse()?;
+//! let words = four_word_networking::encode(addr)?;
+//! assert_eq!(four_word_networking::decode(&words)?, addr);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [`FourWordAdaptiveEncoder`] offers languages, profiles and lenient decoding:
+//!
 //! ```rust
 //! use four_word_networking::FourWordAdaptiveEncoder;
 //!
@@ -32,38 +43,167 @@
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
 
+// Every module but `ffi` also forbids `unsafe` itself
+#![deny(unsafe_code)]
+
+pub mod audit;
+pub mod batch;
+pub mod cache;
+pub mod categorization;
+pub mod cidr;
+#[cfg(feature = "clap")]
+pub mod clap_parser;
 pub mod compression;
+pub mod confusability;
+pub mod conversion;
+#[cfg(unix)]
+pub mod daemon;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod dial;
 pub mod dictionary4k;
+pub mod dictionary_audit;
+#[cfg(feature = "discovery")]
+pub mod discovery;
+pub mod dns_txt;
+pub mod dual_stack;
+pub mod emoji;
+pub mod encoder;
+pub mod encoding_config;
+pub mod endpoint_list;
 pub mod error;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+#[cfg(feature = "ffi")]
+#[allow(unsafe_code)]
+pub mod ffi;
+pub mod filter;
+pub mod fingerprint;
+pub mod format;
 pub mod four_word_adaptive_encoder;
 pub mod four_word_encoder;
 pub mod four_word_ipv6_encoder;
+pub mod fuzzy;
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+pub mod http;
 // Experimental modules removed
+pub mod inspect;
+pub mod integer;
 pub mod ipv6_compression;
 pub mod ipv6_pattern_feistel;
 pub mod ipv6_perfect_patterns;
+pub mod keyed;
+pub mod language;
+#[cfg(feature = "language-packs")]
+pub mod language_pack;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod metrics;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod multiaddr;
+pub mod multicast;
+pub mod nat64;
+#[cfg(all(feature = "tokio", not(target_arch = "wasm32")))]
+pub mod net;
+pub mod normalize;
+pub mod payload;
+pub mod phonetic;
+pub mod phrase;
+pub mod ports;
+pub mod prelude;
+pub mod profile;
+pub mod provider_prefix;
 pub mod pure_ip_compression;
+#[cfg(feature = "qr")]
+pub mod qr;
+#[cfg(feature = "repl")]
+pub mod repl;
+pub mod reserved;
+#[cfg(all(feature = "resolve", not(target_arch = "wasm32")))]
+pub mod resolve;
+pub mod self_check;
+pub mod similarity;
+pub mod slug;
+pub mod suggest;
+pub mod test_vectors;
+pub mod transliteration;
+pub mod transport;
 // Ultra modules removed - used outdated 3-word system
 pub mod universal_ip_compression;
+pub mod uri;
+pub mod verify;
+pub mod version;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod wireguard;
+pub mod word_address;
+pub mod wordlist;
 
+#[cfg(kani)]
+mod proofs;
 #[cfg(test)]
 mod property_tests;
 
+pub use cache::{CacheStats, CachedEncoder};
+pub use confusability::{confusability, phrase_confusability};
+pub use dictionary4k::Dictionary4K;
+pub use encoder::PhraseEncoder;
+pub use encoding_config::{EncodingConfig, SizedEncoder, SizedWordlist};
 pub use error::{FourWordError, Result};
+pub use format::{Alphabet, Formatter};
 // Main API - Four-word encoding
 pub use four_word_adaptive_encoder::FourWordAdaptiveEncoder;
 pub use four_word_encoder::{FourWordEncoder, FourWordEncoding};
 pub use four_word_ipv6_encoder::{FourWordGroup, FourWordIpv6Encoder, Ipv6FourWordGroupEncoding};
+pub use fuzzy::{FuzzyDecoding, WordCorrection, WordSuggestion};
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+pub use http::HttpServer;
 // Compression and IPv6 support modules
+pub use inspect::{EncodingInspector, Inspection};
 pub use ipv6_compression::{CompressedIpv6, Ipv6Category, Ipv6Compressor};
 pub use ipv6_pattern_feistel::{IPv6PatternFeistel, IPv6PatternId};
 pub use ipv6_perfect_patterns::{IPv6Pattern, IPv6PatternDetector};
+pub use keyed::{EphemeralEncoder, KeyedEncoder, PhraseKey};
+pub use language::Language;
+#[cfg(feature = "language-packs")]
+pub use language_pack::{LanguagePack, LanguageRegistry};
+#[cfg(not(target_arch = "wasm32"))]
+pub use metrics::Metrics;
+pub use nat64::Nat64Prefix;
+pub use normalize::VoiceNormalizer;
+pub use phrase::WordPhrase;
+pub use profile::EncodingProfile;
+pub use provider_prefix::ProviderPrefix;
 pub use pure_ip_compression::{MathematicalCompressor, PureIpCompressor};
+pub use similarity::{EndpointRelation, PhraseSimilarity};
+pub use slug::{from_slug, slug};
+pub use suggest::{nearest, suggest};
+pub use transliteration::AliasTable;
 pub use universal_ip_compression::UniversalIpCompressor;
+pub use uri::WordsUri;
+pub use verify::{AddressSampler, VerifyReport};
+pub use word_address::{WordAddress, WordSocketAddr};
+pub use wordlist::Wordlist;
 
 /// Version of the four-word networking library
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Encodes `addr` as English words: 4 for IPv4, 6, 9 or 12 for IPv6
+pub fn encode(addr: impl Into<std::net::SocketAddr>) -> Result<String> {
+    let mut words = String::new();
+    FourWordAdaptiveEncoder::new()?.encode_into(addr.into(), &mut words)?;
+    Ok(words)
+}
+
+/// Decodes English words produced by [`encode`] back to the socket address
+pub fn decode(words: &str) -> Result<std::net::SocketAddr> {
+    FourWordAdaptiveEncoder::new()?.decode_socket_addr(words)
+}
+
+/// Describes how the endpoints behind two English phrases relate
+pub fn similarity(first: &str, second: &str) -> Result<PhraseSimilarity> {
+    FourWordAdaptiveEncoder::new()?.similarity(first, second)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -81,4 +221,20 @@ mod tests {
         assert_eq!(address, decoded);
     }
 
+    #[test]
+    fn test_free_functions() {
+        for address in ["192.168.1.1:443", "[2001:db8::1]:8080", "[::1]:22"] {
+            let addr: std::net::SocketAddr = address.parse().unwrap();
+            let words = encode(addr).unwrap();
+            assert_eq!(
+                words,
+                FourWordAdaptiveEncoder::new()
+                    .unwrap()
+                    .encode(address)
+                    .unwrap()
+            );
+            assert_eq!(decode(&words).unwrap(), addr);
+        }
+        assert!(decode("not real words").is_err());
+    }
 }
@@ -0,0 +1,333 @@
+//! Prometheus metrics for conversion traffic.
+//!
+//! A [`Metrics`] handle counts conversions by direction and category, lossy
+//! encodings by category, failures by [`FourWordError::kind`] and HTTP
+//! requests by endpoint and status, and keeps a histogram of conversion
+//! latency. `Display` (or [`Metrics::render`]) writes them in the Prometheus
+//! text exposition format. [`HttpServer`](crate::http::HttpServer) serves
+//! them at `GET /metrics`; a service embedding the encoder converts through
+//! [`Metrics::convert`] and exposes the rendering from its own endpoint:
+//!
+//! ```rust
+//! use four_word_networking::FourWordAdaptiveEncoder;
+//! use four_word_networking::conversion::Direction;
+//! use four_word_networking::metrics::Metrics;
+//!
+//! let encoder = FourWordAdaptiveEncoder::new()?;
+//! let metrics = Metrics::new();
+//! metrics.convert(&encoder, "192.168.1.1:443")?;
+//! assert!(metrics.convert(&encoder, "ocean qqqq zzzz xxxx").is_err());
+//!
+//! assert_eq!(metrics.conversions(Direction::Encode, "ipv4"), 1);
+//! assert_eq!(metrics.errors("unknown_word"), 1);
+//! assert!(metrics.render().contains(
+//!     r#"fourword_conversions_total{direction="encode",category="ipv4"} 1"#
+//! ));
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+//!
+//! Every label value comes from a fixed set (categories, error kinds,
+//! endpoint names), so the number of series stays bounded whatever clients
+//! send. Counts start at zero when the handle is created; share one handle
+//! between threads behind an `Arc`.
+
+#![forbid(unsafe_code)]
+
+use crate::conversion::{Conversion, Direction};
+use crate::error::{FourWordError, Result};
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::wordlist::Wordlist;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Upper bounds of the latency histogram buckets, in seconds
+pub const LATENCY_BUCKETS: [f64; 10] = [
+    0.000_005, 0.000_01, 0.000_025, 0.000_05, 0.000_1, 0.000_25, 0.000_5, 0.001, 0.005, 0.025,
+];
+
+/// Counters and the latency histogram, shared between threads
+#[derive(Debug, Default)]
+pub struct Metrics {
+    registry: Mutex<Registry>,
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    conversions: BTreeMap<(Direction, &'static str), u64>,
+    lossy: BTreeMap<&'static str, u64>,
+    errors: BTreeMap<&'static str, u64>,
+    requests: BTreeMap<(&'static str, u16), u64>,
+    latency: Histogram,
+}
+
+/// Conversion latencies, one count per bucket (not cumulative)
+#[derive(Debug, Default)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|&bound| seconds <= bound) {
+            self.buckets[bucket] += 1;
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+impl Metrics {
+    /// Creates a handle with every count at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Converts `entry` with [`convert_entry`](FourWordAdaptiveEncoder::convert_entry),
+    /// recording the outcome and how long it took
+    pub fn convert<W: Wordlist>(
+        &self,
+        encoder: &FourWordAdaptiveEncoder<W>,
+        entry: &str,
+    ) -> Result<Conversion> {
+        let started = Instant::now();
+        let result = encoder.convert_entry(entry);
+        match &result {
+            Ok(conversion) => self.record_conversion(conversion, started.elapsed()),
+            Err(error) => self.record_error(error, started.elapsed()),
+        }
+        result
+    }
+
+    /// Records a successful conversion that took `elapsed`
+    pub fn record_conversion(&self, conversion: &Conversion, elapsed: Duration) {
+        let mut registry = lock(&self.registry);
+        *registry
+            .conversions
+            .entry((conversion.direction, conversion.category))
+            .or_default() += 1;
+        if !conversion.lossless {
+            *registry.lossy.entry(conversion.category).or_default() += 1;
+        }
+        registry.latency.observe(elapsed);
+    }
+
+    /// Records a failed conversion that took `elapsed`
+    pub fn record_error(&self, error: &FourWordError, elapsed: Duration) {
+        let mut registry = lock(&self.registry);
+        *registry.errors.entry(error.kind()).or_default() += 1;
+        registry.latency.observe(elapsed);
+    }
+
+    /// Records an HTTP request to `endpoint` answered with `status`
+    pub fn record_request(&self, endpoint: &'static str, status: u16) {
+        *lock(&self.registry)
+            .requests
+            .entry((endpoint, status))
+            .or_default() += 1;
+    }
+
+    /// Successful conversions in `direction` under `category`
+    pub fn conversions(&self, direction: Direction, category: &str) -> u64 {
+        lock(&self.registry)
+            .conversions
+            .iter()
+            .filter(|((d, c), _)| *d == direction && *c == category)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Lossy encodings under `category`
+    pub fn lossy_encodings(&self, category: &str) -> u64 {
+        lock(&self.registry)
+            .lossy
+            .get(category)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Failed conversions of the given [`FourWordError::kind`]
+    pub fn errors(&self, kind: &str) -> u64 {
+        lock(&self.registry).errors.get(kind).copied().unwrap_or(0)
+    }
+
+    /// HTTP requests to `endpoint` answered with `status`
+    pub fn requests(&self, endpoint: &str, status: u16) -> u64 {
+        lock(&self.registry)
+            .requests
+            .iter()
+            .filter(|((e, s), _)| *e == endpoint && *s == status)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// The metrics in the Prometheus text exposition format (version 0.0.4)
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let registry = lock(&self.registry);
+
+        header(
+            f,
+            "fourword_conversions_total",
+            "counter",
+            "Successful conversions by direction and category.",
+        )?;
+        for ((direction, category), count) in &registry.conversions {
+            writeln!(
+                f,
+                "fourword_conversions_total{{direction=\"{direction}\",category=\"{category}\"}} {count}"
+            )?;
+        }
+
+        header(
+            f,
+            "fourword_lossy_encodings_total",
+            "counter",
+            "Encodings whose words do not decode to the input exactly, by category.",
+        )?;
+        for (category, count) in &registry.lossy {
+            writeln!(
+                f,
+                "fourword_lossy_encodings_total{{category=\"{category}\"}} {count}"
+            )?;
+        }
+
+        header(
+            f,
+            "fourword_errors_total",
+            "counter",
+            "Failed conversions by error kind.",
+        )?;
+        for (kind, count) in &registry.errors {
+            writeln!(f, "fourword_errors_total{{kind=\"{kind}\"}} {count}")?;
+        }
+
+        header(
+            f,
+            "fourword_http_requests_total",
+            "counter",
+            "HTTP requests by endpoint and status.",
+        )?;
+        for ((endpoint, status), count) in &registry.requests {
+            writeln!(
+                f,
+                "fourword_http_requests_total{{endpoint=\"{endpoint}\",status=\"{status}\"}} {count}"
+            )?;
+        }
+
+        let latency = &registry.latency;
+        header(
+            f,
+            "fourword_conversion_duration_seconds",
+            "histogram",
+            "Time taken by each conversion, successful or not.",
+        )?;
+        let mut cumulative = 0;
+        for (bound, count) in LATENCY_BUCKETS.iter().zip(latency.buckets) {
+            cumulative += count;
+            writeln!(
+                f,
+                "fourword_conversion_duration_seconds_bucket{{le=\"{bound}\"}} {cumulative}"
+            )?;
+        }
+        writeln!(
+            f,
+            "fourword_conversion_duration_seconds_bucket{{le=\"+Inf\"}} {}",
+            latency.count
+        )?;
+        writeln!(
+            f,
+            "fourword_conversion_duration_seconds_sum {}",
+            latency.sum
+        )?;
+        writeln!(
+            f,
+            "fourword_conversion_duration_seconds_count {}",
+            latency.count
+        )
+    }
+}
+
+/// Writes the `# HELP` and `# TYPE` lines introducing a metric
+fn header(f: &mut fmt::Formatter<'_>, name: &str, kind: &str, help: &str) -> fmt::Result {
+    writeln!(f, "# HELP {name} {help}")?;
+    writeln!(f, "# TYPE {name} {kind}")
+}
+
+/// Locks `mutex`, recovering it if a thread panicked while holding it.
+/// Every update is a single increment, so a poisoned lock is safe to reuse.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_conversions_errors_and_lossy_encodings() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let metrics = Metrics::new();
+
+        let words = metrics.convert(&encoder, "10.0.0.1:80").unwrap().words;
+        metrics.convert(&encoder, &words).unwrap();
+        metrics.convert(&encoder, "[fe80::1]:22").unwrap();
+        assert!(metrics.convert(&encoder, "999.1.1.1").is_err());
+        assert!(metrics.convert(&encoder, "ocean").is_err());
+
+        // A unique local address keeps its prefix but drops the interface ID
+        let lossy = metrics
+            .convert(&encoder, "[fd12:3456:789a:1::1234:5678]:443")
+            .unwrap();
+        assert!(!lossy.lossless);
+
+        assert_eq!(metrics.conversions(Direction::Encode, "ipv4"), 1);
+        assert_eq!(metrics.conversions(Direction::Decode, "ipv4"), 1);
+        assert_eq!(metrics.conversions(Direction::Encode, "link_local"), 1);
+        assert_eq!(metrics.lossy_encodings(lossy.category), 1);
+        assert_eq!(metrics.lossy_encodings("ipv4"), 0);
+        assert_eq!(metrics.errors("invalid_value"), 1);
+        assert_eq!(metrics.errors("wrong_word_count"), 1);
+
+        metrics.record_request("/encode", 200);
+        metrics.record_request("/encode", 200);
+        assert_eq!(metrics.requests("/encode", 200), 2);
+        assert_eq!(metrics.requests("/encode", 400), 0);
+    }
+
+    #[test]
+    fn test_render_exposition_format() {
+        let metrics = Metrics::new();
+        let empty = metrics.render();
+        assert!(empty.contains("# TYPE fourword_conversions_total counter\n"));
+        assert!(empty.contains("fourword_conversion_duration_seconds_bucket{le=\"+Inf\"} 0\n"));
+
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        metrics.convert(&encoder, "192.168.1.1:443").unwrap();
+        metrics.record_error(&FourWordError::unknown_word(0, "x"), Duration::from_secs(1));
+        metrics.record_request("/metrics", 200);
+
+        let text = metrics.render();
+        for line in [
+            r#"fourword_conversions_total{direction="encode",category="ipv4"} 1"#,
+            r#"fourword_errors_total{kind="unknown_word"} 1"#,
+            r#"fourword_http_requests_total{endpoint="/metrics",status="200"} 1"#,
+            r#"fourword_conversion_duration_seconds_bucket{le="+Inf"} 2"#,
+            "fourword_conversion_duration_seconds_count 2",
+        ] {
+            assert!(text.lines().any(|l| l == line), "missing {line}:\n{text}");
+        }
+        // The one-second error falls outside every finite bucket
+        assert!(text.contains(r#"fourword_conversion_duration_seconds_bucket{le="0.025"} 1"#));
+        assert!(text.ends_with('\n'));
+    }
+}
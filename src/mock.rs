@@ -0,0 +1,252 @@
+//! Scriptable encoder for testing code that consumes phrases.
+//!
+//! [`MockEncoder`] implements [`PhraseEncoder`] but answers from a script
+//! instead of the dictionary, so applications can exercise their handling of
+//! decode failures, fuzzy corrections and unknown words without crafting
+//! inputs that trigger them for real. Enable with the `mock` feature,
+//! typically from `[dev-dependencies]`.
+//!
+//! ```rust
+//! use four_word_networking::mock::MockEncoder;
+//! use four_word_networking::{FourWordError, PhraseEncoder};
+//!
+//! let mock = MockEncoder::new()
+//!     .on_decode("ocean thunder falcon star", "10.0.0.1:80")
+//!     .on_decode_error("broken phrase", || FourWordError::unknown_word(0, "broken"));
+//!
+//! assert_eq!(mock.decode("ocean thunder falcon star")?, "10.0.0.1:80");
+//! assert!(mock.decode("broken phrase").is_err());
+//! # Ok::<(), FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::encoder::PhraseEncoder;
+use crate::error::{FourWordError, Result};
+use crate::fuzzy::FuzzyDecoding;
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+/// Builds the error returned by a scripted failure
+type ErrorFactory = Box<dyn Fn() -> FourWordError + Send + Sync>;
+
+/// A scripted outcome, replayed on every matching call
+enum Response<T> {
+    Value(T),
+    Error(ErrorFactory),
+}
+
+impl<T: Clone> Response<T> {
+    fn replay(&self) -> Result<T> {
+        match self {
+            Response::Value(value) => Ok(value.clone()),
+            Response::Error(make_error) => Err(make_error()),
+        }
+    }
+}
+
+/// A call received by a [`MockEncoder`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockCall {
+    /// `encode(input)`
+    Encode(String),
+    /// `decode(words)`
+    Decode(String),
+    /// `decode_fuzzy(words, auto_correct)`
+    DecodeFuzzy(String, bool),
+}
+
+/// A [`PhraseEncoder`] that returns scripted phrases and errors.
+///
+/// Calls with no scripted response fail with [`FourWordError::Unsupported`].
+/// `decode_fuzzy` falls back to the `decode` script, reporting no corrections.
+#[derive(Default)]
+pub struct MockEncoder {
+    encodes: HashMap<String, Response<String>>,
+    decodes: HashMap<String, Response<String>>,
+    fuzzy_decodes: HashMap<String, Response<FuzzyDecoding>>,
+    calls: Mutex<Vec<MockCall>>,
+}
+
+impl MockEncoder {
+    /// Creates a mock with an empty script
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes `encode(input)` return `words`
+    pub fn on_encode(mut self, input: &str, words: &str) -> Self {
+        self.encodes
+            .insert(input.to_string(), Response::Value(words.to_string()));
+        self
+    }
+
+    /// Makes `encode(input)` fail with the error built by `make_error`
+    pub fn on_encode_error(
+        mut self,
+        input: &str,
+        make_error: impl Fn() -> FourWordError + Send + Sync + 'static,
+    ) -> Self {
+        self.encodes
+            .insert(input.to_string(), Response::Error(Box::new(make_error)));
+        self
+    }
+
+    /// Makes `decode(words)` return `address`
+    pub fn on_decode(mut self, words: &str, address: &str) -> Self {
+        self.decodes
+            .insert(words.to_string(), Response::Value(address.to_string()));
+        self
+    }
+
+    /// Makes `decode(words)` fail with the error built by `make_error`
+    pub fn on_decode_error(
+        mut self,
+        words: &str,
+        make_error: impl Fn() -> FourWordError + Send + Sync + 'static,
+    ) -> Self {
+        self.decodes
+            .insert(words.to_string(), Response::Error(Box::new(make_error)));
+        self
+    }
+
+    /// Makes `decode_fuzzy(words, _)` return `decoding`
+    pub fn on_decode_fuzzy(mut self, words: &str, decoding: FuzzyDecoding) -> Self {
+        self.fuzzy_decodes
+            .insert(words.to_string(), Response::Value(decoding));
+        self
+    }
+
+    /// Makes `decode_fuzzy(words, _)` fail with the error built by `make_error`
+    pub fn on_decode_fuzzy_error(
+        mut self,
+        words: &str,
+        make_error: impl Fn() -> FourWordError + Send + Sync + 'static,
+    ) -> Self {
+        self.fuzzy_decodes
+            .insert(words.to_string(), Response::Error(Box::new(make_error)));
+        self
+    }
+
+    /// Returns the calls received so far, in order
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn record(&self, call: MockCall) {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(call);
+    }
+}
+
+/// The error for a call nobody scripted
+fn unscripted(method: &str, input: &str) -> FourWordError {
+    FourWordError::unsupported(
+        "answer an unscripted call",
+        format!("MockEncoder has no {method} response scripted for '{input}'"),
+    )
+}
+
+impl PhraseEncoder for MockEncoder {
+    fn encode(&self, input: &str) -> Result<String> {
+        self.record(MockCall::Encode(input.to_string()));
+        self.encodes
+            .get(input)
+            .ok_or_else(|| unscripted("encode", input))?
+            .replay()
+    }
+
+    fn decode(&self, words: &str) -> Result<String> {
+        self.record(MockCall::Decode(words.to_string()));
+        self.decodes
+            .get(words)
+            .ok_or_else(|| unscripted("decode", words))?
+            .replay()
+    }
+
+    fn decode_fuzzy(&self, words: &str, auto_correct: bool) -> Result<FuzzyDecoding> {
+        self.record(MockCall::DecodeFuzzy(words.to_string(), auto_correct));
+        if let Some(response) = self.fuzzy_decodes.get(words) {
+            return response.replay();
+        }
+        let address = self
+            .decodes
+            .get(words)
+            .ok_or_else(|| unscripted("decode_fuzzy", words))?
+            .replay()?;
+        Ok(FuzzyDecoding {
+            address,
+            corrections: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzzy::WordCorrection;
+
+    #[test]
+    fn test_scripted_responses_and_call_log() {
+        let corrected = FuzzyDecoding {
+            address: "10.0.0.1:80".to_string(),
+            corrections: vec![WordCorrection {
+                position: 0,
+                original: "oshun".to_string(),
+                corrected: "ocean".to_string(),
+                distance: 3,
+            }],
+        };
+        let mock = MockEncoder::new()
+            .on_encode("10.0.0.1:80", "ocean thunder falcon star")
+            .on_decode("ocean thunder falcon star", "10.0.0.1:80")
+            .on_decode_fuzzy("oshun thunder falcon star", corrected.clone())
+            .on_decode_error("ocean", || FourWordError::wrong_word_count(&[4], 1));
+
+        assert_eq!(
+            mock.encode("10.0.0.1:80").unwrap(),
+            "ocean thunder falcon star"
+        );
+        assert_eq!(
+            mock.decode_fuzzy("oshun thunder falcon star", true)
+                .unwrap(),
+            corrected
+        );
+        // Fuzzy decoding falls back to the plain decode script
+        let plain = mock
+            .decode_fuzzy("ocean thunder falcon star", false)
+            .unwrap();
+        assert!(plain.corrections.is_empty());
+        // Errors replay on every call
+        for _ in 0..2 {
+            assert!(matches!(
+                mock.decode("ocean"),
+                Err(FourWordError::WrongWordCount { got: 1, .. })
+            ));
+        }
+        assert!(mock.encode("192.168.0.1").is_err());
+
+        assert_eq!(mock.calls().len(), 6);
+        assert_eq!(mock.calls()[0], MockCall::Encode("10.0.0.1:80".to_string()));
+    }
+
+    #[test]
+    fn test_real_and_mock_share_the_trait() {
+        fn round_trip(encoder: &dyn PhraseEncoder, address: &str) -> Result<String> {
+            encoder.decode(&encoder.encode(address)?)
+        }
+        let real = crate::FourWordAdaptiveEncoder::new().unwrap();
+        let words = real.encode("10.0.0.1:80").unwrap();
+        let mock = MockEncoder::new()
+            .on_encode("10.0.0.1:80", &words)
+            .on_decode(&words, "10.0.0.1:80");
+
+        assert_eq!(round_trip(&real, "10.0.0.1:80").unwrap(), "10.0.0.1:80");
+        assert_eq!(round_trip(&mock, "10.0.0.1:80").unwrap(), "10.0.0.1:80");
+    }
+}
@@ -0,0 +1,340 @@
+//! libp2p multiaddrs as word phrases.
+//!
+//! A multiaddr such as `/ip4/1.2.3.4/tcp/4001` becomes the address phrase
+//! followed by its protocol names, so the dial string stays readable:
+//!
+//! ```text
+//! /ip4/1.2.3.4/tcp/4001                    <address words> tcp
+//! /ip6/2001:db8::1/udp/4001/quic-v1         <address words> udp quic-v1
+//! /ip4/1.2.3.4/tcp/4001/p2p/12D3KooW...     <address words> tcp p2p <peer ID words>
+//! ```
+//!
+//! The protocol names are not dictionary words, so they also mark where the
+//! address ends. The peer ID's multihash bytes are encoded with the
+//! [`payload`](crate::payload) encoder. IPv6 addresses whose regular phrase
+//! cannot carry the port (see [`WordSocketAddr`]) are written as `ip6`
+//! followed by the 18 address and port bytes as payload words instead.
+//!
+//! ```rust
+//! use four_word_networking::multiaddr::{decode_multiaddr, encode_multiaddr};
+//!
+//! let words = encode_multiaddr("/ip4/1.2.3.4/tcp/4001")?;
+//! assert!(words.ends_with(" tcp"));
+//! assert_eq!(decode_multiaddr(&words)?, "/ip4/1.2.3.4/tcp/4001");
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::payload::{decode_bytes, encode_bytes};
+use crate::word_address::WordSocketAddr;
+use std::fmt;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::str::FromStr;
+
+/// Keyword introducing an IPv6 address written as payload words
+const IP6_KEYWORD: &str = "ip6";
+
+/// Keyword introducing the peer ID
+const P2P_KEYWORD: &str = "p2p";
+
+/// Transport protocol stack of a multiaddr
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transport {
+    /// `/tcp/<port>`
+    Tcp,
+    /// `/udp/<port>`
+    Udp,
+    /// `/udp/<port>/quic` (draft-29 QUIC)
+    Quic,
+    /// `/udp/<port>/quic-v1` (RFC 9000 QUIC)
+    QuicV1,
+}
+
+impl Transport {
+    /// Protocol names as they appear in the multiaddr and the phrase
+    fn names(self) -> &'static [&'static str] {
+        match self {
+            Transport::Tcp => &["tcp"],
+            Transport::Udp => &["udp"],
+            Transport::Quic => &["udp", "quic"],
+            Transport::QuicV1 => &["udp", "quic-v1"],
+        }
+    }
+
+    /// Parses the protocol after the port: `None` for plain TCP or UDP
+    fn with_suffix(self, suffix: Option<&str>) -> Result<Self> {
+        match (self, suffix) {
+            (transport, None) => Ok(transport),
+            (Transport::Udp, Some("quic")) => Ok(Transport::Quic),
+            (Transport::Udp, Some("quic-v1")) => Ok(Transport::QuicV1),
+            (_, Some(other)) => Err(FourWordError::invalid_value(
+                "multiaddr protocol",
+                other,
+                "expected quic or quic-v1",
+            )),
+        }
+    }
+
+    /// Parses `tcp` or `udp`
+    fn base(name: &str) -> Option<Self> {
+        match name {
+            "tcp" => Some(Transport::Tcp),
+            "udp" => Some(Transport::Udp),
+            _ => None,
+        }
+    }
+}
+
+/// A dialable libp2p multiaddr: IP, transport, port and optional peer ID
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Multiaddr {
+    /// IP address and port
+    pub addr: SocketAddr,
+    /// Transport protocol stack
+    pub transport: Transport,
+    /// Peer ID multihash bytes from a trailing `/p2p/` segment
+    pub peer_id: Option<Vec<u8>>,
+}
+
+impl Multiaddr {
+    /// Encodes the multiaddr as English words
+    pub fn to_words(&self) -> Result<String> {
+        let address = WordSocketAddr::new(self.addr)?;
+        let mut words = if address.addr() == self.addr {
+            address.phrase().to_string()
+        } else {
+            // The regular phrase would lose the port
+            let mut bytes = match self.addr.ip() {
+                IpAddr::V6(ip) => ip.octets().to_vec(),
+                IpAddr::V4(_) => unreachable!("IPv4 phrases always keep the port"),
+            };
+            bytes.extend_from_slice(&self.addr.port().to_be_bytes());
+            format!("{IP6_KEYWORD} {}", encode_bytes(&bytes)?)
+        };
+        for name in self.transport.names() {
+            words.push(' ');
+            words.push_str(name);
+        }
+        if let Some(peer_id) = &self.peer_id {
+            words.push_str(&format!(" {P2P_KEYWORD} {}", encode_bytes(peer_id)?));
+        }
+        Ok(words)
+    }
+
+    /// Decodes a phrase produced by [`to_words`](Self::to_words)
+    pub fn from_words(words: &str) -> Result<Self> {
+        let tokens: Vec<String> = words.split_whitespace().map(str::to_lowercase).collect();
+        let transport_at = tokens
+            .iter()
+            .position(|token| Transport::base(token).is_some())
+            .ok_or_else(|| {
+                FourWordError::invalid_value("multiaddr phrase", words, "no transport (tcp or udp)")
+            })?;
+        let (address, rest) = tokens.split_at(transport_at);
+
+        let addr = match address.split_first() {
+            Some((keyword, payload)) if keyword == IP6_KEYWORD => {
+                let bytes: [u8; 18] =
+                    decode_bytes(&payload.join(" "))?.try_into().map_err(|_| {
+                        FourWordError::NotEncoding {
+                            expected: "an IPv6 address and port",
+                        }
+                    })?;
+                let ip: [u8; 16] = bytes[..16].try_into().unwrap_or_default();
+                SocketAddr::new(
+                    Ipv6Addr::from(ip).into(),
+                    u16::from_be_bytes([bytes[16], bytes[17]]),
+                )
+            }
+            _ => WordSocketAddr::from_phrase(&address.join(" "))?.addr(),
+        };
+
+        let mut rest = rest.iter().map(String::as_str);
+        let base = rest.next().and_then(Transport::base);
+        let mut next = rest.next();
+        let suffix = next.filter(|&token| token != P2P_KEYWORD);
+        if suffix.is_some() {
+            next = rest.next();
+        }
+        let transport = base
+            .ok_or_else(|| FourWordError::invalid_value("multiaddr phrase", words, "no transport"))?
+            .with_suffix(suffix)?;
+
+        let peer_id = match next {
+            None => None,
+            Some(P2P_KEYWORD) => {
+                let peer_words: Vec<&str> = rest.collect();
+                Some(decode_bytes(&peer_words.join(" "))?)
+            }
+            Some(other) => {
+                return Err(FourWordError::invalid_value(
+                    "multiaddr phrase",
+                    words,
+                    format!("unexpected '{other}' after the transport"),
+                ));
+            }
+        };
+        Ok(Multiaddr {
+            addr,
+            transport,
+            peer_id,
+        })
+    }
+}
+
+impl FromStr for Multiaddr {
+    type Err = FourWordError;
+
+    /// Parses `/ip4|ip6/<ip>/tcp|udp/<port>[/quic|/quic-v1][/p2p/<base58 peer ID>]`
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| FourWordError::invalid_value("multiaddr", s, reason);
+        let mut parts = s
+            .strip_prefix('/')
+            .ok_or_else(|| invalid("must start with '/'"))?
+            .split('/');
+        let mut next = |what: &str| parts.next().ok_or_else(|| invalid(what));
+
+        let ip: IpAddr = match (next("missing protocol")?, next("missing IP address")?) {
+            ("ip4", ip) => IpAddr::V4(ip.parse().map_err(|_| invalid("invalid IPv4 address"))?),
+            ("ip6", ip) => IpAddr::V6(ip.parse().map_err(|_| invalid("invalid IPv6 address"))?),
+            _ => return Err(invalid("must start with /ip4 or /ip6")),
+        };
+        let base = Transport::base(next("missing transport")?)
+            .ok_or_else(|| invalid("transport must be tcp or udp"))?;
+        let port: u16 = next("missing port")?
+            .parse()
+            .map_err(|_| invalid("invalid port"))?;
+
+        let mut protocol = parts.next();
+        let suffix = protocol.filter(|&name| name != P2P_KEYWORD && name != "ipfs");
+        if suffix.is_some() {
+            protocol = parts.next();
+        }
+        let transport = base.with_suffix(suffix)?;
+
+        let peer_id = match protocol {
+            None => None,
+            Some(P2P_KEYWORD | "ipfs") => {
+                let id = parts.next().ok_or_else(|| invalid("missing peer ID"))?;
+                Some(
+                    bs58::decode(id)
+                        .into_vec()
+                        .map_err(|_| invalid("peer ID must be base58"))?,
+                )
+            }
+            Some(other) => return Err(invalid(&format!("unsupported protocol '{other}'"))),
+        };
+        if parts.next().is_some() {
+            return Err(invalid("unexpected trailing segments"));
+        }
+
+        Ok(Multiaddr {
+            addr: SocketAddr::new(ip, port),
+            transport,
+            peer_id,
+        })
+    }
+}
+
+impl fmt::Display for Multiaddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (family, port) = match self.addr {
+            SocketAddr::V4(_) => ("ip4", self.addr.port()),
+            SocketAddr::V6(_) => ("ip6", self.addr.port()),
+        };
+        let mut names = self.transport.names().iter();
+        write!(f, "/{family}/{}", self.addr.ip())?;
+        if let Some(base) = names.next() {
+            write!(f, "/{base}/{port}")?;
+        }
+        for name in names {
+            write!(f, "/{name}")?;
+        }
+        if let Some(peer_id) = &self.peer_id {
+            write!(f, "/p2p/{}", bs58::encode(peer_id).into_string())?;
+        }
+        Ok(())
+    }
+}
+
+/// Encodes a multiaddr string as English words
+pub fn encode_multiaddr(multiaddr: &str) -> Result<String> {
+    multiaddr.parse::<Multiaddr>()?.to_words()
+}
+
+/// Decodes words produced by [`encode_multiaddr`] back to the multiaddr string
+pub fn decode_multiaddr(words: &str) -> Result<String> {
+    Ok(Multiaddr::from_words(words)?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary4k::DICTIONARY;
+
+    /// An Ed25519 peer ID (identity multihash of the protobuf public key)
+    const PEER_ID: &str = "12D3KooWD3eckifWpRn9wQpMG9R9hX3sD158z7EqHWmweQAJU5SA";
+
+    #[test]
+    fn test_round_trips() {
+        for multiaddr in [
+            "/ip4/1.2.3.4/tcp/4001",
+            "/ip4/192.168.1.20/udp/9000",
+            "/ip4/10.0.0.1/udp/4001/quic",
+            "/ip6/::1/udp/4001/quic-v1",
+            "/ip6/fe80::1/tcp/443",
+            // Global unicast that the regular phrase cannot keep the port for
+            "/ip6/2606:4700:4700::1111/udp/443/quic-v1",
+            &format!("/ip4/1.2.3.4/tcp/4001/p2p/{PEER_ID}"),
+            &format!("/ip6/2001:db8:85a3::8a2e:370:7334/udp/4001/quic-v1/p2p/{PEER_ID}"),
+        ] {
+            let words = encode_multiaddr(multiaddr).unwrap();
+            assert_eq!(decode_multiaddr(&words).unwrap(), multiaddr, "{words}");
+        }
+    }
+
+    #[test]
+    fn test_phrase_layout() {
+        let words = encode_multiaddr("/ip4/1.2.3.4/tcp/4001").unwrap();
+        let address = crate::encode(([1, 2, 3, 4], 4001)).unwrap();
+        assert_eq!(words, format!("{address} tcp"));
+
+        let peer =
+            encode_multiaddr(&format!("/ip4/1.2.3.4/udp/4001/quic-v1/p2p/{PEER_ID}")).unwrap();
+        assert!(peer.starts_with(&format!("{address} udp quic-v1 p2p ")));
+    }
+
+    #[test]
+    fn test_keywords_are_not_dictionary_words() {
+        for keyword in ["tcp", "udp", "quic", "quic-v1", IP6_KEYWORD, P2P_KEYWORD] {
+            assert!(!DICTIONARY.contains(keyword), "{keyword}");
+        }
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        for multiaddr in [
+            "ip4/1.2.3.4/tcp/4001",
+            "/ip4/1.2.3.4",
+            "/ip4/1.2.3.4/sctp/4001",
+            "/ip4/1.2.3.4/tcp/4001/quic",
+            "/ip4/1.2.3.4/tcp/99999",
+            "/ip4/1.2.3.4/tcp/4001/p2p/0OIl",
+            "/dns4/example.com/tcp/443",
+        ] {
+            assert!(multiaddr.parse::<Multiaddr>().is_err(), "{multiaddr}");
+        }
+
+        let address = crate::encode(([1, 2, 3, 4], 4001)).unwrap();
+        for words in [
+            address.clone(),
+            format!("{address} quic"),
+            format!("{address} tcp ocean"),
+            format!("{address} tcp p2p"),
+        ] {
+            assert!(Multiaddr::from_words(&words).is_err(), "{words}");
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Well-known IPv6 multicast groups.
+//!
+//! A multicast address is `ff` followed by a flags/scope byte and a 112-bit
+//! group ID. The compressor keeps the flags/scope byte and replaces the group
+//! ID with an index into [`WELL_KNOWN_GROUPS`] when it has one, so `ff02::1`
+//! or `ff05::1:3` take 6 words instead of the 12-word fallback. Solicited-node
+//! groups (`ff02::1:ffXX:XXXX`) keep only their 24 low bits, and any other
+//! group drops the leading zero bytes of its ID.
+//!
+//! ```rust
+//! use four_word_networking::multicast::{group_name, scope_name};
+//!
+//! let ip = "ff02::fb".parse()?;
+//! assert_eq!(group_name(&ip), Some("mDNSv6"));
+//! assert_eq!(scope_name(&ip), Some("link-local"));
+//! # Ok::<(), std::net::AddrParseError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use std::net::Ipv6Addr;
+
+/// A group ID assigned by IANA, valid at any scope
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WellKnownGroup {
+    /// Low 32 bits of the group ID (the upper bits are zero)
+    pub id: u32,
+    /// Registry name
+    pub name: &'static str,
+}
+
+/// IANA-assigned groups, indexed by their position.
+///
+/// Phrases store the index, so entries may only ever be appended.
+pub const WELL_KNOWN_GROUPS: &[WellKnownGroup] = &[
+    WellKnownGroup {
+        id: 0x1,
+        name: "All Nodes",
+    },
+    WellKnownGroup {
+        id: 0x2,
+        name: "All Routers",
+    },
+    WellKnownGroup {
+        id: 0x5,
+        name: "OSPF Routers",
+    },
+    WellKnownGroup {
+        id: 0x6,
+        name: "OSPF Designated Routers",
+    },
+    WellKnownGroup {
+        id: 0x9,
+        name: "RIP Routers",
+    },
+    WellKnownGroup {
+        id: 0xa,
+        name: "EIGRP Routers",
+    },
+    WellKnownGroup {
+        id: 0xc,
+        name: "SSDP",
+    },
+    WellKnownGroup {
+        id: 0xd,
+        name: "All PIM Routers",
+    },
+    WellKnownGroup {
+        id: 0x12,
+        name: "VRRP",
+    },
+    WellKnownGroup {
+        id: 0x16,
+        name: "All MLDv2-capable Routers",
+    },
+    WellKnownGroup {
+        id: 0x1a,
+        name: "All RPL Nodes",
+    },
+    WellKnownGroup {
+        id: 0x6a,
+        name: "All Snoopers",
+    },
+    WellKnownGroup {
+        id: 0xfb,
+        name: "mDNSv6",
+    },
+    WellKnownGroup {
+        id: 0x101,
+        name: "NTP",
+    },
+    WellKnownGroup {
+        id: 0x181,
+        name: "PTP Primary",
+    },
+    WellKnownGroup {
+        id: 0x1_0002,
+        name: "All DHCP Agents",
+    },
+    WellKnownGroup {
+        id: 0x1_0003,
+        name: "All DHCP Servers / LLMNR",
+    },
+];
+
+/// Returns the 112-bit group ID of a multicast address
+pub fn group_id(ip: &Ipv6Addr) -> Option<u128> {
+    ip.is_multicast()
+        .then(|| u128::from(*ip) & (u128::MAX >> 16))
+}
+
+/// Returns the IANA name of a well-known multicast group
+pub fn group_name(ip: &Ipv6Addr) -> Option<&'static str> {
+    let index = group_index(group_id(ip)?)?;
+    Some(WELL_KNOWN_GROUPS[usize::from(index)].name)
+}
+
+/// Returns the name of a multicast address's scope (RFC 7346)
+pub fn scope_name(ip: &Ipv6Addr) -> Option<&'static str> {
+    if !ip.is_multicast() {
+        return None;
+    }
+    Some(match ip.octets()[1] & 0x0F {
+        0x1 => "interface-local",
+        0x2 => "link-local",
+        0x3 => "realm-local",
+        0x4 => "admin-local",
+        0x5 => "site-local",
+        0x8 => "organization-local",
+        0xE => "global",
+        _ => "reserved",
+    })
+}
+
+/// Position of a group ID in [`WELL_KNOWN_GROUPS`]
+pub(crate) fn group_index(id: u128) -> Option<u8> {
+    WELL_KNOWN_GROUPS
+        .iter()
+        .position(|group| u128::from(group.id) == id)
+        .and_then(|index| u8::try_from(index).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_ids_are_unique() {
+        for (i, group) in WELL_KNOWN_GROUPS.iter().enumerate() {
+            assert_eq!(
+                group_index(u128::from(group.id)),
+                Some(i as u8),
+                "{}",
+                group.name
+            );
+        }
+        assert!(WELL_KNOWN_GROUPS.len() <= 256);
+    }
+
+    #[test]
+    fn test_names_and_scopes() {
+        let all_nodes: Ipv6Addr = "ff02::1".parse().unwrap();
+        assert_eq!(group_name(&all_nodes), Some("All Nodes"));
+        assert_eq!(scope_name(&all_nodes), Some("link-local"));
+
+        let dhcp: Ipv6Addr = "ff05::1:3".parse().unwrap();
+        assert_eq!(group_name(&dhcp), Some("All DHCP Servers / LLMNR"));
+        assert_eq!(scope_name(&dhcp), Some("site-local"));
+
+        let unknown: Ipv6Addr = "ff0e::1234".parse().unwrap();
+        assert_eq!(group_name(&unknown), None);
+        assert_eq!(group_id(&unknown), Some(0x1234));
+        assert_eq!(scope_name(&unknown), Some("global"));
+
+        assert_eq!(group_id(&Ipv6Addr::LOCALHOST), None);
+        assert_eq!(scope_name(&Ipv6Addr::LOCALHOST), None);
+    }
+}
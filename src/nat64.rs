@@ -0,0 +1,196 @@
+//! IPv4-embedded IPv6 addresses (RFC 6052).
+//!
+//! NAT64 gateways synthesize IPv6 addresses by embedding an IPv4 address in
+//! a translation prefix. When the prefix is known to both sides, only the
+//! embedded IPv4 address needs to be encoded, so these addresses compress far
+//! better than the 128-bit fallback.
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+/// Prefix lengths permitted by RFC 6052 section 2.2
+pub const VALID_PREFIX_LENGTHS: [u8; 6] = [32, 40, 48, 56, 64, 96];
+
+/// A NAT64 translation prefix such as `64:ff9b:1::/48`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Nat64Prefix {
+    prefix: Ipv6Addr,
+    len: u8,
+}
+
+impl Nat64Prefix {
+    /// The well-known prefix `64:ff9b::/96` (RFC 6052 section 2.1), which
+    /// every decoder knows without registering it
+    pub const WELL_KNOWN: Nat64Prefix = Nat64Prefix {
+        prefix: Ipv6Addr::new(0x64, 0xff9b, 0, 0, 0, 0, 0, 0),
+        len: 96,
+    };
+
+    /// Creates a prefix, checking the length and that no bits are set past it
+    pub fn new(prefix: Ipv6Addr, len: u8) -> Result<Self> {
+        if !VALID_PREFIX_LENGTHS.contains(&len) {
+            return Err(FourWordError::out_of_range(
+                "NAT64 prefix length",
+                format!("one of {VALID_PREFIX_LENGTHS:?}"),
+                format!("/{len}"),
+            ));
+        }
+        let host_bits = u128::MAX.checked_shr(u32::from(len)).unwrap_or(0);
+        if u128::from(prefix) & host_bits != 0 {
+            return Err(FourWordError::invalid_value(
+                "NAT64 prefix",
+                &format!("{prefix}/{len}"),
+                "bits are set beyond the prefix length",
+            ));
+        }
+        Ok(Nat64Prefix { prefix, len })
+    }
+
+    /// Returns the prefix address
+    pub fn prefix(&self) -> Ipv6Addr {
+        self.prefix
+    }
+
+    /// Returns the prefix length in bits
+    pub fn len(&self) -> u8 {
+        self.len
+    }
+
+    /// Always false; a prefix has one of the RFC 6052 lengths
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Synthesizes the IPv6 address for `v4` under this prefix
+    pub fn embed(&self, v4: Ipv4Addr) -> Ipv6Addr {
+        let mut bytes = self.prefix.octets();
+        for (position, octet) in self.ipv4_positions().into_iter().zip(v4.octets()) {
+            bytes[position] = octet;
+        }
+        Ipv6Addr::from(bytes)
+    }
+
+    /// Extracts the embedded IPv4 address if `ip` was synthesized under this prefix.
+    ///
+    /// Only addresses [`embed`](Self::embed) could have produced are accepted:
+    /// the reserved octet (bits 64-71) and the suffix must be zero, so the
+    /// address can be rebuilt exactly from the IPv4 part alone.
+    pub fn extract(&self, ip: &Ipv6Addr) -> Option<Ipv4Addr> {
+        let bytes = ip.octets();
+        let positions = self.ipv4_positions();
+        let v4 = Ipv4Addr::new(
+            bytes[positions[0]],
+            bytes[positions[1]],
+            bytes[positions[2]],
+            bytes[positions[3]],
+        );
+        (self.embed(v4) == *ip).then_some(v4)
+    }
+
+    /// Byte offsets of the four IPv4 octets, skipping the reserved octet 8
+    fn ipv4_positions(&self) -> [usize; 4] {
+        let start = usize::from(self.len / 8);
+        let mut positions = [0; 4];
+        let mut offset = start;
+        for position in &mut positions {
+            if offset == 8 {
+                offset += 1;
+            }
+            *position = offset;
+            offset += 1;
+        }
+        positions
+    }
+}
+
+impl fmt::Display for Nat64Prefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.prefix, self.len)
+    }
+}
+
+impl FromStr for Nat64Prefix {
+    type Err = FourWordError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (prefix, len) = s.split_once('/').ok_or_else(|| {
+            FourWordError::invalid_value("NAT64 prefix", s, "expected ADDRESS/LENGTH")
+        })?;
+        let prefix = prefix
+            .parse()
+            .map_err(|_| FourWordError::invalid_value("NAT64 prefix", s, "invalid IPv6 address"))?;
+        let len = len.parse().map_err(|_| {
+            FourWordError::invalid_value("NAT64 prefix", s, "invalid prefix length")
+        })?;
+        Self::new(prefix, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc6052_examples() {
+        // Table from RFC 6052 section 2.4, embedding 192.0.2.33
+        let v4 = Ipv4Addr::new(192, 0, 2, 33);
+        let cases = [
+            ("2001:db8::/32", "2001:db8:c000:221::"),
+            ("2001:db8:100::/40", "2001:db8:1c0:2:21::"),
+            ("2001:db8:122::/48", "2001:db8:122:c000:2:2100::"),
+            ("2001:db8:122:300::/56", "2001:db8:122:3c0:0:221::"),
+            ("2001:db8:122:344::/64", "2001:db8:122:344:c0:2:2100:0"),
+            ("2001:db8:122:344::/96", "2001:db8:122:344::192.0.2.33"),
+        ];
+        for (prefix, expected) in cases {
+            let prefix: Nat64Prefix = prefix.parse().unwrap();
+            let expected: Ipv6Addr = expected.parse().unwrap();
+            assert_eq!(prefix.embed(v4), expected, "{prefix}");
+            assert_eq!(prefix.extract(&expected), Some(v4), "{prefix}");
+        }
+    }
+
+    #[test]
+    fn test_extract_rejects_foreign_addresses() {
+        let prefix: Nat64Prefix = "2001:db8:122::/48".parse().unwrap();
+        // Different prefix
+        assert_eq!(
+            prefix.extract(&"2001:db8:123:c000:2:2100::".parse().unwrap()),
+            None
+        );
+        // Non-zero reserved octet
+        assert_eq!(
+            prefix.extract(&"2001:db8:122:c000:2:2101::".parse().unwrap()),
+            None
+        );
+        // Non-zero suffix
+        assert_eq!(
+            prefix.extract(&"2001:db8:122:c000:2:2100::1".parse().unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_well_known_prefix() {
+        assert_eq!(
+            Nat64Prefix::WELL_KNOWN,
+            "64:ff9b::/96".parse::<Nat64Prefix>().unwrap()
+        );
+        let ip: Ipv6Addr = "64:ff9b::192.0.2.33".parse().unwrap();
+        assert_eq!(
+            Nat64Prefix::WELL_KNOWN.extract(&ip),
+            Some(Ipv4Addr::new(192, 0, 2, 33))
+        );
+    }
+
+    #[test]
+    fn test_invalid_prefixes() {
+        assert!("2001:db8::/33".parse::<Nat64Prefix>().is_err());
+        assert!("2001:db8::1/96".parse::<Nat64Prefix>().is_err());
+        assert!("2001:db8::".parse::<Nat64Prefix>().is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! Async dialing by phrase on tokio.
+//!
+//! [`connect_tcp`] and [`bind_udp`] decode an English phrase (see
+//! [`WordSocketAddr`] for the accepted forms, including a `:port` suffix) and
+//! open a tokio socket on the result. Failures are reported as [`NetError`],
+//! which keeps a phrase that does not decode apart from an address that
+//! refuses the connection:
+//!
+//! ```rust,no_run
+//! use four_word_networking::net::{NetError, connect_tcp};
+//!
+//! # async fn dial() {
+//! match connect_tcp("ocean thunder falcon star").await {
+//!     Ok(_stream) => println!("connected"),
+//!     Err(NetError::Connect { addr, source }) => eprintln!("{addr} is unreachable: {source}"),
+//!     Err(e) => eprintln!("check the words: {e}"),
+//! }
+//! # }
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::FourWordError;
+use crate::word_address::WordSocketAddr;
+use std::io;
+use std::net::SocketAddr;
+use thiserror::Error;
+use tokio::net::{TcpStream, UdpSocket};
+
+/// Port that marks a phrase encoded without one
+const NO_PORT: u16 = 65535;
+
+/// Result type for the [`net`](self) helpers
+pub type NetResult<T> = std::result::Result<T, NetError>;
+
+/// Why a phrase could not be dialled or bound
+#[derive(Error, Debug)]
+pub enum NetError {
+    /// The phrase is not a valid word address
+    #[error("Cannot decode '{words}': {source}")]
+    Decode {
+        words: String,
+        #[source]
+        source: FourWordError,
+    },
+
+    /// The phrase decodes to an address without a port, which cannot be dialled
+    #[error("'{0}' has no port; add one as a ':port' suffix")]
+    NoPort(String),
+
+    /// The address decoded but the connection failed
+    #[error("Cannot connect to {addr}: {source}")]
+    Connect {
+        addr: SocketAddr,
+        #[source]
+        source: io::Error,
+    },
+
+    /// The address decoded but the socket could not be bound
+    #[error("Cannot bind {addr}: {source}")]
+    Bind {
+        addr: SocketAddr,
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// Decodes `words`, keeping the phrase for the error message
+fn resolve(words: &str) -> NetResult<SocketAddr> {
+    WordSocketAddr::from_phrase(words)
+        .map(SocketAddr::from)
+        .map_err(|source| NetError::Decode {
+            words: words.to_string(),
+            source,
+        })
+}
+
+/// Decodes `words` and opens a TCP connection to the address
+pub async fn connect_tcp(words: &str) -> NetResult<TcpStream> {
+    let addr = resolve(words)?;
+    if addr.port() == NO_PORT {
+        return Err(NetError::NoPort(words.to_string()));
+    }
+    TcpStream::connect(addr)
+        .await
+        .map_err(|source| NetError::Connect { addr, source })
+}
+
+/// Decodes `words` and binds a UDP socket to the address.
+///
+/// A phrase without a port binds an ephemeral port on the decoded address.
+pub async fn bind_udp(words: &str) -> NetResult<UdpSocket> {
+    let mut addr = resolve(words)?;
+    if addr.port() == NO_PORT {
+        addr.set_port(0);
+    }
+    UdpSocket::bind(addr)
+        .await
+        .map_err(|source| NetError::Bind { addr, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_connect_tcp_by_phrase() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let words = crate::encode(listener.local_addr().unwrap()).unwrap();
+
+        let stream = connect_tcp(&words).await.unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), listener.local_addr().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_errors_distinguish_decode_from_connect() {
+        assert!(matches!(
+            connect_tcp("not real words").await,
+            Err(NetError::Decode { .. })
+        ));
+
+        let portless = crate::encode(([127, 0, 0, 1], NO_PORT)).unwrap();
+        assert!(matches!(
+            connect_tcp(&portless).await,
+            Err(NetError::NoPort(_))
+        ));
+
+        // Nothing listens on port 1
+        let closed = crate::encode(([127, 0, 0, 1], 1)).unwrap();
+        assert!(matches!(
+            connect_tcp(&closed).await,
+            Err(NetError::Connect { addr, .. }) if addr.port() == 1
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_bind_udp_by_phrase() {
+        let portless = crate::encode(([127, 0, 0, 1], NO_PORT)).unwrap();
+        let socket = bind_udp(&portless).await.unwrap();
+        let local = socket.local_addr().unwrap();
+        assert_eq!(local.ip(), std::net::IpAddr::from([127, 0, 0, 1]));
+        assert_ne!(local.port(), 0);
+
+        // The port is now taken
+        let words = crate::encode(local).unwrap();
+        assert!(matches!(bind_udp(&words).await, Err(NetError::Bind { .. })));
+    }
+}
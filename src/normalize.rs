@@ -0,0 +1,244 @@
+//! Normalization of spoken or transcribed word phrases.
+//!
+//! Speech-to-text output rarely matches the dictionary exactly: words come
+//! back capitalised, with punctuation, pluralised, or as a homophone of the
+//! intended word ("ate" for "eight"). [`VoiceNormalizer`] folds such input
+//! back onto the words of the wordlist in use before decoding.
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::wordlist::Wordlist;
+use std::collections::HashMap;
+
+/// Homophone groups resolved automatically when exactly one member is in
+/// the wordlist. Groups with several dictionary members (e.g. "for" and
+/// "four") are ambiguous and need an explicit mapping.
+pub(crate) const HOMOPHONE_GROUPS: &[&[&str]] = &[
+    &["eight", "ate", "8"],
+    &["one", "won", "1"],
+    &["zero", "0"],
+    &["three", "3"],
+    &["five", "5"],
+    &["six", "6"],
+    &["seven", "7"],
+    &["nine", "9"],
+    &["ten", "10"],
+    &["new", "knew", "gnu"],
+    &["wait", "weight"],
+    &["flower", "flour"],
+    &["road", "rode", "rowed"],
+    &["break", "brake"],
+    &["blue", "blew"],
+    &["gray", "grey"],
+    &["fourty", "forty"],
+    &["night", "knight"],
+    &["maid", "made"],
+    &["plane", "plain"],
+    &["weak", "week"],
+];
+
+/// Configurable normalization applied to phrases before decoding
+#[derive(Debug, Clone)]
+pub struct VoiceNormalizer {
+    /// Strip punctuation such as trailing full stops and commas from words
+    pub strip_punctuation: bool,
+    /// Fold plurals ("oceans", "berries") onto their dictionary stems
+    pub fold_plurals: bool,
+    /// Resolve the built-in homophone groups when unambiguous
+    pub builtin_homophones: bool,
+    /// Explicit heard -> dictionary word mappings, applied before anything else
+    homophones: HashMap<String, String>,
+}
+
+impl Default for VoiceNormalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VoiceNormalizer {
+    /// Creates a normalizer with every folding step enabled and no custom mappings
+    pub fn new() -> Self {
+        VoiceNormalizer {
+            strip_punctuation: true,
+            fold_plurals: true,
+            builtin_homophones: true,
+            homophones: HashMap::new(),
+        }
+    }
+
+    /// Maps a transcribed word onto a word of `wordlist`.
+    ///
+    /// Explicit mappings take precedence over dictionary matches, which is how
+    /// a deployment resolves ambiguous pairs such as "for" -> "four".
+    pub fn add_homophone<W: Wordlist + ?Sized>(
+        &mut self,
+        wordlist: &W,
+        heard: &str,
+        canonical: &str,
+    ) -> Result<()> {
+        if !wordlist.contains(&canonical.to_lowercase()) {
+            return Err(FourWordError::unknown_word(0, canonical));
+        }
+        self.homophones
+            .insert(heard.to_lowercase(), canonical.to_lowercase());
+        Ok(())
+    }
+
+    /// Normalizes a whole phrase into space-separated words of `wordlist`
+    pub fn normalize<W: Wordlist + ?Sized>(&self, wordlist: &W, phrase: &str) -> String {
+        phrase
+            .split(|c: char| c.is_whitespace() || ".-_".contains(c))
+            .map(|token| self.normalize_word(wordlist, token))
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Normalizes a single token, returning it unchanged (but lowercased) if no rule applies
+    pub fn normalize_word<W: Wordlist + ?Sized>(&self, wordlist: &W, token: &str) -> String {
+        let mut word = token.to_lowercase();
+        if self.strip_punctuation {
+            word.retain(|c| c.is_alphanumeric());
+        }
+
+        if let Some(canonical) = self.homophones.get(&word) {
+            return canonical.clone();
+        }
+        if word.is_empty() || wordlist.contains(&word) {
+            return word;
+        }
+        if self.builtin_homophones
+            && let Some(canonical) = resolve_homophone(wordlist, &word)
+        {
+            return canonical.to_string();
+        }
+        if self.fold_plurals
+            && let Some(stem) = Self::fold_plural(wordlist, &word)
+        {
+            return stem;
+        }
+        word
+    }
+
+    /// Tries common plural endings until one lands on a word of `wordlist`
+    fn fold_plural<W: Wordlist + ?Sized>(wordlist: &W, word: &str) -> Option<String> {
+        let mut candidates = Vec::new();
+        if let Some(stem) = word.strip_suffix("ies") {
+            candidates.push(format!("{stem}y"));
+        }
+        if let Some(stem) = word.strip_suffix("es") {
+            candidates.push(stem.to_string());
+        }
+        if let Some(stem) = word.strip_suffix('s') {
+            candidates.push(stem.to_string());
+        }
+        // Dictionary words that are themselves plural ("clothes") heard as singular
+        candidates.push(format!("{word}s"));
+        candidates.push(format!("{word}es"));
+
+        candidates
+            .into_iter()
+            .find(|candidate| wordlist.contains(candidate))
+    }
+}
+
+/// Returns the sole member of `wordlist` in the homophone group containing `word`
+pub(crate) fn resolve_homophone<W: Wordlist + ?Sized>(
+    wordlist: &W,
+    word: &str,
+) -> Option<&'static str> {
+    let group = HOMOPHONE_GROUPS
+        .iter()
+        .find(|group| group.contains(&word))?;
+    let mut members = group.iter().filter(|member| wordlist.contains(member));
+    let canonical = members.next()?;
+    members.next().is_none().then_some(*canonical)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary4k::DICTIONARY;
+
+    #[test]
+    fn test_casing_and_punctuation() {
+        let normalizer = VoiceNormalizer::new();
+        assert_eq!(
+            normalizer.normalize(&DICTIONARY, "Ocean, Thunder. Falcon!"),
+            "ocean thunder falcon"
+        );
+    }
+
+    #[test]
+    fn test_plural_folding() {
+        let normalizer = VoiceNormalizer::new();
+        assert_eq!(normalizer.normalize_word(&DICTIONARY, "oceans"), "ocean");
+        assert_eq!(normalizer.normalize_word(&DICTIONARY, "Trees"), "tree");
+
+        let strict = VoiceNormalizer {
+            fold_plurals: false,
+            ..VoiceNormalizer::new()
+        };
+        assert_eq!(strict.normalize_word(&DICTIONARY, "oceans"), "oceans");
+    }
+
+    #[test]
+    fn test_builtin_homophones() {
+        let normalizer = VoiceNormalizer::new();
+        assert_eq!(normalizer.normalize_word(&DICTIONARY, "ate"), "eight");
+        assert_eq!(normalizer.normalize_word(&DICTIONARY, "grey"), "gray");
+        assert_eq!(normalizer.normalize_word(&DICTIONARY, "8"), "eight");
+        // Both "for" and "four" are dictionary words, so nothing changes by default
+        assert_eq!(normalizer.normalize_word(&DICTIONARY, "for"), "for");
+    }
+
+    #[test]
+    fn test_custom_homophone_overrides_dictionary() {
+        let mut normalizer = VoiceNormalizer::new();
+        normalizer
+            .add_homophone(&DICTIONARY, "for", "four")
+            .unwrap();
+        normalizer.add_homophone(&DICTIONARY, "4", "four").unwrap();
+        assert_eq!(normalizer.normalize(&DICTIONARY, "for 4"), "four four");
+
+        assert!(
+            normalizer
+                .add_homophone(&DICTIONARY, "x", "notarealword")
+                .is_err()
+        );
+    }
+
+    /// A wordlist of a handful of words, standing in for another language
+    struct Words(&'static [&'static str]);
+
+    impl Wordlist for Words {
+        fn get_word(&self, index: u16) -> Option<&str> {
+            self.0.get(usize::from(index)).copied()
+        }
+
+        fn get_index(&self, word: &str) -> Option<u16> {
+            self.0.iter().position(|w| *w == word).map(|i| i as u16)
+        }
+
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    #[test]
+    fn test_folds_onto_the_wordlist_in_use() {
+        let spanish = Words(&["casa", "gris", "ocho"]);
+        let normalizer = VoiceNormalizer::new();
+        assert_eq!(normalizer.normalize(&spanish, "Casas, gris"), "casa gris");
+        // English homophones and stems never produce words outside the list
+        assert_eq!(normalizer.normalize_word(&spanish, "grey"), "grey");
+        assert_eq!(normalizer.normalize_word(&spanish, "oceans"), "oceans");
+
+        let mut normalizer = VoiceNormalizer::new();
+        assert!(normalizer.add_homophone(&spanish, "8", "eight").is_err());
+        normalizer.add_homophone(&spanish, "8", "ocho").unwrap();
+        assert_eq!(normalizer.normalize_word(&spanish, "8"), "ocho");
+    }
+}
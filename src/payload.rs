@@ -0,0 +1,127 @@
+//! Arbitrary byte strings as words.
+//!
+//! Each word carries 12 bits of the payload, least significant bit first, as
+//! in the IPv6 group encoding. The bits are followed by a single `1` marker
+//! bit and zero padding to the next word, so the payload length needs no
+//! header: `n` bytes take `ceil((8n + 1) / 12)` words. Decoding rejects
+//! phrases whose marker is missing or misplaced, which catches most dropped
+//! or extra words.
+//!
+//! ```rust
+//! use four_word_networking::payload::{decode_bytes, encode_bytes, word_count};
+//!
+//! let words = encode_bytes(b"hello")?;
+//! assert_eq!(words.split(' ').count(), word_count(5));
+//! assert_eq!(decode_bytes(&words)?, b"hello");
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::four_word_ipv6_encoder::{read_index, write_index};
+use crate::language::Language;
+use crate::phrase::write_words;
+use crate::wordlist::Wordlist;
+
+/// Number of words encoding a payload of `len` bytes
+pub const fn word_count(len: usize) -> usize {
+    (8 * len + 1).div_ceil(12)
+}
+
+/// Encodes `bytes` as English words
+pub fn encode_bytes(bytes: &[u8]) -> Result<String> {
+    encode_bytes_with(&Language::English.dictionary(), bytes)
+}
+
+/// Decodes English words produced by [`encode_bytes`]
+pub fn decode_bytes(words: &str) -> Result<Vec<u8>> {
+    decode_bytes_with(&Language::English.dictionary(), words)
+}
+
+/// Encodes `bytes` as space-separated words from `wordlist`
+pub fn encode_bytes_with<W: Wordlist + ?Sized>(wordlist: &W, bytes: &[u8]) -> Result<String> {
+    let mut words = String::new();
+    write_words(wordlist, &bytes_to_indices(bytes), &mut words)?;
+    Ok(words)
+}
+
+/// Decodes words from `wordlist`, separated by whitespace, dots or dashes
+pub fn decode_bytes_with<W: Wordlist + ?Sized>(wordlist: &W, words: &str) -> Result<Vec<u8>> {
+    let indices = words
+        .split(|c: char| c.is_whitespace() || c == '.' || c == '-')
+        .filter(|word| !word.is_empty())
+        .enumerate()
+        .map(|(position, word)| {
+            wordlist
+                .get_index(word)
+                .ok_or_else(|| FourWordError::unknown_word(position, word))
+        })
+        .collect::<Result<Vec<u16>>>()?;
+    indices_to_bytes(&indices)
+}
+
+/// Packs `bytes` and the end marker into word indices
+pub(crate) fn bytes_to_indices(bytes: &[u8]) -> Vec<u16> {
+    let count = word_count(bytes.len());
+    let mut buffer = vec![0u8; (count * 12).div_ceil(8)];
+    buffer[..bytes.len()].copy_from_slice(bytes);
+    buffer[bytes.len()] = 1;
+    (0..count).map(|word| read_index(&buffer, word)).collect()
+}
+
+/// Inverse of [`bytes_to_indices`]
+pub(crate) fn indices_to_bytes(indices: &[u16]) -> Result<Vec<u8>> {
+    let mut buffer = vec![0u8; (indices.len() * 12).div_ceil(8)];
+    for (word, &index) in indices.iter().enumerate() {
+        write_index(&mut buffer, word, index);
+    }
+
+    // The marker is the last set bit; it must start a byte in the last word
+    let invalid = || FourWordError::NotEncoding {
+        expected: "a byte payload",
+    };
+    let marker = buffer
+        .iter()
+        .rposition(|&byte| byte != 0)
+        .ok_or_else(invalid)?;
+    if buffer[marker] != 1 || word_count(marker) != indices.len() {
+        return Err(invalid());
+    }
+    buffer.truncate(marker);
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_all_lengths() {
+        for len in 0..=40 {
+            let bytes: Vec<u8> = (0..len).map(|i| (i * 37 + 11) as u8).collect();
+            let words = encode_bytes(&bytes).unwrap();
+            assert_eq!(words.split(' ').count(), word_count(len), "{len}");
+            assert_eq!(decode_bytes(&words).unwrap(), bytes, "{len}");
+        }
+    }
+
+    #[test]
+    fn test_marker_catches_wrong_word_count() {
+        let words = encode_bytes(&[0xff; 9]).unwrap();
+        let mut list: Vec<&str> = words.split(' ').collect();
+        let first = list[0];
+        list.push(first);
+        assert!(decode_bytes(&list.join(" ")).is_err());
+        assert!(decode_bytes(&list[..list.len() - 2].join(" ")).is_err());
+        assert!(decode_bytes("").is_err());
+        assert!(decode_bytes("notaword").is_err());
+    }
+
+    #[test]
+    fn test_separators_and_case() {
+        let words = encode_bytes(b"peer").unwrap();
+        let dashed = words.to_uppercase().replace(' ', "-");
+        assert_eq!(decode_bytes(&dashed).unwrap(), b"peer");
+    }
+}
@@ -0,0 +1,275 @@
+//! Phonetic read-back of phrases for voice links.
+//!
+//! Over a crackling radio or phone line, "falcon" and "falter" sound alike.
+//! The read-back form spells every word in the ICAO/NATO alphabet and says the
+//! port digit by digit, in groups:
+//!
+//! ```text
+//! ocean: Oscar Charlie Echo Alfa November, thunder: Tango Hotel ..., port: Four Four Three
+//! ```
+//!
+//! [`read_back`] writes it and [`parse_read_back`] reads it back, case and
+//! punctuation aside. A segment may be the word alone, the spelling alone, or
+//! both, in which case they must agree; the port segment is optional and, when
+//! present, lets the listener confirm the port the words carry. Spoken digits
+//! follow ICAO (`Niner`), and common variants such as `Alpha`, `Juliet` and
+//! `X-ray` are accepted. Letters outside a–z, from non-English wordlists, are
+//! spoken as themselves.
+//!
+//! Selected with [`Alphabet::Phonetic`](crate::format::Alphabet) on a
+//! [`Formatter`](crate::format::Formatter).
+//!
+//! ```rust
+//! use four_word_networking::phonetic::{parse_read_back, read_back};
+//!
+//! let spoken = read_back(&["sun", "sky"], Some(8080));
+//! assert_eq!(
+//!     spoken,
+//!     "sun: Sierra Uniform November, sky: Sierra Kilo Yankee, port: Eight Zero - Eight Zero"
+//! );
+//! let (words, port) = parse_read_back("SUN, sierra kilo yankee, port 8080")?;
+//! assert_eq!((words, port), (vec!["sun".to_string(), "sky".to_string()], Some(8080)));
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+
+/// Code words for `a` to `z`
+const LETTERS: [&str; 26] = [
+    "Alfa", "Bravo", "Charlie", "Delta", "Echo", "Foxtrot", "Golf", "Hotel", "India", "Juliett",
+    "Kilo", "Lima", "Mike", "November", "Oscar", "Papa", "Quebec", "Romeo", "Sierra", "Tango",
+    "Uniform", "Victor", "Whiskey", "Xray", "Yankee", "Zulu",
+];
+
+/// Accepted alternative spellings, with the letter each stands for
+const LETTER_VARIANTS: [(&str, char); 3] = [("alpha", 'a'), ("juliet", 'j'), ("whisky", 'w')];
+
+/// Spoken digits `0` to `9`
+const DIGITS: [&str; 10] = [
+    "Zero", "One", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Niner",
+];
+
+/// Introduces the port segment
+const PORT_LABEL: &str = "port";
+
+/// Spells `word` in the phonetic alphabet, one code word per letter
+pub fn spell(word: &str) -> String {
+    let mut spelled = Vec::with_capacity(word.len());
+    for c in word.chars() {
+        let lower = c.to_ascii_lowercase();
+        match lower {
+            'a'..='z' => spelled.push(LETTERS[usize::from(lower as u8 - b'a')].to_string()),
+            _ => spelled.push(c.to_string()),
+        }
+    }
+    spelled.join(" ")
+}
+
+/// Says `port` digit by digit: up to three digits as one group, four as two
+/// pairs, five as a pair then three
+pub fn say_port(port: u16) -> String {
+    let digits: Vec<&str> = port
+        .to_string()
+        .bytes()
+        .map(|digit| DIGITS[usize::from(digit - b'0')])
+        .collect();
+    let split = match digits.len() {
+        4 | 5 => 2,
+        _ => digits.len(),
+    };
+    let (first, rest) = digits.split_at(split);
+    if rest.is_empty() {
+        first.join(" ")
+    } else {
+        format!("{} - {}", first.join(" "), rest.join(" "))
+    }
+}
+
+/// The read-back form of a phrase's words and, if it has one, its port
+pub fn read_back(words: &[&str], port: Option<u16>) -> String {
+    let mut segments: Vec<String> = words
+        .iter()
+        .map(|word| format!("{word}: {}", spell(word)))
+        .collect();
+    if let Some(port) = port {
+        segments.push(format!("{PORT_LABEL}: {}", say_port(port)));
+    }
+    segments.join(", ")
+}
+
+/// Reads the words and the optional port back from a read-back.
+///
+/// Words are returned in lower case.
+pub fn parse_read_back(text: &str) -> Result<(Vec<String>, Option<u16>)> {
+    let mut words = Vec::new();
+    let mut port = None;
+    for segment in text.split([',', '\n']) {
+        let (label, spoken) = match segment.split_once(':') {
+            Some((label, spoken)) => (tokens(label), tokens(spoken)),
+            None => {
+                // Without a colon, a leading word is one its spelling follows
+                let mut spoken = tokens(segment);
+                let label = if is_labelled(&spoken) {
+                    vec![spoken.remove(0)]
+                } else {
+                    Vec::new()
+                };
+                (label, spoken)
+            }
+        };
+        match label.as_slice() {
+            [] if spoken.is_empty() => continue,
+            [label] if label == PORT_LABEL => {
+                if port.is_some() {
+                    return Err(invalid(segment.trim(), "the port is given twice"));
+                }
+                port = Some(parse_port(&spoken)?);
+            }
+            [] => words.push(spelled_word(&spoken)?),
+            [word] if spoken.is_empty() => words.push(word.clone()),
+            [word] => {
+                let spelled = spelled_word(&spoken)?;
+                if *word != spelled {
+                    return Err(invalid(
+                        segment.trim(),
+                        format!("'{word}' is spelled '{spelled}'"),
+                    ));
+                }
+                words.push(spelled);
+            }
+            _ => {
+                return Err(invalid(segment.trim(), "expected one word"));
+            }
+        }
+    }
+    if words.is_empty() {
+        return Err(invalid(text, "no words"));
+    }
+    Ok((words, port))
+}
+
+/// Whether `text` looks like a read-back: it has comma-separated segments,
+/// which phrases never do (a comma after a phrase is only punctuation)
+pub fn is_read_back(text: &str) -> bool {
+    text.trim_matches(|c: char| !c.is_alphanumeric())
+        .contains(',')
+}
+
+/// Whether the first of `tokens` is a word or the port label rather than the
+/// start of a spelling
+fn is_labelled(tokens: &[String]) -> bool {
+    match tokens {
+        [] => false,
+        [_] => true,
+        [first, spelling @ ..] => {
+            first == PORT_LABEL || spelled_word(spelling).is_ok_and(|word| word == *first)
+        }
+    }
+}
+
+/// Lower-case tokens of `text` with punctuation such as the dash in `X-ray`
+/// removed
+fn tokens(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|token| {
+            token
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+/// The letter a code word stands for; a single character stands for itself
+fn letter(token: &str) -> Option<char> {
+    LETTERS
+        .iter()
+        .position(|code| code.eq_ignore_ascii_case(token))
+        .map(|index| char::from(b'a' + index as u8))
+        .or_else(|| {
+            LETTER_VARIANTS
+                .iter()
+                .find(|(variant, _)| *variant == token)
+                .map(|&(_, letter)| letter)
+        })
+        .or_else(|| {
+            let mut chars = token.chars();
+            chars.next().filter(|_| chars.next().is_none())
+        })
+}
+
+fn spelled_word(spoken: &[String]) -> Result<String> {
+    spoken
+        .iter()
+        .map(|token| letter(token).ok_or_else(|| invalid(token, "not a phonetic letter")))
+        .collect()
+}
+
+fn parse_port(spoken: &[String]) -> Result<u16> {
+    let mut digits = String::new();
+    for token in spoken {
+        if token.chars().all(|c| c.is_ascii_digit()) {
+            digits.push_str(token);
+        } else if token == "nine" {
+            digits.push('9');
+        } else {
+            let digit = DIGITS
+                .iter()
+                .position(|spoken| spoken.eq_ignore_ascii_case(token))
+                .ok_or_else(|| invalid(token, "not a spoken digit"))?;
+            digits.push(char::from(b'0' + digit as u8));
+        }
+    }
+    digits.parse().map_err(|_| invalid(&digits, "not a port"))
+}
+
+fn invalid(value: &str, reason: impl Into<String>) -> FourWordError {
+    FourWordError::invalid_value("read-back", value, reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let words = ["ocean", "thunder", "falcon", "star"];
+        for port in [None, Some(0), Some(7), Some(443), Some(8080), Some(65000)] {
+            let spoken = read_back(&words, port);
+            let (parsed, parsed_port) = parse_read_back(&spoken).unwrap();
+            assert_eq!(
+                (parsed, parsed_port),
+                (words.map(String::from).to_vec(), port)
+            );
+        }
+        assert_eq!(say_port(65000), "Six Five - Zero Zero Zero");
+        assert_eq!(say_port(9), "Niner");
+        assert_eq!(spell("Año"), "Alfa ñ Oscar");
+    }
+
+    #[test]
+    fn test_lenient_parsing() {
+        let (words, port) =
+            parse_read_back("Oscar Charlie Echo Alpha November; , thunder,\nport: four four nine")
+                .unwrap();
+        assert_eq!(words, ["ocean", "thunder"]);
+        assert_eq!(port, Some(449));
+
+        let (words, _) = parse_read_back("ox: Oscar X-ray, ab: a b").unwrap();
+        assert_eq!(words, ["ox", "ab"]);
+
+        // Words that are also code words, with and without their spelling
+        let (words, _) =
+            parse_read_back("delta Delta Echo Lima Tango Alfa, echo, Echo C H O").unwrap();
+        assert_eq!(words, ["delta", "echo", "echo"]);
+
+        assert!(parse_read_back("ocean: Oscar Charlie").is_err());
+        assert!(parse_read_back("ocean: Oscar Banana").is_err());
+        assert!(parse_read_back("port: one, port: two, sun").is_err());
+        assert!(parse_read_back("port: Seven").is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//! Allocation-free phrase output.
+//!
+//! [`WordPhrase`] is a fixed-capacity, stack-allocated string for encoded
+//! phrases. Together with the `encode_into` methods on the encoders it lets
+//! embedded and high-throughput callers encode without touching the heap.
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::wordlist::Wordlist;
+use std::fmt;
+use std::ops::Deref;
+
+/// Default [`WordPhrase`] capacity: twelve 14-letter words plus separators,
+/// rounded up
+pub const DEFAULT_PHRASE_CAPACITY: usize = 192;
+
+/// An encoded phrase stored inline in an `N`-byte buffer
+#[derive(Clone, Copy)]
+pub struct WordPhrase<const N: usize = DEFAULT_PHRASE_CAPACITY> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> WordPhrase<N> {
+    /// Creates an empty phrase
+    pub const fn new() -> Self {
+        WordPhrase {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    /// Returns the phrase as a string slice
+    pub fn as_str(&self) -> &str {
+        // Only whole `&str`s are ever appended, so the contents are valid UTF-8
+        std::str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+
+    /// Returns the length of the phrase in bytes
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks whether the phrase is empty
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximum length in bytes
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Empties the phrase so the buffer can be reused
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> Default for WordPhrase<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> fmt::Write for WordPhrase<N> {
+    /// Appends `s`, failing without writing anything if it does not fit
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len.checked_add(s.len()).ok_or(fmt::Error)?;
+        let dest = self.buf.get_mut(self.len..end).ok_or(fmt::Error)?;
+        dest.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+impl<const N: usize> Deref for WordPhrase<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for WordPhrase<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> fmt::Display for WordPhrase<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> fmt::Debug for WordPhrase<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize, const M: usize> PartialEq<WordPhrase<M>> for WordPhrase<N> {
+    fn eq(&self, other: &WordPhrase<M>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for WordPhrase<N> {}
+
+impl<const N: usize> PartialEq<str> for WordPhrase<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for WordPhrase<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// Writes the words for `indices` to `out`, separated by single spaces
+pub(crate) fn write_words<W: Wordlist + ?Sized>(
+    wordlist: &W,
+    indices: &[u16],
+    out: &mut impl fmt::Write,
+) -> Result<()> {
+    for (position, &index) in indices.iter().enumerate() {
+        let word = wordlist
+            .get_word(index)
+            .ok_or(FourWordError::InvalidWordIndex(index))?;
+        if position > 0 {
+            out.write_char(' ')?;
+        }
+        out.write_str(word)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Write;
+
+    #[test]
+    fn test_write_within_capacity() {
+        let mut phrase = WordPhrase::<16>::new();
+        let word = "thunder";
+        write!(phrase, "ocean {word}").unwrap();
+        assert_eq!(phrase, "ocean thunder");
+        assert_eq!(phrase.len(), 13);
+
+        // A write that does not fit leaves the phrase untouched
+        assert!(phrase.write_str(" falcon").is_err());
+        assert_eq!(phrase.as_str(), "ocean thunder");
+
+        phrase.clear();
+        assert!(phrase.is_empty());
+    }
+}
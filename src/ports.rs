@@ -0,0 +1,341 @@
+//! Well-known ports and their service names.
+//!
+//! A handful of ports carry most traffic, so IPv6 encodings store a port from
+//! [`WELL_KNOWN_PORTS`] as a one-byte table index instead of the full 16 bits
+//! whenever that lets the phrase use fewer words or keep a port it would
+//! otherwise drop. IPv4 phrases are unaffected: address and port fill their
+//! four words exactly.
+//!
+//! The same table gives ports a readable form. [`parse_port`] and
+//! [`parse_socket_addr`] accept `https` as well as `443`, and
+//! [`display_socket_addr`] writes `192.0.2.1:https` for callers that prefer
+//! service names:
+//!
+//! ```rust
+//! use four_word_networking::ports::{display_socket_addr, parse_socket_addr, service_name};
+//!
+//! let addr = parse_socket_addr("[2001:db8::1]:https")?;
+//! assert_eq!(addr.port(), 443);
+//! assert_eq!(service_name(22), Some("ssh"));
+//! assert_eq!(display_socket_addr(addr), "[2001:db8::1]:https");
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use std::net::{IpAddr, SocketAddr};
+
+/// A port and its service name (IANA where one is registered)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WellKnownPort {
+    /// Port number
+    pub port: u16,
+    /// Service name
+    pub name: &'static str,
+}
+
+/// Common ports, most frequent first.
+///
+/// Phrases store the index, so entries may only ever be appended, and the
+/// table stays below 255 entries (index 255 marks "no port").
+pub const WELL_KNOWN_PORTS: &[WellKnownPort] = &[
+    WellKnownPort {
+        port: 443,
+        name: "https",
+    },
+    WellKnownPort {
+        port: 80,
+        name: "http",
+    },
+    WellKnownPort {
+        port: 22,
+        name: "ssh",
+    },
+    WellKnownPort {
+        port: 53,
+        name: "domain",
+    },
+    WellKnownPort {
+        port: 8080,
+        name: "http-alt",
+    },
+    WellKnownPort {
+        port: 8443,
+        name: "https-alt",
+    },
+    WellKnownPort {
+        port: 25,
+        name: "smtp",
+    },
+    WellKnownPort {
+        port: 587,
+        name: "submission",
+    },
+    WellKnownPort {
+        port: 465,
+        name: "submissions",
+    },
+    WellKnownPort {
+        port: 993,
+        name: "imaps",
+    },
+    WellKnownPort {
+        port: 995,
+        name: "pop3s",
+    },
+    WellKnownPort {
+        port: 143,
+        name: "imap",
+    },
+    WellKnownPort {
+        port: 110,
+        name: "pop3",
+    },
+    WellKnownPort {
+        port: 123,
+        name: "ntp",
+    },
+    WellKnownPort {
+        port: 21,
+        name: "ftp",
+    },
+    WellKnownPort {
+        port: 3478,
+        name: "stun",
+    },
+    WellKnownPort {
+        port: 5349,
+        name: "stuns",
+    },
+    WellKnownPort {
+        port: 5060,
+        name: "sip",
+    },
+    WellKnownPort {
+        port: 5061,
+        name: "sips",
+    },
+    WellKnownPort {
+        port: 51820,
+        name: "wireguard",
+    },
+    WellKnownPort {
+        port: 1194,
+        name: "openvpn",
+    },
+    WellKnownPort {
+        port: 500,
+        name: "isakmp",
+    },
+    WellKnownPort {
+        port: 4500,
+        name: "ipsec-nat-t",
+    },
+    WellKnownPort {
+        port: 853,
+        name: "domain-s",
+    },
+    WellKnownPort {
+        port: 5353,
+        name: "mdns",
+    },
+    WellKnownPort {
+        port: 1883,
+        name: "mqtt",
+    },
+    WellKnownPort {
+        port: 8883,
+        name: "secure-mqtt",
+    },
+    WellKnownPort {
+        port: 3306,
+        name: "mysql",
+    },
+    WellKnownPort {
+        port: 5432,
+        name: "postgresql",
+    },
+    WellKnownPort {
+        port: 6379,
+        name: "redis",
+    },
+    WellKnownPort {
+        port: 27017,
+        name: "mongodb",
+    },
+    WellKnownPort {
+        port: 5672,
+        name: "amqp",
+    },
+    WellKnownPort {
+        port: 389,
+        name: "ldap",
+    },
+    WellKnownPort {
+        port: 636,
+        name: "ldaps",
+    },
+    WellKnownPort {
+        port: 3389,
+        name: "ms-wbt-server",
+    },
+    WellKnownPort {
+        port: 5900,
+        name: "rfb",
+    },
+    WellKnownPort {
+        port: 179,
+        name: "bgp",
+    },
+    WellKnownPort {
+        port: 161,
+        name: "snmp",
+    },
+    WellKnownPort {
+        port: 67,
+        name: "bootps",
+    },
+    WellKnownPort {
+        port: 68,
+        name: "bootpc",
+    },
+    WellKnownPort {
+        port: 546,
+        name: "dhcpv6-client",
+    },
+    WellKnownPort {
+        port: 547,
+        name: "dhcpv6-server",
+    },
+    WellKnownPort {
+        port: 69,
+        name: "tftp",
+    },
+    WellKnownPort {
+        port: 23,
+        name: "telnet",
+    },
+    WellKnownPort {
+        port: 445,
+        name: "microsoft-ds",
+    },
+    WellKnownPort {
+        port: 88,
+        name: "kerberos",
+    },
+    WellKnownPort {
+        port: 5222,
+        name: "xmpp-client",
+    },
+    WellKnownPort {
+        port: 1812,
+        name: "radius",
+    },
+    WellKnownPort {
+        port: 1813,
+        name: "radius-acct",
+    },
+];
+
+/// Returns the service name of a well-known port
+pub fn service_name(port: u16) -> Option<&'static str> {
+    WELL_KNOWN_PORTS
+        .iter()
+        .find(|entry| entry.port == port)
+        .map(|entry| entry.name)
+}
+
+/// Parses a port number or a service name from [`WELL_KNOWN_PORTS`], ignoring case
+pub fn parse_port(text: &str) -> Result<u16> {
+    let text = text.trim();
+    if let Ok(port) = text.parse() {
+        return Ok(port);
+    }
+    WELL_KNOWN_PORTS
+        .iter()
+        .find(|entry| entry.name.eq_ignore_ascii_case(text))
+        .map(|entry| entry.port)
+        .ok_or_else(|| FourWordError::invalid_value("port", text, "unknown port or service"))
+}
+
+/// Parses `IP:PORT` or `[IPv6]:PORT` where the port may be a service name
+pub fn parse_socket_addr(text: &str) -> Result<SocketAddr> {
+    let text = text.trim();
+    if let Ok(addr) = text.parse() {
+        return Ok(addr);
+    }
+    let invalid = || FourWordError::invalid_value("socket address", text, "expected IP:PORT");
+    let (ip, port) = text.rsplit_once(':').ok_or_else(invalid)?;
+    let ip: IpAddr = match ip.strip_prefix('[').and_then(|ip| ip.strip_suffix(']')) {
+        Some(v6) => IpAddr::V6(v6.parse().map_err(|_| invalid())?),
+        None => IpAddr::V4(ip.parse().map_err(|_| invalid())?),
+    };
+    Ok(SocketAddr::new(ip, parse_port(port)?))
+}
+
+/// Writes `addr` with the service name in place of a well-known port number
+pub fn display_socket_addr(addr: SocketAddr) -> String {
+    match (service_name(addr.port()), addr.ip()) {
+        (Some(name), IpAddr::V4(ip)) => format!("{ip}:{name}"),
+        (Some(name), IpAddr::V6(ip)) => format!("[{ip}]:{name}"),
+        (None, _) => addr.to_string(),
+    }
+}
+
+/// Position of `port` in [`WELL_KNOWN_PORTS`]
+pub(crate) fn port_code(port: u16) -> Option<u8> {
+    WELL_KNOWN_PORTS
+        .iter()
+        .position(|entry| entry.port == port)
+        .and_then(|index| u8::try_from(index).ok())
+}
+
+/// Port stored at `code` in [`WELL_KNOWN_PORTS`]
+pub(crate) fn code_port(code: u8) -> Option<u16> {
+    WELL_KNOWN_PORTS
+        .get(usize::from(code))
+        .map(|entry| entry.port)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_is_consistent() {
+        assert!(WELL_KNOWN_PORTS.len() < 255);
+        for (i, entry) in WELL_KNOWN_PORTS.iter().enumerate() {
+            assert_eq!(port_code(entry.port), Some(i as u8), "{}", entry.name);
+            assert_eq!(code_port(i as u8), Some(entry.port));
+            assert_eq!(
+                parse_port(entry.name).unwrap(),
+                entry.port,
+                "{}",
+                entry.name
+            );
+        }
+        assert_eq!(port_code(4242), None);
+        assert_eq!(code_port(255), None);
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(parse_port("HTTPS").unwrap(), 443);
+        assert_eq!(parse_port("4242").unwrap(), 4242);
+        assert!(parse_port("gopher-plus").is_err());
+        assert!(parse_port("70000").is_err());
+
+        for (text, canonical, named) in [
+            ("192.0.2.1:https", "192.0.2.1:443", "192.0.2.1:https"),
+            ("192.0.2.1:4242", "192.0.2.1:4242", "192.0.2.1:4242"),
+            ("[::1]:ssh", "[::1]:22", "[::1]:ssh"),
+        ] {
+            let addr = parse_socket_addr(text).unwrap();
+            assert_eq!(addr.to_string(), canonical);
+            assert_eq!(display_socket_addr(addr), named);
+        }
+        assert!(parse_socket_addr("::1:ssh").is_err());
+        assert!(parse_socket_addr("192.0.2.1").is_err());
+    }
+}
@@ -0,0 +1,17 @@
+//! Commonly used items, for glob import.
+//!
+//! ```rust
+//! use four_word_networking::prelude::*;
+//!
+//! let words = encode("192.168.1.1:443".parse::<SocketAddr>()?)?;
+//! assert_eq!(decode(&words)?, "192.168.1.1:443".parse::<SocketAddr>()?);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+pub use crate::{
+    Dictionary4K, FourWordAdaptiveEncoder, FourWordError, Language, PhraseEncoder, Result,
+    WordAddress, WordPhrase, WordSocketAddr, Wordlist, decode, encode, similarity,
+};
+pub use std::net::SocketAddr;
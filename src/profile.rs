@@ -0,0 +1,260 @@
+//! Deployment-specific encoding profiles.
+//!
+//! Some compression depends on network facts that are not universal, such as
+//! the NAT64 prefix an operator translates through or the provider prefixes
+//! most of its traffic comes from. An [`EncodingProfile`]
+//! carries that configuration into the compressor. Phrases that use profile
+//! data only decode under a profile with the same entries, so every party
+//! exchanging words must be configured alike.
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::nat64::Nat64Prefix;
+use crate::provider_prefix::ProviderPrefix;
+use sha2::{Digest, Sha256};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Maximum number of NAT64 prefixes; each is referenced by a one-byte index
+pub const MAX_NAT64_PREFIXES: usize = 256;
+
+/// Maximum number of provider prefixes; each is referenced by a one-byte index
+pub const MAX_PROVIDER_PREFIXES: usize = 256;
+
+/// Maximum number of reserved words, not counting the escape word
+pub const MAX_RESERVED_WORDS: usize = 64;
+
+/// Network configuration shared by the parties exchanging phrases
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodingProfile {
+    nat64_prefixes: Vec<Nat64Prefix>,
+    provider_prefixes: Vec<ProviderPrefix>,
+    /// Introduces a stand-in for a reserved word; `None` if nothing is reserved
+    escape_word: Option<String>,
+    reserved_words: Vec<String>,
+}
+
+impl EncodingProfile {
+    /// Creates an empty profile, equivalent to the built-in behaviour
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a NAT64 translation prefix.
+    ///
+    /// Prefixes are referenced by registration order inside the encoding, so
+    /// both sides must register the same prefixes in the same order.
+    pub fn add_nat64_prefix(&mut self, prefix: Nat64Prefix) -> Result<()> {
+        if self.nat64_prefixes.contains(&prefix) {
+            return Ok(());
+        }
+        if self.nat64_prefixes.len() == MAX_NAT64_PREFIXES {
+            return Err(FourWordError::out_of_range(
+                "NAT64 prefix count",
+                format!("at most {MAX_NAT64_PREFIXES}"),
+                self.nat64_prefixes.len() + 1,
+            ));
+        }
+        self.nat64_prefixes.push(prefix);
+        Ok(())
+    }
+
+    /// Returns the registered NAT64 prefixes in registration order
+    pub fn nat64_prefixes(&self) -> &[Nat64Prefix] {
+        &self.nat64_prefixes
+    }
+
+    /// Registers a provider prefix for global unicast compression.
+    ///
+    /// Like NAT64 prefixes, provider prefixes are referenced by registration
+    /// order, so both sides must register the same list in the same order.
+    /// The first registered prefix containing an address is used.
+    pub fn add_provider_prefix(&mut self, prefix: ProviderPrefix) -> Result<()> {
+        if self.provider_prefixes.contains(&prefix) {
+            return Ok(());
+        }
+        if self.provider_prefixes.len() == MAX_PROVIDER_PREFIXES {
+            return Err(FourWordError::out_of_range(
+                "Provider prefix count",
+                format!("at most {MAX_PROVIDER_PREFIXES}"),
+                self.provider_prefixes.len() + 1,
+            ));
+        }
+        self.provider_prefixes.push(prefix);
+        Ok(())
+    }
+
+    /// Returns the registered provider prefixes in registration order
+    pub fn provider_prefixes(&self) -> &[ProviderPrefix] {
+        &self.provider_prefixes
+    }
+
+    /// Reserves `words` as protocol keywords that encodings never emit.
+    ///
+    /// Encodings that would use a reserved word, or `escape` itself, emit
+    /// `escape` followed by a stand-in word instead (see
+    /// [`reserved`](crate::reserved)). Replaces any earlier reservation. The
+    /// words must be in the wordlist of encoders using this profile, and
+    /// cannot include its first word, which tags escaped phrases.
+    pub fn reserve_words(&mut self, escape: &str, words: &[&str]) -> Result<()> {
+        if words.len() > MAX_RESERVED_WORDS {
+            return Err(FourWordError::out_of_range(
+                "Reserved word count",
+                format!("at most {MAX_RESERVED_WORDS}"),
+                words.len(),
+            ));
+        }
+        let escape = escape.trim().to_lowercase();
+        let mut reserved: Vec<String> = Vec::with_capacity(words.len());
+        for word in words {
+            let word = word.trim().to_lowercase();
+            if word.is_empty() || word == escape || reserved.contains(&word) {
+                return Err(FourWordError::invalid_value(
+                    "reserved word",
+                    &word,
+                    "must be distinct, non-empty and differ from the escape word",
+                ));
+            }
+            reserved.push(word);
+        }
+        if escape.is_empty() {
+            return Err(FourWordError::invalid_value(
+                "escape word",
+                &escape,
+                "must not be empty",
+            ));
+        }
+        self.escape_word = Some(escape);
+        self.reserved_words = reserved;
+        Ok(())
+    }
+
+    /// Returns the escape word, or `None` if no words are reserved
+    pub fn escape_word(&self) -> Option<&str> {
+        self.escape_word.as_deref()
+    }
+
+    /// Returns the reserved words in reservation order
+    pub fn reserved_words(&self) -> &[String] {
+        &self.reserved_words
+    }
+
+    /// Returns a SHA-256 fingerprint identifying this profile's encoding.
+    ///
+    /// Phrases are only guaranteed to decode identically under profiles with
+    /// the same fingerprint; the default profile has a fixed fingerprint.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        for prefix in &self.nat64_prefixes {
+            hasher.update(format!("nat64 {prefix}\n"));
+        }
+        for prefix in &self.provider_prefixes {
+            hasher.update(format!("provider {prefix}\n"));
+        }
+        if let Some(escape) = &self.escape_word {
+            hasher.update(format!("escape {escape}\n"));
+        }
+        for word in &self.reserved_words {
+            hasher.update(format!("reserved {word}\n"));
+        }
+        hasher.finalize().into()
+    }
+
+    /// Finds the first registered prefix `ip` was synthesized under
+    pub(crate) fn match_nat64(&self, ip: &Ipv6Addr) -> Option<(u8, Ipv4Addr)> {
+        self.nat64_prefixes
+            .iter()
+            .enumerate()
+            .find_map(|(id, prefix)| prefix.extract(ip).map(|v4| (id as u8, v4)))
+    }
+
+    /// Looks up a prefix by the index stored in an encoding
+    pub(crate) fn nat64_prefix(&self, id: u8) -> Result<&Nat64Prefix> {
+        self.nat64_prefixes.get(usize::from(id)).ok_or_else(|| {
+            FourWordError::DecompressionError(format!(
+                "NAT64 prefix #{id} is not configured in this profile"
+            ))
+        })
+    }
+
+    /// Finds the first registered provider prefix containing `ip`
+    pub(crate) fn match_provider(&self, ip: &Ipv6Addr) -> Option<(u8, &ProviderPrefix)> {
+        self.provider_prefixes
+            .iter()
+            .enumerate()
+            .find(|(_, prefix)| prefix.contains(ip))
+            .map(|(id, prefix)| (id as u8, prefix))
+    }
+
+    /// Looks up a provider prefix by the index stored in an encoding
+    pub(crate) fn provider_prefix(&self, id: u8) -> Result<&ProviderPrefix> {
+        self.provider_prefixes.get(usize::from(id)).ok_or_else(|| {
+            FourWordError::DecompressionError(format!(
+                "Provider prefix #{id} is not configured in this profile"
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registration_order_is_the_index() {
+        let mut profile = EncodingProfile::new();
+        profile
+            .add_nat64_prefix("2001:db8:64::/96".parse().unwrap())
+            .unwrap();
+        profile
+            .add_nat64_prefix("2001:db8:122::/48".parse().unwrap())
+            .unwrap();
+        // Re-registering is a no-op and keeps the original index
+        profile
+            .add_nat64_prefix("2001:db8:64::/96".parse().unwrap())
+            .unwrap();
+        assert_eq!(profile.nat64_prefixes().len(), 2);
+
+        let ip: Ipv6Addr = "2001:db8:122:c000:2:2100::".parse().unwrap();
+        assert_eq!(
+            profile.match_nat64(&ip),
+            Some((1, Ipv4Addr::new(192, 0, 2, 33)))
+        );
+        assert!(profile.nat64_prefix(1).is_ok());
+        assert!(profile.nat64_prefix(2).is_err());
+    }
+
+    #[test]
+    fn test_provider_prefixes() {
+        let mut profile = EncodingProfile::new();
+        let default = profile.fingerprint();
+        profile
+            .add_provider_prefix("2a00:1450::/32".parse().unwrap())
+            .unwrap();
+        profile
+            .add_provider_prefix("2a00:1450:4001::/48".parse().unwrap())
+            .unwrap();
+        assert_ne!(profile.fingerprint(), default);
+
+        // The first registered match wins, even if a later one is longer
+        let ip: Ipv6Addr = "2a00:1450:4001::1".parse().unwrap();
+        assert_eq!(profile.match_provider(&ip).map(|(id, _)| id), Some(0));
+        assert_eq!(profile.match_provider(&Ipv6Addr::LOCALHOST), None);
+        assert!(profile.provider_prefix(1).is_ok());
+        assert!(profile.provider_prefix(2).is_err());
+    }
+
+    #[test]
+    fn test_reserve_words_and_fingerprint() {
+        let mut profile = EncodingProfile::new();
+        let default = profile.fingerprint();
+        assert!(profile.reserve_words("over", &["over"]).is_err());
+        assert!(profile.reserve_words("over", &["stop", "Stop "]).is_err());
+        assert_eq!(profile.fingerprint(), default);
+
+        profile.reserve_words(" Over", &["repeat", "stop"]).unwrap();
+        assert_eq!(profile.escape_word(), Some("over"));
+        assert_eq!(profile.reserved_words(), ["repeat", "stop"]);
+        assert_ne!(profile.fingerprint(), default);
+    }
+}
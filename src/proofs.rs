@@ -0,0 +1,105 @@
+//! Kani proof harnesses for the core mapping layer.
+//!
+//! These prove that the conversions underneath every encoding are bijective
+//! for all inputs, not just the ones the test suite happens to sample:
+//!
+//! - IPv4 address + port <-> four 12-bit word indices
+//! - IPv6 header byte <-> (category, data length)
+//! - byte payload <-> 12-bit word indices
+//! - word index <-> dictionary word
+//!
+//! Only compiled under `cargo kani`, which sets `cfg(kani)`:
+//!
+//! ```text
+//! cargo kani --harness ipv4_packing_is_bijective
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::dictionary4k::DICTIONARY;
+use crate::four_word_encoder::{pack_ipv4, unpack_ipv4};
+use crate::four_word_ipv6_encoder::{
+    bytes_to_indices, indices_to_bytes, pack_header, unpack_header,
+};
+use crate::ipv6_compression::Ipv6Category;
+use std::net::Ipv4Addr;
+
+/// Number of 12-bit words in the longest (12-word) IPv6 encoding
+const MAX_WORDS: usize = 12;
+
+/// Bytes exactly filled by [`MAX_WORDS`] words
+const MAX_BYTES: usize = MAX_WORDS * 12 / 8;
+
+#[kani::proof]
+fn ipv4_packing_is_bijective() {
+    let addr = Ipv4Addr::from(kani::any::<u32>());
+    let port: u16 = kani::any();
+
+    let indices = pack_ipv4(addr, port);
+    assert!(indices.iter().all(|&index| index < 4096));
+    assert_eq!(unpack_ipv4(indices), (addr, port));
+}
+
+#[kani::proof]
+fn ipv4_unpacking_is_bijective() {
+    let indices: [u16; 4] = kani::any();
+    kani::assume(indices.iter().all(|&index| index < 4096));
+
+    let (addr, port) = unpack_ipv4(indices);
+    assert_eq!(pack_ipv4(addr, port), indices);
+}
+
+#[kani::proof]
+fn category_bits_are_bijective() {
+    let bits: u8 = kani::any();
+    match Ipv6Category::from_bits(bits) {
+        Ok(category) => assert_eq!(category.to_bits(), bits),
+        Err(_) => assert!(bits >= 7),
+    }
+}
+
+#[kani::proof]
+fn header_byte_is_bijective() {
+    let bits: u8 = kani::any();
+    let data_len: u8 = kani::any();
+    kani::assume(bits < 7 && data_len < 32);
+    let category = Ipv6Category::from_bits(bits).unwrap();
+
+    let header = pack_header(category, data_len);
+    let (bits_out, len) = unpack_header(header);
+    assert_eq!(bits_out, bits);
+    assert_eq!(Ipv6Category::from_encoded(bits_out, &[]).unwrap(), category);
+    assert_eq!(len, data_len as usize);
+}
+
+#[kani::proof]
+#[kani::unwind(20)]
+fn payload_packing_is_bijective() {
+    let bytes: [u8; MAX_BYTES] = kani::any();
+
+    let indices = bytes_to_indices(&bytes, MAX_WORDS);
+    assert!(indices.iter().all(|&index| index < 4096));
+    assert_eq!(indices_to_bytes(&indices), bytes);
+}
+
+#[kani::proof]
+#[kani::unwind(20)]
+fn payload_unpacking_is_bijective() {
+    let indices: [u16; MAX_WORDS] = kani::any();
+    kani::assume(indices.iter().all(|&index| index < 4096));
+
+    let bytes = indices_to_bytes(&indices);
+    assert_eq!(bytes_to_indices(&bytes, MAX_WORDS), indices);
+}
+
+#[kani::proof]
+#[kani::unwind(20)]
+fn dictionary_lookup_is_bijective() {
+    // Word -> index uniqueness is enforced by build.rs, so index -> word -> index
+    // returning the same index for every index proves the mapping is bijective
+    let index: u16 = kani::any();
+    kani::assume(index < 4096);
+
+    let word = DICTIONARY.get_word(index).unwrap();
+    assert_eq!(DICTIONARY.get_index(word), Some(index));
+}
@@ -3,6 +3,8 @@
 //! This module contains property-based tests that verify mathematical invariants
 //! and properties of the encoding system using proptest.
 
+#![forbid(unsafe_code)]
+
 use crate::FourWordAdaptiveEncoder;
 use proptest::prelude::*;
 use std::net::{Ipv4Addr, Ipv6Addr};
@@ -63,11 +65,10 @@ mod tests {
             let encoder = FourWordAdaptiveEncoder::new().unwrap();
             let original = format!("{ip}:{port}");
 
-            if let Ok(words) = encoder.encode(&original) {
-                if let Ok(decoded) = encoder.decode(&words) {
+            if let Ok(words) = encoder.encode(&original)
+                && let Ok(decoded) = encoder.decode(&words) {
                     prop_assert_eq!(original, decoded);
                 }
-            }
         }
     }
 
@@ -81,11 +82,10 @@ mod tests {
             let encoder = FourWordAdaptiveEncoder::new().unwrap();
             let addr_str = format!("{ip}:{port}");
 
-            if let Ok(words1) = encoder.encode(&addr_str) {
-                if let Ok(words2) = encoder.encode(&addr_str) {
+            if let Ok(words1) = encoder.encode(&addr_str)
+                && let Ok(words2) = encoder.encode(&addr_str) {
                     prop_assert_eq!(words1, words2);
                 }
-            }
         }
     }
 
@@ -122,7 +122,7 @@ mod tests {
             if let Ok(words) = encoder.encode(&addr_str) {
                 // Each word should be from the dictionary
                 for word in words.split(' ') {
-                    prop_assert!(word.len() >= 1);  // Dictionary includes single-character words
+                    prop_assert!(!word.is_empty());  // Dictionary includes single-character words
                     // No maximum length restriction - frequency-based words can be longer
                     prop_assert!(word.chars().all(|c| c.is_ascii_lowercase()));
                 }
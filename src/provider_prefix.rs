@@ -0,0 +1,230 @@
+//! Provider prefixes for compressing global unicast addresses.
+//!
+//! Most global unicast traffic comes from a few thousand ISP and hosting
+//! allocations. When both sides register the same [`ProviderPrefix`] list in
+//! their [`EncodingProfile`](crate::EncodingProfile), an address under a
+//! registered prefix is encoded as a one-byte index, the subnet bits after the
+//! prefix without trailing zero bytes and the interface ID without leading
+//! zero bytes. A typical
+//! `/48` host such as `2a00:1450:4001::1` then takes 6 words instead of 12,
+//! and keeps its port.
+//!
+//! Phrases never get shorter than 6 words: a 4-word phrase always decodes as
+//! IPv4, so the IPv6 minimum still applies.
+//!
+//! [`parse_prefix_list`] builds the table from a plain list (one prefix per
+//! line) or from RIR delegation statistics files, so a deployment can compile
+//! its list offline and ship it to every party:
+//!
+//! ```rust
+//! use four_word_networking::provider_prefix::parse_prefix_list;
+//!
+//! let list = "\
+//! 2a00:1450::/32
+//! ripencc|NL|ipv6|2a02:a400::|26|20110126|allocated
+//! ripencc|NL|ipv4|192.0.2.0|256|20110126|allocated
+//! ";
+//! let prefixes = parse_prefix_list(list)?;
+//! assert_eq!(prefixes.len(), 2);
+//! assert_eq!(prefixes[1].to_string(), "2a02:a400::/26");
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use std::fmt;
+use std::net::Ipv6Addr;
+use std::str::FromStr;
+
+/// Shortest prefix accepted; anything shorter saves nothing over the index byte
+pub const MIN_PROVIDER_PREFIX_LEN: u8 = 16;
+
+/// Longest prefix accepted; the interface ID is always encoded
+pub const MAX_PROVIDER_PREFIX_LEN: u8 = 64;
+
+/// A provider allocation such as `2a00:1450::/32`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProviderPrefix {
+    prefix: Ipv6Addr,
+    len: u8,
+}
+
+impl ProviderPrefix {
+    /// Creates a prefix, checking the length and that no bits are set past it
+    pub fn new(prefix: Ipv6Addr, len: u8) -> Result<Self> {
+        if !(MIN_PROVIDER_PREFIX_LEN..=MAX_PROVIDER_PREFIX_LEN).contains(&len) {
+            return Err(FourWordError::out_of_range(
+                "Provider prefix length",
+                format!("/{MIN_PROVIDER_PREFIX_LEN} to /{MAX_PROVIDER_PREFIX_LEN}"),
+                format!("/{len}"),
+            ));
+        }
+        if u128::from(prefix) & !mask(len) != 0 {
+            return Err(FourWordError::invalid_value(
+                "provider prefix",
+                &format!("{prefix}/{len}"),
+                "bits are set beyond the prefix length",
+            ));
+        }
+        Ok(ProviderPrefix { prefix, len })
+    }
+
+    /// Returns the prefix address
+    pub fn prefix(&self) -> Ipv6Addr {
+        self.prefix
+    }
+
+    /// Returns the prefix length in bits
+    pub fn len(&self) -> u8 {
+        self.len
+    }
+
+    /// Always false; a prefix is at least [`MIN_PROVIDER_PREFIX_LEN`] bits
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Returns true if `ip` is inside the prefix
+    pub fn contains(&self, ip: &Ipv6Addr) -> bool {
+        u128::from(*ip) & mask(self.len) == u128::from(self.prefix)
+    }
+
+    /// Number of whole leading bytes the index stands in for
+    pub(crate) fn byte_len(&self) -> usize {
+        usize::from(self.len / 8)
+    }
+}
+
+/// Network mask with the top `len` bits set
+fn mask(len: u8) -> u128 {
+    u128::MAX.checked_shl(128 - u32::from(len)).unwrap_or(0)
+}
+
+impl fmt::Display for ProviderPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.prefix, self.len)
+    }
+}
+
+impl FromStr for ProviderPrefix {
+    type Err = FourWordError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (prefix, len) = s.split_once('/').ok_or_else(|| {
+            FourWordError::invalid_value("provider prefix", s, "expected ADDRESS/LENGTH")
+        })?;
+        let prefix = prefix.parse().map_err(|_| {
+            FourWordError::invalid_value("provider prefix", s, "invalid IPv6 address")
+        })?;
+        let len = len.parse().map_err(|_| {
+            FourWordError::invalid_value("provider prefix", s, "invalid prefix length")
+        })?;
+        Self::new(prefix, len)
+    }
+}
+
+/// Parses a provider prefix list, keeping the first occurrence of each prefix.
+///
+/// Each line is either a plain prefix (`2a00:1450::/32`, optionally followed
+/// by other fields such as an origin AS) or an RIR delegation statistics
+/// record (`registry|cc|ipv6|start|length|date|status`). Blank lines and `#`
+/// comments are skipped, as are RIR headers, summaries, non-IPv6 records and
+/// IPv6 records whose length is outside the supported range. Any other line
+/// that does not parse is an error naming the line.
+pub fn parse_prefix_list(text: &str) -> Result<Vec<ProviderPrefix>> {
+    let mut prefixes: Vec<ProviderPrefix> = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let parsed = if line.contains('|') {
+            parse_rir_record(line)
+        } else {
+            line.split_whitespace()
+                .next()
+                .map(str::parse::<ProviderPrefix>)
+                .transpose()
+        };
+        let prefix = parsed.map_err(|e| {
+            FourWordError::invalid_value(
+                "prefix list entry",
+                line,
+                format!("line {}: {e}", number + 1),
+            )
+        })?;
+        if let Some(prefix) = prefix
+            && !prefixes.contains(&prefix)
+        {
+            prefixes.push(prefix);
+        }
+    }
+    Ok(prefixes)
+}
+
+/// Reads an RIR delegation record, or `None` for records that are not usable
+/// IPv6 allocations
+fn parse_rir_record(line: &str) -> Result<Option<ProviderPrefix>> {
+    let fields: Vec<&str> = line.split('|').collect();
+    let [_registry, _cc, kind, start, len, ..] = fields[..] else {
+        // Version headers have fewer columns
+        return Ok(None);
+    };
+    if kind != "ipv6" || start == "*" {
+        return Ok(None);
+    }
+    let start: Ipv6Addr = start.parse().map_err(|_| {
+        FourWordError::invalid_value("delegation record", line, "invalid IPv6 start address")
+    })?;
+    let len: u8 = len.parse().map_err(|_| {
+        FourWordError::invalid_value("delegation record", line, "invalid prefix length")
+    })?;
+    if !(MIN_PROVIDER_PREFIX_LEN..=MAX_PROVIDER_PREFIX_LEN).contains(&len) {
+        return Ok(None);
+    }
+    ProviderPrefix::new(start, len).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_validation() {
+        let prefix: ProviderPrefix = "2a00:1450::/32".parse().unwrap();
+        assert_eq!(prefix.to_string(), "2a00:1450::/32");
+        assert!(prefix.contains(&"2a00:1450:4001::1".parse().unwrap()));
+        assert!(!prefix.contains(&"2a00:1451::1".parse().unwrap()));
+        assert_eq!(prefix.byte_len(), 4);
+
+        for invalid in [
+            "2a00::/8",
+            "2a00:1450::/65",
+            "2a00:1450::1/32",
+            "2a00:1450::",
+        ] {
+            assert!(invalid.parse::<ProviderPrefix>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_parse_prefix_list() {
+        let list = "\
+            2|ripencc|1700000000|3|19830705|20240101|+0100\n\
+            ripencc|*|ipv6|*|2|summary\n\
+            ripencc|DE|ipv4|192.0.2.0|256|20090401|allocated\n\
+            ripencc|DE|ipv6|2a00:1450::|32|20090401|allocated\n\
+            ripencc|ZZ|ipv6|2a00::|12|20090401|allocated\n\
+            \n\
+            # BGP dump: prefix and origin AS\n\
+            2a03:2880::/29 32934\n\
+            2a00:1450::/32\n";
+        let prefixes = parse_prefix_list(list).unwrap();
+        let prefixes: Vec<String> = prefixes.iter().map(ToString::to_string).collect();
+        assert_eq!(prefixes, ["2a00:1450::/32", "2a03:2880::/29"]);
+
+        let error = parse_prefix_list("2a00:1450::/32\nnot a prefix\n").unwrap_err();
+        assert!(error.to_string().contains("line 2"), "{error}");
+    }
+}
@@ -3,6 +3,8 @@
 //! This module tackles the fundamental challenge: compress 48 bits (IP+port)
 //! into 42 bits using pure mathematical techniques without any special cases.
 
+#![forbid(unsafe_code)]
+
 use crate::error::FourWordError;
 use std::net::Ipv4Addr;
 
@@ -43,7 +45,7 @@ impl PureIpCompressor {
             return Ok(compressed);
         }
 
-        Err(FourWordError::InvalidInput(format!(
+        Err(FourWordError::CompressionError(format!(
             "Cannot compress {ip}:{port} (48→42 bits)"
         )))
     }
@@ -92,7 +94,7 @@ impl PureIpCompressor {
             return Ok(compressed);
         }
 
-        Err(FourWordError::InvalidInput(
+        Err(FourWordError::CompressionError(
             "No suitable factorization".to_string(),
         ))
     }
@@ -163,7 +165,7 @@ impl PureIpCompressor {
             }
         }
 
-        Err(FourWordError::InvalidInput(
+        Err(FourWordError::CompressionError(
             "No suitable base found".to_string(),
         ))
     }
@@ -171,7 +173,7 @@ impl PureIpCompressor {
     /// Decompress using strategy detection
     pub fn decompress(compressed: u64) -> Result<(Ipv4Addr, u16), FourWordError> {
         if compressed > MAX_42_BITS {
-            return Err(FourWordError::InvalidInput(
+            return Err(FourWordError::DecompressionError(
                 "Invalid compressed value".to_string(),
             ));
         }
@@ -199,7 +201,7 @@ impl PureIpCompressor {
             let ip_u32 = bases[base_idx] + offset;
             Ok((Ipv4Addr::from(ip_u32), port))
         } else {
-            Err(FourWordError::InvalidInput(
+            Err(FourWordError::DecompressionError(
                 "Invalid base index".to_string(),
             ))
         }
@@ -224,7 +226,7 @@ impl PureIpCompressor {
         let primes = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47];
 
         for &p in &primes {
-            while n % (p as u64) == 0 {
+            while n.is_multiple_of(p as u64) {
                 factors.push(p);
                 n /= p as u64;
                 if factors.len() >= 3 {
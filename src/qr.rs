@@ -0,0 +1,149 @@
+//! QR codes of phrases (`qr` feature).
+//!
+//! During pairing one device shows a phrase and the other scans it instead of
+//! having it read out. [`PhraseQr`] encodes a phrase as a QR code and renders
+//! it for a terminal, as SVG or as PNG; `4wn --qr ADDRESS` prints the terminal
+//! form under the words.
+//!
+//! ASCII letters are stored upper-cased: QR's alphanumeric mode packs
+//! upper-case letters and spaces at 5.5 bits a character instead of 8, which
+//! often makes the code a size smaller, and phrases decode regardless of
+//! case. Other characters, from custom or non-English wordlists, are kept as
+//! they are.
+//!
+//! ```rust
+//! use four_word_networking::qr::PhraseQr;
+//!
+//! let qr = PhraseQr::new("ocean thunder falcon star")?;
+//! assert_eq!(qr.data(), "OCEAN THUNDER FALCON STAR");
+//! assert!(qr.to_svg().starts_with("<?xml"));
+//! assert!(qr.to_png(4)?.starts_with(b"\x89PNG"));
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use qrcode::render::{svg, unicode};
+use qrcode::{Color, QrCode};
+
+/// Light modules around the code that scanners need, per the QR standard
+pub const QUIET_ZONE: usize = 4;
+
+/// Pixel size of an SVG module
+const SVG_MODULE_PX: u32 = 8;
+
+/// A phrase encoded as a QR code
+pub struct PhraseQr {
+    data: String,
+    code: QrCode,
+}
+
+impl PhraseQr {
+    /// Encodes `phrase`, with surrounding whitespace removed
+    pub fn new(phrase: &str) -> Result<Self> {
+        let data = phrase.trim().to_ascii_uppercase();
+        let code = QrCode::new(&data)
+            .map_err(|e| FourWordError::EncodingError(format!("QR code: {e}")))?;
+        Ok(PhraseQr { data, code })
+    }
+
+    /// The text stored in the code
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// Modules along one side, without the quiet zone
+    pub fn width(&self) -> usize {
+        self.code.width()
+    }
+
+    /// Lines of half-block characters, two modules per character cell.
+    ///
+    /// Light modules are drawn as blocks, so the code shows the right way
+    /// round on a dark terminal background.
+    pub fn to_terminal(&self) -> String {
+        self.code
+            .render::<unicode::Dense1x2>()
+            .dark_color(unicode::Dense1x2::Light)
+            .light_color(unicode::Dense1x2::Dark)
+            .build()
+    }
+
+    /// An SVG document, black on white
+    pub fn to_svg(&self) -> String {
+        self.code
+            .render::<svg::Color>()
+            .module_dimensions(SVG_MODULE_PX, SVG_MODULE_PX)
+            .build()
+    }
+
+    /// An 8-bit greyscale PNG with `scale` pixels per module (at least 1)
+    pub fn to_png(&self, scale: u32) -> Result<Vec<u8>> {
+        let scale = scale.max(1) as usize;
+        let side = self.width() + 2 * QUIET_ZONE;
+        let pixels = side * scale;
+        let colors = self.code.to_colors();
+        let mut image = vec![u8::MAX; pixels * pixels];
+        for (index, color) in colors.iter().enumerate() {
+            if *color == Color::Light {
+                continue;
+            }
+            let x = (index % self.width() + QUIET_ZONE) * scale;
+            let y = (index / self.width() + QUIET_ZONE) * scale;
+            for row in y..y + scale {
+                image[row * pixels + x..row * pixels + x + scale].fill(0);
+            }
+        }
+
+        let png_error = |e: png::EncodingError| FourWordError::EncodingError(format!("PNG: {e}"));
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, pixels as u32, pixels as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(png_error)?;
+        writer.write_image_data(&image).map_err(png_error)?;
+        writer.finish().map_err(png_error)?;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upper_case_shrinks_the_code() {
+        let phrase = crate::encode(([192, 168, 1, 1], 443)).unwrap();
+        let qr = PhraseQr::new(&phrase).unwrap();
+        assert_eq!(qr.data(), phrase.to_ascii_uppercase());
+        assert_eq!(
+            crate::decode(qr.data()).unwrap(),
+            ([192, 168, 1, 1], 443).into()
+        );
+        assert!(qr.width() <= QrCode::new(&phrase).unwrap().width());
+
+        // Non-ASCII letters are not case-folded
+        assert_eq!(PhraseQr::new(" canción ").unwrap().data(), "CANCIóN");
+    }
+
+    #[test]
+    fn test_renderings() {
+        let qr = PhraseQr::new("ocean thunder falcon star").unwrap();
+        let side = qr.width() + 2 * QUIET_ZONE;
+
+        let terminal = qr.to_terminal();
+        assert_eq!(terminal.lines().count(), side.div_ceil(2));
+        assert!(terminal.lines().all(|line| line.chars().count() == side));
+
+        let svg = qr.to_svg();
+        assert!(svg.contains(&format!(r#"width="{}""#, side as u32 * SVG_MODULE_PX)));
+
+        let png = qr.to_png(3).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        // IHDR width and height
+        let dimension = ((side * 3) as u32).to_be_bytes();
+        assert_eq!(png[16..20], dimension);
+        assert_eq!(png[20..24], dimension);
+    }
+}
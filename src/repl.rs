@@ -0,0 +1,433 @@
+//! Interactive phrase entry with word completion and live previews.
+//!
+//! [`Repl`] is the line editor behind `4wn repl`, built for reading phrases
+//! out over the phone. Tab completes the word being typed from the
+//! dictionary, a preview line under the prompt decodes the phrase (or encodes
+//! the address) after every key, and Up/Down recall earlier entries.
+//!
+//! The editing state is independent of the terminal, so frontends can drive
+//! it with their own key events:
+//!
+//! ```rust
+//! use four_word_networking::FourWordAdaptiveEncoder;
+//! use four_word_networking::repl::Repl;
+//!
+//! let encoder = FourWordAdaptiveEncoder::new()?;
+//! let phrase = encoder.encode("192.168.1.1:443")?;
+//! let mut repl = Repl::new(encoder);
+//!
+//! repl.insert_str(&phrase[..phrase.len() - 1]);
+//! let candidates = repl.complete();
+//! assert!(candidates.iter().any(|word| phrase.ends_with(word.as_str())));
+//!
+//! repl.clear();
+//! repl.insert_str(&phrase);
+//! assert_eq!(repl.preview(), "→ 192.168.1.1:443");
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::conversion::Conversion;
+use crate::error::Result;
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use console::{Key, Term};
+use std::io::BufRead;
+
+/// Prompt written before the line being edited
+pub const PROMPT: &str = "4wn> ";
+
+/// Most candidates listed in the preview when Tab is ambiguous
+const MAX_LISTED: usize = 8;
+
+/// Word counts a phrase can have
+const PHRASE_LENGTHS: [usize; 4] = [4, 6, 9, 12];
+
+/// Line editor with dictionary completion, previews and history
+pub struct Repl {
+    encoder: FourWordAdaptiveEncoder,
+    line: String,
+    history: Vec<String>,
+    /// Position in `history` while recalling, and the line it replaced
+    recall: Option<(usize, String)>,
+}
+
+impl Repl {
+    /// Creates an editor with an empty line and no history
+    pub fn new(encoder: FourWordAdaptiveEncoder) -> Self {
+        Repl {
+            encoder,
+            line: String::new(),
+            history: Vec::new(),
+            recall: None,
+        }
+    }
+
+    /// Returns the line being edited
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// Returns earlier entries, oldest first
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Returns the encoder entries are converted with
+    pub fn encoder(&self) -> &FourWordAdaptiveEncoder {
+        &self.encoder
+    }
+
+    /// Appends text to the line
+    pub fn insert_str(&mut self, text: &str) {
+        self.line.push_str(text);
+    }
+
+    /// Removes the last character of the line
+    pub fn backspace(&mut self) {
+        self.line.pop();
+    }
+
+    /// Empties the line
+    pub fn clear(&mut self) {
+        self.line.clear();
+        self.recall = None;
+    }
+
+    /// Dictionary words starting with `prefix`, ignoring case, alphabetically
+    pub fn completions(&self, prefix: &str) -> Vec<&str> {
+        self.encoder.wordlist().words_with_prefix(prefix).collect()
+    }
+
+    /// Completes the word being typed.
+    ///
+    /// The word is extended to the longest prefix its candidates share, and a
+    /// space is added once only one candidate is left. Returns the candidates,
+    /// which is empty when there is nothing to complete.
+    pub fn complete(&mut self) -> Vec<String> {
+        let Some(partial) = self.partial_word() else {
+            return Vec::new();
+        };
+        let candidates: Vec<String> = self
+            .completions(partial)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let Some(first) = candidates.first() else {
+            return candidates;
+        };
+        let shared = candidates
+            .iter()
+            .fold(first.as_str(), |shared, word| common_prefix(shared, word));
+        let start = self.line.len() - partial.len();
+        self.line.truncate(start);
+        self.line.push_str(shared);
+        if candidates.len() == 1 {
+            self.line.push(' ');
+        }
+        candidates
+    }
+
+    /// Replaces the line with the previous history entry
+    pub fn history_back(&mut self) {
+        let position = match &self.recall {
+            Some((0, _)) => return,
+            Some((position, _)) => position - 1,
+            None if self.history.is_empty() => return,
+            None => self.history.len() - 1,
+        };
+        let draft = match self.recall.take() {
+            Some((_, draft)) => draft,
+            None => std::mem::take(&mut self.line),
+        };
+        self.line = self.history[position].clone();
+        self.recall = Some((position, draft));
+    }
+
+    /// Replaces the line with the next history entry, or the line being
+    /// typed before recalling
+    pub fn history_forward(&mut self) {
+        let Some((position, draft)) = self.recall.take() else {
+            return;
+        };
+        if position + 1 < self.history.len() {
+            self.line = self.history[position + 1].clone();
+            self.recall = Some((position + 1, draft));
+        } else {
+            self.line = draft;
+        }
+    }
+
+    /// Converts the line, records it in the history and starts a new line.
+    /// Returns `None` for a blank line
+    pub fn submit(&mut self) -> Option<Result<Conversion>> {
+        let entry = std::mem::take(&mut self.line);
+        self.recall = None;
+        let entry = entry.trim();
+        if entry.is_empty() {
+            return None;
+        }
+        if self.history.last().map(String::as_str) != Some(entry) {
+            self.history.push(entry.to_string());
+        }
+        Some(self.encoder.convert_entry(entry))
+    }
+
+    /// One-line preview of the line: the conversion once it is complete,
+    /// otherwise what is missing or wrong so far
+    pub fn preview(&self) -> String {
+        let entry = self.line.trim();
+        if entry.is_empty() {
+            return String::new();
+        }
+        if entry.contains(|c: char| c.is_ascii_digit() || c == ':') {
+            return match self.encoder.convert_entry(entry) {
+                Ok(conversion) => format!("→ {conversion}"),
+                Err(e) => e.to_string(),
+            };
+        }
+
+        let words = split_phrase(entry);
+        let wordlist = self.encoder.wordlist();
+        for (i, word) in words.iter().enumerate() {
+            if wordlist.get_index(word).is_some() {
+                continue;
+            }
+            if i + 1 == words.len() && self.partial_word().is_some() {
+                return match self.completions(word).as_slice() {
+                    [] => format!("No word starts with '{word}'"),
+                    [only] => format!("Tab: {only}"),
+                    candidates => format!("{} words start with '{word}'", candidates.len()),
+                };
+            }
+            return format!("'{word}' is not in the dictionary");
+        }
+        if !PHRASE_LENGTHS.contains(&words.len()) {
+            return format!("{} of 4 (IPv4) or 6, 9 or 12 (IPv6) words", words.len());
+        }
+        match self.encoder.convert_entry(entry) {
+            Ok(conversion) => format!("→ {conversion}"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    /// Runs the editor on the terminal until Ctrl-D on an empty line, Ctrl-C
+    /// or Escape twice.
+    ///
+    /// When `term` is not a terminal, each input line is converted instead and
+    /// its result (or error) printed, so piped sessions still work.
+    pub fn run(&mut self, term: &Term) -> Result<()> {
+        if !term.is_term() {
+            for line in std::io::stdin().lock().lines() {
+                self.line = line?;
+                if let Some(result) = self.submit() {
+                    term.write_line(&result_line(&result))?;
+                }
+            }
+            return Ok(());
+        }
+
+        term.write_line("Type an address or phrase; Tab completes words, Up/Down recall history")?;
+        self.start_line(term)?;
+        let mut escaped = false;
+        loop {
+            let mut listed = None;
+            match term.read_key()? {
+                Key::Char('\u{4}') if self.line.is_empty() => break,
+                Key::CtrlC => break,
+                Key::Escape if escaped || self.line.is_empty() => break,
+                Key::Escape => {
+                    self.clear();
+                    escaped = true;
+                    self.redraw(term, None)?;
+                    continue;
+                }
+                Key::Char(c) if !c.is_control() => self.line.push(c),
+                Key::Backspace => self.backspace(),
+                Key::Tab => {
+                    let candidates = self.complete();
+                    if candidates.len() > 1 {
+                        listed = Some(candidates);
+                    }
+                }
+                Key::ArrowUp => self.history_back(),
+                Key::ArrowDown => self.history_forward(),
+                Key::Enter => {
+                    let result = self.submit();
+                    term.move_cursor_down(1)?;
+                    term.clear_line()?;
+                    if let Some(result) = result {
+                        term.write_line(&result_line(&result))?;
+                    }
+                    self.start_line(term)?;
+                }
+                _ => {}
+            }
+            escaped = false;
+            self.redraw(term, listed.as_deref())?;
+        }
+        term.move_cursor_down(1)?;
+        term.clear_line()?;
+        Ok(())
+    }
+
+    /// The last word of the line, unless the line ends in a separator
+    fn partial_word(&self) -> Option<&str> {
+        let word = self.line.rsplit(is_separator).next()?;
+        (!word.is_empty()).then_some(word)
+    }
+
+    /// Writes a fresh prompt, leaving a line free below it for the preview
+    fn start_line(&self, term: &Term) -> Result<()> {
+        term.write_line("")?;
+        term.move_cursor_up(1)?;
+        self.redraw(term, None)
+    }
+
+    /// Rewrites the prompt line and the preview (or Tab candidates) below it
+    fn redraw(&self, term: &Term, candidates: Option<&[String]>) -> Result<()> {
+        let status = match candidates {
+            Some(candidates) => {
+                let mut listed = candidates[..candidates.len().min(MAX_LISTED)].join(" ");
+                if candidates.len() > MAX_LISTED {
+                    listed.push_str(&format!(" (+{} more)", candidates.len() - MAX_LISTED));
+                }
+                listed
+            }
+            None => self.preview(),
+        };
+        let width = usize::from(term.size().1).saturating_sub(1);
+        term.clear_line()?;
+        term.write_str("\n")?;
+        term.clear_line()?;
+        term.write_str(&status.chars().take(width).collect::<String>())?;
+        term.move_cursor_up(1)?;
+        term.write_str(&format!("\r{PROMPT}{}", self.line))?;
+        term.flush()?;
+        Ok(())
+    }
+}
+
+/// Whether `c` separates words in a phrase
+fn is_separator(c: char) -> bool {
+    c.is_whitespace() || c == '.' || c == '-'
+}
+
+/// The longest prefix `a` shares with `b`
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let len = a
+        .char_indices()
+        .zip(b.chars())
+        .take_while(|((_, x), y)| x == y)
+        .last()
+        .map_or(0, |((i, x), _)| i + x.len_utf8());
+    &a[..len]
+}
+
+/// Splits a phrase on any mix of separators
+fn split_phrase(phrase: &str) -> Vec<&str> {
+    phrase
+        .split(is_separator)
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Output line for a submitted entry
+fn result_line(result: &Result<Conversion>) -> String {
+    match result {
+        Ok(conversion) => conversion.to_string(),
+        Err(e) => format!("Error: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repl() -> Repl {
+        Repl::new(FourWordAdaptiveEncoder::new().unwrap())
+    }
+
+    #[test]
+    fn test_completion() {
+        let mut repl = repl();
+        repl.insert_str("10.0.0.1 a");
+        let candidates = repl.complete();
+        assert!(candidates.len() > 1);
+        assert!(candidates.iter().all(|word| word.starts_with('a')));
+        assert_eq!(repl.line(), "10.0.0.1 a");
+
+        // A prefix only one word has completes the word and moves on
+        let wordlist = repl.encoder().wordlist();
+        let (word, prefix) = (0..wordlist.len() as u16)
+            .filter_map(|index| wordlist.get_word(index))
+            .find_map(|word| {
+                let prefix = &word[..word.len() - 1];
+                (repl.completions(prefix) == [word]).then_some((word.to_string(), prefix))
+            })
+            .unwrap();
+        let mut repl = self::repl();
+        repl.insert_str(&prefix.to_uppercase());
+        assert_eq!(repl.preview(), format!("Tab: {word}"));
+        assert_eq!(repl.complete(), [word.as_str()]);
+        assert_eq!(repl.line(), format!("{word} "));
+        assert!(repl.complete().is_empty());
+
+        let mut repl = self::repl();
+        repl.insert_str("xqzv");
+        assert!(repl.complete().is_empty());
+        assert_eq!(repl.line(), "xqzv");
+        assert_eq!(repl.preview(), "No word starts with 'xqzv'");
+
+        let phrase = repl.encoder().encode("10.0.0.1:80").unwrap();
+        repl.clear();
+        repl.insert_str(&phrase);
+        assert_eq!(repl.preview(), "→ 10.0.0.1:80");
+    }
+
+    #[test]
+    fn test_preview() {
+        let mut repl = repl();
+        assert_eq!(repl.preview(), "");
+
+        repl.insert_str("192.168.1.1:443");
+        let words = repl.encoder().encode("192.168.1.1:443").unwrap();
+        assert_eq!(repl.preview(), format!("→ {words}"));
+
+        repl.clear();
+        let first = words.split(' ').next().unwrap();
+        repl.insert_str(&format!("{first} {first} "));
+        assert_eq!(repl.preview(), "2 of 4 (IPv4) or 6, 9 or 12 (IPv6) words");
+
+        repl.clear();
+        repl.insert_str("xqzv ");
+        assert_eq!(repl.preview(), "'xqzv' is not in the dictionary");
+    }
+
+    #[test]
+    fn test_history() {
+        let mut repl = repl();
+        for entry in ["10.0.0.1", "10.0.0.2", "10.0.0.2", " "] {
+            repl.insert_str(entry);
+            repl.submit();
+        }
+        assert_eq!(repl.history(), ["10.0.0.1", "10.0.0.2"]);
+
+        repl.insert_str("draft");
+        repl.history_back();
+        assert_eq!(repl.line(), "10.0.0.2");
+        repl.history_back();
+        repl.history_back();
+        assert_eq!(repl.line(), "10.0.0.1");
+        repl.history_forward();
+        assert_eq!(repl.line(), "10.0.0.2");
+        repl.history_forward();
+        assert_eq!(repl.line(), "draft");
+
+        repl.history_back();
+        let result = repl.submit().unwrap().unwrap();
+        assert_eq!(result.input, "10.0.0.2");
+        assert_eq!(repl.line(), "");
+        assert!(repl.submit().is_none());
+    }
+}
@@ -0,0 +1,318 @@
+//! Reserved protocol words.
+//!
+//! Applications that speak phrases over a voice channel sometimes need
+//! keywords of their own: a separator between addresses, "correction",
+//! "repeat". Reserving dictionary words for them (see
+//! [`EncodingProfile::reserve_words`]) guarantees those words never appear in
+//! an encoding.
+//!
+//! Whenever an encoding would use a reserved word, it emits the profile's
+//! escape word followed by a stand-in: the `j`th unreserved word of the list
+//! stands for the `j`th entry of the escape table (the escape word itself,
+//! then the reserved words in order). Phrases therefore grow by one word per
+//! escape, and both sides must use profiles with the same
+//! [`fingerprint`](EncodingProfile::fingerprint).
+//!
+//! The reservation is recorded in the phrase itself: an escaped phrase leads
+//! with the [`ESCAPE_TAG`] word and is padded with trailing escape words
+//! until no decoder would read its length as a plain phrase (4, 6, 9 or 12
+//! words, or one more for a version tag or selector). A decoder with the
+//! reservation strips the tag and padding and restores the reserved words;
+//! [`strip_tag`](crate::version) refuses the phrase in a decoder without
+//! it, rather than reading the escape pairs as address words. Reserved words
+//! anywhere but an escaped phrase are refused too, so no phrase decodes to
+//! one address with the reservation and another without.
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::profile::EncodingProfile;
+use crate::version::{ESCAPE_TAG, is_escaped, plain_length};
+use crate::wordlist::Wordlist;
+use std::fmt;
+
+/// Escape table resolved against a wordlist: the escape word's index first
+struct EscapeTable {
+    indices: Vec<u16>,
+}
+
+impl EscapeTable {
+    /// Resolves the profile's words, or `None` if nothing is reserved
+    fn resolve<W: Wordlist + ?Sized>(
+        wordlist: &W,
+        profile: &EncodingProfile,
+    ) -> Result<Option<Self>> {
+        let Some(escape) = profile.escape_word() else {
+            return Ok(None);
+        };
+        let indices = std::iter::once(escape)
+            .chain(profile.reserved_words().iter().map(String::as_str))
+            .map(|word| match wordlist.get_index(word) {
+                None => Err(FourWordError::invalid_value(
+                    "reserved word",
+                    word,
+                    "not in the wordlist",
+                )),
+                Some(ESCAPE_TAG) => Err(FourWordError::invalid_value(
+                    "reserved word",
+                    word,
+                    "leads escaped phrases, so it cannot be reserved",
+                )),
+                Some(index) => Ok(index),
+            })
+            .collect::<Result<_>>()?;
+        Ok(Some(EscapeTable { indices }))
+    }
+
+    /// Position of `index` in the table, if it is reserved
+    fn position(&self, index: u16) -> Option<usize> {
+        self.indices.iter().position(|&reserved| reserved == index)
+    }
+
+    /// Index of the stand-in for table entry `position`
+    fn stand_in(&self, position: usize) -> u16 {
+        (0..u16::MAX)
+            .filter(|index| !self.indices.contains(index))
+            .nth(position)
+            .unwrap_or(u16::MAX)
+    }
+
+    /// Table entry a stand-in word stands for
+    fn entry_for(&self, stand_in: u16) -> Option<u16> {
+        if self.indices.contains(&stand_in) {
+            return None;
+        }
+        let below = self.indices.iter().filter(|&&i| i < stand_in).count();
+        self.indices.get(usize::from(stand_in) - below).copied()
+    }
+}
+
+/// Writes `phrase` to `out`. If it uses a reserved word, the phrase is
+/// written behind the [`ESCAPE_TAG`] word with each reserved word replaced by
+/// its escape pair, and padded out of the plain lengths.
+pub(crate) fn escape<W: Wordlist + ?Sized>(
+    wordlist: &W,
+    profile: &EncodingProfile,
+    phrase: &str,
+    out: &mut impl fmt::Write,
+) -> Result<()> {
+    let Some(table) = EscapeTable::resolve(wordlist, profile)? else {
+        return Ok(out.write_str(phrase)?);
+    };
+    let entry = |word: &str| {
+        wordlist
+            .get_index(word)
+            .and_then(|index| table.position(index))
+    };
+    if !phrase.split(' ').any(|word| entry(word).is_some()) {
+        return Ok(out.write_str(phrase)?);
+    }
+
+    let escape_word = word_at(wordlist, table.indices[0])?;
+    out.write_str(word_at(wordlist, ESCAPE_TAG)?)?;
+    let mut len = 1;
+    for word in phrase.split(' ') {
+        match entry(word) {
+            Some(entry) => {
+                let stand_in = word_at(wordlist, table.stand_in(entry))?;
+                write!(out, " {escape_word} {stand_in}")?;
+                len += 2;
+            }
+            None => {
+                write!(out, " {word}")?;
+                len += 1;
+            }
+        }
+    }
+    while plain_length(len) {
+        write!(out, " {escape_word}")?;
+        len += 1;
+    }
+    Ok(())
+}
+
+/// Looks up the word at `index`
+fn word_at<W: Wordlist + ?Sized>(wordlist: &W, index: u16) -> Result<&str> {
+    wordlist
+        .get_word(index)
+        .ok_or(FourWordError::InvalidWordIndex(index))
+}
+
+/// Restores the reserved words in an escaped phrase.
+///
+/// Returns `None` when the profile reserves nothing or `words` is not an
+/// escaped phrase, so the phrase can be decoded as it is. Reserved words in
+/// any other phrase are an error, since [`escape`] never writes one.
+pub(crate) fn unescape<W: Wordlist + ?Sized>(
+    wordlist: &W,
+    profile: &EncodingProfile,
+    words: &[&str],
+) -> Result<Option<String>> {
+    let Some(table) = EscapeTable::resolve(wordlist, profile)? else {
+        return Ok(None);
+    };
+    let phrase = || words.join(" ");
+    if !is_escaped(wordlist, words) {
+        let reserved = words.iter().any(|word| {
+            wordlist
+                .get_index(word)
+                .is_some_and(|index| table.position(index).is_some())
+        });
+        if reserved {
+            return Err(FourWordError::invalid_value(
+                "phrase",
+                &phrase(),
+                "reserved words only appear in escaped phrases",
+            ));
+        }
+        return Ok(None);
+    }
+
+    // Trailing escape words are padding; stand-ins are never the escape word
+    let is_escape = |word: &str| wordlist.get_index(word) == Some(table.indices[0]);
+    let body = &words[1..];
+    let body = body
+        .iter()
+        .rposition(|word| !is_escape(word))
+        .map_or(&body[..0], |last| &body[..=last]);
+    if !body.iter().any(|word| is_escape(word)) {
+        // Led by word 0 by chance, as keyed phrases can be
+        return Ok(None);
+    }
+
+    let mut restored = Vec::with_capacity(words.len());
+    let mut iter = body.iter();
+    while let Some(&word) = iter.next() {
+        let index = wordlist.get_index(word);
+        match index.and_then(|index| table.position(index)) {
+            None => restored.push(word),
+            Some(0) => {
+                let stand_in = iter.next().ok_or_else(|| {
+                    FourWordError::invalid_value("phrase", &phrase(), "ends with the escape word")
+                })?;
+                let entry = wordlist
+                    .get_index(stand_in)
+                    .and_then(|index| table.entry_for(index))
+                    .and_then(|index| wordlist.get_word(index))
+                    .ok_or_else(|| {
+                        FourWordError::invalid_value(
+                            "escape",
+                            stand_in,
+                            format!("not a valid escape after '{word}'"),
+                        )
+                    })?;
+                restored.push(entry);
+            }
+            Some(_) => {
+                return Err(FourWordError::invalid_value(
+                    "word",
+                    word,
+                    "reserved words cannot appear in a phrase",
+                ));
+            }
+        }
+    }
+    Ok(Some(restored.join(" ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary4k::DICTIONARY;
+
+    fn profile() -> EncodingProfile {
+        let dict = DICTIONARY;
+        let mut profile = EncodingProfile::new();
+        let (escape, reserved) = (dict.get_word(5).unwrap(), dict.get_word(2).unwrap());
+        profile.reserve_words(escape, &[reserved]).unwrap();
+        profile
+    }
+
+    #[test]
+    fn test_escape_round_trip() {
+        let profile = profile();
+        let dict = DICTIONARY;
+        let word = |index| dict.get_word(index).unwrap();
+        // Indices 2 and 5 are reserved, so stand-ins are 0 (escape) and 1 (index 2)
+        let phrase = [word(2), word(3), word(5), word(0)].join(" ");
+
+        // The tag and six words would read as a tagged IPv6 phrase, so one
+        // word of padding follows
+        let mut escaped = String::new();
+        escape(&dict, &profile, &phrase, &mut escaped).unwrap();
+        let expected = [
+            word(ESCAPE_TAG),
+            word(5),
+            word(1),
+            word(3),
+            word(5),
+            word(0),
+            word(0),
+            word(5),
+        ]
+        .join(" ");
+        assert_eq!(escaped, expected);
+
+        let words: Vec<&str> = escaped.split(' ').collect();
+        let restored = unescape(&dict, &profile, &words).unwrap();
+        assert_eq!(restored.as_deref(), Some(phrase.as_str()));
+    }
+
+    #[test]
+    fn test_unescape_rejects_misuse() {
+        let profile = profile();
+        let dict = DICTIONARY;
+        let word = |index| dict.get_word(index).unwrap();
+        let tagged = |body: &[u16]| {
+            let mut words = vec![word(ESCAPE_TAG)];
+            words.extend(body.iter().map(|&index| word(index)));
+            words
+        };
+        for bad in [
+            // Reserved words outside an escaped phrase
+            vec![word(2), word(0)],
+            vec![word(5), word(2)],
+            vec![word(5), word(9)],
+            // A reserved word as a stand-in, or left unescaped
+            tagged(&[5, 2, 3, 3, 3, 3, 3]),
+            tagged(&[5, 1, 2, 3, 3, 3, 3]),
+        ] {
+            assert!(unescape(&dict, &profile, &bad).is_err(), "{bad:?}");
+        }
+        let plain = [word(0), word(1)];
+        assert_eq!(unescape(&dict, &profile, &plain).unwrap(), None);
+    }
+
+    #[test]
+    fn test_escaped_phrases_never_have_plain_length() {
+        let profile = profile();
+        let dict = DICTIONARY;
+        let word = |index| dict.get_word(index).unwrap();
+        for len in [4, 6, 9, 12] {
+            let mut phrase = vec![word(7); len];
+            phrase[len - 1] = word(2);
+            let mut escaped = String::new();
+            escape(&dict, &profile, &phrase.join(" "), &mut escaped).unwrap();
+            let words: Vec<&str> = escaped.split(' ').collect();
+            assert!(!plain_length(words.len()), "{escaped}");
+            let restored = unescape(&dict, &profile, &words).unwrap();
+            assert_eq!(restored, Some(phrase.join(" ")));
+        }
+
+        // Escape pairs without the tag were not written by escape
+        let untagged = [word(5), word(1), word(3), word(4), word(6)];
+        assert!(unescape(&dict, &profile, &untagged).is_err());
+        // A tagged phrase with nothing escaped is left for strip_tag to refuse
+        let padding = [
+            word(0),
+            word(1),
+            word(3),
+            word(4),
+            word(6),
+            word(5),
+            word(5),
+            word(5),
+        ];
+        assert_eq!(unescape(&dict, &profile, &padding).unwrap(), None);
+    }
+}
@@ -0,0 +1,177 @@
+//! Hostnames to phrases (`resolve` feature).
+//!
+//! [`resolve_and_encode`] takes `host`, `host:port` or `host:service`,
+//! resolves the host through the system resolver on tokio and encodes every
+//! A and AAAA record it returns, so a name can be turned into phrases
+//! without running `dig` and pasting the addresses back in. Each
+//! [`ResolvedPhrase`] says which record type it came from; duplicate
+//! addresses are dropped and the resolver's order is kept.
+//!
+//! ```rust,no_run
+//! use four_word_networking::resolve::resolve_and_encode;
+//!
+//! # async fn show() -> four_word_networking::Result<()> {
+//! for resolved in resolve_and_encode("example.com:443").await? {
+//!     println!("{:<4} {} {}", resolved.family, resolved.addr, resolved.words);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Without a port the phrases are encoded without one.
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::ports::parse_port;
+use crate::wordlist::Wordlist;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::net::SocketAddr;
+
+/// Port that marks a phrase encoded without one
+const NO_PORT: u16 = 65535;
+
+/// Longest hostname, in characters, without a trailing dot
+const MAX_HOSTNAME_LEN: usize = 253;
+
+/// One resolved address and its phrase
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ResolvedPhrase {
+    /// DNS record type the address came from: `A` or `AAAA`
+    pub family: &'static str,
+    /// The resolved address, with port 65535 if none was given
+    pub addr: SocketAddr,
+    /// The phrase, words separated by single spaces
+    pub words: String,
+    /// Whether the phrase decodes back to `addr` exactly
+    pub lossless: bool,
+}
+
+/// Resolves `input` (`host`, `host:port` or `host:service`) and encodes each
+/// address as English words
+pub async fn resolve_and_encode(input: &str) -> Result<Vec<ResolvedPhrase>> {
+    resolve_and_encode_with(&FourWordAdaptiveEncoder::new()?, input).await
+}
+
+/// Resolves `input` and encodes each address with `encoder`
+pub async fn resolve_and_encode_with<W: Wordlist>(
+    encoder: &FourWordAdaptiveEncoder<W>,
+    input: &str,
+) -> Result<Vec<ResolvedPhrase>> {
+    let (host, port) = parse_host(input)?;
+    let addrs = tokio::net::lookup_host((host, port.unwrap_or(NO_PORT)))
+        .await
+        .map_err(|e| FourWordError::Dns(format!("Cannot resolve {host}: {e}")))?;
+
+    let mut resolved: Vec<ResolvedPhrase> = Vec::new();
+    for addr in addrs {
+        if resolved.iter().any(|seen| seen.addr == addr) {
+            continue;
+        }
+        let mut words = String::new();
+        encoder.encode_into(addr, &mut words)?;
+        resolved.push(ResolvedPhrase {
+            family: if addr.is_ipv4() { "A" } else { "AAAA" },
+            lossless: encoder.decode_socket_addr(&words)? == addr,
+            addr,
+            words,
+        });
+    }
+    if resolved.is_empty() {
+        return Err(FourWordError::Dns(format!(
+            "{host} has no A or AAAA records"
+        )));
+    }
+    Ok(resolved)
+}
+
+/// Splits `host[:port]` into a hostname and an optional port or service name
+pub fn parse_host(input: &str) -> Result<(&str, Option<u16>)> {
+    let input = input.trim();
+    let (host, port) = match input.rsplit_once(':') {
+        Some((host, port)) => (host, Some(parse_port(port)?)),
+        None => (input, None),
+    };
+    if !is_hostname(host) {
+        return Err(FourWordError::invalid_value(
+            "hostname",
+            host,
+            "not a valid DNS name",
+        ));
+    }
+    Ok((host, port))
+}
+
+/// Whether `host` is a syntactically valid DNS hostname: dot-separated
+/// labels of 1-63 letters, digits and inner dashes, optionally ending in a
+/// dot, with at least one letter so IPv4 addresses do not qualify
+pub fn is_hostname(host: &str) -> bool {
+    let host = host.strip_suffix('.').unwrap_or(host);
+    !host.is_empty()
+        && host.len() <= MAX_HOSTNAME_LEN
+        && host.bytes().any(|b| b.is_ascii_alphabetic())
+        && host.split('.').all(|label| {
+            (1..=63).contains(&label.len())
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_parse_host() {
+        assert_eq!(
+            parse_host("example.com:443").unwrap(),
+            ("example.com", Some(443))
+        );
+        assert_eq!(
+            parse_host("example.com:https").unwrap(),
+            ("example.com", Some(443))
+        );
+        assert_eq!(parse_host(" localhost ").unwrap(), ("localhost", None));
+        assert_eq!(parse_host("example.com.").unwrap(), ("example.com.", None));
+        for bad in [
+            "",
+            "10.0.0.1",
+            "-bad.com",
+            "bad-.com",
+            "a..b",
+            "host:nope",
+            "2001:db8::1",
+        ] {
+            assert!(parse_host(bad).is_err(), "{bad}");
+        }
+        assert!(!is_hostname(&"a".repeat(64)));
+        assert!(is_hostname("xn--bcher-kva.example"));
+    }
+
+    #[tokio::test]
+    async fn test_resolves_localhost() {
+        let resolved = resolve_and_encode("localhost:8080").await.unwrap();
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        for entry in &resolved {
+            assert!(entry.addr.ip().is_loopback());
+            assert_eq!(entry.addr.port(), 8080);
+            assert_eq!(entry.family == "A", entry.addr.is_ipv4());
+            assert!(entry.lossless);
+            assert_eq!(
+                encoder.decode_socket_addr(&entry.words).unwrap(),
+                entry.addr
+            );
+        }
+        let unique: HashSet<_> = resolved.iter().map(|entry| entry.addr).collect();
+        assert_eq!(unique.len(), resolved.len());
+
+        assert!(resolve_and_encode("10.0.0.1:80").await.is_err());
+    }
+}
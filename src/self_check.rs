@@ -0,0 +1,206 @@
+//! Start-up verification of the wordlist and bit layout.
+//!
+//! [`FourWordAdaptiveEncoder::self_check`] is cheap enough to call every time
+//! a service starts. It catches corrupted or malformed custom wordlists, and
+//! builds whose packing differs from the published layout, before any phrase
+//! is handed out.
+
+#![forbid(unsafe_code)]
+
+use crate::dictionary4k::DICTIONARY_SIZE;
+use crate::error::{FourWordError, Result};
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::wordlist::Wordlist;
+use std::net::IpAddr;
+
+/// Known-answer vectors: an address and the word indices it must encode to.
+///
+/// Indices rather than words pin the bit layout independently of the
+/// wordlist. Together they cover both families, every IPv6 compression
+/// category, each phrase length, ports 0 and 65534, and the no-port marker.
+const KNOWN_ANSWERS: &[(&str, &[u16])] = &[
+    ("192.168.1.1:443", &[443, 16, 2049, 3082]),
+    ("10.0.0.1", &[4095, 31, 0, 160]),
+    ("0.0.0.0:0", &[0, 0, 0, 0]),
+    ("255.255.255.255:65534", &[4094, 4095, 4095, 4095]),
+    ("[::1]:443", &[6, 0, 1, 0, 2816, 27]),
+    ("[::]:0", &[166, 0, 0, 0, 0, 0]),
+    ("[fe80::1]:22", &[294, 48, 1, 0, 1536, 1]),
+    // Unique local encoding keeps only the /64, so the interface ID is zero;
+    // a well-known port fits beside it in 6 words, others take 9
+    (
+        "[fd00:1234:5678:9abc::]:8080",
+        &[71, 288, 1588, 1925, 3226, 2123],
+    ),
+    (
+        "[fd00:1234:5678:9abc::]:4242",
+        &[3400, 15, 1042, 1379, 2680, 3017, 146, 1, 0],
+    ),
+    (
+        "[2001:db8::1]:65534",
+        &[103, 0, 0, 16, 259, 4064, 255, 0, 0],
+    ),
+    (
+        "[2001:db8:1:2:3:4:5:6]:443",
+        &[109, 16, 512, 48, 768, 0, 4, 80, 1536, 2992, 1, 0],
+    ),
+    // Sixteen-byte payloads leave no room for a port in twelve words
+    (
+        "2606:4700::1111",
+        &[1680, 98, 71, 0, 0, 0, 0, 0, 0, 0, 273, 4081],
+    ),
+    (
+        "2606:4700:1:2:3:4:5:6",
+        &[1680, 98, 71, 0, 1, 32, 768, 0, 4, 80, 1536, 4080],
+    ),
+    // Well-known multicast groups are stored as a table index
+    ("ff02::1", &[2787, 32, 3840, 4095, 0, 0]),
+    // IPv4-compatible addresses are deprecated and take the special fallback
+    ("::c000:201", &[208, 0, 0, 0, 0, 0, 0, 0, 0, 12, 258, 4080]),
+];
+
+impl<W: Wordlist> FourWordAdaptiveEncoder<W> {
+    /// Verifies the wordlist and the encoding layout.
+    ///
+    /// Checks that every index maps to a word that maps back to the same
+    /// index, then encodes a fixed set of addresses and compares the word
+    /// indices against pinned values before decoding them again. Vectors
+    /// covered by a NAT64 or provider prefix in the encoder's profile are
+    /// skipped, since the profile legitimately changes their encoding.
+    pub fn self_check(&self) -> Result<()> {
+        check_wordlist(self.wordlist())?;
+
+        for &(address, expected) in KNOWN_ANSWERS {
+            if let Some(IpAddr::V6(ip)) = address
+                .trim_start_matches('[')
+                .split(']')
+                .next()
+                .and_then(|ip| ip.parse().ok())
+                && (self.profile().match_nat64(&ip).is_some()
+                    || self.profile().match_provider(&ip).is_some())
+            {
+                continue;
+            }
+
+            let words = self.encode(address)?;
+            let indices: Vec<Option<u16>> = words
+                .split(' ')
+                .map(|word| self.wordlist().get_index(word))
+                .collect();
+            if !indices
+                .iter()
+                .copied()
+                .eq(expected.iter().map(|&i| Some(i)))
+            {
+                return Err(FourWordError::SelfCheckFailed(format!(
+                    "{address} encoded as '{words}', expected word indices {expected:?}"
+                )));
+            }
+
+            let decoded = self.decode(&words)?;
+            if decoded != address {
+                return Err(FourWordError::SelfCheckFailed(format!(
+                    "'{words}' decoded as {decoded}, expected {address}"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks that `wordlist` is a bijection between 0..4096 and its words
+fn check_wordlist<W: Wordlist + ?Sized>(wordlist: &W) -> Result<()> {
+    if wordlist.len() != DICTIONARY_SIZE {
+        return Err(FourWordError::SelfCheckFailed(format!(
+            "wordlist has {} words, expected {DICTIONARY_SIZE}",
+            wordlist.len()
+        )));
+    }
+    for index in 0..DICTIONARY_SIZE as u16 {
+        let word = wordlist.get_word(index).ok_or_else(|| {
+            FourWordError::SelfCheckFailed(format!("wordlist has no word at index {index}"))
+        })?;
+        let found = wordlist.get_index(word);
+        if found != Some(index) {
+            return Err(FourWordError::SelfCheckFailed(format!(
+                "word '{word}' at index {index} looks up as {found:?}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary4k::{DICTIONARY, Dictionary4K};
+    use crate::language::Language;
+    use crate::nat64::Nat64Prefix;
+    use crate::profile::EncodingProfile;
+    use crate::wordlist::Wordlist;
+
+    /// Wordlist whose reverse lookup sends one word to the wrong index
+    struct Corrupted(Dictionary4K);
+
+    impl Wordlist for Corrupted {
+        fn get_word(&self, index: u16) -> Option<&str> {
+            self.0.get_word(index)
+        }
+
+        fn get_index(&self, word: &str) -> Option<u16> {
+            match self.0.get_index(word)? {
+                7 => Some(8),
+                index => Some(index),
+            }
+        }
+
+        fn len(&self) -> usize {
+            DICTIONARY_SIZE
+        }
+    }
+
+    #[test]
+    fn test_builtin_languages_pass() {
+        for &language in Language::ALL {
+            let encoder = FourWordAdaptiveEncoder::with_language(language).unwrap();
+            if let Err(e) = encoder.self_check() {
+                panic!("{language}: {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_corrupted_wordlist_fails() {
+        let corrupted = Corrupted(DICTIONARY);
+        let encoder = FourWordAdaptiveEncoder::with_wordlist(&corrupted).unwrap();
+        let error = encoder.self_check().unwrap_err();
+        assert!(
+            matches!(error, FourWordError::SelfCheckFailed(_)),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_nat64_profile_skips_covered_vectors() {
+        let mut profile = EncodingProfile::new();
+        profile
+            .add_nat64_prefix("2606:4700::/96".parse::<Nat64Prefix>().unwrap())
+            .unwrap();
+        let encoder = FourWordAdaptiveEncoder::new()
+            .unwrap()
+            .with_profile(profile);
+        encoder.self_check().unwrap();
+    }
+
+    #[test]
+    fn test_provider_profile_skips_covered_vectors() {
+        let mut profile = EncodingProfile::new();
+        profile
+            .add_provider_prefix("2606:4700::/32".parse().unwrap())
+            .unwrap();
+        let encoder = FourWordAdaptiveEncoder::new()
+            .unwrap()
+            .with_profile(profile);
+        encoder.self_check().unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+//! Comparing two phrases in terms a user can act on.
+//!
+//! Before connecting, a user may want to confirm that the phrase they were
+//! given and the phrase they typed point at the same endpoint. Comparing the
+//! words alone is misleading: one changed word can mean a different port on
+//! the same host or a machine on the other side of the world. [`similarity`]
+//! decodes both phrases and reports how the endpoints relate, alongside how
+//! many words differ.
+//!
+//! [`similarity`]: crate::FourWordAdaptiveEncoder::similarity
+
+#![forbid(unsafe_code)]
+
+use crate::error::Result;
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::wordlist::Wordlist;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+
+/// IPv4 prefix length treated as one site
+pub const SITE_PREFIX_V4: u8 = 24;
+
+/// IPv6 prefix length treated as one site
+pub const SITE_PREFIX_V6: u8 = 64;
+
+/// How the endpoints behind two phrases relate, closest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EndpointRelation {
+    /// Same address and port
+    Identical,
+    /// Same address, different port
+    SameHostDifferentPort,
+    /// Different hosts in the same /24 (IPv4) or /64 (IPv6)
+    SameSite,
+    /// Unrelated endpoints, including IPv4 versus IPv6
+    Different,
+}
+
+impl fmt::Display for EndpointRelation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EndpointRelation::Identical => "same endpoint",
+            EndpointRelation::SameHostDifferentPort => "same host, different port",
+            EndpointRelation::SameSite => "same network, different host",
+            EndpointRelation::Different => "entirely different endpoint",
+        })
+    }
+}
+
+/// Result of comparing two phrases
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhraseSimilarity {
+    /// How the decoded endpoints relate
+    pub relation: EndpointRelation,
+    /// Word positions that differ, counting words only one phrase has
+    pub differing_words: usize,
+    /// Words in the longer phrase
+    pub total_words: usize,
+    /// Endpoint decoded from the first phrase
+    pub first: SocketAddr,
+    /// Endpoint decoded from the second phrase
+    pub second: SocketAddr,
+}
+
+impl PhraseSimilarity {
+    /// Checks whether both phrases reach the same endpoint
+    pub fn is_identical(&self) -> bool {
+        self.relation == EndpointRelation::Identical
+    }
+}
+
+impl fmt::Display for PhraseSimilarity {
+    /// Formats as e.g. "same host, different port (1 of 4 words differ)"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.differing_words {
+            0 => write!(f, "{} (words match)", self.relation),
+            1 => write!(
+                f,
+                "{} (1 of {} words differs)",
+                self.relation, self.total_words
+            ),
+            n => write!(
+                f,
+                "{} ({n} of {} words differ)",
+                self.relation, self.total_words
+            ),
+        }
+    }
+}
+
+impl<W: Wordlist> FourWordAdaptiveEncoder<W> {
+    /// Decodes both phrases and describes how their endpoints relate.
+    ///
+    /// Separators and letter case are ignored when counting differing words.
+    pub fn similarity(&self, first: &str, second: &str) -> Result<PhraseSimilarity> {
+        let first_addr = self.decode_socket_addr(first)?;
+        let second_addr = self.decode_socket_addr(second)?;
+
+        let first_words = Self::split_words(first);
+        let second_words = Self::split_words(second);
+        let total_words = first_words.len().max(second_words.len());
+        let matching = first_words
+            .iter()
+            .zip(&second_words)
+            .filter(|(a, b)| a.eq_ignore_ascii_case(b))
+            .count();
+
+        Ok(PhraseSimilarity {
+            relation: relation(first_addr, second_addr),
+            differing_words: total_words - matching,
+            total_words,
+            first: first_addr,
+            second: second_addr,
+        })
+    }
+}
+
+/// Classifies two endpoints
+fn relation(first: SocketAddr, second: SocketAddr) -> EndpointRelation {
+    if first == second {
+        return EndpointRelation::Identical;
+    }
+    if first.ip() == second.ip() {
+        return EndpointRelation::SameHostDifferentPort;
+    }
+    let same_site = match (first.ip(), second.ip()) {
+        (IpAddr::V4(a), IpAddr::V4(b)) => {
+            (u32::from(a) ^ u32::from(b)).leading_zeros() >= u32::from(SITE_PREFIX_V4)
+        }
+        (IpAddr::V6(a), IpAddr::V6(b)) => {
+            (u128::from(a) ^ u128::from(b)).leading_zeros() >= u32::from(SITE_PREFIX_V6)
+        }
+        _ => false,
+    };
+    if same_site {
+        EndpointRelation::SameSite
+    } else {
+        EndpointRelation::Different
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relations() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let compare = |a: &str, b: &str| {
+            let a = encoder.encode(a).unwrap();
+            let b = encoder.encode(b).unwrap();
+            encoder.similarity(&a, &b).unwrap()
+        };
+
+        let same = compare("192.168.1.10:443", "192.168.1.10:443");
+        assert!(same.is_identical());
+        assert_eq!(same.differing_words, 0);
+
+        let port = compare("192.168.1.10:443", "192.168.1.10:8443");
+        assert_eq!(port.relation, EndpointRelation::SameHostDifferentPort);
+        assert!(port.differing_words > 0);
+
+        assert_eq!(
+            compare("192.168.1.10:443", "192.168.1.20:443").relation,
+            EndpointRelation::SameSite
+        );
+        assert_eq!(
+            compare("[2001:db8::1]:443", "[2001:db8::2]:443").relation,
+            EndpointRelation::SameSite
+        );
+        let far = compare("192.168.1.10:443", "[::1]:443");
+        assert_eq!(far.relation, EndpointRelation::Different);
+        assert_eq!(far.total_words, 6);
+    }
+
+    #[test]
+    fn test_display_and_separators() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let words = encoder.encode("10.0.0.1:80").unwrap();
+        let dotted = words.replace(' ', ".").to_uppercase();
+        let similarity = encoder.similarity(&words, &dotted).unwrap();
+        assert!(similarity.is_identical());
+        assert_eq!(similarity.to_string(), "same endpoint (words match)");
+
+        assert!(encoder.similarity(&words, "not real words here").is_err());
+    }
+}
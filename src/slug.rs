@@ -0,0 +1,129 @@
+//! URL- and filename-safe normal form for phrases.
+//!
+//! [`slug`] turns a phrase into a string of lowercase ASCII letters, digits,
+//! `-` and `_` that can name a directory, an object-store key or a URL path
+//! segment without quoting, even on case-insensitive filesystems. Words are
+//! lowercased and joined with `-`; any other character, including non-ASCII
+//! letters from custom or accented wordlists, is escaped byte by byte as `_`
+//! followed by two lowercase hex digits. [`from_slug`] reverses the escaping.
+//!
+//! ```rust
+//! use four_word_networking::{from_slug, slug};
+//!
+//! assert_eq!(slug("Ocean Thunder canción"), "ocean-thunder-canci_c3_b3n");
+//! assert_eq!(from_slug("ocean-thunder-canci_c3_b3n")?, "ocean thunder canción");
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use std::fmt::Write;
+
+/// Separator between words in a slug
+const SEPARATOR: char = '-';
+
+/// Introduces an escaped byte
+const ESCAPE: char = '_';
+
+/// Converts a phrase (words separated by spaces, dots or dashes) to its slug
+pub fn slug(phrase: &str) -> String {
+    let mut slug = String::with_capacity(phrase.len());
+    let words = phrase
+        .split(|c: char| c.is_whitespace() || c == '.' || c == SEPARATOR)
+        .filter(|word| !word.is_empty());
+    for (position, word) in words.enumerate() {
+        if position > 0 {
+            slug.push(SEPARATOR);
+        }
+        for c in word.to_lowercase().chars() {
+            if c.is_ascii_lowercase() || c.is_ascii_digit() {
+                slug.push(c);
+            } else {
+                let mut buf = [0; 4];
+                for byte in c.encode_utf8(&mut buf).bytes() {
+                    // Writing to a String cannot fail
+                    let _ = write!(slug, "{ESCAPE}{byte:02x}");
+                }
+            }
+        }
+    }
+    slug
+}
+
+/// Recovers the space-separated phrase from a slug produced by [`slug`]
+pub fn from_slug(slug: &str) -> Result<String> {
+    let invalid = |reason: &str| FourWordError::invalid_value("slug", slug, reason);
+
+    let mut words = Vec::new();
+    for word in slug.split(SEPARATOR) {
+        if word.is_empty() {
+            return Err(invalid("empty word"));
+        }
+        let mut bytes = Vec::with_capacity(word.len());
+        let mut chars = word.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                ESCAPE => {
+                    let hex: String = chars.by_ref().take(2).collect();
+                    let byte = (hex.len() == 2)
+                        .then(|| u8::from_str_radix(&hex, 16).ok())
+                        .flatten()
+                        .ok_or_else(|| invalid("escape must be '_' and two hex digits"))?;
+                    bytes.push(byte);
+                }
+                'a'..='z' | '0'..='9' => bytes.push(c as u8),
+                _ => return Err(invalid(&format!("unexpected character '{c}'"))),
+            }
+        }
+        words.push(String::from_utf8(bytes).map_err(|_| invalid("escapes are not valid UTF-8"))?);
+    }
+    Ok(words.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FourWordAdaptiveEncoder;
+
+    #[test]
+    fn test_slug_round_trip() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        for address in ["192.168.1.1:443", "[2001:db8::1]:8080"] {
+            let words = encoder.encode(address).unwrap();
+            let slug = slug(&words);
+            assert!(
+                slug.chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-_".contains(c)),
+                "{slug}"
+            );
+            assert_eq!(encoder.decode(&from_slug(&slug).unwrap()).unwrap(), address);
+        }
+    }
+
+    #[test]
+    fn test_escaping_is_reversible() {
+        for phrase in ["canción über straße", "o'brien x_y", "日本 語"] {
+            let slug = slug(phrase);
+            assert!(slug.is_ascii(), "{slug}");
+            assert_eq!(from_slug(&slug).unwrap(), phrase);
+        }
+        // Separators and case are normalised
+        assert_eq!(slug(" Ocean.THUNDER-falcon "), "ocean-thunder-falcon");
+    }
+
+    #[test]
+    fn test_invalid_slugs() {
+        for bad in [
+            "",
+            "ocean--thunder",
+            "ocean_4",
+            "ocean_zz",
+            "Ocean",
+            "ocean thunder",
+            "_ff",
+        ] {
+            assert!(from_slug(bad).is_err(), "{bad}");
+        }
+    }
+}
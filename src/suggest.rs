@@ -0,0 +1,89 @@
+//! Word suggestions for pickers and autocomplete.
+//!
+//! GUI and web frontends can offer the dictionary as you type without
+//! shipping the wordlist themselves: [`suggest`] lists the words starting with
+//! a prefix and [`nearest`] the words closest to a misspelling. Both search the
+//! built-in English dictionary; [`suggest_in`] and [`nearest_in`] take any
+//! [`Dictionary4K`], such as another language or a custom list.
+//!
+//! Prefix lookups binary-search the dictionary's alphabetical index, so they
+//! cost a handful of comparisons however short the prefix.
+//!
+//! ```rust
+//! use four_word_networking::{nearest, suggest};
+//!
+//! let words = suggest("oce", 5);
+//! assert!(words.contains(&"ocean"));
+//! assert!(words.iter().all(|word| word.starts_with("oce")));
+//!
+//! assert_eq!(nearest("oceen", 3).first(), Some(&"ocean"));
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::dictionary4k::{DICTIONARY, Dictionary4K};
+use crate::fuzzy::nearest_indices;
+
+/// Up to `limit` built-in English words starting with `prefix` (ignoring
+/// case), in alphabetical order
+pub fn suggest(prefix: &str, limit: usize) -> Vec<&'static str> {
+    let dictionary = DICTIONARY;
+    dictionary
+        .prefix_indices(prefix)
+        .iter()
+        .take(limit)
+        .filter_map(|&index| dictionary.static_word(index))
+        .collect()
+}
+
+/// Same as [`suggest`] but against an explicit dictionary
+pub fn suggest_in<'a>(dictionary: &'a Dictionary4K, prefix: &str, limit: usize) -> Vec<&'a str> {
+    dictionary.words_with_prefix(prefix).take(limit).collect()
+}
+
+/// Up to `limit` built-in English words nearest to `word`, closest first.
+///
+/// Only words within [`MAX_SUGGESTION_DISTANCE`](crate::fuzzy::MAX_SUGGESTION_DISTANCE)
+/// edits are returned; ties keep dictionary order.
+pub fn nearest(word: &str, limit: usize) -> Vec<&'static str> {
+    let dictionary = DICTIONARY;
+    nearest_indices(&dictionary, word, limit)
+        .into_iter()
+        .filter_map(|(_, index)| dictionary.static_word(index))
+        .collect()
+}
+
+/// Same as [`nearest`] but against an explicit dictionary
+pub fn nearest_in<'a>(dictionary: &'a Dictionary4K, word: &str, limit: usize) -> Vec<&'a str> {
+    nearest_indices(dictionary, word, limit)
+        .into_iter()
+        .filter_map(|(_, index)| dictionary.get_word(index))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest() {
+        let words = suggest("Oce", 100);
+        assert!(words.contains(&"ocean"));
+        assert!(words.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(words, suggest_in(&DICTIONARY, "oce", 100));
+
+        assert_eq!(suggest("", 3).len(), 3);
+        assert!(suggest("a", 0).is_empty());
+        assert!(suggest("xqzv", 10).is_empty());
+    }
+
+    #[test]
+    fn test_nearest() {
+        let words = nearest("oceen", 5);
+        assert_eq!(words.first(), Some(&"ocean"));
+        assert!(words.len() <= 5);
+        assert_eq!(words, nearest_in(&DICTIONARY, "oceen", 5));
+        assert_eq!(nearest("ocean", 1), ["ocean"]);
+        assert!(nearest("xqzzvkjw", 5).is_empty());
+    }
+}
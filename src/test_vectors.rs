@@ -0,0 +1,254 @@
+//! Published golden test vectors.
+//!
+//! Implementations in other languages prove compatibility by reproducing
+//! these vectors byte for byte. Each one records an input, the word indices
+//! and words it encodes to with the built-in English list, and what those
+//! words decode back to. Together they cover IPv4, every IPv6 category that
+//! needs no profile, each phrase length, network prefixes, and the port edge
+//! cases: 0, 1, 65534, and 65535, which is the "no port" marker.
+//!
+//! The set only ever grows, and a vector never changes within an
+//! [`ENCODING_VERSION`]. `4wn gen-vectors` prints the set as JSON:
+//!
+//! ```rust
+//! use four_word_networking::test_vectors::test_vectors;
+//!
+//! let set = test_vectors()?;
+//! let encoder = four_word_networking::FourWordAdaptiveEncoder::new()?;
+//! let first = &set.vectors[0];
+//! assert_eq!(encoder.decode(&first.words)?, first.output);
+//! # #[cfg(feature = "serde")]
+//! println!("{}", set.to_json()?);
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::conversion::category_name;
+use crate::dictionary4k::DICTIONARY;
+use crate::error::{FourWordError, Result};
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::ipv6_compression::Ipv6Category;
+use crate::version::ENCODING_VERSION;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::net::Ipv6Addr;
+
+/// Inputs of the published vectors, in publication order
+const INPUTS: &[&str] = &[
+    // IPv4 and its port edge cases
+    "0.0.0.0:0",
+    "127.0.0.1:1",
+    "10.0.0.1",
+    "172.16.0.1:80",
+    "192.168.1.1:443",
+    "203.0.113.7:8080",
+    "8.8.8.8:65535",
+    "255.255.255.255:65534",
+    // Loopback and unspecified
+    "[::1]:443",
+    "::1",
+    "[::]:0",
+    // Link local
+    "[fe80::1]:22",
+    "fe80::1234:5678:9abc:def0",
+    // Unique local keeps only the /64; a well-known port fits in 6 words
+    "[fd00:1234:5678:9abc::1]:8080",
+    "[fc00:1234:5678:9abc::]:4242",
+    // Documentation, with an explicit port 65535 kept apart from none
+    "[2001:db8::1]:65534",
+    "[2001:db8::1]:65535",
+    "[2001:db8:1:2:3:4:5:6]:443",
+    // Global unicast, with and without room for a port
+    "2606:4700::1111",
+    "[2606:4700:4700::1111]:4433",
+    "2606:4700:1:2:3:4:5:6",
+    // Transition mechanisms
+    "[64:ff9b::c000:201]:80",
+    "[::ffff:192.0.2.1]:443",
+    "[2001:0:4136:e378:8000:63bf:3fff:fdd2]:3544",
+    "[2002:c000:201::1]:80",
+    // Multicast: a well-known group and a trimmed group ID
+    "ff02::1",
+    "[ff05::1:3]:547",
+    // Deprecated IPv4-compatible form takes the special fallback
+    "::c000:201",
+    // Network prefixes
+    "2001:db8:abcd::/48",
+    "2001:db8:abcd:1200::/56",
+    "2001:db8:abcd:12::/64",
+];
+
+/// One input and what it must encode and decode to
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TestVector {
+    /// Address, address and port, or IPv6 network prefix
+    pub input: String,
+    /// `ipv4`, or the IPv6 category the input is encoded under
+    pub category: &'static str,
+    /// Word indices of the phrase, in order
+    pub indices: Vec<u16>,
+    /// The phrase, words separated by single spaces
+    pub words: String,
+    /// What `words` decode to, written like `input`; differs from it when
+    /// the encoding drops part of the input or the port is 65535
+    pub output: String,
+}
+
+/// The published vectors with what they were generated against
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TestVectorSet {
+    /// Encoding version the vectors pin
+    pub encoding_version: u16,
+    /// Hex fingerprint of the wordlist the words come from
+    pub wordlist_fingerprint: String,
+    /// The vectors, in publication order
+    pub vectors: Vec<TestVector>,
+}
+
+impl TestVectorSet {
+    #[cfg(feature = "serde")]
+    /// Serializes the set as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Generates the published vectors with the built-in English wordlist
+pub fn test_vectors() -> Result<TestVectorSet> {
+    let encoder = FourWordAdaptiveEncoder::new()?;
+    let vectors = INPUTS
+        .iter()
+        .map(|input| match input.split_once('/') {
+            Some((network, length)) => prefix_vector(&encoder, input, network, length),
+            None => address_vector(&encoder, input),
+        })
+        .collect::<Result<_>>()?;
+    Ok(TestVectorSet {
+        encoding_version: ENCODING_VERSION,
+        wordlist_fingerprint: DICTIONARY.fingerprint_hex(),
+        vectors,
+    })
+}
+
+/// Vector for an address with or without a port
+fn address_vector(encoder: &FourWordAdaptiveEncoder, input: &str) -> Result<TestVector> {
+    let words = encoder.encode(input)?;
+    let addr = encoder.decode_socket_addr(&words)?;
+    Ok(TestVector {
+        input: input.to_string(),
+        category: category_name(encoder.ipv6_category(addr)?),
+        indices: indices(&words)?,
+        output: encoder.decode(&words)?,
+        words,
+    })
+}
+
+/// Vector for an IPv6 network prefix such as `2001:db8::/48`
+fn prefix_vector(
+    encoder: &FourWordAdaptiveEncoder,
+    input: &str,
+    network: &str,
+    length: &str,
+) -> Result<TestVector> {
+    let invalid = || FourWordError::invalid_value("prefix", input, "expected NETWORK/LENGTH");
+    let network: Ipv6Addr = network.parse().map_err(|_| invalid())?;
+    let length: u8 = length.parse().map_err(|_| invalid())?;
+    let words = encoder.encode_prefix(network, length)?;
+    let (decoded, decoded_length) = encoder.decode_prefix(&words)?;
+    Ok(TestVector {
+        input: input.to_string(),
+        category: category_name(Some(Ipv6Category::Prefix)),
+        indices: indices(&words)?,
+        output: format!("{decoded}/{decoded_length}"),
+        words,
+    })
+}
+
+/// Looks up the index of every word of `words`
+fn indices(words: &str) -> Result<Vec<u16>> {
+    words
+        .split(' ')
+        .enumerate()
+        .map(|(position, word)| {
+            DICTIONARY
+                .get_index(word)
+                .ok_or_else(|| FourWordError::unknown_word(position, word))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    #[test]
+    fn test_vectors_are_pinned() {
+        let set = test_vectors().unwrap();
+        assert_eq!(set.vectors.len(), INPUTS.len());
+        // A change here breaks every third-party implementation
+        let mut hasher = Sha256::new();
+        for vector in &set.vectors {
+            hasher.update(format!(
+                "{}|{:?}|{}\n",
+                vector.input, vector.indices, vector.output
+            ));
+        }
+        assert_eq!(
+            hex::encode(hasher.finalize()),
+            "6639abb12e97fb6ebe4639673ca9daf67c8a7680f59f504ce12998bd62be844e"
+        );
+    }
+
+    #[test]
+    fn test_vectors_cover_categories_and_ports() {
+        let set = test_vectors().unwrap();
+        for category in [
+            "ipv4",
+            "loopback",
+            "unspecified",
+            "link_local",
+            "unique_local",
+            "documentation",
+            "global_unicast",
+            "nat64",
+            "v4_mapped",
+            "teredo",
+            "six_to_four",
+            "multicast",
+            "special",
+            "prefix",
+        ] {
+            assert!(
+                set.vectors.iter().any(|vector| vector.category == category),
+                "{category}"
+            );
+        }
+        for length in [4, 6, 9, 12] {
+            assert!(
+                set.vectors
+                    .iter()
+                    .any(|vector| vector.indices.len() == length)
+            );
+        }
+
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        for vector in &set.vectors {
+            let words: Vec<&str> = vector.words.split(' ').collect();
+            assert_eq!(words.len(), vector.indices.len());
+            if vector.category != "prefix" {
+                assert_eq!(encoder.decode(&vector.words).unwrap(), vector.output);
+            }
+        }
+        // The no-port marker is dropped from the output
+        let marker = set
+            .vectors
+            .iter()
+            .find(|v| v.input == "8.8.8.8:65535")
+            .unwrap();
+        assert_eq!(marker.output, "8.8.8.8");
+    }
+}
@@ -0,0 +1,266 @@
+//! Transliteration aliases for dictated phrases.
+//!
+//! Callers reading words aloud produce spellings the dictionary does not use:
+//! British spellings ("colour" for "color"), homophones ("ate" for "eight"),
+//! or accented forms of the accent-folded Spanish and French lists. An
+//! [`AliasTable`] maps such words onto their canonical dictionary word and is
+//! consulted by lenient decoding
+//! ([`FourWordAdaptiveEncoder::decode_fuzzy`](crate::FourWordAdaptiveEncoder::decode_fuzzy))
+//! before typo correction, and by
+//! [`decode_normalized`](crate::FourWordAdaptiveEncoder::decode_normalized)
+//! for words the normalizer leaves unknown. The built-in homophones are the
+//! normalizer's own groups, so both paths agree on them. Deployments extend
+//! the table with mis-hearings gathered from their own call-center
+//! transcripts.
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::language::Language;
+use crate::normalize::{HOMOPHONE_GROUPS, resolve_homophone};
+use crate::wordlist::Wordlist;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+
+/// Built-in British spellings of English words as (heard, canonical) pairs
+const ENGLISH_SPELLINGS: &[(&str, &str)] = &[
+    ("colour", "color"),
+    ("favour", "favor"),
+    ("centre", "center"),
+    ("theatre", "theater"),
+    ("metre", "meter"),
+    ("tyre", "tire"),
+    ("cheque", "check"),
+    ("neighbour", "neighbor"),
+    ("honour", "honor"),
+    ("humour", "humor"),
+    ("harbour", "harbor"),
+    ("armour", "armor"),
+    ("flavour", "flavor"),
+    ("labour", "labor"),
+    ("vapour", "vapor"),
+    ("fibre", "fiber"),
+    ("defence", "defense"),
+    ("offence", "offense"),
+    ("licence", "license"),
+    ("programme", "program"),
+    ("catalogue", "catalog"),
+];
+
+/// Maps words that are not in a dictionary onto words that are
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AliasTable {
+    aliases: HashMap<String, String>,
+}
+
+impl AliasTable {
+    /// Creates an empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the built-in table for `language`.
+    ///
+    /// Holds the spelling variants for `language` and every homophone group
+    /// member that resolves to exactly one word of its dictionary. Entries
+    /// whose canonical word is missing from the dictionary, or whose heard word
+    /// is itself a dictionary word, are skipped.
+    pub fn builtin(language: Language) -> Self {
+        let dictionary = language.dictionary();
+        // Accented forms are handled by diacritic folding in `resolve`
+        let spellings = if language == Language::English {
+            ENGLISH_SPELLINGS
+        } else {
+            &[]
+        };
+        let homophones = HOMOPHONE_GROUPS
+            .iter()
+            .flat_map(|group| group.iter())
+            // Digits are read as dictionary indices, never as words
+            .filter(|heard| !heard.starts_with(|c: char| c.is_ascii_digit()))
+            .filter_map(|&heard| Some((heard, resolve_homophone(&dictionary, heard)?)));
+
+        let mut table = Self::new();
+        for (heard, canonical) in spellings.iter().copied().chain(homophones) {
+            // Entries are curated, so rejected ones simply do not apply to this list
+            let _ = table.insert(&dictionary, heard, canonical);
+        }
+        table
+    }
+
+    /// Adds an alias from `heard` to `canonical`.
+    ///
+    /// `canonical` must be in `wordlist`. `heard` must not be: aliasing a real
+    /// word would change the meaning of phrases that use it legitimately.
+    pub fn insert<W: Wordlist + ?Sized>(
+        &mut self,
+        wordlist: &W,
+        heard: &str,
+        canonical: &str,
+    ) -> Result<()> {
+        let heard = heard.trim().to_lowercase();
+        let canonical = canonical.trim().to_lowercase();
+        if heard.is_empty() {
+            return Err(FourWordError::invalid_value(
+                "alias",
+                &heard,
+                "must not be empty",
+            ));
+        }
+        if !wordlist.contains(&canonical) {
+            return Err(FourWordError::unknown_word(0, &canonical));
+        }
+        if wordlist.contains(&heard) {
+            return Err(FourWordError::invalid_value(
+                "alias",
+                &heard,
+                format!("'{heard}' is already a dictionary word"),
+            ));
+        }
+        self.aliases.insert(heard, canonical);
+        Ok(())
+    }
+
+    /// Adds aliases read from `reader`, returning how many were added.
+    ///
+    /// Each line holds `heard,canonical`; blank lines and lines starting with
+    /// `#` are ignored. The whole input is rejected if any line is invalid,
+    /// with the line number in the error.
+    pub fn extend_from_reader<W: Wordlist + ?Sized>(
+        &mut self,
+        wordlist: &W,
+        reader: impl Read,
+    ) -> Result<usize> {
+        let mut staged = self.clone();
+        let mut added = 0;
+        for (number, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (heard, canonical) = line.split_once(',').ok_or_else(|| {
+                FourWordError::invalid_value(
+                    "alias line",
+                    line,
+                    format!("line {} must be 'heard,canonical'", number + 1),
+                )
+            })?;
+            staged.insert(wordlist, heard, canonical).map_err(|e| {
+                FourWordError::invalid_value(
+                    "alias line",
+                    line,
+                    format!("line {}: {e}", number + 1),
+                )
+            })?;
+            added += 1;
+        }
+        *self = staged;
+        Ok(added)
+    }
+
+    /// Returns the canonical word for `word`, or `None` if no rule applies.
+    ///
+    /// Diacritics are folded first ("canción" -> "cancion"), then the table is
+    /// consulted with both the original and the folded spelling.
+    pub fn resolve<W: Wordlist + ?Sized>(&self, wordlist: &W, word: &str) -> Option<String> {
+        let word = word.to_lowercase();
+        if let Some(canonical) = self.aliases.get(&word) {
+            return Some(canonical.clone());
+        }
+        let folded = fold_diacritics(&word);
+        if folded == word {
+            return None;
+        }
+        if wordlist.contains(&folded) {
+            return Some(folded);
+        }
+        self.aliases.get(&folded).cloned()
+    }
+
+    /// Iterates over (heard, canonical) pairs in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.aliases
+            .iter()
+            .map(|(heard, canonical)| (heard.as_str(), canonical.as_str()))
+    }
+
+    /// Returns the number of aliases
+    pub fn len(&self) -> usize {
+        self.aliases.len()
+    }
+
+    /// Checks whether the table has no aliases
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+}
+
+/// Replaces accented Latin letters with their unaccented base letter
+fn fold_diacritics(word: &str) -> String {
+    word.chars()
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ä' | 'ã' | 'å' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'í' | 'ì' | 'î' | 'ï' => 'i',
+            'ó' | 'ò' | 'ô' | 'ö' | 'õ' => 'o',
+            'ú' | 'ù' | 'û' | 'ü' => 'u',
+            'ý' | 'ÿ' => 'y',
+            'ñ' => 'n',
+            'ç' => 'c',
+            other => other,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary4k::DICTIONARY;
+
+    #[test]
+    fn test_builtin_english_aliases() {
+        let dict = DICTIONARY;
+        let table = AliasTable::builtin(Language::English);
+        assert_eq!(table.resolve(&dict, "forty").as_deref(), Some("fourty"));
+        assert_eq!(table.resolve(&dict, "Grey").as_deref(), Some("gray"));
+        assert_eq!(table.resolve(&dict, "ocean"), None);
+        for (heard, canonical) in table.iter() {
+            assert!(dict.contains(canonical), "{heard} -> {canonical}");
+            assert!(!dict.contains(heard), "{heard} -> {canonical}");
+        }
+    }
+
+    #[test]
+    fn test_insert_validation() {
+        let dict = DICTIONARY;
+        let mut table = AliasTable::new();
+        assert!(table.insert(&dict, "oshun", "ocean").is_ok());
+        assert!(table.insert(&dict, "x", "notarealword").is_err());
+        // Aliasing a dictionary word would break phrases that use it
+        assert!(table.insert(&dict, "ocean", "thunder").is_err());
+        assert_eq!(table.len(), 1);
+    }
+
+    #[test]
+    fn test_extend_from_reader() {
+        let dict = DICTIONARY;
+        let mut table = AliasTable::new();
+        let data = "# exported from call logs\noshun,ocean\n\nthunda, thunder\n";
+        assert_eq!(table.extend_from_reader(&dict, data.as_bytes()).unwrap(), 2);
+        assert_eq!(table.resolve(&dict, "thunda").as_deref(), Some("thunder"));
+
+        // A bad line rejects the whole batch
+        let bad = "oshen,ocean\nbroken line\n";
+        assert!(table.extend_from_reader(&dict, bad.as_bytes()).is_err());
+        assert_eq!(table.len(), 2);
+    }
+
+    #[test]
+    fn test_diacritics_fold_onto_dictionary() {
+        let dict = DICTIONARY;
+        let table = AliasTable::new();
+        assert_eq!(fold_diacritics("canción"), "cancion");
+        assert_eq!(table.resolve(&dict, "óceán").as_deref(), Some("ocean"));
+    }
+}
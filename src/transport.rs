@@ -0,0 +1,211 @@
+//! Transport protocol tags on word addresses.
+//!
+//! A phrase says where a service is but not whether to reach it over TCP,
+//! UDP, QUIC or SCTP. [`encode_tagged`] appends the protocol name to the
+//! phrase (`ocean thunder falcon star udp`) and [`decode_tagged`] returns it
+//! alongside the address, so the phrase is self-contained.
+//!
+//! The tag is a trailing keyword rather than bits inside the phrase: an
+//! IPv4 phrase's 48 bits are exactly the address and port, and IPv6
+//! encodings only have padding bits to spare for some addresses, so there is
+//! nowhere to put a tag that works for every phrase. The keywords are words
+//! of no built-in language, so a tagged phrase can never be mistaken for a
+//! longer address, and untagged phrases decode as before with no protocol.
+//!
+//! The keyword may follow the phrase after any separator the decoder
+//! accepts (whitespace, `.` or `-`), and [`encode_tagged_with`] and
+//! [`decode_tagged_with`] tag phrases in another language or profile:
+//! protocol names are the same acronyms in every language.
+//!
+//! ```rust
+//! use four_word_networking::transport::{Protocol, decode_tagged, encode_tagged};
+//!
+//! let words = encode_tagged("192.168.1.20:5353".parse()?, Protocol::Udp)?;
+//! assert!(words.ends_with(" udp"));
+//! assert_eq!(
+//!     decode_tagged(&words)?,
+//!     ("192.168.1.20:5353".parse()?, Some(Protocol::Udp))
+//! );
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::word_address::{exact_words_with, from_exact_words_with};
+use crate::wordlist::Wordlist;
+use std::fmt;
+use std::net::SocketAddr;
+use std::str::FromStr;
+
+/// Transport protocol a service listens on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Protocol {
+    /// `tcp`
+    Tcp,
+    /// `udp`
+    Udp,
+    /// `quic` (over UDP)
+    Quic,
+    /// `sctp`
+    Sctp,
+}
+
+impl Protocol {
+    /// Every protocol, in tag order
+    pub const ALL: [Protocol; 4] = [Protocol::Tcp, Protocol::Udp, Protocol::Quic, Protocol::Sctp];
+
+    /// Keyword written after the phrase
+    pub fn keyword(self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+            Protocol::Quic => "quic",
+            Protocol::Sctp => "sctp",
+        }
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.keyword())
+    }
+}
+
+impl FromStr for Protocol {
+    type Err = FourWordError;
+
+    /// Parses a keyword, ignoring case
+    fn from_str(s: &str) -> Result<Self> {
+        Protocol::ALL
+            .into_iter()
+            .find(|protocol| s.eq_ignore_ascii_case(protocol.keyword()))
+            .ok_or_else(|| {
+                FourWordError::invalid_value("transport", s, "expected tcp, udp, quic or sctp")
+            })
+    }
+}
+
+/// Encodes `addr` as English words followed by the `protocol` keyword
+pub fn encode_tagged(addr: SocketAddr, protocol: Protocol) -> Result<String> {
+    encode_tagged_with(&FourWordAdaptiveEncoder::new()?, addr, protocol)
+}
+
+/// Decodes an English phrase with or without a trailing protocol keyword
+pub fn decode_tagged(words: &str) -> Result<(SocketAddr, Option<Protocol>)> {
+    decode_tagged_with(&FourWordAdaptiveEncoder::new()?, words)
+}
+
+/// Encodes `addr` with `encoder`, followed by the `protocol` keyword.
+///
+/// Fails with [`FourWordError::Unsupported`] if the keyword is a word of the
+/// encoder's wordlist, which only a custom dictionary can make it.
+pub fn encode_tagged_with<W: Wordlist>(
+    encoder: &FourWordAdaptiveEncoder<W>,
+    addr: SocketAddr,
+    protocol: Protocol,
+) -> Result<String> {
+    if encoder.wordlist().get_index(protocol.keyword()).is_some() {
+        return Err(FourWordError::unsupported(
+            "tag transport",
+            format!("'{protocol}' is a word of this wordlist"),
+        ));
+    }
+    Ok(format!("{} {protocol}", exact_words_with(encoder, addr)?))
+}
+
+/// Decodes a phrase in the wordlist of `encoder`, with or without a trailing
+/// protocol keyword
+pub fn decode_tagged_with<W: Wordlist>(
+    encoder: &FourWordAdaptiveEncoder<W>,
+    words: &str,
+) -> Result<(SocketAddr, Option<Protocol>)> {
+    let words = words.trim();
+    let tagged = words
+        .rsplit_once(|c: char| c.is_whitespace() || c == '.' || c == '-')
+        .filter(|(_, tag)| encoder.wordlist().get_index(tag).is_none())
+        .and_then(|(phrase, tag)| Some((phrase, tag.parse::<Protocol>().ok()?)));
+    match tagged {
+        Some((phrase, protocol)) => Ok((from_exact_words_with(encoder, phrase)?, Some(protocol))),
+        None => Ok((from_exact_words_with(encoder, words)?, None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language::Language;
+
+    #[test]
+    fn test_round_trip_every_protocol() {
+        for addr in [
+            "192.168.1.20:5353",
+            "[fe80::1]:443",
+            "[2606:4700:4700::1111]:443",
+        ] {
+            let addr: SocketAddr = addr.parse().unwrap();
+            for protocol in Protocol::ALL {
+                let words = encode_tagged(addr, protocol).unwrap();
+                assert_eq!(decode_tagged(&words).unwrap(), (addr, Some(protocol)));
+                assert_eq!(
+                    decode_tagged(&words.to_uppercase()).unwrap(),
+                    (addr, Some(protocol))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_untagged_phrases_still_decode() {
+        let addr: SocketAddr = "10.0.0.1:22".parse().unwrap();
+        let words = crate::encode(addr).unwrap();
+        assert_eq!(decode_tagged(&words).unwrap(), (addr, None));
+        assert!(decode_tagged(&format!("{words} ftp")).is_err());
+    }
+
+    #[test]
+    fn test_dashed_and_dotted_phrases() {
+        for addr in ["192.168.1.20:5353", "[2606:4700:4700::1111]:443"] {
+            let addr: SocketAddr = addr.parse().unwrap();
+            let words = encode_tagged(addr, Protocol::Quic).unwrap();
+            for separator in ["-", "."] {
+                let joined = words.replace(' ', separator);
+                assert_eq!(
+                    decode_tagged(&joined).unwrap(),
+                    (addr, Some(Protocol::Quic)),
+                    "{joined}"
+                );
+            }
+            let untagged = words.trim_end_matches(" quic").replace(' ', "-");
+            assert_eq!(decode_tagged(&untagged).unwrap(), (addr, None));
+        }
+    }
+
+    #[test]
+    fn test_other_languages() {
+        for &language in Language::ALL {
+            let encoder = FourWordAdaptiveEncoder::with_language(language).unwrap();
+            let addr: SocketAddr = "[fe80::1]:443".parse().unwrap();
+            let words = encode_tagged_with(&encoder, addr, Protocol::Sctp).unwrap();
+            assert_eq!(
+                decode_tagged_with(&encoder, &words.replace(' ', ".")).unwrap(),
+                (addr, Some(Protocol::Sctp)),
+                "{language}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_keywords_are_not_dictionary_words() {
+        for &language in Language::ALL {
+            let dict = language.dictionary();
+            for protocol in Protocol::ALL {
+                assert!(
+                    dict.get_index(protocol.keyword()).is_none(),
+                    "{language}: {protocol}"
+                );
+            }
+        }
+    }
+}
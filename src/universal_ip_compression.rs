@@ -4,6 +4,8 @@
 //! combination into 42 bits (four words) using mathematical compression without
 //! special cases or type prefixes.
 
+#![forbid(unsafe_code)]
+
 use crate::error::FourWordError;
 use std::net::Ipv4Addr;
 
@@ -54,7 +56,7 @@ impl UniversalIpCompressor {
             return Ok(compressed);
         }
 
-        Err(FourWordError::InvalidInput(format!(
+        Err(FourWordError::CompressionError(format!(
             "Cannot compress {ip}:{port} into 42 bits with any strategy"
         )))
     }
@@ -77,7 +79,7 @@ impl UniversalIpCompressor {
             }
         }
 
-        Err(FourWordError::InvalidInput(
+        Err(FourWordError::CompressionError(
             "Port frequency compression failed".to_string(),
         ))
     }
@@ -101,7 +103,7 @@ impl UniversalIpCompressor {
             return Ok(compressed);
         }
 
-        Err(FourWordError::InvalidInput(
+        Err(FourWordError::CompressionError(
             "No IP pattern match".to_string(),
         ))
     }
@@ -128,7 +130,7 @@ impl UniversalIpCompressor {
         if result <= MAX_VALUE {
             Ok(result)
         } else {
-            Err(FourWordError::InvalidInput(
+            Err(FourWordError::CompressionError(
                 "Lossy compression overflow".to_string(),
             ))
         }
@@ -163,7 +165,7 @@ impl UniversalIpCompressor {
             }
         }
 
-        Err(FourWordError::InvalidInput(
+        Err(FourWordError::CompressionError(
             "No suitable range found".to_string(),
         ))
     }
@@ -218,7 +220,7 @@ impl UniversalIpCompressor {
     /// Decompress back to IP and port
     pub fn decompress(&self, compressed: u64) -> Result<(Ipv4Addr, u16), FourWordError> {
         if compressed > MAX_VALUE {
-            return Err(FourWordError::InvalidInput(
+            return Err(FourWordError::DecompressionError(
                 "Invalid compressed value".to_string(),
             ));
         }
@@ -0,0 +1,442 @@
+//! `words://` URIs for links, QR codes and deep links.
+//!
+//! A words URI names a host by the phrase of its IP address, with the words
+//! joined by dashes so the whole URI needs no quoting:
+//!
+//! ```text
+//! words-uri = "words://" phrase [ ":" port ] [ path ] [ "?" query ] [ "#" fragment ]
+//! phrase    = word *( ( "-" / "." ) word )   ; an address without a port
+//! port      = 1*5DIGIT                       ; 0-65535
+//! path      = *( "/" segment )
+//! ```
+//!
+//! The scheme and words are case-insensitive. A phrase that carries its own
+//! port is also accepted without `:port`, but [`WordsUri`] always displays
+//! the canonical form: lowercase words for the bare address, then `:port`.
+//! Path, query and fragment are kept as written (already percent-encoded)
+//! and may not contain whitespace or control characters.
+//!
+//! After decoding, [`WordsUri::to_url`] and its shorthands give the `http`,
+//! `https` or `tcp` URL to open, and [`WordsUri::from_url`] goes the other
+//! way for URLs whose host is an IP address:
+//!
+//! ```rust
+//! use four_word_networking::uri::WordsUri;
+//!
+//! let uri = WordsUri::from_url("https://192.168.1.1:8443/status?verbose=1")?;
+//! assert_eq!(
+//!     uri.to_string(),
+//!     "words://zoo-acid-junk-restriction:8443/status?verbose=1"
+//! );
+//! let parsed: WordsUri = "WORDS://zoo.acid.junk.restriction:8443/status?verbose=1".parse()?;
+//! assert_eq!(parsed, uri);
+//! assert_eq!(parsed.to_https_url(), "https://192.168.1.1:8443/status?verbose=1");
+//! assert_eq!(parsed.to_tcp_url()?, "tcp://192.168.1.1:8443");
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::word_address::WordAddress;
+use crate::wordlist::Wordlist;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+
+/// Scheme of a words URI, without `://`
+pub const SCHEME: &str = "words";
+
+/// Port that marks a phrase encoded without one
+const NO_PORT: u16 = 65535;
+
+/// A parsed `words://` URI
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WordsUri {
+    host: WordAddress,
+    port: Option<u16>,
+    path: String,
+    query: Option<String>,
+    fragment: Option<String>,
+}
+
+impl WordsUri {
+    /// URI for `ip`, and `port` if given, with English words and no path
+    pub fn new(ip: impl Into<IpAddr>, port: Option<u16>) -> Result<Self> {
+        Self::new_with(&FourWordAdaptiveEncoder::new()?, ip, port)
+    }
+
+    /// URI for `ip` and `port` with words from `encoder`
+    pub fn new_with<W: Wordlist>(
+        encoder: &FourWordAdaptiveEncoder<W>,
+        ip: impl Into<IpAddr>,
+        port: Option<u16>,
+    ) -> Result<Self> {
+        Ok(WordsUri {
+            host: WordAddress::new_with(encoder, ip)?,
+            port,
+            path: String::new(),
+            query: None,
+            fragment: None,
+        })
+    }
+
+    /// Parses an English words URI
+    pub fn parse(uri: &str) -> Result<Self> {
+        Self::parse_with(&FourWordAdaptiveEncoder::new()?, uri)
+    }
+
+    /// Parses a words URI whose phrase comes from `encoder`'s wordlist
+    pub fn parse_with<W: Wordlist>(
+        encoder: &FourWordAdaptiveEncoder<W>,
+        uri: &str,
+    ) -> Result<Self> {
+        let invalid = |reason: &str| FourWordError::invalid_value("words URI", uri, reason);
+        let rest = uri
+            .trim()
+            .split_once("://")
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case(SCHEME))
+            .map(|(_, rest)| rest)
+            .ok_or_else(|| invalid("expected the words:// scheme"))?;
+
+        let (authority, path, query, fragment) = split_reference(rest);
+        let (phrase, port) = match authority.rsplit_once(':') {
+            Some((phrase, port)) => {
+                let port = port
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|_| port.bytes().all(|b| b.is_ascii_digit()))
+                    .ok_or_else(|| invalid("port must be a number from 0 to 65535"))?;
+                (phrase, Some(port))
+            }
+            None => (authority, None),
+        };
+        if phrase.is_empty() {
+            return Err(invalid("missing phrase"));
+        }
+        if phrase.contains(char::is_whitespace) {
+            return Err(invalid("join the words with '-'"));
+        }
+
+        let addr = encoder.decode_socket_addr(phrase)?;
+        let port = match (addr.port(), port) {
+            (NO_PORT, port) => port,
+            (own, None) => Some(own),
+            (own, Some(_)) => {
+                return Err(invalid(&format!(
+                    "phrase already carries port {own}; give it without ':port'"
+                )));
+            }
+        };
+        Self::new_with(encoder, addr.ip(), port)?
+            .with_path(path)?
+            .with_query(query)?
+            .with_fragment(fragment)
+    }
+
+    /// Converts an `http`, `https`, `tcp` or other URL whose host is an IP
+    /// address, dropping its scheme; hostnames must be resolved first
+    pub fn from_url(url: &str) -> Result<Self> {
+        let invalid = |reason: &str| FourWordError::invalid_value("URL", url, reason);
+        let (_, rest) = url
+            .trim()
+            .split_once("://")
+            .ok_or_else(|| invalid("missing scheme"))?;
+        let (authority, path, query, fragment) = split_reference(rest);
+        if authority.contains('@') {
+            return Err(invalid("user info is not supported"));
+        }
+
+        let (host, port) = match authority.strip_prefix('[') {
+            Some(bracketed) => {
+                let (host, after) = bracketed
+                    .split_once(']')
+                    .ok_or_else(|| invalid("unclosed '['"))?;
+                match after {
+                    "" => (host, None),
+                    _ => (
+                        host,
+                        Some(after.strip_prefix(':').ok_or_else(|| invalid("bad port"))?),
+                    ),
+                }
+            }
+            None => match authority.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            },
+        };
+        let ip = host
+            .parse::<IpAddr>()
+            .map_err(|_| invalid("host must be an IP address"))?;
+        let port = port
+            .map(|port| port.parse::<u16>().map_err(|_| invalid("bad port")))
+            .transpose()?;
+        Self::new(ip, port)?
+            .with_path(path)?
+            .with_query(query)?
+            .with_fragment(fragment)
+    }
+
+    /// Replaces the path, which must be empty or start with `/`
+    pub fn with_path(mut self, path: &str) -> Result<Self> {
+        if !(path.is_empty() || path.starts_with('/')) || path.contains(['?', '#']) {
+            return Err(FourWordError::invalid_value(
+                "URI path",
+                path,
+                "must be empty or start with '/', without '?' or '#'",
+            ));
+        }
+        self.path = checked_part("path", path)?;
+        Ok(self)
+    }
+
+    /// Replaces the query, without the leading `?`
+    pub fn with_query(mut self, query: Option<&str>) -> Result<Self> {
+        if query.is_some_and(|query| query.contains('#')) {
+            return Err(FourWordError::invalid_value(
+                "URI query",
+                query.unwrap_or_default(),
+                "may not contain '#'",
+            ));
+        }
+        self.query = query
+            .map(|query| checked_part("query", query))
+            .transpose()?;
+        Ok(self)
+    }
+
+    /// Replaces the fragment, without the leading `#`
+    pub fn with_fragment(mut self, fragment: Option<&str>) -> Result<Self> {
+        self.fragment = fragment
+            .map(|fragment| checked_part("fragment", fragment))
+            .transpose()?;
+        Ok(self)
+    }
+
+    /// Returns the host address and its phrase
+    pub fn host(&self) -> &WordAddress {
+        &self.host
+    }
+
+    /// Returns the port, if the URI has one
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    /// Returns the socket address, if the URI has a port
+    pub fn socket_addr(&self) -> Option<SocketAddr> {
+        self.port.map(|port| SocketAddr::new(self.host.ip(), port))
+    }
+
+    /// Returns the path, empty or starting with `/`
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the query, without the leading `?`
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    /// Returns the fragment, without the leading `#`
+    pub fn fragment(&self) -> Option<&str> {
+        self.fragment.as_deref()
+    }
+
+    /// The URL with `scheme` and the decoded address in place of the phrase
+    pub fn to_url(&self, scheme: &str) -> String {
+        let mut url = format!("{scheme}://");
+        self.write_host(&mut url);
+        url.push_str(&self.path);
+        if let Some(query) = &self.query {
+            url.push('?');
+            url.push_str(query);
+        }
+        if let Some(fragment) = &self.fragment {
+            url.push('#');
+            url.push_str(fragment);
+        }
+        url
+    }
+
+    /// `http://` URL for the decoded address
+    pub fn to_http_url(&self) -> String {
+        self.to_url("http")
+    }
+
+    /// `https://` URL for the decoded address
+    pub fn to_https_url(&self) -> String {
+        self.to_url("https")
+    }
+
+    /// `tcp://ip:port` URL; fails without a port. Path, query and fragment
+    /// have no meaning for TCP and are left out
+    pub fn to_tcp_url(&self) -> Result<String> {
+        if self.port.is_none() {
+            return Err(FourWordError::unsupported(
+                "format a tcp URL",
+                "the URI has no port",
+            ));
+        }
+        let mut url = "tcp://".to_string();
+        self.write_host(&mut url);
+        Ok(url)
+    }
+
+    /// Writes the address, bracketed if IPv6, and `:port` if present
+    fn write_host(&self, url: &mut String) {
+        match self.host.ip() {
+            IpAddr::V4(ip) => url.push_str(&ip.to_string()),
+            IpAddr::V6(ip) => url.push_str(&format!("[{ip}]")),
+        }
+        if let Some(port) = self.port {
+            url.push_str(&format!(":{port}"));
+        }
+    }
+}
+
+impl FromStr for WordsUri {
+    type Err = FourWordError;
+
+    fn from_str(uri: &str) -> Result<Self> {
+        Self::parse(uri)
+    }
+}
+
+impl fmt::Display for WordsUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{SCHEME}://{}", self.host.phrase().replace(' ', "-"))?;
+        if let Some(port) = self.port {
+            write!(f, ":{port}")?;
+        }
+        f.write_str(&self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{query}")?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{fragment}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits what follows `scheme://` into authority, path, query and fragment
+fn split_reference(rest: &str) -> (&str, &str, Option<&str>, Option<&str>) {
+    let (rest, fragment) = match rest.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (rest, None),
+    };
+    let (rest, query) = match rest.split_once('?') {
+        Some((rest, query)) => (rest, Some(query)),
+        None => (rest, None),
+    };
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    (authority, path, query, fragment)
+}
+
+/// Returns `value` owned if it has no whitespace or control characters
+fn checked_part(part: &str, value: &str) -> Result<String> {
+    if value.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(FourWordError::invalid_value(
+            "URI part",
+            value,
+            format!("the {part} may not contain whitespace or control characters"),
+        ));
+    }
+    Ok(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_canonical_form() {
+        for (ip, port) in [
+            ("10.0.0.1", Some(22)),
+            ("192.168.1.1", None),
+            ("2001:db8::1", Some(443)),
+            ("::1", Some(0)),
+        ] {
+            let ip: IpAddr = ip.parse().unwrap();
+            let uri = WordsUri::new(ip, port)
+                .unwrap()
+                .with_path("/a/b%20c")
+                .unwrap()
+                .with_query(Some("x=1&y"))
+                .unwrap()
+                .with_fragment(Some("top"))
+                .unwrap();
+            let text = uri.to_string();
+            assert!(text.starts_with("words://"), "{text}");
+            assert!(!text.contains(' '), "{text}");
+            assert!(text.ends_with("/a/b%20c?x=1&y#top"), "{text}");
+            assert_eq!(text.parse::<WordsUri>().unwrap(), uri);
+            assert_eq!(uri.port(), port);
+            assert_eq!(
+                uri.socket_addr(),
+                port.map(|port| SocketAddr::new(ip, port))
+            );
+        }
+
+        // A phrase with its own port becomes phrase-then-port
+        let words = crate::encode(([10, 0, 0, 1], 8080))
+            .unwrap()
+            .replace(' ', "-");
+        let uri = WordsUri::parse(&format!("words://{words}/x")).unwrap();
+        assert_eq!(uri.port(), Some(8080));
+        assert_eq!(uri.to_http_url(), "http://10.0.0.1:8080/x");
+        assert_eq!(
+            uri.to_string(),
+            WordsUri::from_url("http://10.0.0.1:8080/x")
+                .unwrap()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_url_conversions() {
+        let uri = WordsUri::from_url("https://[2001:db8::1]/path?q#f").unwrap();
+        assert_eq!(uri.port(), None);
+        assert_eq!(uri.to_https_url(), "https://[2001:db8::1]/path?q#f");
+        assert!(uri.to_tcp_url().is_err());
+
+        let uri = WordsUri::from_url("tcp://203.0.113.7:9000").unwrap();
+        assert_eq!(uri.to_tcp_url().unwrap(), "tcp://203.0.113.7:9000");
+        assert_eq!(uri.path(), "");
+        assert_eq!(uri.query(), None);
+
+        assert!(WordsUri::from_url("https://example.com/").is_err());
+        assert!(WordsUri::from_url("https://user@10.0.0.1/").is_err());
+        assert!(WordsUri::from_url("10.0.0.1:80").is_err());
+    }
+
+    #[test]
+    fn test_invalid_uris() {
+        let words = WordsUri::new([10, 0, 0, 1], None).unwrap().to_string();
+        let phrase = &words["words://".len()..];
+        for uri in [
+            format!("http://{phrase}"),
+            "words://".to_string(),
+            "words://:80".to_string(),
+            format!("words://{phrase}:99999"),
+            format!("words://{phrase}:+80"),
+            format!("words://{phrase}/has space"),
+            format!("words://{}", phrase.replace('-', " ")),
+            "words://ocean-qqqq-zzzz-xxxx".to_string(),
+        ] {
+            assert!(WordsUri::parse(&uri).is_err(), "{uri}");
+        }
+        let with_port = crate::encode(([10, 0, 0, 1], 8080))
+            .unwrap()
+            .replace(' ', "-");
+        assert!(WordsUri::parse(&format!("words://{with_port}:80")).is_err());
+        assert!(
+            WordsUri::new([10, 0, 0, 1], None)
+                .unwrap()
+                .with_path("relative")
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,315 @@
+//! Round-trip verification over address ranges and seeded samples.
+//!
+//! Before a build goes out, operators can encode and decode large numbers of
+//! addresses and confirm every phrase decodes back to its address.
+//! [`FourWordAdaptiveEncoder::round_trip`] checks one address and
+//! [`FourWordAdaptiveEncoder::verify`] a whole iterator of them, counting
+//! failures in a [`VerifyReport`]. Reports [`merge`](VerifyReport::merge), so
+//! callers can split the work across threads; `4wn verify` does.
+//!
+//! Some phrases drop information by design, and those count as lossy rather
+//! than failed: the interface ID of a unique local address, and a port that
+//! does not fit the longest phrase. Any other difference is a failure.
+//!
+//! [`AddressSampler`] produces reproducible addresses from a seed: IPv4 with
+//! random ports, and IPv6 drawn from every compression category.
+//!
+//! ```rust
+//! use four_word_networking::FourWordAdaptiveEncoder;
+//! use four_word_networking::verify::AddressSampler;
+//!
+//! let encoder = FourWordAdaptiveEncoder::new()?;
+//! let mut sampler = AddressSampler::new(42);
+//! let report = encoder.verify((0..1000).map(|_| sampler.ipv6()));
+//! assert_eq!(report.checked, 1000);
+//! assert!(report.is_ok(), "{:?}", report.examples);
+//! # Ok::<(), four_word_networking::FourWordError>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::ipv6_compression::Ipv6Category;
+use crate::ports::WELL_KNOWN_PORTS;
+use crate::wordlist::Wordlist;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// Port that marks an address without one
+const NO_PORT: u16 = 65535;
+
+/// Most failures a report keeps as examples
+pub const MAX_EXAMPLES: usize = 16;
+
+/// IPv6 sample shapes: fixed bits, then the bits drawn at random.
+///
+/// Together they reach every compression category and the special cases
+/// inside them (EUI-64 and small link-local IDs, solicited-node and
+/// well-known multicast groups).
+const IPV6_SHAPES: &[(u128, u128)] = &[
+    // Loopback and unspecified
+    (1, 0),
+    (0, 0),
+    // Link-local: any interface ID, small IDs, EUI-64 with a zero last segment
+    (0xfe80 << 112, u64::MAX as u128),
+    (0xfe80 << 112, 0xFFFF),
+    (
+        0xfe80_0000_0000_0000_0200_0000_0000_0000,
+        0xFDFF_FFFF_FFFF_0000,
+    ),
+    // Unique local, documentation, global unicast
+    (0xfc00 << 112, u128::MAX >> 7),
+    (0x2001_0db8 << 96, u128::MAX >> 32),
+    (0x2000 << 112, u128::MAX >> 3),
+    // Multicast: any group, well-known groups, solicited-node
+    (0xff << 120, u128::MAX >> 8),
+    (0xff00 << 112, 0x000F << 112 | 0x1_FFFF),
+    (0xff02_0000_0000_0000_0000_0001_ff00_0000, 0xFF_FFFF),
+    // NAT64, IPv4-mapped and -translated, Teredo, 6to4
+    (0x64_ff9b << 96, u32::MAX as u128),
+    (0xffff << 32, u32::MAX as u128),
+    (0xffff << 48, u32::MAX as u128),
+    (0x2001 << 112, u128::MAX >> 32),
+    (0x2002 << 112, u128::MAX >> 16),
+    // Anything, which is mostly reserved space
+    (0, u128::MAX),
+];
+
+/// An address whose phrase does not decode back to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// The address that was encoded
+    pub addr: SocketAddr,
+    /// Its phrase, if encoding succeeded
+    pub phrase: Option<String>,
+    /// What went wrong
+    pub problem: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.phrase {
+            Some(phrase) => write!(f, "{} ('{phrase}'): {}", self.addr, self.problem),
+            None => write!(f, "{}: {}", self.addr, self.problem),
+        }
+    }
+}
+
+/// Counts from a verification run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// Addresses checked
+    pub checked: u64,
+    /// Addresses that decoded with a documented loss
+    pub lossy: u64,
+    /// Addresses that failed
+    pub failed: u64,
+    /// The first [`MAX_EXAMPLES`] failures
+    pub examples: Vec<Mismatch>,
+}
+
+impl VerifyReport {
+    /// True when no address failed
+    pub fn is_ok(&self) -> bool {
+        self.failed == 0
+    }
+
+    /// Adds the counts and examples of `other`
+    pub fn merge(&mut self, other: VerifyReport) {
+        self.checked += other.checked;
+        self.lossy += other.lossy;
+        self.failed += other.failed;
+        let room = MAX_EXAMPLES.saturating_sub(self.examples.len());
+        self.examples.extend(other.examples.into_iter().take(room));
+    }
+}
+
+impl fmt::Display for VerifyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} checked, {} exact, {} lossy by design, {} failed",
+            self.checked,
+            self.checked - self.lossy - self.failed,
+            self.lossy,
+            self.failed
+        )
+    }
+}
+
+impl<W: Wordlist> FourWordAdaptiveEncoder<W> {
+    /// Encodes and decodes `addr` (port 65535 for "no port").
+    ///
+    /// Returns `Ok(true)` when the phrase decodes to exactly `addr`,
+    /// `Ok(false)` when it loses only what the encoding drops by design, and
+    /// the mismatch otherwise.
+    pub fn round_trip(&self, addr: SocketAddr) -> Result<bool, Mismatch> {
+        let mismatch = |phrase: Option<&str>, problem: String| Mismatch {
+            addr,
+            phrase: phrase.map(str::to_string),
+            problem,
+        };
+        let mut phrase = String::new();
+        self.encode_into(addr, &mut phrase)
+            .map_err(|e| mismatch(None, format!("encoding failed: {e}")))?;
+        let decoded = self
+            .decode_socket_addr(&phrase)
+            .map_err(|e| mismatch(Some(&phrase), format!("decoding failed: {e}")))?;
+        if decoded == addr {
+            return Ok(true);
+        }
+
+        let port_dropped = decoded.port() == NO_PORT;
+        let ip_kept = match (addr.ip(), decoded.ip()) {
+            (IpAddr::V6(ip), IpAddr::V6(decoded)) if ip != decoded => {
+                // Unique local phrases keep the /64
+                self.ipv6_category(addr)
+                    .is_ok_and(|category| category == Some(Ipv6Category::UniqueLocal))
+                    && u128::from(ip) >> 64 == u128::from(decoded) >> 64
+                    && u128::from(decoded) as u64 == 0
+            }
+            (ip, decoded_ip) => ip == decoded_ip,
+        };
+        if ip_kept && (port_dropped || decoded.port() == addr.port()) {
+            Ok(false)
+        } else {
+            Err(mismatch(Some(&phrase), format!("decoded as {decoded}")))
+        }
+    }
+
+    /// Round-trips every address in `addrs`
+    pub fn verify(&self, addrs: impl IntoIterator<Item = SocketAddr>) -> VerifyReport {
+        let mut report = VerifyReport::default();
+        for addr in addrs {
+            report.checked += 1;
+            match self.round_trip(addr) {
+                Ok(true) => {}
+                Ok(false) => report.lossy += 1,
+                Err(mismatch) => {
+                    report.failed += 1;
+                    if report.examples.len() < MAX_EXAMPLES {
+                        report.examples.push(mismatch);
+                    }
+                }
+            }
+        }
+        report
+    }
+}
+
+/// Reproducible addresses for verification (SplitMix64)
+#[derive(Debug, Clone)]
+pub struct AddressSampler {
+    state: u64,
+    shape: usize,
+}
+
+impl AddressSampler {
+    /// Creates a sampler; the same seed always gives the same addresses
+    pub fn new(seed: u64) -> Self {
+        AddressSampler {
+            state: seed,
+            shape: 0,
+        }
+    }
+
+    /// A random IPv4 address with a random port
+    pub fn ipv4(&mut self) -> SocketAddr {
+        let ip = Ipv4Addr::from(self.next_u64() as u32);
+        SocketAddr::new(IpAddr::V4(ip), self.port())
+    }
+
+    /// A random IPv6 address, cycling through the compression categories.
+    ///
+    /// Every other address also has random 16-bit groups cleared, since runs
+    /// of zeros take the compressors' shorter paths.
+    pub fn ipv6(&mut self) -> SocketAddr {
+        let (fixed, random) = IPV6_SHAPES[self.shape % IPV6_SHAPES.len()];
+        let mut bits = (u128::from(self.next_u64()) << 64 | u128::from(self.next_u64())) & random;
+        if self.shape / IPV6_SHAPES.len() % 2 == 1 {
+            let keep = self.next_u64();
+            for group in 0..8 {
+                if keep >> group & 1 == 0 {
+                    bits &= !(0xFFFF << (16 * group));
+                }
+            }
+        }
+        self.shape = self.shape.wrapping_add(1);
+        SocketAddr::new(IpAddr::V6(Ipv6Addr::from(fixed | bits)), self.port())
+    }
+
+    /// No port, a well-known port or any port, a quarter, quarter and half of
+    /// the time
+    fn port(&mut self) -> u16 {
+        let n = self.next_u64();
+        match n % 4 {
+            0 => NO_PORT,
+            1 => WELL_KNOWN_PORTS[(n >> 8) as usize % WELL_KNOWN_PORTS.len()].port,
+            _ => (n >> 16) as u16,
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// `ip` with a port derived from it and `seed`, for checking IPv4 ranges
+/// exhaustively while still varying the port
+pub fn ipv4_with_port(ip: Ipv4Addr, seed: u64) -> SocketAddr {
+    let mut sampler = AddressSampler::new(seed ^ u64::from(u32::from(ip)).rotate_left(32));
+    SocketAddr::new(IpAddr::V4(ip), sampler.next_u64() as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samples_cover_every_category() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let mut sampler = AddressSampler::new(7);
+        let mut seen = std::collections::HashSet::new();
+        let samples: Vec<SocketAddr> = (0..2000).map(|_| sampler.ipv6()).collect();
+        for &addr in &samples {
+            seen.insert(format!("{:?}", encoder.ipv6_category(addr).unwrap()));
+        }
+        // Prefix and Provider need encode_prefix or a profile
+        assert_eq!(seen.len(), 12, "{seen:?}");
+
+        let report = encoder.verify(samples.iter().copied());
+        assert!(report.is_ok(), "{:?}", report.examples);
+        assert!(report.lossy > 0);
+
+        let mut again = AddressSampler::new(7);
+        assert_eq!(again.ipv6(), samples[0]);
+    }
+
+    #[test]
+    fn test_round_trip_outcomes() {
+        let encoder = FourWordAdaptiveEncoder::new().unwrap();
+        let exact: SocketAddr = "192.168.1.1:443".parse().unwrap();
+        assert_eq!(encoder.round_trip(exact), Ok(true));
+        let ula: SocketAddr = "[fd00:1234:5678:9abc::1]:80".parse().unwrap();
+        assert_eq!(encoder.round_trip(ula), Ok(false));
+        let no_room: SocketAddr = "[2606:4700:4700::1111]:4433".parse().unwrap();
+        assert_eq!(encoder.round_trip(no_room), Ok(false));
+
+        let report = encoder
+            .verify((0..=255u32).map(|i| ipv4_with_port(Ipv4Addr::from(0x0A00_0000 | i), 1)));
+        assert_eq!((report.checked, report.lossy, report.failed), (256, 0, 0));
+
+        let mut total = VerifyReport::default();
+        total.merge(report.clone());
+        total.merge(report);
+        assert_eq!(total.checked, 512);
+        assert_eq!(
+            total.to_string(),
+            "512 checked, 512 exact, 0 lossy by design, 0 failed"
+        );
+    }
+}
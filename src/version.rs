@@ -0,0 +1,207 @@
+//! Encoding version tags.
+//!
+//! Phrases carry no spare bits (an IPv4 phrase spends all 48 on the address
+//! and port), so the version is signalled by length instead: a tagged phrase
+//! is one word longer than a plain one, and its first word is the tag. The
+//! tag is the word at index `version` of the wordlist, restricted to the
+//! first [`MAX_VERSION`] + 1 words, which later wordlists must keep in place.
+//! The words after those lead version 1 phrases that avoid confusable
+//! neighbours (see [`confusability`](crate::confusability)), so such a
+//! phrase needs no tag of its own.
+//!
+//! Word 0 names no version. It leads phrases written with reserved-word
+//! escapes instead ([`ESCAPE_TAG`], see [`reserved`](crate::reserved)), which
+//! have a length no plain, tagged or selector-led phrase has. A decoder
+//! without the reservation refuses them rather than reading the escape pairs
+//! as address words.
+//!
+//! Untagged phrases are version 1 ([`ENCODING_VERSION`]) forever. A future
+//! wordlist or layout change will emit tagged phrases, and a decoder that
+//! meets a version it does not implement fails with
+//! [`FourWordError::UnsupportedVersion`] instead of decoding the words to a
+//! wrong address:
+//!
+//! ```rust
+//! use four_word_networking::{FourWordAdaptiveEncoder, FourWordError};
+//! use four_word_networking::version::ENCODING_VERSION;
+//!
+//! let encoder = FourWordAdaptiveEncoder::new()?;
+//! let addr = "192.168.1.1:443".parse()?;
+//! let tagged = encoder.encode_tagged(addr)?;
+//! assert_eq!(tagged.split(' ').count(), 5);
+//! assert_eq!(encoder.decode_socket_addr(&tagged)?, addr);
+//!
+//! // The same words under a version-2 tag are refused
+//! let words = encoder.encode("192.168.1.1:443")?;
+//! let future = format!("{} {words}", encoder.wordlist().get_word(2).unwrap());
+//! assert!(matches!(
+//!     encoder.decode_socket_addr(&future),
+//!     Err(FourWordError::UnsupportedVersion { required: 2, supported: ENCODING_VERSION })
+//! ));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::wordlist::Wordlist;
+use std::borrow::Cow;
+use std::fmt;
+
+/// Version of the encoding this crate reads and writes
+pub const ENCODING_VERSION: u16 = 1;
+
+/// Highest version a tag word can name
+pub const MAX_VERSION: u16 = 63;
+
+/// Index of the tag word leading phrases with reserved-word escapes
+pub const ESCAPE_TAG: u16 = 0;
+
+/// Word counts of untagged phrases: IPv4, then the IPv6 lengths
+pub(crate) const PLAIN_LENGTHS: [usize; 4] = [4, 6, 9, 12];
+
+/// Whether phrases of `len` words read as plain: the plain lengths, and one
+/// more for a version tag or selector
+pub(crate) fn plain_length(len: usize) -> bool {
+    PLAIN_LENGTHS
+        .iter()
+        .any(|&plain| len == plain || len == plain + 1)
+}
+
+/// Whether `words` is led by the [`ESCAPE_TAG`] word at a length no plain
+/// phrase has, so its body carries reserved-word escapes
+pub fn is_escaped<W: Wordlist + ?Sized>(wordlist: &W, words: &[&str]) -> bool {
+    !plain_length(words.len())
+        && words
+            .first()
+            .is_some_and(|first| wordlist.get_index(first) == Some(ESCAPE_TAG))
+}
+
+/// Returns the version named by the tag word leading `words`, or `None` for
+/// an untagged phrase.
+///
+/// A phrase is tagged when it is one word longer than a plain phrase and its
+/// first word names a version from 1 to [`MAX_VERSION`]. A leading
+/// [confusion selector](crate::confusability) also counts as a version 1
+/// tag, since only version 1 has selectors.
+pub fn phrase_version<W: Wordlist + ?Sized>(wordlist: &W, words: &[&str]) -> Option<u16> {
+    let (first, rest) = words.split_first()?;
+    if !PLAIN_LENGTHS.contains(&rest.len()) {
+        return None;
+    }
+    match wordlist.get_index(first)? {
+        0 => None,
+        version @ 1..=MAX_VERSION => Some(version),
+        _ => Some(ENCODING_VERSION),
+    }
+}
+
+/// Writes the tag word for `version` to `out`, without a separator
+pub fn write_tag<W: Wordlist + ?Sized>(
+    wordlist: &W,
+    version: u16,
+    out: &mut impl fmt::Write,
+) -> Result<()> {
+    if !(1..=MAX_VERSION).contains(&version) {
+        return Err(FourWordError::out_of_range(
+            "Encoding version",
+            format!("1-{MAX_VERSION}"),
+            version,
+        ));
+    }
+    let word = wordlist
+        .get_word(version)
+        .ok_or(FourWordError::InvalidWordIndex(version))?;
+    Ok(out.write_str(word)?)
+}
+
+/// Removes a tag for [`ENCODING_VERSION`] from `words`, failing with
+/// [`FourWordError::UnsupportedVersion`] for any other version.
+///
+/// Untagged phrases are returned as they are, and so are phrases led by a
+/// selector, which [`unshift`](crate::confusability::unshift) removes.
+/// Escaped phrases only get here when the decoder reserves no words, so
+/// they fail with [`FourWordError::Unsupported`].
+pub(crate) fn strip_tag<'a, W: Wordlist + ?Sized>(
+    wordlist: &W,
+    words: &'a str,
+    split: &[&str],
+) -> Result<Cow<'a, str>> {
+    if is_escaped(wordlist, split) {
+        return Err(FourWordError::unsupported(
+            "reserved-word escapes",
+            "decode with the profile that reserved the words",
+        ));
+    }
+    match phrase_version(wordlist, split) {
+        None => Ok(Cow::Borrowed(words)),
+        Some(ENCODING_VERSION) if wordlist.get_index(split[0]) == Some(ENCODING_VERSION) => {
+            Ok(Cow::Owned(split[1..].join(" ")))
+        }
+        Some(ENCODING_VERSION) => Ok(Cow::Borrowed(words)),
+        Some(required) => Err(FourWordError::UnsupportedVersion {
+            required,
+            supported: ENCODING_VERSION,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary4k::DICTIONARY;
+
+    #[test]
+    fn test_phrase_version() {
+        let dictionary = DICTIONARY;
+        let tag = dictionary.get_word(7).unwrap();
+        let selector = dictionary.get_word(MAX_VERSION + 1).unwrap();
+        let zero = dictionary.get_word(0).unwrap();
+        let plain = ["ocean"; 4];
+        assert_eq!(phrase_version(&dictionary, &plain), None);
+        assert_eq!(
+            phrase_version(&dictionary, &[&[tag][..], &plain].concat()),
+            Some(7)
+        );
+        assert_eq!(
+            phrase_version(&dictionary, &[&[selector][..], &plain].concat()),
+            Some(ENCODING_VERSION)
+        );
+        assert_eq!(
+            phrase_version(&dictionary, &[&[zero][..], &plain].concat()),
+            None
+        );
+        assert_eq!(phrase_version(&dictionary, &[tag; 4]), None);
+        assert_eq!(phrase_version(&dictionary, &[tag; 13]), Some(7));
+        assert_eq!(phrase_version(&dictionary, &[]), None);
+    }
+
+    #[test]
+    fn test_write_tag_range() {
+        let mut tag = String::new();
+        write_tag(&DICTIONARY, ENCODING_VERSION, &mut tag).unwrap();
+        assert_eq!(DICTIONARY.get_index(&tag), Some(ENCODING_VERSION));
+        assert!(write_tag(&DICTIONARY, 0, &mut tag).is_err());
+        assert!(write_tag(&DICTIONARY, MAX_VERSION + 1, &mut tag).is_err());
+    }
+
+    #[test]
+    fn test_escape_tag() {
+        let dictionary = DICTIONARY;
+        let tag = dictionary.get_word(ESCAPE_TAG).unwrap();
+        let other = dictionary.get_word(7).unwrap();
+        let escaped = [tag; 8];
+        assert!(is_escaped(&dictionary, &escaped));
+        assert!(!is_escaped(&dictionary, &[other; 8]));
+        // At plain lengths word 0 is an ordinary address word
+        for len in [4, 5, 6, 7, 9, 10, 12, 13] {
+            assert!(!is_escaped(&dictionary, &vec![tag; len]), "{len}");
+        }
+
+        let phrase = escaped.join(" ");
+        assert!(matches!(
+            strip_tag(&dictionary, &phrase, &escaped),
+            Err(FourWordError::Unsupported { .. })
+        ));
+    }
+}
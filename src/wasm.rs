@@ -0,0 +1,194 @@
+//! JavaScript bindings for web builds.
+//!
+//! Enabled by the `wasm` feature, this module exposes the encoder to
+//! JavaScript so web UIs can render and read phrases client-side. Build the
+//! module as a `cdylib` and generate the JavaScript glue with `wasm-bindgen`:
+//!
+//! ```sh
+//! cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib
+//! wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/four_word_networking.wasm
+//! ```
+//!
+//! ```js
+//! import init, { WordEncoder, encode, decode } from "./pkg/four_word_networking.js";
+//!
+//! await init();
+//!
+//! const words = encode("192.168.1.1:443");
+//! const encoder = new WordEncoder();
+//! const { ip, port, isIpv6 } = encoder.decodeAddress(words);
+//! const picks = encoder.suggest("oce", 8);
+//! ```
+//!
+//! Errors surface as JavaScript `Error`s carrying the Rust error message.
+
+#![forbid(unsafe_code)]
+
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+use crate::language::Language;
+use crate::suggest::{nearest_in, suggest_in};
+use std::net::{IpAddr, SocketAddr};
+use wasm_bindgen::prelude::*;
+
+/// Port value marking "no port specified" in phrases
+const NO_PORT: u16 = 65535;
+
+/// An address decoded from a phrase
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedAddress {
+    ip: IpAddr,
+    port: Option<u16>,
+}
+
+#[wasm_bindgen]
+impl DecodedAddress {
+    /// The IP address in its canonical text form
+    #[wasm_bindgen(getter)]
+    pub fn ip(&self) -> String {
+        self.ip.to_string()
+    }
+
+    /// The port, or `undefined` if the phrase was encoded without one
+    #[wasm_bindgen(getter)]
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    /// Whether the address is IPv6
+    #[wasm_bindgen(getter, js_name = isIpv6)]
+    pub fn is_ipv6(&self) -> bool {
+        self.ip.is_ipv6()
+    }
+
+    /// The address as `ip`, `ip:port` or `[ip]:port`
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_js_string(&self) -> String {
+        match self.port {
+            Some(port) => SocketAddr::new(self.ip, port).to_string(),
+            None => self.ip.to_string(),
+        }
+    }
+}
+
+impl From<SocketAddr> for DecodedAddress {
+    fn from(addr: SocketAddr) -> Self {
+        DecodedAddress {
+            ip: addr.ip(),
+            port: (addr.port() != NO_PORT).then_some(addr.port()),
+        }
+    }
+}
+
+/// Encoder for one language, reusable across calls
+#[wasm_bindgen]
+pub struct WordEncoder {
+    inner: FourWordAdaptiveEncoder,
+}
+
+#[wasm_bindgen]
+impl WordEncoder {
+    /// Creates an encoder for `language` (an ISO 639-1 code), English if omitted
+    #[wasm_bindgen(constructor)]
+    pub fn new(language: Option<String>) -> Result<WordEncoder, JsError> {
+        let language = match language {
+            Some(code) => code.parse()?,
+            None => Language::English,
+        };
+        Ok(WordEncoder {
+            inner: FourWordAdaptiveEncoder::with_language(language)?,
+        })
+    }
+
+    /// The language code this encoder reads and writes
+    #[wasm_bindgen(getter)]
+    pub fn language(&self) -> String {
+        self.inner.language().unwrap_or_default().code().to_string()
+    }
+
+    /// Encodes an address such as `192.168.1.1:443`, `10.0.0.1` or `[::1]:80`
+    pub fn encode(&self, address: &str) -> Result<String, JsError> {
+        Ok(self.inner.encode(address)?)
+    }
+
+    /// Encodes `ip` with an optional `port`
+    #[wasm_bindgen(js_name = encodeAddress)]
+    pub fn encode_address(&self, ip: &str, port: Option<u16>) -> Result<String, JsError> {
+        let ip: IpAddr = ip
+            .parse()
+            .map_err(|_| JsError::new(&format!("Invalid IP address: {ip}")))?;
+        let mut words = String::new();
+        self.inner
+            .encode_into(SocketAddr::new(ip, port.unwrap_or(NO_PORT)), &mut words)?;
+        Ok(words)
+    }
+
+    /// Decodes a phrase to its address text, omitting an unspecified port
+    pub fn decode(&self, words: &str) -> Result<String, JsError> {
+        Ok(self.inner.decode(words)?)
+    }
+
+    /// Decodes a phrase to its IP and port
+    #[wasm_bindgen(js_name = decodeAddress)]
+    pub fn decode_address(&self, words: &str) -> Result<DecodedAddress, JsError> {
+        Ok(self.inner.decode_socket_addr(words)?.into())
+    }
+
+    /// Up to `limit` words starting with `prefix`, alphabetically, for word pickers
+    pub fn suggest(&self, prefix: &str, limit: usize) -> Vec<String> {
+        suggest_in(self.inner.wordlist(), prefix, limit)
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Up to `limit` words nearest to a misspelled `word`, closest first
+    pub fn nearest(&self, word: &str, limit: usize) -> Vec<String> {
+        nearest_in(self.inner.wordlist(), word, limit)
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Encodes an address with the English wordlist
+#[wasm_bindgen]
+pub fn encode(address: &str) -> Result<String, JsError> {
+    WordEncoder::new(None)?.encode(address)
+}
+
+/// Decodes an English phrase to its address text
+#[wasm_bindgen]
+pub fn decode(words: &str) -> Result<String, JsError> {
+    WordEncoder::new(None)?.decode(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Error paths construct JavaScript values, which only exist on wasm32
+    fn ok<T>(result: Result<T, JsError>) -> T {
+        result.unwrap_or_else(|_| panic!("unexpected error"))
+    }
+
+    #[test]
+    fn test_round_trip_with_and_without_port() {
+        let encoder = ok(WordEncoder::new(None));
+        assert_eq!(encoder.language(), "en");
+
+        let words = ok(encoder.encode_address("192.168.1.1", Some(443)));
+        assert_eq!(words, ok(encode("192.168.1.1:443")));
+        let decoded = ok(encoder.decode_address(&words));
+        assert_eq!(decoded.ip(), "192.168.1.1");
+        assert_eq!(decoded.port(), Some(443));
+        assert!(!decoded.is_ipv6());
+
+        let words = ok(encoder.encode_address("2001:db8::1", None));
+        let decoded = ok(encoder.decode_address(&words));
+        assert!(decoded.is_ipv6());
+        assert_eq!(decoded.port(), None);
+        assert_eq!(decoded.to_js_string(), "2001:db8::1");
+        assert_eq!(ok(decode(&words)), "2001:db8::1");
+    }
+}
@@ -0,0 +1,167 @@
+//! WireGuard peers as one phrase.
+//!
+//! A peer's endpoint and 32-byte public key are packed into a single
+//! [`payload`](crate::payload) phrase: the IP octets, the port (big-endian)
+//! and the key. That is 26 words for an IPv4 endpoint and 34 for IPv6, and
+//! the payload's end marker rejects a phrase with a word missing or added.
+//!
+//! ```rust
+//! use four_word_networking::wireguard::WireGuardPeer;
+//!
+//! let peer = WireGuardPeer::from_base64(
+//!     "203.0.113.7:51820".parse()?,
+//!     "xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=",
+//! )?;
+//! let words = peer.to_words()?;
+//! assert_eq!(words.split(' ').count(), 26);
+//!
+//! let decoded = WireGuardPeer::from_words(&words)?;
+//! assert_eq!(decoded, peer);
+//! println!("{}", decoded.to_stanza());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::payload::{decode_bytes, encode_bytes};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// Length of a Curve25519 public key
+pub const KEY_LEN: usize = 32;
+
+/// A WireGuard peer: where to reach it and the key it authenticates with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WireGuardPeer {
+    /// `Endpoint` of the peer
+    pub endpoint: SocketAddr,
+    /// `PublicKey` of the peer
+    pub public_key: [u8; KEY_LEN],
+}
+
+impl WireGuardPeer {
+    /// Creates a peer from its endpoint and raw public key
+    pub fn new(endpoint: SocketAddr, public_key: [u8; KEY_LEN]) -> Self {
+        WireGuardPeer {
+            endpoint,
+            public_key,
+        }
+    }
+
+    /// Creates a peer from its endpoint and the base64 key used in WireGuard configs
+    pub fn from_base64(endpoint: SocketAddr, public_key: &str) -> Result<Self> {
+        let public_key = STANDARD
+            .decode(public_key.trim())
+            .ok()
+            .and_then(|key| <[u8; KEY_LEN]>::try_from(key).ok())
+            .ok_or_else(|| {
+                FourWordError::invalid_value(
+                    "WireGuard key",
+                    public_key,
+                    format!("expected {KEY_LEN} bytes in base64"),
+                )
+            })?;
+        Ok(Self::new(endpoint, public_key))
+    }
+
+    /// Returns the public key in base64, as written in WireGuard configs
+    pub fn public_key_base64(&self) -> String {
+        STANDARD.encode(self.public_key)
+    }
+
+    /// Encodes the endpoint and public key as one English phrase
+    pub fn to_words(&self) -> Result<String> {
+        let mut bytes = match self.endpoint {
+            SocketAddr::V4(addr) => addr.ip().octets().to_vec(),
+            SocketAddr::V6(addr) => addr.ip().octets().to_vec(),
+        };
+        bytes.extend_from_slice(&self.endpoint.port().to_be_bytes());
+        bytes.extend_from_slice(&self.public_key);
+        encode_bytes(&bytes)
+    }
+
+    /// Decodes a phrase produced by [`to_words`](Self::to_words)
+    pub fn from_words(words: &str) -> Result<Self> {
+        let bytes = decode_bytes(words)?;
+        let (ip, rest) = match bytes.len() {
+            len if len == 4 + 2 + KEY_LEN => {
+                let (ip, rest) = bytes.split_at(4);
+                let octets: [u8; 4] = ip.try_into().unwrap_or_default();
+                (Ipv4Addr::from(octets).into(), rest)
+            }
+            len if len == 16 + 2 + KEY_LEN => {
+                let (ip, rest) = bytes.split_at(16);
+                let octets: [u8; 16] = ip.try_into().unwrap_or_default();
+                (Ipv6Addr::from(octets).into(), rest)
+            }
+            _ => {
+                return Err(FourWordError::NotEncoding {
+                    expected: "a WireGuard endpoint and key",
+                });
+            }
+        };
+        let (port, key) = rest.split_at(2);
+        Ok(Self::new(
+            SocketAddr::new(ip, u16::from_be_bytes([port[0], port[1]])),
+            key.try_into().unwrap_or_default(),
+        ))
+    }
+
+    /// Renders the `[Peer]` section of a WireGuard config for this peer
+    pub fn to_stanza(&self) -> String {
+        format!(
+            "[Peer]\nPublicKey = {}\nEndpoint = {}\n",
+            self.public_key_base64(),
+            self.endpoint
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payload::word_count;
+
+    const KEY: &str = "xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=";
+
+    #[test]
+    fn test_round_trip_both_families() {
+        for (endpoint, words) in [
+            ("203.0.113.7:51820", word_count(38)),
+            ("[2001:db8::7]:51820", word_count(50)),
+        ] {
+            let peer = WireGuardPeer::from_base64(endpoint.parse().unwrap(), KEY).unwrap();
+            let phrase = peer.to_words().unwrap();
+            assert_eq!(phrase.split(' ').count(), words);
+            assert_eq!(WireGuardPeer::from_words(&phrase).unwrap(), peer);
+        }
+    }
+
+    #[test]
+    fn test_stanza() {
+        let peer = WireGuardPeer::from_base64("[2001:db8::7]:51820".parse().unwrap(), KEY).unwrap();
+        assert_eq!(
+            peer.to_stanza(),
+            format!("[Peer]\nPublicKey = {KEY}\nEndpoint = [2001:db8::7]:51820\n")
+        );
+    }
+
+    #[test]
+    fn test_rejects_bad_keys_and_phrases() {
+        let endpoint = "203.0.113.7:51820".parse().unwrap();
+        assert!(WireGuardPeer::from_base64(endpoint, "not base64!").is_err());
+        assert!(WireGuardPeer::from_base64(endpoint, "AAAA").is_err());
+
+        // A valid payload of the wrong length
+        let words = encode_bytes(&[1, 2, 3, 4, 0, 80]).unwrap();
+        assert!(WireGuardPeer::from_words(&words).is_err());
+
+        let phrase = WireGuardPeer::new(endpoint, [7; KEY_LEN])
+            .to_words()
+            .unwrap();
+        let dropped: Vec<&str> = phrase.split(' ').skip(1).collect();
+        assert!(WireGuardPeer::from_words(&dropped.join(" ")).is_err());
+    }
+}
@@ -0,0 +1,528 @@
+//! Addresses that carry their word phrase.
+//!
+//! [`WordSocketAddr`] and [`WordAddress`] pair a socket address or bare IP
+//! address with its phrase. Both are only constructed by encoding an address
+//! or by decoding a phrase, so a value always holds a phrase that decodes to
+//! its address. With the `serde` feature they serialize as the phrase string
+//! and deserialize through the decoder, so configs and JSON APIs can carry
+//! word addresses without validating them by hand:
+//!
+//! ```rust
+//! # #[cfg(feature = "serde")]
+//! # {
+//! use four_word_networking::WordSocketAddr;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     peer: WordSocketAddr,
+//! }
+//!
+//! let words = four_word_networking::encode(([192, 168, 1, 1], 443))?;
+//! let config: Config = serde_json::from_str(&format!("{{\"peer\": \"{words}\"}}"))?;
+//! assert_eq!(config.peer.addr(), "192.168.1.1:443".parse()?);
+//! assert!(serde_json::from_str::<Config>("{\"peer\": \"not real words\"}").is_err());
+//! # }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Serde uses the English wordlist and default profile; the `*_with`
+//! constructors take an encoder for other languages or profiles.
+//!
+//! Both types implement [`FromStr`] and [`Display`](fmt::Display) (as the
+//! phrase), and [`WordSocketAddr`] implements [`ToSocketAddrs`], so a phrase
+//! can be dialled directly. A port-less phrase may carry the port as a
+//! `:port` suffix:
+//!
+//! ```rust,no_run
+//! use four_word_networking::WordSocketAddr;
+//! use std::net::TcpStream;
+//!
+//! let peer: WordSocketAddr = "ocean thunder falcon star:443".parse()?;
+//! let stream = TcpStream::connect(&peer)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#![forbid(unsafe_code)]
+
+use crate::error::{FourWordError, Result};
+use crate::format::normalize_phrase;
+use crate::four_word_adaptive_encoder::FourWordAdaptiveEncoder;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+use crate::ipv6_compression::Ipv6Category;
+use crate::payload::{decode_bytes_with, encode_bytes_with, word_count};
+use crate::wordlist::Wordlist;
+use std::fmt;
+use std::io;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::str::FromStr;
+
+/// Port that marks a phrase encoded without one
+const NO_PORT: u16 = 65535;
+
+/// Words in the payload form of an IPv6 socket address (16 address and 2 port bytes)
+pub(crate) const EXACT_V6_WORDS: usize = word_count(18);
+
+/// Encodes `addr` as English words without losing the port.
+///
+/// This is the phrase whenever it keeps the port. IPv6 addresses whose
+/// phrase would drop it are written as [`EXACT_V6_WORDS`] payload words
+/// instead, a count no phrase has.
+pub(crate) fn exact_words(addr: SocketAddr) -> Result<String> {
+    exact_words_with(&FourWordAdaptiveEncoder::new()?, addr)
+}
+
+/// [`exact_words`] in the wordlist and profile of `encoder`
+pub(crate) fn exact_words_with<W: Wordlist>(
+    encoder: &FourWordAdaptiveEncoder<W>,
+    addr: SocketAddr,
+) -> Result<String> {
+    let address = WordSocketAddr::new_with(encoder, addr)?;
+    if address.addr == addr {
+        return Ok(address.phrase);
+    }
+    let mut bytes = match addr.ip() {
+        IpAddr::V6(ip) => ip.octets().to_vec(),
+        IpAddr::V4(_) => unreachable!("IPv4 phrases always keep the port"),
+    };
+    bytes.extend_from_slice(&addr.port().to_be_bytes());
+    encode_bytes_with(encoder.wordlist(), &bytes)
+}
+
+/// Inverse of [`exact_words`]
+pub(crate) fn from_exact_words(words: &str) -> Result<SocketAddr> {
+    from_exact_words_with(&FourWordAdaptiveEncoder::new()?, words)
+}
+
+/// Inverse of [`exact_words_with`]; words may be separated by whitespace,
+/// `.` or `-`
+pub(crate) fn from_exact_words_with<W: Wordlist>(
+    encoder: &FourWordAdaptiveEncoder<W>,
+    words: &str,
+) -> Result<SocketAddr> {
+    let normalized = normalize_phrase(words);
+    if normalized.split(' ').count() != EXACT_V6_WORDS {
+        return Ok(WordSocketAddr::from_phrase_with(encoder, words)?.addr);
+    }
+    let bytes: [u8; 18] = decode_bytes_with(encoder.wordlist(), &normalized)?
+        .try_into()
+        .map_err(|_| FourWordError::NotEncoding {
+            expected: "an IPv6 socket address",
+        })?;
+    let [ip @ .., high, low] = bytes;
+    Ok(SocketAddr::new(
+        Ipv6Addr::from(ip).into(),
+        u16::from_be_bytes([high, low]),
+    ))
+}
+
+/// A socket address together with its word phrase
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WordSocketAddr {
+    addr: SocketAddr,
+    phrase: String,
+}
+
+impl WordSocketAddr {
+    /// Encodes `addr` as English words
+    pub fn new(addr: impl Into<SocketAddr>) -> Result<Self> {
+        Self::new_with(&FourWordAdaptiveEncoder::new()?, addr)
+    }
+
+    /// Encodes `addr` with `encoder`
+    pub fn new_with<W: Wordlist>(
+        encoder: &FourWordAdaptiveEncoder<W>,
+        addr: impl Into<SocketAddr>,
+    ) -> Result<Self> {
+        let mut phrase = String::new();
+        encoder.encode_into(addr.into(), &mut phrase)?;
+        // Some IPv6 encodings cannot keep the port, so record what decodes
+        let addr = encoder.decode_socket_addr(&phrase)?;
+        Ok(WordSocketAddr { addr, phrase })
+    }
+
+    /// Decodes an English phrase
+    pub fn from_phrase(words: &str) -> Result<Self> {
+        Self::from_phrase_with(&FourWordAdaptiveEncoder::new()?, words)
+    }
+
+    /// Decodes `words` with `encoder`.
+    ///
+    /// `words` is either a full phrase or a phrase without a port followed by
+    /// `:port`. The stored phrase is re-encoded from the address, so any
+    /// accepted separator, letter case or port suffix comes back in the
+    /// canonical spelling.
+    pub fn from_phrase_with<W: Wordlist>(
+        encoder: &FourWordAdaptiveEncoder<W>,
+        words: &str,
+    ) -> Result<Self> {
+        let suffix = words
+            .trim()
+            .rsplit_once(':')
+            .filter(|(phrase, _)| !phrase.contains(':'));
+        let addr = match suffix {
+            Some((phrase, port)) => {
+                let port = port.parse::<u16>().map_err(|_| {
+                    FourWordError::invalid_value("port suffix", port, "expected 0 to 65535")
+                })?;
+                let ip = WordAddress::from_phrase_with(encoder, phrase)?.ip;
+                SocketAddr::new(ip, port)
+            }
+            None => encoder.decode_socket_addr(words)?,
+        };
+        Self::new_with(encoder, addr)
+    }
+
+    /// Returns the socket address
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the space-separated phrase
+    pub fn phrase(&self) -> &str {
+        &self.phrase
+    }
+}
+
+impl From<WordSocketAddr> for SocketAddr {
+    fn from(address: WordSocketAddr) -> Self {
+        address.addr
+    }
+}
+
+impl FromStr for WordSocketAddr {
+    type Err = FourWordError;
+
+    /// Decodes an English phrase, optionally with a `:port` suffix
+    fn from_str(words: &str) -> Result<Self> {
+        Self::from_phrase(words)
+    }
+}
+
+impl fmt::Display for WordSocketAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.phrase)
+    }
+}
+
+impl ToSocketAddrs for WordSocketAddr {
+    type Iter = std::option::IntoIter<SocketAddr>;
+
+    fn to_socket_addrs(&self) -> io::Result<Self::Iter> {
+        Ok(Some(self.addr).into_iter())
+    }
+}
+
+/// An IP address without a port together with its word phrase
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WordAddress {
+    ip: IpAddr,
+    phrase: String,
+}
+
+impl WordAddress {
+    /// Encodes `ip` as English words
+    pub fn new(ip: impl Into<IpAddr>) -> Result<Self> {
+        Self::new_with(&FourWordAdaptiveEncoder::new()?, ip)
+    }
+
+    /// Encodes `ip` with `encoder`
+    pub fn new_with<W: Wordlist>(
+        encoder: &FourWordAdaptiveEncoder<W>,
+        ip: impl Into<IpAddr>,
+    ) -> Result<Self> {
+        let address = WordSocketAddr::new_with(encoder, SocketAddr::new(ip.into(), NO_PORT))?;
+        Ok(WordAddress {
+            ip: address.addr.ip(),
+            phrase: address.phrase,
+        })
+    }
+
+    /// Decodes an English phrase
+    pub fn from_phrase(words: &str) -> Result<Self> {
+        Self::from_phrase_with(&FourWordAdaptiveEncoder::new()?, words)
+    }
+
+    /// Decodes `words` with `encoder`, rejecting phrases that carry a port
+    pub fn from_phrase_with<W: Wordlist>(
+        encoder: &FourWordAdaptiveEncoder<W>,
+        words: &str,
+    ) -> Result<Self> {
+        let addr = encoder.decode_socket_addr(words)?;
+        if addr.port() != NO_PORT {
+            return Err(FourWordError::NotEncoding {
+                expected: "an address without a port",
+            });
+        }
+        Self::new_with(encoder, addr.ip())
+    }
+
+    /// Returns the IP address
+    pub fn ip(&self) -> IpAddr {
+        self.ip
+    }
+
+    /// Returns the space-separated phrase
+    pub fn phrase(&self) -> &str {
+        &self.phrase
+    }
+}
+
+impl From<WordAddress> for IpAddr {
+    fn from(address: WordAddress) -> Self {
+        address.ip
+    }
+}
+
+impl FromStr for WordAddress {
+    type Err = FourWordError;
+
+    fn from_str(words: &str) -> Result<Self> {
+        Self::from_phrase(words)
+    }
+}
+
+impl fmt::Display for WordAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.phrase)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use super::{WordAddress, WordSocketAddr};
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    impl Serialize for WordSocketAddr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&self.phrase)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for WordSocketAddr {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let words = String::deserialize(deserializer)?;
+            WordSocketAddr::from_phrase(&words).map_err(D::Error::custom)
+        }
+    }
+
+    impl Serialize for WordAddress {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&self.phrase)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for WordAddress {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let words = String::deserialize(deserializer)?;
+            WordAddress::from_phrase(&words).map_err(D::Error::custom)
+        }
+    }
+}
+
+/// Shared by the `arbitrary` and `proptest` generators: an IPv4 address, or
+/// an IPv6 address from the category `random` selects
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+fn sample_ip(v4: bool, v4_bits: u32, category: Ipv6Category, random: [u8; 16]) -> IpAddr {
+    if v4 {
+        IpAddr::V4(v4_bits.into())
+    } else {
+        IpAddr::V6(category.sample_address(random))
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls {
+    use super::{WordAddress, WordSocketAddr, sample_ip};
+    use arbitrary::{Arbitrary, Error, Unstructured};
+    use std::net::SocketAddr;
+
+    /// Half IPv4, half IPv6 spread over the categories, encoded in English
+    impl<'a> Arbitrary<'a> for WordAddress {
+        fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+            let ip = sample_ip(
+                u.arbitrary()?,
+                u.arbitrary()?,
+                u.arbitrary()?,
+                u.arbitrary()?,
+            );
+            WordAddress::new(ip).map_err(|_| Error::IncorrectFormat)
+        }
+    }
+
+    /// Like [`WordAddress`] with any port, including 65535 for "no port"
+    impl<'a> Arbitrary<'a> for WordSocketAddr {
+        fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+            let ip = sample_ip(
+                u.arbitrary()?,
+                u.arbitrary()?,
+                u.arbitrary()?,
+                u.arbitrary()?,
+            );
+            WordSocketAddr::new(SocketAddr::new(ip, u.arbitrary()?))
+                .map_err(|_| Error::IncorrectFormat)
+        }
+    }
+}
+
+#[cfg(feature = "proptest")]
+mod proptest_impls {
+    use super::{WordAddress, WordSocketAddr, sample_ip};
+    use crate::ipv6_compression::Ipv6Category;
+    use proptest::prelude::*;
+    use std::net::{IpAddr, SocketAddr};
+
+    /// Same distribution as the `arbitrary` implementations
+    fn ips() -> impl Strategy<Value = IpAddr> {
+        (
+            any::<bool>(),
+            any::<u32>(),
+            any::<Ipv6Category>(),
+            any::<[u8; 16]>(),
+        )
+            .prop_map(|(v4, v4_bits, category, random)| sample_ip(v4, v4_bits, category, random))
+    }
+
+    impl Arbitrary for WordAddress {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<WordAddress>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            ips()
+                .prop_filter_map("address failed to encode", |ip| WordAddress::new(ip).ok())
+                .boxed()
+        }
+    }
+
+    impl Arbitrary for WordSocketAddr {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<WordSocketAddr>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            (ips(), any::<u16>())
+                .prop_filter_map("address failed to encode", |(ip, port)| {
+                    WordSocketAddr::new(SocketAddr::new(ip, port)).ok()
+                })
+                .boxed()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_socket_addr_round_trip() {
+        let addr: SocketAddr = "192.168.1.1:443".parse().unwrap();
+        let address = WordSocketAddr::new(addr).unwrap();
+        assert_eq!(address.phrase(), crate::encode(addr).unwrap());
+
+        // Other separators and letter case decode to the canonical phrase
+        let shouted = address.phrase().to_uppercase().replace(' ', ".");
+        let decoded = WordSocketAddr::from_phrase(&shouted).unwrap();
+        assert_eq!(decoded, address);
+        assert_eq!(SocketAddr::from(decoded), addr);
+
+        assert!(WordSocketAddr::from_phrase("not real words").is_err());
+    }
+
+    #[test]
+    fn test_address_rejects_port() {
+        let address = WordAddress::new([10, 0, 0, 1]).unwrap();
+        assert_eq!(address.ip(), IpAddr::from([10, 0, 0, 1]));
+        assert_eq!(WordAddress::from_phrase(address.phrase()).unwrap(), address);
+
+        let with_port = crate::encode(([10, 0, 0, 1], 80)).unwrap();
+        assert!(WordAddress::from_phrase(&with_port).is_err());
+    }
+
+    #[test]
+    fn test_exact_words_keep_the_port() {
+        for addr in [
+            "192.168.1.1:443",
+            "[::1]:8080",
+            "[2606:4700:4700::1111]:4433",
+        ] {
+            let addr: SocketAddr = addr.parse().unwrap();
+            let words = exact_words(addr).unwrap();
+            assert_eq!(from_exact_words(&words).unwrap(), addr, "{words}");
+        }
+
+        // Only addresses whose phrase drops the port use the payload form
+        let lossy: SocketAddr = "[2606:4700:4700::1111]:4433".parse().unwrap();
+        assert_ne!(WordSocketAddr::new(lossy).unwrap().addr(), lossy);
+        let words = exact_words(lossy).unwrap();
+        assert_eq!(words.split(' ').count(), EXACT_V6_WORDS);
+    }
+
+    #[test]
+    fn test_parse_display_and_connect() {
+        use std::net::{TcpListener, TcpStream};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let host = WordAddress::new([127, 0, 0, 1]).unwrap();
+
+        let peer: WordSocketAddr = format!("{host}:{port}").parse().unwrap();
+        assert_eq!(peer.addr(), listener.local_addr().unwrap());
+        assert_eq!(peer.to_string().parse::<WordSocketAddr>().unwrap(), peer);
+        assert_eq!(host.to_string().parse::<WordAddress>().unwrap(), host);
+        TcpStream::connect(&peer).unwrap();
+
+        // A port suffix needs a phrase without one, and a valid port
+        let with_port = crate::encode(([127, 0, 0, 1], 80)).unwrap();
+        assert!(
+            format!("{with_port}:{port}")
+                .parse::<WordSocketAddr>()
+                .is_err()
+        );
+        assert!(format!("{host}:99999").parse::<WordSocketAddr>().is_err());
+        assert!("[::1]:80".parse::<WordSocketAddr>().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_uses_phrase() {
+        let address = WordSocketAddr::new(([192, 168, 1, 1], 443)).unwrap();
+        let json = serde_json::to_string(&address).unwrap();
+        assert_eq!(json, format!("\"{}\"", address.phrase()));
+        assert_eq!(
+            serde_json::from_str::<WordSocketAddr>(&json).unwrap(),
+            address
+        );
+
+        assert!(serde_json::from_str::<WordSocketAddr>("\"ocean thunder\"").is_err());
+        assert!(serde_json::from_str::<WordAddress>(&json).is_err());
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_addresses_round_trip() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let bytes: Vec<u8> = (0..8192u32)
+            .map(|n| n.wrapping_mul(2_654_435_761) as u8)
+            .collect();
+        let mut u = Unstructured::new(&bytes);
+        for _ in 0..64 {
+            let address = WordAddress::arbitrary(&mut u).unwrap();
+            assert_eq!(WordAddress::from_phrase(address.phrase()).unwrap(), address);
+            let socket = WordSocketAddr::arbitrary(&mut u).unwrap();
+            let decoded = WordSocketAddr::from_phrase(socket.phrase()).unwrap();
+            assert_eq!(decoded.phrase(), socket.phrase());
+        }
+    }
+
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        #[test]
+        fn prop_word_socket_addr_round_trips(
+            address in proptest::prelude::any::<WordSocketAddr>()
+        ) {
+            let decoded = WordSocketAddr::from_phrase(address.phrase()).unwrap();
+            proptest::prop_assert_eq!(decoded.phrase(), address.phrase());
+        }
+    }
+}
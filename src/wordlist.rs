@@ -0,0 +1,155 @@
+//! Pluggable wordlist backends.
+//!
+//! The encoders only need four operations from a dictionary, captured by the
+//! [`Wordlist`] trait. [`Dictionary4K`](crate::dictionary4k::Dictionary4K) is
+//! the built-in implementation; downstream crates can implement the trait for
+//! FST-backed, memory-mapped or database-backed lists and pass them to
+//! [`FourWordAdaptiveEncoder::with_wordlist`](crate::FourWordAdaptiveEncoder::with_wordlist).
+
+#![forbid(unsafe_code)]
+
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// A bidirectional mapping between 12-bit indices and words.
+///
+/// Implementations used for encoding must hold exactly 4,096 words, map every
+/// index below 4,096 to a distinct word, and map each of those words back to
+/// its index. Lookups by word should ignore ASCII case.
+pub trait Wordlist {
+    /// Gets the word at `index`, or `None` if out of range
+    fn get_word(&self, index: u16) -> Option<&str>;
+
+    /// Gets the index of `word`, or `None` if it is not in the list
+    fn get_index(&self, word: &str) -> Option<u16>;
+
+    /// Returns the number of words in the list
+    fn len(&self) -> usize;
+
+    /// Checks whether the list is empty
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks whether `word` is in the list
+    fn contains(&self, word: &str) -> bool {
+        self.get_index(word).is_some()
+    }
+
+    /// Returns a SHA-256 fingerprint of the words in index order.
+    ///
+    /// Backends that can cache the value should override this; the default
+    /// hashes every word on each call.
+    fn fingerprint(&self) -> [u8; 32] {
+        let len = u16::try_from(self.len()).unwrap_or(u16::MAX);
+        fingerprint_words((0..len).filter_map(|index| self.get_word(index)))
+    }
+}
+
+impl<W: Wordlist + ?Sized> Wordlist for &W {
+    fn get_word(&self, index: u16) -> Option<&str> {
+        (**self).get_word(index)
+    }
+
+    fn get_index(&self, word: &str) -> Option<u16> {
+        (**self).get_index(word)
+    }
+
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn fingerprint(&self) -> [u8; 32] {
+        (**self).fingerprint()
+    }
+}
+
+impl<W: Wordlist + ?Sized> Wordlist for Arc<W> {
+    fn get_word(&self, index: u16) -> Option<&str> {
+        (**self).get_word(index)
+    }
+
+    fn get_index(&self, word: &str) -> Option<u16> {
+        (**self).get_index(word)
+    }
+
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn fingerprint(&self) -> [u8; 32] {
+        (**self).fingerprint()
+    }
+}
+
+/// Hashes words in index order, newline-terminated so boundaries are unambiguous
+pub(crate) fn fingerprint_words<'a>(words: impl Iterator<Item = &'a str>) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for word in words {
+        hasher.update(word.as_bytes());
+        hasher.update(b"\n");
+    }
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary4k::DICTIONARY;
+    use std::collections::HashMap;
+
+    /// A deliberately naive backend, standing in for an external store
+    struct MapWordlist {
+        words: Vec<String>,
+        indices: HashMap<String, u16>,
+    }
+
+    impl MapWordlist {
+        fn new() -> Self {
+            let words: Vec<String> = (0..4096u16)
+                .map(|i| DICTIONARY.get_word(i).unwrap().to_string())
+                .collect();
+            let indices = words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| (w.clone(), i as u16))
+                .collect();
+            MapWordlist { words, indices }
+        }
+    }
+
+    impl Wordlist for MapWordlist {
+        fn get_word(&self, index: u16) -> Option<&str> {
+            self.words.get(index as usize).map(String::as_str)
+        }
+
+        fn get_index(&self, word: &str) -> Option<u16> {
+            self.indices.get(&word.to_ascii_lowercase()).copied()
+        }
+
+        fn len(&self) -> usize {
+            self.words.len()
+        }
+    }
+
+    #[test]
+    fn test_default_fingerprint_matches_builtin() {
+        let map = MapWordlist::new();
+        assert_eq!(Wordlist::fingerprint(&map), DICTIONARY.fingerprint());
+        assert_eq!(Arc::new(map).fingerprint(), DICTIONARY.fingerprint());
+    }
+
+    #[test]
+    fn test_custom_backend_encodes_like_builtin() {
+        // Non-Clone backends are shared by reference (or behind an `Arc`)
+        let map = MapWordlist::new();
+        let encoder = crate::FourWordAdaptiveEncoder::with_wordlist(&map).unwrap();
+        let builtin = crate::FourWordAdaptiveEncoder::new().unwrap();
+
+        for addr in ["192.168.1.1:443", "[fe80::1]:22", "[2001:db8::1]:8080"] {
+            let words = encoder.encode(addr).unwrap();
+            assert_eq!(words, builtin.encode(addr).unwrap());
+            assert_eq!(encoder.decode(&words).unwrap(), addr);
+        }
+    }
+}
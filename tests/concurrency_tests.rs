@@ -0,0 +1,155 @@
+/// Concurrency guarantees for sharing one encoder across threads.
+///
+/// The encode/decode core holds no mutable or global state, so a single
+/// `FourWordAdaptiveEncoder` can be shared freely. These tests pin that down:
+/// the std-thread tests also run under Miri, and the loom models (including
+/// one racing `CachedEncoder` lookups and inserts) run with
+/// `RUSTFLAGS="--cfg loom" cargo test --test concurrency_tests --release`.
+use four_word_networking::{
+    CachedEncoder, FourWordAdaptiveEncoder, VoiceNormalizer, dictionary4k::Dictionary4K,
+};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_shared_types_are_send_and_sync() {
+    assert_send_sync::<FourWordAdaptiveEncoder>();
+    assert_send_sync::<CachedEncoder>();
+    assert_send_sync::<Dictionary4K>();
+    assert_send_sync::<VoiceNormalizer>();
+    #[cfg(unix)]
+    assert_send_sync::<four_word_networking::daemon::Daemon>();
+}
+
+#[cfg(not(loom))]
+#[test]
+fn test_shared_encoder_across_threads() {
+    use std::sync::Arc;
+    use std::thread;
+
+    let encoder = Arc::new(FourWordAdaptiveEncoder::new().unwrap());
+    // Keep the work small so the test stays practical under Miri
+    let addresses = ["192.168.1.1:443", "[::1]:8080", "[fe80::1]:22"];
+
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let encoder = Arc::clone(&encoder);
+            thread::spawn(move || {
+                let addr = addresses[i % addresses.len()];
+                let words = encoder.encode(addr).unwrap();
+                assert_eq!(encoder.decode(&words).unwrap(), addr);
+                words
+            })
+        })
+        .collect();
+
+    for (i, handle) in handles.into_iter().enumerate() {
+        let words = handle.join().expect("Thread failed");
+        assert_eq!(
+            words,
+            encoder.encode(addresses[i % addresses.len()]).unwrap()
+        );
+    }
+}
+
+#[cfg(not(loom))]
+#[test]
+fn test_shared_cached_encoder_across_threads() {
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+    use std::thread;
+
+    // A capacity smaller than the working set forces evictions under contention
+    let cached = Arc::new(CachedEncoder::new(
+        FourWordAdaptiveEncoder::new().unwrap(),
+        2,
+    ));
+    let addresses: [SocketAddr; 3] = [
+        "192.168.1.1:443".parse().unwrap(),
+        "[::1]:8080".parse().unwrap(),
+        "[fe80::1]:22".parse().unwrap(),
+    ];
+
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let cached = Arc::clone(&cached);
+            thread::spawn(move || {
+                for round in 0..3 {
+                    let addr = addresses[(i + round) % addresses.len()];
+                    let words = cached.encode(addr).unwrap();
+                    assert_eq!(cached.decode(&words).unwrap(), addr);
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().expect("Thread failed");
+    }
+    let stats = cached.stats();
+    assert_eq!(stats.hits + stats.misses, 24);
+    assert!(cached.len() <= 4);
+}
+
+#[cfg(loom)]
+#[test]
+fn loom_shared_encoder() {
+    use loom::sync::Arc;
+    use loom::thread;
+
+    loom::model(|| {
+        let encoder = Arc::new(FourWordAdaptiveEncoder::new().unwrap());
+
+        let handles: Vec<_> = ["10.0.0.1:80", "[2001:db8::1]:443"]
+            .into_iter()
+            .map(|addr| {
+                let encoder = Arc::clone(&encoder);
+                thread::spawn(move || {
+                    let words = encoder.encode(addr).unwrap();
+                    assert_eq!(encoder.decode(&words).unwrap(), addr);
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    });
+}
+
+#[cfg(loom)]
+#[test]
+fn loom_cached_encoder_get_and_insert() {
+    use loom::sync::Arc;
+    use loom::thread;
+    use std::net::SocketAddr;
+
+    loom::model(|| {
+        // One slot, so the second insert always evicts the first
+        let cached = Arc::new(CachedEncoder::new(
+            FourWordAdaptiveEncoder::new().unwrap(),
+            1,
+        ));
+        let addresses: [SocketAddr; 2] = [
+            "10.0.0.1:80".parse().unwrap(),
+            "10.0.0.2:80".parse().unwrap(),
+        ];
+
+        let other = Arc::clone(&cached);
+        let handle = thread::spawn(move || other.encode(addresses[1]).unwrap());
+        // Races the other thread's lookup and insert on the same slot
+        let words = cached.encode(addresses[0]).unwrap();
+        let encoded = handle.join().unwrap();
+
+        assert_eq!(
+            cached.encoder().decode_socket_addr(&words).unwrap(),
+            addresses[0]
+        );
+        assert_eq!(
+            cached.encoder().decode_socket_addr(&encoded).unwrap(),
+            addresses[1]
+        );
+        assert_eq!(cached.stats().misses, 2);
+        assert_eq!(cached.len(), 1);
+    });
+}
@@ -0,0 +1,113 @@
+/// Guards the sandbox-friendly core: the encode/decode path must not use
+/// statics, lazy initialisation, the filesystem or the clock, so it can run inside WASM
+/// sandboxes and seccomp-confined workers.
+use four_word_networking::FourWordAdaptiveEncoder;
+use std::fs;
+use std::path::Path;
+
+/// Modules allowed to reach the host, and why; every other module in `src/`
+/// is core
+const HOST_MODULES: &[&str] = &[
+    // binds a UNIX socket under the runtime directory
+    "daemon.rs",
+    // times probes
+    "dial.rs",
+    // times browse windows
+    "discovery.rs",
+    // `extern "C"` entry points read caller pointers
+    "ffi.rs",
+    // times conversions
+    "metrics.rs",
+];
+
+/// Every module in `src/` with its source, by file name
+fn module_sources() -> Vec<(String, String)> {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    let mut sources: Vec<_> = fs::read_dir(&src)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+        .map(|file| {
+            let source = fs::read_to_string(src.join(&file)).unwrap();
+            (file, source)
+        })
+        .collect();
+    sources.sort();
+    sources
+}
+
+/// Constructs that introduce global state or touch the host environment,
+/// including its clock
+const FORBIDDEN: &[&str] = &[
+    "static mut",
+    "static ",
+    "Lazy<",
+    "OnceCell",
+    "OnceLock",
+    "LazyLock",
+    "thread_local!",
+    "std::fs",
+    "std::env",
+    "File::",
+    "SystemTime::now",
+    "Instant::now",
+    "unsafe ",
+];
+
+#[test]
+fn test_core_has_no_global_state() {
+    let sources = module_sources();
+    assert!(sources.iter().any(|(file, _)| file == "dictionary4k.rs"));
+
+    for (file, source) in &sources {
+        if HOST_MODULES.contains(&file.as_str()) {
+            continue;
+        }
+        for (number, line) in source.lines().enumerate() {
+            let code = line.split("//").next().unwrap_or("");
+            // `&'static str` is a lifetime, not a static item
+            let code = code.replace("'static", "");
+            for pattern in FORBIDDEN {
+                assert!(
+                    !code.contains(pattern),
+                    "{file}:{}: core code must not use `{}`: {}",
+                    number + 1,
+                    pattern.trim(),
+                    line.trim()
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_modules_forbid_unsafe() {
+    // The compiler enforces this; the test catches a module added without it
+    for (file, source) in module_sources() {
+        if file == "ffi.rs" {
+            continue;
+        }
+        if file == "lib.rs" {
+            assert!(source.contains("#![deny(unsafe_code)]"));
+        } else {
+            assert!(
+                source.contains("#![forbid(unsafe_code)]"),
+                "{file} must start with #![forbid(unsafe_code)]"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_independent_encoders_agree() {
+    // With no shared state, separately constructed encoders are interchangeable
+    let a = FourWordAdaptiveEncoder::new().unwrap();
+    let b = FourWordAdaptiveEncoder::new().unwrap();
+
+    for addr in ["192.168.1.1:443", "[fe80::1]:22", "[2001:db8::1]:8080"] {
+        let words = a.encode(addr).unwrap();
+        assert_eq!(words, b.encode(addr).unwrap());
+        assert_eq!(b.decode(&words).unwrap(), addr);
+    }
+}